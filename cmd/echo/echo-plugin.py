@@ -52,6 +52,15 @@ SERVERS = {
         "env": lambda port: {},
         "startup_wait_s": 0.5,
     },
+    "ksvc-sqpoll": {
+        "dir": "rust/ksvc",
+        "build": "cargo build --release",
+        "cargo_package": "ksvc-echo",
+        "cmd": "target/release/ksvc-echo",
+        "args": lambda port: ["--backend", "sqpoll", str(port)],
+        "env": lambda port: {},
+        "startup_wait_s": 0.5,
+    },
 }
 
 
@@ -193,7 +202,7 @@ def run_bench(server_name: str, port: int, wrk_threads: int = 2, **kwargs) -> Te
             p50_us=bench.get("p50_us"),
             p99_us=bench.get("p99_us"),
             duration_s=bench["duration_s"],
-            threads=1 if server_name == "ksvc" else 0,  # 0 = multi/unknown
+            threads=1 if server_name in ("ksvc", "ksvc-sqpoll") else 0,  # 0 = multi/unknown
             extra={
                 "connections": scenario["connections"],
                 "msg_size": scenario["msg_size"],
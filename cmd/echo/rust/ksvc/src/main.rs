@@ -7,7 +7,10 @@
 //!
 //! Usage:
 //!     cargo build --release -p ksvc-echo
-//!     ./target/release/ksvc-echo [port] [max_conns]
+//!     ./target/release/ksvc-echo [--backend basic|sqpoll] [port] [max_conns]
+//!
+//! `--backend sqpoll` runs the same loop on `SqpollIoUring` (a kernel
+//! thread polls the SQ) instead of `BasicIoUring`, for comparing the two.
 //!
 //! Test with:
 //!     # Correctness:
@@ -21,11 +24,14 @@
 //!     for i in $(seq 1 100); do echo "ping $i" | nc -q0 localhost 9999 & done
 
 use ksvc_core::entry::{CorrId, SubmitEntry};
+use ksvc_core::error::Result;
 use ksvc_core::io_backend::{IoBackend, IoCompletion};
 use ksvc_core::router::SyscallRouter;
 
 use ksvc_module::basic_iouring::{BasicIoUring, BasicIoUringConfig};
+use ksvc_module::capabilities::IoCapabilities;
 use ksvc_module::probe_router::ProbeRouter;
+use ksvc_module::sqpoll_iouring::SqpollIoUring;
 
 use std::sync::atomic::{AtomicBool, Ordering};
 
@@ -36,21 +42,50 @@ const NR_SENDTO: u32 = 44;
 const NR_RECVFROM: u32 = 45;
 
 // ── Op types encoded in corr_id high bits ──
-const OP_ACCEPT: u64 = 1 << 56;
-const OP_RECV: u64 = 2 << 56;
-const OP_SEND: u64 = 3 << 56;
-const OP_CLOSE: u64 = 4 << 56;
-const OP_MASK: u64 = 0xFF << 56;
-const IDX_MASK: u64 = (1 << 56) - 1;
-
-fn make_id(op: u64, idx: usize) -> CorrId {
-    CorrId(op | idx as u64)
+const OP_ACCEPT: u8 = 1;
+const OP_RECV: u8 = 2;
+const OP_SEND: u8 = 3;
+const OP_CLOSE: u8 = 4;
+
+fn make_id(op: u8, idx: usize) -> CorrId {
+    CorrId::with_op(op, idx as u64)
 }
-fn decode_op(id: CorrId) -> u64 {
-    id.0 & OP_MASK
+
+// ── Backends ──
+
+/// What the event loop needs beyond `IoBackend`: both io_uring
+/// backends have these as inherent methods.
+trait EchoRing: IoBackend {
+    fn flush_and_wait(&mut self, min_complete: usize) -> Result<usize>;
+    fn capabilities(&self) -> &'static IoCapabilities;
+    /// SQ thread wakeups (SQPOLL only)
+    fn wakeups(&self) -> Option<u64> {
+        None
+    }
 }
-fn decode_idx(id: CorrId) -> usize {
-    (id.0 & IDX_MASK) as usize
+
+impl EchoRing for BasicIoUring {
+    fn flush_and_wait(&mut self, min_complete: usize) -> Result<usize> {
+        BasicIoUring::flush_and_wait(self, min_complete)
+    }
+
+    fn capabilities(&self) -> &'static IoCapabilities {
+        BasicIoUring::capabilities(self)
+    }
+}
+
+impl EchoRing for SqpollIoUring {
+    fn flush_and_wait(&mut self, min_complete: usize) -> Result<usize> {
+        SqpollIoUring::flush_and_wait(self, min_complete)
+    }
+
+    fn capabilities(&self) -> &'static IoCapabilities {
+        SqpollIoUring::capabilities(self)
+    }
+
+    fn wakeups(&self) -> Option<u64> {
+        Some(SqpollIoUring::wakeups(self))
+    }
 }
 
 // ── Per-connection state ──
@@ -167,8 +202,8 @@ fn setup_listener(port: u16) -> i32 {
 
 // ── Submit helpers ──
 
-fn submit_accept(
-    io: &mut BasicIoUring,
+fn submit_accept<B: EchoRing>(
+    io: &mut B,
     router: &ProbeRouter,
     listener: i32,
     addr: &mut libc::sockaddr_in,
@@ -187,10 +222,10 @@ fn submit_accept(
             0, 0,
         ],
     };
-    let _ = io.submit_with_opcode(&entry, route.iouring_opcode);
+    let _ = io.submit(&entry, route.iouring_opcode);
 }
 
-fn submit_recv(io: &mut BasicIoUring, router: &ProbeRouter, conn: &mut Conn, idx: usize) {
+fn submit_recv<B: EchoRing>(io: &mut B, router: &ProbeRouter, conn: &mut Conn, idx: usize) {
     let route = router.route(NR_RECVFROM);
     let entry = SubmitEntry {
         corr_id: make_id(OP_RECV, idx),
@@ -203,10 +238,10 @@ fn submit_recv(io: &mut BasicIoUring, router: &ProbeRouter, conn: &mut Conn, idx
             0, 0, 0,
         ],
     };
-    let _ = io.submit_with_opcode(&entry, route.iouring_opcode);
+    let _ = io.submit(&entry, route.iouring_opcode);
 }
 
-fn submit_send(io: &mut BasicIoUring, router: &ProbeRouter, conn: &Conn, idx: usize) {
+fn submit_send<B: EchoRing>(io: &mut B, router: &ProbeRouter, conn: &Conn, idx: usize) {
     let route = router.route(NR_SENDTO);
     let entry = SubmitEntry {
         corr_id: make_id(OP_SEND, idx),
@@ -219,10 +254,10 @@ fn submit_send(io: &mut BasicIoUring, router: &ProbeRouter, conn: &Conn, idx: us
             0, 0, 0,
         ],
     };
-    let _ = io.submit_with_opcode(&entry, route.iouring_opcode);
+    let _ = io.submit(&entry, route.iouring_opcode);
 }
 
-fn submit_close(io: &mut BasicIoUring, router: &ProbeRouter, fd: i32, idx: usize) {
+fn submit_close<B: EchoRing>(io: &mut B, router: &ProbeRouter, fd: i32, idx: usize) {
     let route = router.route(NR_CLOSE);
     let entry = SubmitEntry {
         corr_id: make_id(OP_CLOSE, idx),
@@ -230,7 +265,7 @@ fn submit_close(io: &mut BasicIoUring, router: &ProbeRouter, fd: i32, idx: usize
         flags: 0,
         args: [fd as u64, 0, 0, 0, 0, 0],
     };
-    let _ = io.submit_with_opcode(&entry, route.iouring_opcode);
+    let _ = io.submit(&entry, route.iouring_opcode);
 }
 
 // ── Main event loop ──
@@ -238,9 +273,18 @@ fn submit_close(io: &mut BasicIoUring, router: &ProbeRouter, fd: i32, idx: usize
 static RUNNING: AtomicBool = AtomicBool::new(true);
 
 fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    let port: u16 = args.get(1).and_then(|s| s.parse().ok()).unwrap_or(9999);
-    let max_conns: usize = args.get(2).and_then(|s| s.parse().ok()).unwrap_or(1024);
+    let mut backend = String::from("basic");
+    let mut positional = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--backend" {
+            backend = args.next().unwrap_or_default();
+        } else {
+            positional.push(arg);
+        }
+    }
+    let port: u16 = positional.first().and_then(|s| s.parse().ok()).unwrap_or(9999);
+    let max_conns: usize = positional.get(1).and_then(|s| s.parse().ok()).unwrap_or(1024);
 
     // SIGINT handler for clean shutdown
     unsafe {
@@ -248,17 +292,29 @@ fn main() {
         libc::signal(libc::SIGTERM, handle_sigint as usize);
     }
 
-    eprintln!("ksvc-echo: starting on port {} (max {} connections)", port, max_conns);
+    eprintln!("ksvc-echo: starting on port {} (max {} connections, {} backend)", port, max_conns, backend);
 
     // Setup
     let listener = setup_listener(port);
-    let mut io = BasicIoUring::new(BasicIoUringConfig {
+    let config = BasicIoUringConfig {
         sq_entries: 256,
         ..Default::default()
-    }).expect("io_uring setup failed");
+    };
+    match backend.as_str() {
+        "basic" => serve(BasicIoUring::new(config).expect("io_uring setup failed"), listener, port, max_conns),
+        "sqpoll" => serve(SqpollIoUring::new(config).expect("SQPOLL io_uring setup failed"), listener, port, max_conns),
+        other => {
+            eprintln!("ksvc-echo: unknown backend {:?} (expected basic or sqpoll)", other);
+            std::process::exit(2);
+        }
+    }
+    unsafe { libc::close(listener); }
+    eprintln!("ksvc-echo: done.");
+}
 
-    let supported = io.probe_opcodes_static();
-    let router = ProbeRouter::new(&supported);
+/// Run the event loop on `io` until SIGINT/SIGTERM
+fn serve<B: EchoRing>(mut io: B, listener: i32, port: u16, max_conns: usize) {
+    let router = ProbeRouter::from_capabilities(io.capabilities());
     let counts = router.tier_counts();
     eprintln!("ksvc-echo: routing T0={} T1={} T2={} T3={}", counts.tier0, counts.tier1, counts.tier2, counts.tier3);
 
@@ -296,8 +352,8 @@ fn main() {
 
         for i in 0..n {
             let cqe = &comp_buf[i];
-            let op = decode_op(cqe.corr_id);
-            let idx = decode_idx(cqe.corr_id);
+            let op = cqe.corr_id.op();
+            let idx = cqe.corr_id.payload() as usize;
             let result = cqe.result;
 
             match op {
@@ -395,8 +451,9 @@ fn main() {
     // Shutdown
     eprintln!("\nksvc-echo: shutting down...");
     stats.print(&conns, start.elapsed().as_secs_f64());
-    unsafe { libc::close(listener); }
-    eprintln!("ksvc-echo: done.");
+    if let Some(wakeups) = io.wakeups() {
+        eprintln!("ksvc-echo: SQ thread woken {} times", wakeups);
+    }
 }
 
 extern "C" fn handle_sigint(_sig: libc::c_int) {
@@ -9,6 +9,11 @@
 //! - `GVT_LOW_WORKERS=<n>` - Number of low-priority workers (default: 1)
 //! - `GVT_GVTHREADS=<n>` - Number of GVThreads to spawn (default: 3)
 //! - `GVT_YIELDS=<n>` - Number of yields per GVThread (default: 3)
+//! - `GVT_READY_QUEUE=<kind>` - `priority` (default here) or `simple`
+//!
+//! All GVThreads are spawned from one parent GVThread, HIGH last. With
+//! the priority queue and `GVT_WORKERS=1 GVT_LOW_WORKERS=0` they are all
+//! queued before any runs, so the HIGH one starts first (position 0).
 //!
 //! Logging:
 //! - `GVT_LOG_LEVEL=<level>` - Log level: off, error, warn, info, debug, trace (default: info)
@@ -16,7 +21,7 @@
 //! - `GVT_FLUSH_EPRINT=1` - Flush output immediately (for crash debugging)
 //! - `GVT_DEBUG=1` - Enable scheduler debug logging
 
-use gvthread::{Runtime, spawn, spawn_with_priority, yield_now, Priority, SchedulerConfig};
+use gvthread::{Runtime, spawn, spawn_with_priority, yield_now, Priority, ReadyQueueKind, SchedulerConfig};
 use gvthread::{kinfo, kdebug, env_get, env_get_bool};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
@@ -30,17 +35,20 @@ fn main() {
     let num_gvthreads: usize = env_get("GVT_GVTHREADS", 3);
     let num_yields: usize = env_get("GVT_YIELDS", 3);
     let debug_logging: bool = env_get_bool("GVT_DEBUG", true);
+    let ready_queue: ReadyQueueKind = env_get("GVT_READY_QUEUE", ReadyQueueKind::Priority);
     
     println!("Configuration:");
     println!("  Workers: {} (low-priority: {})", num_workers, num_low_workers);
     println!("  GVThreads: {}, yields per thread: {}", num_gvthreads, num_yields);
     println!("  Debug logging: {}", debug_logging);
+    println!("  Ready queue: {:?}", ready_queue);
     println!();
     
     let config = SchedulerConfig::default()
         .num_workers(num_workers)
         .num_low_priority_workers(num_low_workers)
-        .debug_logging(debug_logging);
+        .debug_logging(debug_logging)
+        .ready_queue(ready_queue);
     
     let mut runtime = Runtime::new(config);
     
@@ -48,53 +56,53 @@ fn main() {
     let total_expected = num_gvthreads + 1; // +1 for HIGH priority
     let completed = Arc::new(AtomicUsize::new(0));
     
-    runtime.block_on(|| {
+    // Returns once every spawned GVThread has finished
+    runtime.block_on_until_idle(|| {
         kinfo!("Spawning {} normal + 1 HIGH priority GVThreads", num_gvthreads);
         
-        // Spawn normal priority GVThreads
-        for i in 1..=num_gvthreads {
-            let c = completed.clone();
-            let yields = num_yields;
-            let id = spawn(move |_token| {
-                kdebug!("GVThread {} started", i);
-                
-                for j in 0..yields {
-                    kdebug!("GVThread {} iteration {}", i, j);
-                    yield_now();
-                }
-                
-                kdebug!("GVThread {} finished", i);
-                c.fetch_add(1, Ordering::SeqCst);
-            });
-            println!("Spawned normal GVThread {} (ID={})", i, id);
-        }
+        // Order in which GVThreads start running
+        let started = Arc::new(AtomicUsize::new(0));
         
-        // Spawn a HIGH priority GVThread
+        // Spawn everything from one GVThread, so (with a single worker)
+        // all are queued before any runs
         let c = completed.clone();
-        let high_id = spawn_with_priority(move |_token| {
-            kdebug!("HIGH priority started");
-            yield_now();
-            kdebug!("HIGH priority finished");
-            c.fetch_add(1, Ordering::SeqCst);
-        }, Priority::High);
-        println!("Spawned HIGH priority GVThread (ID={})", high_id);
-        
-        // Wait for all to complete
-        println!("\nWaiting for {} GVThreads to complete...\n", total_expected);
-        let start = std::time::Instant::now();
-        let timeout = std::time::Duration::from_secs(10);
-        
-        while completed.load(Ordering::SeqCst) < total_expected {
-            if start.elapsed() > timeout {
-                println!("WARNING: Timeout!");
-                break;
+        spawn(move |_token| {
+            // Spawn normal priority GVThreads
+            for i in 1..=num_gvthreads {
+                let c = c.clone();
+                let started = started.clone();
+                let yields = num_yields;
+                let id = spawn(move |_token| {
+                    let pos = started.fetch_add(1, Ordering::SeqCst);
+                    kdebug!("GVThread {} started (position {})", i, pos);
+                    
+                    for j in 0..yields {
+                        kdebug!("GVThread {} iteration {}", i, j);
+                        yield_now();
+                    }
+                    
+                    kdebug!("GVThread {} finished", i);
+                    c.fetch_add(1, Ordering::SeqCst);
+                });
+                println!("Spawned normal GVThread {} (ID={})", i, id);
             }
-            std::thread::sleep(std::time::Duration::from_millis(50));
-        }
+            
+            // Spawn a HIGH priority GVThread last
+            let high_id = spawn_with_priority(move |_token| {
+                let pos = started.fetch_add(1, Ordering::SeqCst);
+                kinfo!("HIGH priority started (position {})", pos);
+                yield_now();
+                kdebug!("HIGH priority finished");
+                c.fetch_add(1, Ordering::SeqCst);
+            }, Priority::High);
+            println!("Spawned HIGH priority GVThread (ID={})", high_id);
+        });
         
-        let count = completed.load(Ordering::SeqCst);
-        kinfo!("{}/{} GVThread(s) completed", count, total_expected);
+        println!("\nWaiting for {} GVThreads to complete...\n", total_expected);
     });
     
+    let count = completed.load(Ordering::SeqCst);
+    kinfo!("{}/{} GVThread(s) completed", count, total_expected);
+    
     println!("\n=== Example Complete ===");
 }
\ No newline at end of file
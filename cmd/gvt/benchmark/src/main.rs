@@ -2,14 +2,22 @@
 //!
 //! Measures various performance metrics.
 
-use gvthread::{Runtime, spawn, yield_now, channel, SchedulerConfig};
-use std::time::Instant;
+use gvthread::{Runtime, env_get, spawn, yield_now, yield_to, channel, current_id, sleep_us, GVThreadId, SchedulerConfig};
+use gvthread_core::SpinLock;
+use std::cell::UnsafeCell;
+use std::collections::BinaryHeap;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 fn main() {
     println!("=== GVThread Benchmarks ===\n");
     
     let config = SchedulerConfig::default()
-        .num_workers(4);
+        .num_workers(env_get("GVT_NUM_WORKERS", 4));
+    
+    bench_lock_contention("pure spin", &PureSpinLock::new(BinaryHeap::new()));
+    bench_lock_contention("SpinLock", &SpinLock::new(BinaryHeap::new()));
     
     let mut runtime = Runtime::new(config);
     
@@ -17,6 +25,9 @@ fn main() {
         bench_spawn();
         bench_yield();
         bench_channel();
+        bench_sleep_queue();
+        bench_ping_pong("yield_now", false);
+        bench_ping_pong("yield_to", true);
     });
     
     println!("\n=== Benchmarks Complete ===");
@@ -80,3 +91,169 @@ fn bench_channel() {
     println!("  Per op:      {:.1} ns", per_op);
     println!("  Rate:        {:.0} ops/sec\n", (iterations * 2) as f64 / elapsed.as_secs_f64());
 }
+
+/// Two GVThreads taking turns on a shared counter
+///
+/// Each hands control to the other whenever it is not its turn, either
+/// through the scheduler (`yield_now`) or directly (`yield_to`). The
+/// direct handoff only applies while both share a worker; run with
+/// GVT_NUM_WORKERS=1 for the pure switch cost.
+fn bench_ping_pong(name: &str, direct: bool) {
+    println!("Benchmark: Ping-pong ({})", name);
+    println!("{}", "─".repeat(40));
+    
+    let rounds: u64 = 100_000;
+    let turn = Arc::new(AtomicU64::new(0));
+    let ids = Arc::new([AtomicU32::new(u32::MAX), AtomicU32::new(u32::MAX)]);
+    let done = Arc::new(AtomicU64::new(0));
+    
+    let start = Instant::now();
+    for me in 0..2u64 {
+        let (turn, ids, done) = (turn.clone(), ids.clone(), done.clone());
+        spawn(move |_| {
+            ids[me as usize].store(current_id().as_u32(), Ordering::Release);
+            loop {
+                let t = turn.load(Ordering::Acquire);
+                if t >= rounds * 2 {
+                    break;
+                }
+                if t % 2 == me {
+                    turn.store(t + 1, Ordering::Release);
+                }
+                let other = ids[1 - me as usize].load(Ordering::Acquire);
+                if direct && other != u32::MAX {
+                    yield_to(GVThreadId::new(other));
+                } else {
+                    yield_now();
+                }
+            }
+            done.fetch_add(1, Ordering::AcqRel);
+        });
+    }
+    while done.load(Ordering::Acquire) < 2 {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    let elapsed = start.elapsed();
+    
+    let per_switch = elapsed.as_nanos() as f64 / (rounds * 2) as f64;
+    println!("  Round trips: {}", rounds);
+    println!("  Total time:  {:?}", elapsed);
+    println!("  Per switch:  {:.1} ns\n", per_switch);
+}
+
+/// User + system CPU time of the whole process
+fn cpu_time() -> Duration {
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) };
+    let tv = |t: libc::timeval| Duration::new(t.tv_sec as u64, t.tv_usec as u32 * 1000);
+    tv(usage.ru_utime) + tv(usage.ru_stime)
+}
+
+/// A heap behind a lock, as the timer's sleep queue is
+trait HeapLock: Sync {
+    fn with(&self, f: &mut dyn FnMut(&mut BinaryHeap<u64>));
+}
+
+impl HeapLock for SpinLock<BinaryHeap<u64>> {
+    fn with(&self, f: &mut dyn FnMut(&mut BinaryHeap<u64>)) {
+        f(&mut self.lock());
+    }
+}
+
+/// Baseline: spins until the lock is free, never ceding the core
+struct PureSpinLock {
+    locked: AtomicBool,
+    heap: UnsafeCell<BinaryHeap<u64>>,
+}
+
+unsafe impl Sync for PureSpinLock {}
+
+impl PureSpinLock {
+    fn new(heap: BinaryHeap<u64>) -> Self {
+        Self { locked: AtomicBool::new(false), heap: UnsafeCell::new(heap) }
+    }
+}
+
+impl HeapLock for PureSpinLock {
+    fn with(&self, f: &mut dyn FnMut(&mut BinaryHeap<u64>)) {
+        while self.locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            std::hint::spin_loop();
+        }
+        f(unsafe { &mut *self.heap.get() });
+        self.locked.store(false, Ordering::Release);
+    }
+}
+
+/// OS threads (more than CPUs) pushing and popping a shared heap
+///
+/// Oversubscribed, a waiter that only spins burns its whole time slice
+/// while the holder sits preempted; CPU time per op shows the waste.
+fn bench_lock_contention(name: &str, lock: &dyn HeapLock) {
+    println!("Benchmark: Lock contention ({})", name);
+    println!("{}", "─".repeat(40));
+    
+    let cpus = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+    let threads = cpus * 2 + 2;
+    let per_thread: u64 = 200_000;
+    
+    let cpu_start = cpu_time();
+    let start = Instant::now();
+    std::thread::scope(|s| {
+        for t in 0..threads as u64 {
+            s.spawn(move || {
+                for i in 0..per_thread {
+                    lock.with(&mut |heap| {
+                        heap.push(i * 31 + t);
+                        if heap.len() > 1024 {
+                            heap.pop();
+                        }
+                    });
+                }
+            });
+        }
+    });
+    let elapsed = start.elapsed();
+    let cpu = cpu_time() - cpu_start;
+    
+    let ops = threads as u64 * per_thread;
+    println!("  Threads:     {} ({} CPUs)", threads, cpus);
+    println!("  Total time:  {:?}", elapsed);
+    println!("  CPU time:    {:?}", cpu);
+    println!("  CPU per op:  {:.1} ns\n", cpu.as_nanos() as f64 / ops as f64);
+}
+
+/// Many GVThreads sleeping briefly in a loop: every sleep and wake goes
+/// through the timer's `SpinLock`-guarded sleep queue
+fn bench_sleep_queue() {
+    println!("Benchmark: Sleep queue traffic");
+    println!("{}", "─".repeat(40));
+    
+    let sleepers = 256;
+    let rounds = 100;
+    let done = Arc::new(AtomicU64::new(0));
+    
+    let cpu_start = cpu_time();
+    let start = Instant::now();
+    for _ in 0..sleepers {
+        let done = done.clone();
+        spawn(move |_| {
+            for _ in 0..rounds {
+                sleep_us(20);
+            }
+            done.fetch_add(1, Ordering::AcqRel);
+        });
+    }
+    while done.load(Ordering::Acquire) < sleepers {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    let elapsed = start.elapsed();
+    let cpu = cpu_time() - cpu_start;
+    
+    println!("  Sleeps:      {} x {}", sleepers, rounds);
+    println!("  Total time:  {:?}", elapsed);
+    println!("  CPU time:    {:?}", cpu);
+    println!("  CPU / wall:  {:.2}\n", cpu.as_secs_f64() / elapsed.as_secs_f64());
+}
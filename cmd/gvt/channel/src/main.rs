@@ -13,7 +13,8 @@ fn main() {
     
     let mut runtime = Runtime::new(config);
     
-    runtime.block_on(|| {
+    // Returns once both GVThreads have finished
+    runtime.block_on_until_idle(|| {
         // Create a bounded channel
         let (tx, rx) = channel::<i32>(10);
         
@@ -41,19 +42,11 @@ fn main() {
             // Give producer time to send
             std::thread::sleep(std::time::Duration::from_millis(10));
             
-            loop {
-                match rx.try_recv() {
-                    Ok(val) => println!("[Consumer] Received: {}", val),
-                    Err(_) => {
-                        println!("[Consumer] Channel empty, done!");
-                        break;
-                    }
-                }
+            for val in rx.try_iter() {
+                println!("[Consumer] Received: {}", val);
             }
+            println!("[Consumer] Channel empty, done!");
         });
-        
-        // Give time for GVThreads to run
-        std::thread::sleep(std::time::Duration::from_millis(100));
     });
     
     println!("\n=== Example Complete ===");
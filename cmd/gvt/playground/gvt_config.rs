@@ -33,9 +33,16 @@ pub const TIMER_INTERVAL_MS: u64 = 1;
 // Maximum timer thread sleep duration (ms)
 pub const TIMER_MAX_SLEEP_MS: u64 = 10;
 
+// Minimum timer thread sleep duration (us): the granularity of sleep()
+pub const TIMER_MIN_SLEEP_US: u64 = 100;
+
 // Enable SIGURG-based forced preemption
 pub const ENABLE_FORCED_PREEMPT: bool = true;
 
+// Let SIGURG switch a CPU-bound GVThread out, not just flag it (unsafe
+// around println! and statically linked allocators)
+pub const FORCED_SWITCH: bool = false;
+
 // Enable debug logging
 pub const DEBUG_LOGGING: bool = false;
 
@@ -52,4 +59,13 @@ pub const GLOBAL_QUEUE_CAPACITY: usize = 65536;
 pub const IDLE_SPINS: u32 = 10;
 
 // Worker park timeout (ms)
-pub const PARK_TIMEOUT_MS: u64 = 100;
\ No newline at end of file
+pub const PARK_TIMEOUT_MS: u64 = 100;
+
+// Pin worker i to CPU i (modulo the CPU count)
+pub const PIN_WORKERS: bool = false;
+
+// Release finished stacks with MADV_FREE (RSS drops only under pressure)
+pub const LAZY_STACK_FREE: bool = false;
+
+// Restart a worker whose scheduler loop panics (re-queues its GVThread)
+pub const SUPERVISE_WORKERS: bool = false;
@@ -47,12 +47,14 @@ fn main() {
     
     let mut runtime = Runtime::new(config);
     
-    runtime.block_on(|| {
-        let spawned = Arc::new(AtomicUsize::new(0));
-        let started = Arc::new(AtomicUsize::new(0));
-        let completed = Arc::new(AtomicUsize::new(0));
-        let total_yields = Arc::new(AtomicUsize::new(0));
-        
+    let spawned = Arc::new(AtomicUsize::new(0));
+    let started = Arc::new(AtomicUsize::new(0));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let total_yields = Arc::new(AtomicUsize::new(0));
+    let start = std::time::Instant::now();
+    
+    // Returns once every GVThread has finished (no timeout - Ctrl-C to abort)
+    runtime.block_on_until_idle(|| {
         kinfo!("Spawning {} GVThreads, each yielding {} times", 
                total_gvthreads, yields_per_gvthread);
         
@@ -91,49 +93,32 @@ fn main() {
         let spawned_count = spawned.load(Ordering::SeqCst);
         kinfo!("Spawned {} GVThreads (IDs 0..{})", spawned_count, 
                spawn_ids.last().map(|id| id.as_u32()).unwrap_or(0));
-        
-        // Wait for completion (no timeout - Ctrl-C to abort)
-        let start = std::time::Instant::now();
-        let mut last_print = std::time::Instant::now();
-        
-        while completed.load(Ordering::SeqCst) < total_gvthreads {
-            std::thread::sleep(std::time::Duration::from_millis(100));
-            
-            // Progress indicator every 5 seconds
-            if last_print.elapsed() >= std::time::Duration::from_secs(5) {
-                let c = completed.load(Ordering::SeqCst);
-                let y = total_yields.load(Ordering::SeqCst);
-                eprintln!("[PROGRESS] completed={}/{}, yields={}/{}", 
-                         c, total_gvthreads, y, total_gvthreads * yields_per_gvthread);
-                last_print = std::time::Instant::now();
-            }
+    });
+    
+    let sp = spawned.load(Ordering::SeqCst);
+    let st = started.load(Ordering::SeqCst);
+    let c = completed.load(Ordering::SeqCst);
+    let y = total_yields.load(Ordering::SeqCst);
+    let expected_yields = total_gvthreads * yields_per_gvthread;
+    
+    println!("\n=== Results ===");
+    println!("Spawned:   {}/{}", sp, total_gvthreads);
+    println!("Started:   {}/{}", st, total_gvthreads);
+    println!("Completed: {}/{}", c, total_gvthreads);
+    println!("Yields:    {} (expected: {})", y, expected_yields);
+    println!("Time:      {:?}", start.elapsed());
+    
+    if c == total_gvthreads && y == expected_yields {
+        println!("\n*** SUCCESS ***");
+    } else {
+        println!("\n*** FAILURE ***");
+        if st < sp {
+            println!("  -> {} GVThreads never started!", sp - st);
         }
-        
-        let sp = spawned.load(Ordering::SeqCst);
-        let st = started.load(Ordering::SeqCst);
-        let c = completed.load(Ordering::SeqCst);
-        let y = total_yields.load(Ordering::SeqCst);
-        let expected_yields = total_gvthreads * yields_per_gvthread;
-        
-        println!("\n=== Results ===");
-        println!("Spawned:   {}/{}", sp, total_gvthreads);
-        println!("Started:   {}/{}", st, total_gvthreads);
-        println!("Completed: {}/{}", c, total_gvthreads);
-        println!("Yields:    {} (expected: {})", y, expected_yields);
-        println!("Time:      {:?}", start.elapsed());
-        
-        if c == total_gvthreads && y == expected_yields {
-            println!("\n*** SUCCESS ***");
-        } else {
-            println!("\n*** FAILURE ***");
-            if st < sp {
-                println!("  -> {} GVThreads never started!", sp - st);
-            }
-            if c < st {
-                println!("  -> {} GVThreads started but didn't complete!", st - c);
-            }
+        if c < st {
+            println!("  -> {} GVThreads started but didn't complete!", st - c);
         }
-    });
+    }
     
     println!("\n=== Done ===");
 }
\ No newline at end of file
@@ -1,11 +1,14 @@
 //! Stress test - many GVThreads
 //!
-//! Tests spawning and running large numbers of GVThreads.
+//! Tests spawning and running large numbers of GVThreads, and reports
+//! RSS before, during and after the run: finished GVThreads' stacks are
+//! released, so RSS should drop back close to where it started.
 
 use gvthread::{Runtime, spawn, yield_now, SchedulerConfig};
+use gvthread_runtime::memory::resident_kb;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 fn main() {
     println!("=== GVThread Stress Test ===\n");
@@ -24,10 +27,13 @@ fn main() {
     let mut runtime = Runtime::new(config);
     
     let completed = Arc::new(AtomicU64::new(0));
+    let rss_start = resident_kb();
+    let start = Instant::now();
+    let mut spawn_time = Duration::ZERO;
+    let mut rss_spawned = 0;
     
-    runtime.block_on(|| {
-        let start = Instant::now();
-        
+    // Returns once every GVThread has finished
+    runtime.block_on_until_idle(|| {
         // Spawn many GVThreads
         for i in 0..num_gvthreads {
             let completed = completed.clone();
@@ -47,42 +53,30 @@ fn main() {
             }
         }
         
-        let spawn_time = start.elapsed();
+        spawn_time = start.elapsed();
+        rss_spawned = resident_kb();
         println!("\n\nSpawn time: {:?}", spawn_time);
         println!("Spawn rate: {:.0} GVThreads/sec", 
             num_gvthreads as f64 / spawn_time.as_secs_f64());
         
-        // Wait for completion
         println!("\nWaiting for completion...");
-        let run_start = Instant::now();
-        
-        loop {
-            let done = completed.load(Ordering::Relaxed) as usize;
-            if done >= num_gvthreads {
-                break;
-            }
-            
-            if run_start.elapsed().as_secs() > 30 {
-                println!("Timeout! Only {}/{} completed", done, num_gvthreads);
-                break;
-            }
-            
-            print!("\rCompleted: {}/{}", done, num_gvthreads);
-            std::thread::sleep(std::time::Duration::from_millis(100));
-        }
-        
-        let total_time = start.elapsed();
-        let run_time = run_start.elapsed();
-        
-        println!("\n\n=== Results ===");
-        println!("Total GVThreads: {}", num_gvthreads);
-        println!("Completed:       {}", completed.load(Ordering::Relaxed));
-        println!("Spawn time:      {:?}", spawn_time);
-        println!("Run time:        {:?}", run_time);
-        println!("Total time:      {:?}", total_time);
-        println!("Throughput:      {:.0} GVThreads/sec", 
-            num_gvthreads as f64 / total_time.as_secs_f64());
     });
     
+    let total_time = start.elapsed();
+    let run_time = total_time - spawn_time;
+    let rss_end = resident_kb();
+    
+    println!("\n=== Results ===");
+    println!("Total GVThreads: {}", num_gvthreads);
+    println!("Completed:       {}", completed.load(Ordering::Relaxed));
+    println!("Spawn time:      {:?}", spawn_time);
+    println!("Run time:        {:?}", run_time);
+    println!("Total time:      {:?}", total_time);
+    println!("Throughput:      {:.0} GVThreads/sec", 
+        num_gvthreads as f64 / total_time.as_secs_f64());
+    println!("RSS start:       {} KB", rss_start);
+    println!("RSS spawned:     {} KB", rss_spawned);
+    println!("RSS end:         {} KB", rss_end);
+    
     println!("\n=== Stress Test Complete ===");
 }
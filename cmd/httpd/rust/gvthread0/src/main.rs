@@ -19,34 +19,50 @@
 //!
 //! ## Usage
 //!
-//!     cargo run -p gvthread-httpd --release -- [--port 8080] [--workers 4]
+//!     cargo run -p gvthread-httpd --release -- [--port 8080] [--workers 4] [--idle-timeout 60000]
+//!
+//! A keep-alive connection idle for `--idle-timeout` ms (env
+//! `gvt_app_idle_ms`, default 60000, 0 = never) is closed, freeing its
+//! GVThread and slot even if the client never hangs up.
 //!
 //! ## Benchmark
 //!
 //!     wrk -t4 -c100 -d10s http://127.0.0.1:8080/
 
-use gvthread::{Runtime, SchedulerConfig, spawn, Priority};
+use gvthread::{Runtime, SchedulerConfig, spawn, try_spawn, Timeout};
+use gerror::codes::{ERR_EAGAIN, ERR_EINTR, SYS_LINUX, UC_ACCEPT};
+use gerror::{match_error, GError};
 use ksvc_gvthread::{Reactor, ReactorConfig, GvtListener, GvtStream};
 use ksvc_gvthread::reactor::ReactorShared;
 
 use std::env;
+use std::io::IoSlice;
+use std::time::Duration;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 
 // ── Configuration ──
 
 const RECV_BUF_SIZE: usize = 4096;
+const DEFAULT_IDLE_TIMEOUT_MS: u64 = 60_000;
 
 static RUNNING: AtomicBool = AtomicBool::new(true);
 static TOTAL_REQUESTS: AtomicU64 = AtomicU64::new(0);
 static TOTAL_CONNECTIONS: AtomicU64 = AtomicU64::new(0);
 static ACTIVE_CONNECTIONS: AtomicU64 = AtomicU64::new(0);
+/// Accept loop passes skipped because all GVThread slots were in use
+static THROTTLED: AtomicU64 = AtomicU64::new(0);
+/// Connections closed for being idle longer than `IDLE_TIMEOUT_MS`
+static IDLE_CLOSED: AtomicU64 = AtomicU64::new(0);
+/// Keep-alive idle timeout in ms (0 = never); set once in `main`
+static IDLE_TIMEOUT_MS: AtomicU64 = AtomicU64::new(DEFAULT_IDLE_TIMEOUT_MS);
 
 // ── HTTP response ──
 
 const HELLO_BODY: &[u8] = b"Hello from GVThread!\n";
 
-fn make_hello_response() -> Vec<u8> {
+/// Response header; the body goes out as a second `writev` slice
+fn make_hello_header() -> Vec<u8> {
     format!(
         "HTTP/1.1 200 OK\r\n\
          Content-Type: text/plain\r\n\
@@ -57,9 +73,6 @@ fn make_hello_response() -> Vec<u8> {
         HELLO_BODY.len()
     )
     .into_bytes()
-    .into_iter()
-    .chain(HELLO_BODY.iter().copied())
-    .collect()
 }
 
 // ── HTTP parsing ──
@@ -81,9 +94,14 @@ fn has_complete_request(buf: &[u8], len: usize) -> bool {
 /// This is the beauty of the green thread model: straightforward
 /// sequential code, no callbacks, no async/await, no state machines.
 fn handle_connection(stream: GvtStream) {
-    let response = make_hello_response();
+    let header = make_hello_header();
+    let response = [IoSlice::new(&header), IoSlice::new(HELLO_BODY)];
     let mut buf = [0u8; RECV_BUF_SIZE];
     let mut recv_len: usize = 0;
+    let idle_timeout = match IDLE_TIMEOUT_MS.load(Ordering::Relaxed) {
+        0 => None,
+        ms => Some(Duration::from_millis(ms)),
+    };
 
     ACTIVE_CONNECTIONS.fetch_add(1, Ordering::Relaxed);
 
@@ -93,8 +111,17 @@ fn handle_connection(stream: GvtStream) {
             break;
         }
 
-        // Read data
-        let n = stream.read(&mut buf[recv_len..]);
+        // Read data; give up on a client that goes quiet
+        let n = match idle_timeout {
+            Some(timeout) => match stream.read_timeout(&mut buf[recv_len..], timeout) {
+                Ok(n) => n,
+                Err(Timeout) => {
+                    IDLE_CLOSED.fetch_add(1, Ordering::Relaxed);
+                    break;
+                }
+            },
+            None => stream.read(&mut buf[recv_len..]),
+        };
         if n <= 0 {
             // EOF or error — client disconnected
             break;
@@ -113,7 +140,7 @@ fn handle_connection(stream: GvtStream) {
         // Got a complete request — send response
         TOTAL_REQUESTS.fetch_add(1, Ordering::Relaxed);
 
-        let sent = stream.write_all(&response);
+        let sent = stream.write_all_vectored(&response);
         if sent < 0 {
             break;
         }
@@ -123,13 +150,18 @@ fn handle_connection(stream: GvtStream) {
     }
 
     ACTIVE_CONNECTIONS.fetch_sub(1, Ordering::Relaxed);
-    // GvtStream::drop() closes the fd
+    // The pending read is done (or cancelled by its timeout): close
+    // through the reactor
+    stream.close();
 }
 
 // ── Accept loop ──
 
 /// The accept loop runs as a GVThread. It blocks on accept() (via io_uring)
 /// and spawns a new GVThread for each incoming connection.
+///
+/// Backpressure: while every GVThread slot is taken it stops accepting,
+/// leaving new connections queued in the kernel's listen backlog.
 fn accept_loop(listener: GvtListener, shared: Arc<ReactorShared>) {
     eprintln!("gvthread-httpd: accept loop running (GVThread)");
 
@@ -138,20 +170,36 @@ fn accept_loop(listener: GvtListener, shared: Arc<ReactorShared>) {
             break;
         }
 
+        let (in_use, max) = gvthread::slot_usage();
+        if in_use >= max {
+            THROTTLED.fetch_add(1, Ordering::Relaxed);
+            gvthread::sleep_ms(1);
+            continue;
+        }
+
         match listener.accept() {
             Ok(stream) => {
                 TOTAL_CONNECTIONS.fetch_add(1, Ordering::Relaxed);
                 // Spawn a GVThread for this connection — just like Go!
-                spawn(move |_token| {
-                    handle_connection(stream);
-                });
+                if let Err(e) = try_spawn(move |_token| handle_connection(stream)) {
+                    // Lost a race for the last slot; the stream was dropped (closed)
+                    eprintln!("gvthread-httpd: connection dropped: {}", e);
+                }
             }
             Err(e) => {
-                if e == -(libc::EAGAIN as i64) || e == -(libc::EINTR as i64) {
+                let Some(err) = GError::from_cqe(e, SYS_LINUX, UC_ACCEPT) else {
+                    continue;
+                };
+                let retry = match_error!(err, {
+                    (SYS_LINUX, ERR_EAGAIN, _) => true,
+                    (SYS_LINUX, ERR_EINTR, _) => true,
+                    (_, _, _) => false,
+                });
+                if retry {
                     gvthread::yield_now();
                     continue;
                 }
-                eprintln!("gvthread-httpd: accept error: {}", e);
+                eprintln!("gvthread-httpd: accept error: {}", err);
                 if !RUNNING.load(Ordering::Relaxed) {
                     break;
                 }
@@ -178,10 +226,12 @@ fn stats_loop() {
         let rps = delta as f64 / 5.0;
         let active = ACTIVE_CONNECTIONS.load(Ordering::Relaxed);
         let total_conns = TOTAL_CONNECTIONS.load(Ordering::Relaxed);
+        let throttled = THROTTLED.load(Ordering::Relaxed);
+        let idle_closed = IDLE_CLOSED.load(Ordering::Relaxed);
 
         eprintln!(
-            "[{:.1}s] active={} total_conns={} reqs={} rps={:.0}",
-            elapsed, active, total_conns, total_reqs, rps,
+            "[{:.1}s] active={} total_conns={} reqs={} rps={:.0} throttled={} idle_closed={}",
+            elapsed, active, total_conns, total_reqs, rps, throttled, idle_closed,
         );
         last_reqs = total_reqs;
     }
@@ -198,6 +248,9 @@ fn main() {
     let mut num_workers: usize = 4;
     let mut max_gvthreads: usize = 100_000;
     let mut sq_entries: u32 = 1024;
+    let mut idle_timeout_ms: u64 = env::var("gvt_app_idle_ms").ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_IDLE_TIMEOUT_MS);
 
     eprintln!("~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~ appPort-1={}", port);
     let mut i = 1;
@@ -219,6 +272,10 @@ fn main() {
                 i += 1;
                 sq_entries = args.get(i).and_then(|s| s.parse().ok()).unwrap_or(1024);
             }
+            "--idle-timeout" => {
+                i += 1;
+                idle_timeout_ms = args.get(i).and_then(|s| s.parse().ok()).unwrap_or(DEFAULT_IDLE_TIMEOUT_MS);
+            }
             s if s.parse::<u16>().is_ok() => {
                 port = s.parse().unwrap();
             }
@@ -233,8 +290,10 @@ fn main() {
         libc::signal(libc::SIGPIPE, libc::SIG_IGN);
     }
 
-    eprintln!("gvthread-httpd: port={} workers={} max_gvt={} sq={}",
-        port, num_workers, max_gvthreads, sq_entries);
+    IDLE_TIMEOUT_MS.store(idle_timeout_ms, Ordering::Relaxed);
+
+    eprintln!("gvthread-httpd: port={} workers={} max_gvt={} sq={} idle_timeout={}ms",
+        port, num_workers, max_gvthreads, sq_entries, idle_timeout_ms);
     eprintln!("gvthread-httpd: model = one GVThread per connection + io_uring reactor");
 
     // ── 1. Start GVThread runtime ──
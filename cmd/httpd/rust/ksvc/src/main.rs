@@ -14,7 +14,7 @@
 //!     wrk -t4 -c100 -d10s http://127.0.0.1:8080/
 //!     ab -n 100000 -c 100 -k http://127.0.0.1:8080/
 
-use ksvc_core::entry::{CorrId, SubmitEntry};
+use ksvc_core::entry::{ConnTag, CorrId, SubmitEntry};
 use ksvc_core::io_backend::{IoBackend, IoCompletion};
 use ksvc_core::router::SyscallRouter;
 
@@ -35,19 +35,15 @@ const NR_RECVFROM: u32 = 45;
 
 // ── Op + state encoded in corr_id ──
 // Layout: [op:8][state:8][file_idx:16][conn_idx:32]
-const OP_ACCEPT: u64 = 1 << 56;
-const OP_RECV: u64 = 2 << 56;
-const OP_SEND: u64 = 3 << 56;
-const OP_CLOSE: u64 = 4 << 56;
-const OP_FILE_OPEN: u64 = 5 << 56;
-const OP_FILE_READ: u64 = 6 << 56;
-const OP_FILE_CLOSE: u64 = 7 << 56;
-const OP_MASK: u64 = 0xFF << 56;
-const IDX_MASK: u64 = 0xFFFFFFFF;
-
-fn make_id(op: u64, idx: usize) -> CorrId { CorrId(op | idx as u64) }
-fn decode_op(id: CorrId) -> u64 { id.0 & OP_MASK }
-fn decode_idx(id: CorrId) -> usize { (id.0 & IDX_MASK) as usize }
+const OP_ACCEPT: u8 = 1;
+const OP_RECV: u8 = 2;
+const OP_SEND: u8 = 3;
+const OP_CLOSE: u8 = 4;
+const OP_FILE_OPEN: u8 = 5;
+const OP_FILE_READ: u8 = 6;
+const OP_FILE_CLOSE: u8 = 7;
+
+fn make_id(op: u8, idx: usize) -> CorrId { CorrId::with_op(op, ConnTag::conn(idx as u32).pack()) }
 
 // ── Buffers ──
 const RECV_BUF: usize = 4096;
@@ -283,7 +279,7 @@ fn setup_listener(port: u16) -> i32 {
 fn submit_accept(io: &mut BasicIoUring, r: &ProbeRouter, listener: i32,
     addr: &mut libc::sockaddr_in, addr_len: &mut libc::socklen_t) {
     let route = r.route(NR_ACCEPT4);
-    let _ = io.submit_with_opcode(&SubmitEntry {
+    let _ = io.submit(&SubmitEntry {
         corr_id: make_id(OP_ACCEPT, 0), syscall_nr: NR_ACCEPT4, flags: 0,
         args: [listener as u64, addr as *mut _ as u64, addr_len as *mut _ as u64,
                libc::SOCK_CLOEXEC as u64, 0, 0],
@@ -293,7 +289,7 @@ fn submit_accept(io: &mut BasicIoUring, r: &ProbeRouter, listener: i32,
 fn submit_recv(io: &mut BasicIoUring, r: &ProbeRouter, conn: &mut Conn, idx: usize) {
     let route = r.route(NR_RECVFROM);
     let offset = conn.recv_len;
-    let _ = io.submit_with_opcode(&SubmitEntry {
+    let _ = io.submit(&SubmitEntry {
         corr_id: make_id(OP_RECV, idx), syscall_nr: NR_RECVFROM, flags: 0,
         args: [conn.fd as u64,
                conn.recv_buf[offset..].as_mut_ptr() as u64,
@@ -305,7 +301,7 @@ fn submit_recv(io: &mut BasicIoUring, r: &ProbeRouter, conn: &mut Conn, idx: usi
 fn submit_send(io: &mut BasicIoUring, r: &ProbeRouter, conn: &Conn, idx: usize) {
     let route = r.route(NR_SENDTO);
     let remaining = conn.send_buf.len() - conn.send_off;
-    let _ = io.submit_with_opcode(&SubmitEntry {
+    let _ = io.submit(&SubmitEntry {
         corr_id: make_id(OP_SEND, idx), syscall_nr: NR_SENDTO, flags: 0,
         args: [conn.fd as u64,
                conn.send_buf[conn.send_off..].as_ptr() as u64,
@@ -316,7 +312,7 @@ fn submit_send(io: &mut BasicIoUring, r: &ProbeRouter, conn: &Conn, idx: usize)
 
 fn submit_close(io: &mut BasicIoUring, r: &ProbeRouter, fd: i32, idx: usize) {
     let route = r.route(NR_CLOSE);
-    let _ = io.submit_with_opcode(&SubmitEntry {
+    let _ = io.submit(&SubmitEntry {
         corr_id: make_id(OP_CLOSE, idx), syscall_nr: NR_CLOSE, flags: 0,
         args: [fd as u64, 0, 0, 0, 0, 0],
     }, route.iouring_opcode);
@@ -324,7 +320,7 @@ fn submit_close(io: &mut BasicIoUring, r: &ProbeRouter, fd: i32, idx: usize) {
 
 fn submit_file_open(io: &mut BasicIoUring, r: &ProbeRouter, conn: &Conn, idx: usize) {
     let route = r.route(NR_OPENAT);
-    let _ = io.submit_with_opcode(&SubmitEntry {
+    let _ = io.submit(&SubmitEntry {
         corr_id: make_id(OP_FILE_OPEN, idx), syscall_nr: NR_OPENAT, flags: 0,
         args: [libc::AT_FDCWD as u64,
                conn.file_path.as_ptr() as u64,
@@ -335,7 +331,7 @@ fn submit_file_open(io: &mut BasicIoUring, r: &ProbeRouter, conn: &Conn, idx: us
 
 fn submit_file_read(io: &mut BasicIoUring, r: &ProbeRouter, conn: &mut Conn, idx: usize) {
     let route = r.route(NR_READ);
-    let _ = io.submit_with_opcode(&SubmitEntry {
+    let _ = io.submit(&SubmitEntry {
         corr_id: make_id(OP_FILE_READ, idx), syscall_nr: NR_READ, flags: 0,
         args: [conn.file_fd as u64,
                conn.file_buf.as_mut_ptr() as u64,
@@ -346,7 +342,7 @@ fn submit_file_read(io: &mut BasicIoUring, r: &ProbeRouter, conn: &mut Conn, idx
 
 fn submit_file_close(io: &mut BasicIoUring, r: &ProbeRouter, fd: i32, idx: usize) {
     let route = r.route(NR_CLOSE);
-    let _ = io.submit_with_opcode(&SubmitEntry {
+    let _ = io.submit(&SubmitEntry {
         corr_id: make_id(OP_FILE_CLOSE, idx), syscall_nr: NR_CLOSE, flags: 0,
         args: [fd as u64, 0, 0, 0, 0, 0],
     }, route.iouring_opcode);
@@ -371,8 +367,7 @@ fn worker_loop(wid: usize, num_workers: usize, port: u16, max_conns: usize,
         ..Default::default()
     }).expect("io_uring setup failed");
 
-    let supported = io.probe_opcodes_static();
-    let router = ProbeRouter::new(&supported);
+    let router = ProbeRouter::from_capabilities(io.capabilities());
 
     if wid == 0 {
         let counts = router.tier_counts();
@@ -406,8 +401,8 @@ fn worker_loop(wid: usize, num_workers: usize, port: u16, max_conns: usize,
 
         for ci in 0..n {
             let cqe = comp_buf[ci];
-            let op = decode_op(cqe.corr_id);
-            let idx = decode_idx(cqe.corr_id);
+            let op = cqe.corr_id.op();
+            let idx = ConnTag::unpack(cqe.corr_id.payload()).conn_idx as usize;
             let result = cqe.result;
 
             match op {
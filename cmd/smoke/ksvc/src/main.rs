@@ -95,7 +95,7 @@ fn submit_and_wait(
     entry: &SubmitEntry,
     opcode: u8,
 ) -> Option<IoCompletion> {
-    if io.submit_with_opcode(entry, opcode).is_err() {
+    if io.submit(entry, opcode).is_err() {
         return None;
     }
     if io.flush().is_err() {
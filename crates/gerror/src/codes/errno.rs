@@ -34,6 +34,8 @@ pub const ERR_EINVAL:          GlobalId = GlobalId::new("EINVAL", 2022);
 pub const ERR_EPIPE:           GlobalId = GlobalId::new("EPIPE", 2032);
 pub const ERR_EFBIG:           GlobalId = GlobalId::new("EFBIG", 2027);
 pub const ERR_ENOSPC:          GlobalId = GlobalId::new("ENOSPC", 2028);
+pub const ERR_ETIME:           GlobalId = GlobalId::new("ETIME", 2062);   // io_uring timeouts
+pub const ERR_ECANCELED:       GlobalId = GlobalId::new("ECANCELED", 2125);
 
 // ── Networking ────────────────────────────────────────────────────
 
@@ -91,6 +93,7 @@ pub fn errno_to_global_id(errno: i32) -> GlobalId {
         27  => ERR_EFBIG,
         28  => ERR_ENOSPC,
         32  => ERR_EPIPE,
+        62  => ERR_ETIME,
         98  => ERR_EADDRINUSE,
         99  => ERR_EADDRNOTAVAIL,
         100 => ERR_ENETDOWN,
@@ -105,6 +108,7 @@ pub fn errno_to_global_id(errno: i32) -> GlobalId {
         113 => ERR_EHOSTUNREACH,
         114 => ERR_EALREADY,
         115 => ERR_EINPROGRESS,
+        125 => ERR_ECANCELED,
         _   => GlobalId::new("errno", 2000 + errno as u64),
     }
 }
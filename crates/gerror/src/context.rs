@@ -36,8 +36,9 @@ pub struct ErrorContext {
     pub source:     Option<Box<dyn Error + Send + Sync>>,
 
     // ── Backtrace ─────────────────────────────────────────────
+    /// Captured by `err!()`; symbols are resolved only when displayed.
     #[cfg(feature = "backtrace")]
-    pub backtrace:  Option<String>,
+    pub backtrace:  Option<std::backtrace::Backtrace>,
 }
 
 impl ErrorContext {
@@ -57,10 +58,12 @@ impl ErrorContext {
         self
     }
 
+    /// Capture a backtrace, subject to `RUST_BACKTRACE` /
+    /// `RUST_LIB_BACKTRACE` like `std::backtrace::Backtrace::capture`.
     #[cfg(feature = "backtrace")]
     pub fn capture_backtrace(&mut self) {
         if self.backtrace.is_none() {
-            self.backtrace = Some(std::backtrace::Backtrace::capture().to_string());
+            self.backtrace = Some(std::backtrace::Backtrace::capture());
         }
     }
 }
@@ -1,5 +1,7 @@
+use std::backtrace::Backtrace;
 use std::error::Error;
 use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::context::ErrorContext;
 use crate::GlobalId;
@@ -45,6 +47,16 @@ enum Repr {
     Full(Box<ErrorContext>),
 }
 
+static BACKTRACE_DISPLAY: AtomicBool = AtomicBool::new(false);
+
+/// Append captured backtraces to `Display` output of Full errors.
+///
+/// Off by default. Has no effect without the `backtrace` feature, or when
+/// the backtrace was disabled by `RUST_BACKTRACE` at capture time.
+pub fn set_backtrace_display(on: bool) {
+    BACKTRACE_DISPLAY.store(on, Ordering::Relaxed);
+}
+
 // ── Constructors ──────────────────────────────────────────────────
 
 impl GError {
@@ -95,6 +107,102 @@ impl GError {
         }
     }
 
+    /// Create a zero-allocation error for a failed syscall.
+    ///
+    /// `error_code` must be `errno`'s code (`codes::errno_to_global_id`):
+    /// `os_error()` reads the errno back from it, so Simple stays 32 bytes.
+    #[inline]
+    pub fn simple_os(
+        system: GlobalId,
+        error_code: GlobalId,
+        user_code: GlobalId,
+        errno: i32,
+    ) -> Self {
+        debug_assert_eq!(error_code, crate::codes::errno_to_global_id(errno));
+        Self::simple(system, error_code, user_code)
+    }
+
+    /// Decode an io_uring CQE result (or raw syscall return value).
+    ///
+    /// `None` for success (`result >= 0`), otherwise a `simple_os` error
+    /// for errno `-result`.
+    ///
+    /// ```
+    /// use gerror::codes::*;
+    /// use gerror::GError;
+    ///
+    /// assert!(GError::from_cqe(512, SYS_LINUX, UC_RECV).is_none());
+    /// let err = GError::from_cqe(-104, SYS_LINUX, UC_RECV).unwrap();
+    /// assert_eq!(err.error_code(), &ERR_ECONNRESET);
+    /// assert_eq!(err.os_error(), Some(104));
+    /// ```
+    #[inline]
+    pub fn from_cqe(result: i64, system: GlobalId, user_code: GlobalId) -> Option<Self> {
+        if result >= 0 {
+            return None;
+        }
+        let errno = result.unsigned_abs().min(i32::MAX as u64) as i32;
+        let error_code = crate::codes::errno_to_global_id(errno);
+        Some(Self::simple_os(system, error_code, user_code, errno))
+    }
+
+    /// Full diagnostics for one in every `n` errors at `site`, Simple otherwise.
+    ///
+    /// Bumps the site's counter once. The occurrence that finds it at a
+    /// multiple of `n` (the first, the `n+1`th, …) calls `ctx` and returns a
+    /// Full error, with the given codes and `site` written over the
+    /// context's, so both variants match the same arms; the rest return
+    /// `simple_site`-style errors and never run `ctx`, so a noisy site stays
+    /// allocation-free while still capturing a message (and backtrace, with
+    /// `err!`-built contexts) periodically. `metrics::reset` restarts the
+    /// cycle. `n == 0` is treated as 1; `SiteId::NONE` or a site beyond
+    /// `MAX_SITES` has no counter and is always Full.
+    ///
+    /// ```
+    /// use gerror::{ErrorContext, GError, GlobalId, SiteId};
+    /// const SYS_NET: GlobalId = GlobalId::new("net", 3);
+    /// const ERR_EAGAIN: GlobalId = GlobalId::new("eagain", 11);
+    /// const UC_ACCEPT: GlobalId = GlobalId::new("accept", 1);
+    /// const SITE: SiteId = SiteId::new(500, 7);
+    ///
+    /// let full: Vec<bool> = (0..5)
+    ///     .map(|_| {
+    ///         GError::sampled_full(SYS_NET, ERR_EAGAIN, UC_ACCEPT, SITE, 2, || {
+    ///             ErrorContext::default().with_source(std::io::Error::from_raw_os_error(11))
+    ///         })
+    ///         .context()
+    ///         .is_some()
+    ///     })
+    ///     .collect();
+    /// assert_eq!(full, [true, false, true, false, true]);
+    /// ```
+    #[cfg(feature = "metrics")]
+    #[inline]
+    pub fn sampled_full(
+        system: GlobalId,
+        error_code: GlobalId,
+        user_code: GlobalId,
+        site: SiteId,
+        n: u64,
+        ctx: impl FnOnce() -> ErrorContext,
+    ) -> Self {
+        let phase = crate::metrics::bump(site) % n.max(1);
+        if phase != 0 {
+            return Self {
+                repr: Repr::Simple { system, error_code, user_code, site_id: site },
+            };
+        }
+        let mut ctx = ctx();
+        ctx.system = system;
+        ctx.error_code = error_code;
+        ctx.user_code = user_code;
+        ctx.site_id = site;
+        // Counted above; don't go through `full`, which would bump again.
+        Self {
+            repr: Repr::Full(Box::new(ctx)),
+        }
+    }
+
     /// Create a full diagnostic error from a pre-built ErrorContext.
     ///
     /// Prefer the `err!` macro over calling this directly.
@@ -177,6 +285,28 @@ impl GError {
         }
     }
 
+    /// The errno, if the error code is one (`codes::errno`, 2001–2998),
+    /// as for errors from `simple_os` and `from_cqe`.
+    #[inline]
+    pub fn os_error(&self) -> Option<i32> {
+        match self.error_code().code {
+            code @ 2001..=2998 => Some((code - 2000) as i32),
+            _ => None,
+        }
+    }
+
+    /// The backtrace captured at construction (`feature = "backtrace"`).
+    /// Always `None` for Simple errors: the fast path never captures one.
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        match &self.repr {
+            Repr::Simple { .. } => None,
+            #[cfg(feature = "backtrace")]
+            Repr::Full(ctx) => ctx.backtrace.as_ref(),
+            #[cfg(not(feature = "backtrace"))]
+            Repr::Full(_) => None,
+        }
+    }
+
     /// Returns `true` if this is a zero-allocation Simple error.
     #[inline]
     pub fn is_simple(&self) -> bool {
@@ -255,6 +385,14 @@ impl fmt::Display for GError {
                     write!(f, " at {}:{}", ctx.file, ctx.line)?;
                 }
 
+                if BACKTRACE_DISPLAY.load(Ordering::Relaxed) {
+                    if let Some(bt) = self.backtrace() {
+                        if bt.status() == std::backtrace::BacktraceStatus::Captured {
+                            write!(f, "\nstack backtrace:\n{}", bt)?;
+                        }
+                    }
+                }
+
                 Ok(())
             }
         }
@@ -322,6 +460,27 @@ mod tests {
         assert_eq!(err.site_id().unique_id(), 1001);
     }
 
+    #[test]
+    fn from_cqe_decodes_errno() {
+        use crate::codes::{self, SYS_LINUX, UC_RECV};
+
+        assert!(GError::from_cqe(0, SYS_LINUX, UC_RECV).is_none());
+        for (errno, code) in [
+            (11, codes::ERR_EAGAIN),
+            (104, codes::ERR_ECONNRESET),
+            (32, codes::ERR_EPIPE),
+            (110, codes::ERR_ETIMEDOUT),
+            (125, codes::ERR_ECANCELED),
+            (200, GlobalId::new("errno", 2200)),
+        ] {
+            let err = GError::from_cqe(-(errno as i64), SYS_LINUX, UC_RECV).unwrap();
+            assert!(err.is_simple());
+            assert_eq!(err.kind(), (&SYS_LINUX, &code, &UC_RECV));
+            assert_eq!(err.os_error(), Some(errno));
+        }
+        assert_eq!(GError::simple(SYS_NET, ERR_EAGAIN, UC_ACCEPT).os_error(), None);
+    }
+
     #[test]
     fn full_error() {
         let ctx = ErrorContext {
@@ -345,6 +504,59 @@ mod tests {
         assert!(err.context().is_some());
     }
 
+    #[test]
+    fn simple_never_captures_backtrace() {
+        // Holds with `--features backtrace` too: Simple has nowhere to put one.
+        let site = SiteId::new(3, 7);
+        assert!(GError::simple(SYS_NET, ERR_EAGAIN, UC_ACCEPT).backtrace().is_none());
+        assert!(GError::simple_site(SYS_NET, ERR_EAGAIN, UC_ACCEPT, site).backtrace().is_none());
+        assert!(GError::from_cqe(-11, SYS_NET, UC_ACCEPT).unwrap().backtrace().is_none());
+    }
+
+    #[cfg(feature = "backtrace")]
+    #[test]
+    fn full_backtrace_display_toggle() {
+        let ctx = ErrorContext {
+            system: SYS_NET,
+            error_code: ERR_BIND,
+            user_code: UC_LISTEN,
+            backtrace: Some(Backtrace::force_capture()),
+            ..Default::default()
+        };
+        let err = GError::full(ctx);
+        assert!(err.backtrace().is_some());
+        assert!(!format!("{}", err).contains("stack backtrace"));
+        set_backtrace_display(true);
+        let shown = format!("{}", err);
+        set_backtrace_display(false);
+        assert!(shown.contains("stack backtrace"), "expected backtrace in: {}", shown);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn sampled_full_every_nth() {
+        let site = SiteId::new(10010, 1);
+        crate::metrics::reset(site);
+        let mut built = 0;
+        let errs: Vec<GError> = (0..7)
+            .map(|_| {
+                GError::sampled_full(SYS_NET, ERR_EAGAIN, UC_ACCEPT, site, 3, || {
+                    built += 1;
+                    ErrorContext::default()
+                })
+            })
+            .collect();
+        let full: Vec<bool> = errs.iter().map(|e| !e.is_simple()).collect();
+        assert_eq!(full, [true, false, false, true, false, false, true]);
+        assert_eq!(built, 3);
+        assert!(errs.iter().all(|e| e.site_id() == site && e.error_code() == &ERR_EAGAIN));
+        assert_eq!(crate::metrics::count(site), 7);
+
+        // No counter: every occurrence is Full
+        let e = GError::sampled_full(SYS_NET, ERR_EAGAIN, UC_ACCEPT, SiteId::NONE, 3, Default::default);
+        assert!(!e.is_simple());
+    }
+
     #[test]
     fn kind_triple() {
         let err = GError::simple(SYS_NET, ERR_EAGAIN, UC_ACCEPT);
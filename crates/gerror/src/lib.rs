@@ -57,7 +57,7 @@
 //! | Flag         | Effect |
 //! |--------------|--------|
 //! | `production` | Strips `message`, `file`, `line`, `metadata` at compile time |
-//! | `backtrace`  | Captures `std::backtrace::Backtrace` on error construction |
+//! | `backtrace`  | Captures `std::backtrace::Backtrace` in Full errors built by `err!()`; see `set_backtrace_display` |
 //! | `metrics`    | Per-site AtomicU64 counters, registry, Prometheus dump |
 //!
 //! ## Dependencies
@@ -72,6 +72,8 @@ mod error;
 mod macros;
 mod convert;
 
+pub mod codes;
+
 #[cfg(feature = "metrics")]
 pub mod metrics;
 
@@ -80,7 +82,7 @@ pub mod metrics;
 pub use id::GlobalId;
 pub use site::SiteId;
 pub use context::ErrorContext;
-pub use error::GError;
+pub use error::{set_backtrace_display, GError};
 pub use convert::{ResultExt, SYS_IO};
 
 // Re-export macros (they use #[macro_export] so they're already at crate root,
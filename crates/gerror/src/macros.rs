@@ -144,72 +144,109 @@ macro_rules! err {
 ///
 /// Each arm's identifiers are compared by `.code` (u64) against the error's
 /// GlobalIds. Use `_` as a wildcard for any position.
+///
+/// An arm may add a fourth element, a pattern matched against
+/// [`GError::os_error`](crate::GError::os_error) (`Option<i32>`), to branch
+/// on the raw errno:
+///
+/// ```ignore
+/// match_error!(err, {
+///     (SYS_NET, _, _, Some(libc::EAGAIN | libc::EINTR)) => { /* retry */ },
+///     (SYS_NET, _, _, Some(_))                          => { /* fatal errno */ },
+///     (_, _, _)                                         => { /* not an errno */ },
+/// })
+/// ```
+///
+/// Three- and four-element arms can be mixed; a three-element arm matches
+/// whatever the errno. Bindings in the errno pattern (`Some(errno)`) are in
+/// scope in the arm's handler.
 #[macro_export]
 macro_rules! match_error {
     ($error:expr, {
-        $( ($sys:tt, $err:tt, $uc:tt) => $handler:expr ),*
+        $( ($sys:tt, $err:tt, $uc:tt $(, $os:pat)?) => $handler:expr ),*
         $(,)*
     }) => {{
         let __e = &$error;
         let (__sys, __err, __uc) = __e.kind();
-        $crate::__match_error_arms!(__sys, __err, __uc; $( ($sys, $err, $uc) => $handler ),* )
+        let __os = __e.os_error();
+        $crate::__match_error_arms!(__sys, __err, __uc, __os;
+            $( ($sys, $err, $uc, [$($os)?]) => $handler ),* )
     }};
 }
 
 /// Internal helper for match_error! — handles wildcards.
+///
+/// The errno pattern arrives as `[]` (any) or `[pat]`.
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __match_error_arms {
     // Terminal: no arms left → unreachable / default
-    ($sys:ident, $err:ident, $uc:ident; ) => {
-        unreachable!("unhandled GError: {:?}", ($sys, $err, $uc))
+    ($sys:ident, $err:ident, $uc:ident, $os:ident; ) => {
+        unreachable!("unhandled GError: {:?}", ($sys, $err, $uc, $os))
     };
 
     // Wildcard-all arm: _
-    ($sys:ident, $err:ident, $uc:ident;
-     (_, _, _) => $handler:expr
-     $(, ($sys2:tt, $err2:tt, $uc2:tt) => $handler2:expr)*
+    ($sys:ident, $err:ident, $uc:ident, $os:ident;
+     (_, _, _, []) => $handler:expr
+     $(, ($sys2:tt, $err2:tt, $uc2:tt, $os2:tt) => $handler2:expr)*
     ) => {
         $handler
     };
 
+    // (_, _, _, OS) — match errno only
+    ($sys:ident, $err:ident, $uc:ident, $os:ident;
+     (_, _, _, [$o:pat]) => $handler:expr
+     $(, ($sys2:tt, $err2:tt, $uc2:tt, $os2:tt) => $handler2:expr)*
+    ) => {
+        match $os {
+            $o => $handler,
+            _ => $crate::__match_error_arms!($sys, $err, $uc, $os; $( ($sys2, $err2, $uc2, $os2) => $handler2 ),* ),
+        }
+    };
+
     // (SYS, _, _) — match system only
-    ($sys:ident, $err:ident, $uc:ident;
-     ($s:expr, _, _) => $handler:expr
-     $(, ($sys2:tt, $err2:tt, $uc2:tt) => $handler2:expr)*
+    ($sys:ident, $err:ident, $uc:ident, $os:ident;
+     ($s:expr, _, _, [$($o:pat)?]) => $handler:expr
+     $(, ($sys2:tt, $err2:tt, $uc2:tt, $os2:tt) => $handler2:expr)*
     ) => {
-        if $sys.code == $s.code {
-            $handler
-        } else {
-            $crate::__match_error_arms!($sys, $err, $uc; $( ($sys2, $err2, $uc2) => $handler2 ),* )
+        match $os {
+            $crate::__match_error_os!($($o)?) if $sys.code == $s.code => $handler,
+            _ => $crate::__match_error_arms!($sys, $err, $uc, $os; $( ($sys2, $err2, $uc2, $os2) => $handler2 ),* ),
         }
     };
 
     // (SYS, ERR, _) — match system + error_code
-    ($sys:ident, $err:ident, $uc:ident;
-     ($s:expr, $e:expr, _) => $handler:expr
-     $(, ($sys2:tt, $err2:tt, $uc2:tt) => $handler2:expr)*
+    ($sys:ident, $err:ident, $uc:ident, $os:ident;
+     ($s:expr, $e:expr, _, [$($o:pat)?]) => $handler:expr
+     $(, ($sys2:tt, $err2:tt, $uc2:tt, $os2:tt) => $handler2:expr)*
     ) => {
-        if $sys.code == $s.code && $err.code == $e.code {
-            $handler
-        } else {
-            $crate::__match_error_arms!($sys, $err, $uc; $( ($sys2, $err2, $uc2) => $handler2 ),* )
+        match $os {
+            $crate::__match_error_os!($($o)?) if $sys.code == $s.code && $err.code == $e.code => $handler,
+            _ => $crate::__match_error_arms!($sys, $err, $uc, $os; $( ($sys2, $err2, $uc2, $os2) => $handler2 ),* ),
         }
     };
 
     // (SYS, ERR, UC) — match all three
-    ($sys:ident, $err:ident, $uc:ident;
-     ($s:expr, $e:expr, $u:expr) => $handler:expr
-     $(, ($sys2:tt, $err2:tt, $uc2:tt) => $handler2:expr)*
+    ($sys:ident, $err:ident, $uc:ident, $os:ident;
+     ($s:expr, $e:expr, $u:expr, [$($o:pat)?]) => $handler:expr
+     $(, ($sys2:tt, $err2:tt, $uc2:tt, $os2:tt) => $handler2:expr)*
     ) => {
-        if $sys.code == $s.code && $err.code == $e.code && $uc.code == $u.code {
-            $handler
-        } else {
-            $crate::__match_error_arms!($sys, $err, $uc; $( ($sys2, $err2, $uc2) => $handler2 ),* )
+        match $os {
+            $crate::__match_error_os!($($o)?)
+                if $sys.code == $s.code && $err.code == $e.code && $uc.code == $u.code => $handler,
+            _ => $crate::__match_error_arms!($sys, $err, $uc, $os; $( ($sys2, $err2, $uc2, $os2) => $handler2 ),* ),
         }
     };
 }
 
+/// Internal helper for match_error! — the errno pattern, `_` if absent.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __match_error_os {
+    () => { _ };
+    ($o:pat) => { $o };
+}
+
 /// Quick fire-and-forget error with just system + error_code.
 ///
 /// ```ignore
@@ -369,6 +406,20 @@ mod tests {
         assert_eq!(result, "fallback");
     }
 
+    #[test]
+    fn match_error_os_error() {
+        let classify = |e: GError| match_error!(e, {
+            (SYS_NET, _, _, Some(11 | 4)) => "retry",
+            (SYS_NET, _, _, Some(_)) => "fatal_errno",
+            (_, _, _, None) => "not_errno",
+            (_, _, _) => "unreachable",
+        });
+        assert_eq!(classify(GError::from_cqe(-11, SYS_NET, UC_ACCEPT).unwrap()), "retry");
+        assert_eq!(classify(GError::from_cqe(-4, SYS_NET, UC_LISTEN).unwrap()), "retry");
+        assert_eq!(classify(GError::from_cqe(-104, SYS_NET, UC_ACCEPT).unwrap()), "fatal_errno");
+        assert_eq!(classify(GError::simple(SYS_NET, ERR_BIND, UC_ACCEPT)), "not_errno");
+    }
+
     // Needed for source() in test
     use std::error::Error;
 }
@@ -14,6 +14,9 @@
 //!       ▼  Prometheus scrape / bench-runner dump
 //! REGISTRY[counter_index] → { subsystem, class, action, ... }
 //! ```
+//!
+//! The same counter drives `GError::sampled_full`, which builds the Full
+//! variant only for every `n`th error at a site.
 
 use core::sync::atomic::{AtomicU64, Ordering};
 
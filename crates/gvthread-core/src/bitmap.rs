@@ -2,9 +2,13 @@
 //!
 //! Uses atomic bitmaps to track which GVThreads are ready to run.
 //! Separate bitmaps for each priority level, scanned in order.
-//! Random starting block for fairness across GVThreads.
+//! Random starting block and bit for fairness across GVThreads: a
+//! GVThread that keeps yielding can't keep winning on its id alone.
+//!
+//! A one-word summary (bit per priority level) answers "which is the
+//! highest level with anything ready" with a single trailing-zeros.
 
-use core::sync::atomic::{AtomicU64, Ordering};
+use core::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use crate::id::GVThreadId;
 use crate::state::Priority;
 use crate::spinlock::SpinLock;
@@ -40,33 +44,37 @@ impl ReadyBitmap {
     }
     
     /// Set a GVThread as ready
+    ///
+    /// Returns true if the bit was newly set.
     #[inline]
-    pub fn set(&self, id: GVThreadId) {
+    pub fn set(&self, id: GVThreadId) -> bool {
         let idx = id.as_usize();
         if idx >= self.max_id as usize {
-            return;
+            return false;
         }
         
         let block_idx = idx / BITS_PER_BLOCK;
         let bit_idx = idx % BITS_PER_BLOCK;
         let mask = 1u64 << bit_idx;
         
-        self.blocks[block_idx].fetch_or(mask, Ordering::Release);
+        self.blocks[block_idx].fetch_or(mask, Ordering::Release) & mask == 0
     }
     
     /// Clear a GVThread from ready
+    ///
+    /// Returns true if the bit was set.
     #[inline]
-    pub fn clear(&self, id: GVThreadId) {
+    pub fn clear(&self, id: GVThreadId) -> bool {
         let idx = id.as_usize();
         if idx >= self.max_id as usize {
-            return;
+            return false;
         }
         
         let block_idx = idx / BITS_PER_BLOCK;
         let bit_idx = idx % BITS_PER_BLOCK;
-        let mask = !(1u64 << bit_idx);
+        let mask = 1u64 << bit_idx;
         
-        self.blocks[block_idx].fetch_and(mask, Ordering::Release);
+        self.blocks[block_idx].fetch_and(!mask, Ordering::Release) & mask != 0
     }
     
     /// Check if a GVThread is ready
@@ -86,10 +94,11 @@ impl ReadyBitmap {
     
     /// Find and atomically claim a ready GVThread
     ///
-    /// Uses random starting block for fairness.
+    /// Uses random starting block (and bit within it) for fairness.
     /// Returns None if no ready GVThreads.
     pub fn find_and_claim(&self, start_hint: usize) -> Option<GVThreadId> {
         let start_block = start_hint % self.num_blocks;
+        let start_bit = (start_hint / self.num_blocks) % BITS_PER_BLOCK;
         
         // Scan from start_block, wrapping around
         for i in 0..self.num_blocks {
@@ -102,7 +111,7 @@ impl ReadyBitmap {
             }
             
             // Try to claim a bit from this block
-            if let Some(bit_idx) = self.try_claim_from_block(block_idx) {
+            if let Some(bit_idx) = self.try_claim_from_block(block_idx, start_bit) {
                 let gvthread_idx = block_idx * BITS_PER_BLOCK + bit_idx;
                 return Some(GVThreadId::new(gvthread_idx as u32));
             }
@@ -111,16 +120,17 @@ impl ReadyBitmap {
         None
     }
     
-    /// Try to atomically claim a bit from a specific block
-    fn try_claim_from_block(&self, block_idx: usize) -> Option<usize> {
+    /// Try to atomically claim a bit from a specific block: the first
+    /// set bit at or after `start_bit`, wrapping around
+    fn try_claim_from_block(&self, block_idx: usize, start_bit: usize) -> Option<usize> {
         loop {
             let current = self.blocks[block_idx].load(Ordering::Acquire);
             if current == 0 {
                 return None;
             }
             
-            // Find lowest set bit
-            let bit_idx = current.trailing_zeros() as usize;
+            let rotated = current.rotate_right(start_bit as u32);
+            let bit_idx = (rotated.trailing_zeros() as usize + start_bit) % BITS_PER_BLOCK;
             let mask = 1u64 << bit_idx;
             
             // Try to clear it atomically
@@ -146,6 +156,21 @@ impl ReadyBitmap {
         false
     }
     
+    /// Ready GVThreads in id order (for diagnostics; not a snapshot)
+    pub fn ready_ids(&self) -> impl Iterator<Item = GVThreadId> + '_ {
+        self.blocks.iter().enumerate().flat_map(|(block_idx, block)| {
+            let mut bits = block.load(Ordering::Relaxed);
+            core::iter::from_fn(move || {
+                if bits == 0 {
+                    return None;
+                }
+                let bit_idx = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                Some(GVThreadId::new((block_idx * BITS_PER_BLOCK + bit_idx) as u32))
+            })
+        })
+    }
+    
     /// Count ready GVThreads (for debugging/stats)
     pub fn count_ready(&self) -> usize {
         let mut count = 0;
@@ -161,6 +186,12 @@ pub struct ReadyBitmaps {
     /// One bitmap per priority level
     bitmaps: [ReadyBitmap; Priority::COUNT],
     
+    /// Ready GVThreads per priority level (counted before the bit is set)
+    counts: [AtomicUsize; Priority::COUNT],
+    
+    /// Summary: bit `i` set while priority index `i` has ready GVThreads
+    levels: AtomicU32,
+    
     /// Per-worker random state for fair block selection
     worker_rng: SpinLock<Vec<u64>>,
 }
@@ -175,6 +206,8 @@ impl ReadyBitmaps {
                 ReadyBitmap::new(max_gvthreads), // Normal
                 ReadyBitmap::new(max_gvthreads), // Low
             ],
+            counts: Default::default(),
+            levels: AtomicU32::new(0),
             worker_rng: SpinLock::new(vec![12345u64; num_workers]),
         }
     }
@@ -182,13 +215,68 @@ impl ReadyBitmaps {
     /// Mark a GVThread as ready at the given priority
     #[inline]
     pub fn set_ready(&self, id: GVThreadId, priority: Priority) {
-        self.bitmaps[priority.as_index()].set(id);
+        // Count (and publish the level) first, so a claimer can never
+        // take the bit before it was counted
+        self.added(priority);
+        if !self.bitmaps[priority.as_index()].set(id) {
+            self.removed(priority);
+        }
+    }
+    
+    /// Clear a GVThread from ready at the given priority
+    ///
+    /// Returns true if it was ready there.
+    #[inline]
+    pub fn clear_ready(&self, id: GVThreadId, priority: Priority) -> bool {
+        let was_ready = self.bitmaps[priority.as_index()].clear(id);
+        if was_ready {
+            self.removed(priority);
+        }
+        was_ready
+    }
+    
+    /// Mark `priority`'s level non-empty in the summary
+    #[inline]
+    pub fn set(&self, priority: Priority) {
+        self.levels.fetch_or(1 << priority.as_index(), Ordering::SeqCst);
     }
     
-    /// Clear a GVThread from ready (all priorities)
+    /// Mark `priority`'s level empty in the summary
     #[inline]
-    pub fn clear_ready(&self, id: GVThreadId, priority: Priority) {
-        self.bitmaps[priority.as_index()].clear(id);
+    pub fn clear(&self, priority: Priority) {
+        self.levels.fetch_and(!(1 << priority.as_index()), Ordering::SeqCst);
+    }
+    
+    /// Highest priority with a ready GVThread, in O(1)
+    ///
+    /// Concurrent with `set_ready`/claims this is a snapshot: a level
+    /// can show up just before its GVThread's bit does.
+    #[inline]
+    pub fn highest_nonempty(&self) -> Option<Priority> {
+        let levels = self.levels.load(Ordering::Acquire);
+        if levels == 0 {
+            return None;
+        }
+        Priority::from_index(levels.trailing_zeros() as usize)
+    }
+    
+    #[inline]
+    fn added(&self, priority: Priority) {
+        if self.counts[priority.as_index()].fetch_add(1, Ordering::SeqCst) == 0 {
+            self.set(priority);
+        }
+    }
+    
+    #[inline]
+    fn removed(&self, priority: Priority) {
+        let count = &self.counts[priority.as_index()];
+        if count.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.clear(priority);
+            // An add racing with the clear may have found the bit still set
+            if count.load(Ordering::SeqCst) > 0 {
+                self.set(priority);
+            }
+        }
     }
     
     /// Find and claim a ready GVThread
@@ -214,18 +302,20 @@ impl ReadyBitmaps {
             }
         };
         
+        // Only scan levels the summary says are non-empty, highest first
+        let mut levels = self.levels.load(Ordering::Acquire);
         if low_priority_only {
             // Dedicated LOW priority worker
-            if let Some(id) = self.bitmaps[Priority::Low.as_index()].find_and_claim(start_hint) {
-                return Some((id, Priority::Low));
-            }
-        } else {
-            // Normal worker: check all priorities in order
-            for priority in Priority::iter() {
-                if let Some(id) = self.bitmaps[priority.as_index()].find_and_claim(start_hint) {
-                    return Some((id, priority));
-                }
+            levels &= 1 << Priority::Low.as_index();
+        }
+        while levels != 0 {
+            let idx = levels.trailing_zeros() as usize;
+            if let Some(id) = self.bitmaps[idx].find_and_claim(start_hint) {
+                let priority = Priority::from_index(idx)?;
+                self.removed(priority);
+                return Some((id, priority));
             }
+            levels &= levels - 1;
         }
         
         None
@@ -250,6 +340,29 @@ impl ReadyBitmaps {
     pub fn total_ready(&self) -> usize {
         self.bitmaps.iter().map(|b| b.count_ready()).sum()
     }
+    
+    /// Ready count at one priority, in O(1)
+    ///
+    /// From the level's counter rather than its bits, so concurrent with
+    /// `set_ready`/claims it is approximate like `highest_nonempty`.
+    #[inline]
+    pub fn ready_at(&self, priority: Priority) -> usize {
+        self.counts[priority.as_index()].load(Ordering::Acquire)
+    }
+    
+    /// Up to `max` ready GVThreads, highest priority first (for
+    /// diagnostics; not a snapshot)
+    pub fn ready_ids(&self, max: usize) -> Vec<(GVThreadId, Priority)> {
+        self.bitmaps
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, bitmap)| {
+                let priority = Priority::from_index(idx).unwrap_or_default();
+                bitmap.ready_ids().map(move |id| (id, priority))
+            })
+            .take(max)
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -296,6 +409,20 @@ mod tests {
         assert!(claimed4.is_none());
     }
     
+    #[test]
+    fn test_bitmap_claim_starts_at_the_hinted_bit() {
+        let bitmap = ReadyBitmap::new(64);
+        let mut first = std::collections::BTreeSet::new();
+        for hint in 0..64 {
+            bitmap.set(GVThreadId::new(3));
+            bitmap.set(GVThreadId::new(40));
+            first.insert(bitmap.find_and_claim(hint).unwrap().as_u32());
+            bitmap.find_and_claim(hint).unwrap();
+        }
+        // Neither id always wins
+        assert_eq!(first.into_iter().collect::<Vec<_>>(), vec![3, 40]);
+    }
+    
     #[test]
     fn test_ready_bitmaps_priority() {
         let bitmaps = ReadyBitmaps::new(1000, 4);
@@ -330,6 +457,133 @@ mod tests {
         assert!(bitmaps.find_and_claim(0, false).is_none());
     }
     
+    #[test]
+    fn test_ready_ids_and_counts() {
+        let bitmaps = ReadyBitmaps::new(200, 1);
+        bitmaps.set_ready(GVThreadId::new(130), Priority::Normal);
+        bitmaps.set_ready(GVThreadId::new(5), Priority::Normal);
+        bitmaps.set_ready(GVThreadId::new(70), Priority::High);
+        assert_eq!(bitmaps.ready_at(Priority::Normal), 2);
+        
+        // Highest priority first, then by id
+        assert_eq!(
+            bitmaps.ready_ids(8),
+            vec![
+                (GVThreadId::new(70), Priority::High),
+                (GVThreadId::new(5), Priority::Normal),
+                (GVThreadId::new(130), Priority::Normal),
+            ]
+        );
+        assert_eq!(bitmaps.ready_ids(1).len(), 1);
+        
+        assert!(bitmaps.clear_ready(GVThreadId::new(5), Priority::Normal));
+        assert!(!bitmaps.clear_ready(GVThreadId::new(5), Priority::Normal));
+        assert_eq!(bitmaps.ready_at(Priority::Normal), 1);
+    }
+    
+    /// xorshift64, as the workers use for their start hints
+    fn next(rng: &mut u64) -> u64 {
+        *rng ^= *rng << 13;
+        *rng ^= *rng >> 7;
+        *rng ^= *rng << 17;
+        *rng
+    }
+    
+    #[test]
+    fn test_highest_nonempty_matches_model_for_level_set_clear() {
+        let bitmaps = ReadyBitmaps::new(64, 1);
+        let mut model = [false; Priority::COUNT];
+        let mut rng = 0x9E37_79B9_7F4A_7C15;
+        for _ in 0..10_000 {
+            let r = next(&mut rng);
+            let priority = Priority::from_index(r as usize % Priority::COUNT).unwrap();
+            let on = r & 0x100 != 0;
+            if on {
+                bitmaps.set(priority);
+            } else {
+                bitmaps.clear(priority);
+            }
+            model[priority.as_index()] = on;
+            let expected = model.iter().position(|&b| b).and_then(Priority::from_index);
+            assert_eq!(bitmaps.highest_nonempty(), expected);
+        }
+    }
+    
+    #[test]
+    fn test_highest_nonempty_tracks_ready_and_claims() {
+        const MAX: usize = 200;
+        let bitmaps = ReadyBitmaps::new(MAX, 2);
+        // Reference model: ready ids per level
+        let mut model: [std::collections::BTreeSet<u32>; Priority::COUNT] = Default::default();
+        let mut rng = 0xDEAD_BEEF_CAFE_F00D;
+        for _ in 0..20_000 {
+            let r = next(&mut rng);
+            let priority = Priority::from_index((r >> 8) as usize % Priority::COUNT).unwrap();
+            let id = (r >> 16) as u32 % MAX as u32;
+            match r % 4 {
+                0 | 1 => {
+                    bitmaps.set_ready(GVThreadId::new(id), priority);
+                    model[priority.as_index()].insert(id);
+                }
+                2 => {
+                    bitmaps.clear_ready(GVThreadId::new(id), priority);
+                    model[priority.as_index()].remove(&id);
+                }
+                _ => {
+                    let low_only = r & 0x10 != 0;
+                    let claimed = bitmaps.find_and_claim((r & 1) as usize, low_only);
+                    let expected = if low_only {
+                        (!model[Priority::Low.as_index()].is_empty()).then_some(Priority::Low)
+                    } else {
+                        model.iter().position(|s| !s.is_empty()).and_then(Priority::from_index)
+                    };
+                    assert_eq!(claimed.map(|(_, p)| p), expected);
+                    if let Some((id, p)) = claimed {
+                        assert!(model[p.as_index()].remove(&id.as_u32()));
+                    }
+                }
+            }
+            let expected = model.iter().position(|s| !s.is_empty()).and_then(Priority::from_index);
+            assert_eq!(bitmaps.highest_nonempty(), expected);
+        }
+        assert_eq!(bitmaps.total_ready(), model.iter().map(|s| s.len()).sum::<usize>());
+    }
+    
+    #[test]
+    fn test_summary_settles_after_concurrent_churn() {
+        use std::sync::Arc;
+        use std::thread;
+        
+        let bitmaps = Arc::new(ReadyBitmaps::new(64, 4));
+        bitmaps.set_ready(GVThreadId::new(63), Priority::Low);
+        let handles: Vec<_> = (0..4u32)
+            .map(|t| {
+                let bitmaps = Arc::clone(&bitmaps);
+                thread::spawn(move || {
+                    let priority = Priority::from_index(t as usize % 3).unwrap();
+                    for i in 0..10_000u32 {
+                        let id = GVThreadId::new(t * 8 + i % 8);
+                        bitmaps.set_ready(id, priority);
+                        if i % 2 == 0 {
+                            bitmaps.clear_ready(id, priority);
+                        } else {
+                            bitmaps.find_and_claim(t as usize, false);
+                        }
+                    }
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        // Whatever is left, the summary agrees with the bitmaps
+        while let Some(top) = bitmaps.highest_nonempty() {
+            let (_, p) = bitmaps.find_and_claim(0, false).unwrap();
+            assert_eq!(p, top);
+        }
+        assert_eq!(bitmaps.total_ready(), 0);
+    }
+    
     #[test]
     fn test_concurrent_claim() {
         use std::sync::Arc;
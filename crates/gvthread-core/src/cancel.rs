@@ -2,11 +2,81 @@
 //!
 //! GVThreads can check for cancellation via their token and exit gracefully.
 //! Tokens can be linked to form parent-child relationships.
+//!
+//! # Linked tokens
+//!
+//! `child_token()` returns a token that is cancelled when its parent is.
+//! Cancellation is pushed down, not only polled: the parent keeps a
+//! registry of its live children, and `cancel()` walks it. A child that
+//! is bound to GVThreads (see `bind_gvthread`) sets each one's metadata
+//! `cancelled` flag and hands it to the wake hook installed by the
+//! runtime, so a GVThread blocked in `sleep_cancellable` resumes at once.
+//! Dropping the last clone of a child deregisters it.
+//!
+//! Cancelling a GVThread's own token wakes it the same way, so a GVThread
+//! asleep or waiting on a channel observes cancellation immediately
+//! rather than when it next wakes on its own.
+//!
+//! Children of a GVThread's own (metadata) token live in a process-wide
+//! registry keyed by slot and generation, since metadata has no room for
+//! a child list.
 
-use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
-use std::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock, Weak};
 use crate::error::{SchedError, SchedResult};
 use crate::metadata::GVThreadMetadata;
+use crate::spinlock::SpinLock;
+
+/// Called for each GVThread whose metadata flag a cancellation sets
+///
+/// Installed once by the runtime; wakes the GVThread if it is blocked
+/// in a cancellable wait.
+pub type CancelWakeHook = fn(&GVThreadMetadata);
+
+static WAKE_HOOK: OnceLock<CancelWakeHook> = OnceLock::new();
+
+/// Install the runtime's wake hook (first call wins)
+pub fn set_wake_hook(hook: CancelWakeHook) {
+    let _ = WAKE_HOOK.set(hook);
+}
+
+/// Reports whether the calling GVThread has been cancelled
+///
+/// Installed once by the runtime; lets blocking primitives in this crate
+/// (which can't see the scheduler's TLS) give up on cancellation.
+pub type CurrentCancelledHook = fn() -> bool;
+
+static CURRENT_HOOK: OnceLock<CurrentCancelledHook> = OnceLock::new();
+
+/// Install the runtime's current-GVThread cancellation check (first call wins)
+pub fn set_current_cancelled_hook(hook: CurrentCancelledHook) {
+    let _ = CURRENT_HOOK.set(hook);
+}
+
+/// Whether the calling GVThread has been cancelled (`false` outside a
+/// GVThread or before the runtime started)
+#[inline]
+pub fn current_cancelled() -> bool {
+    CURRENT_HOOK.get().is_some_and(|hook| hook())
+}
+
+/// Children of metadata tokens: (slot id, generation, child)
+static METADATA_CHILDREN: SpinLock<Vec<(u32, u32, Weak<OwnedCancellation>)>> =
+    SpinLock::new(Vec::new());
+
+/// Flag a GVThread cancelled and wake it, if it is still the same GVThread
+fn cancel_gvthread(meta: &GVThreadMetadata, generation: u32) {
+    if meta.get_generation() != generation {
+        return; // Slot reused - not ours any more
+    }
+    meta.request_cancel();
+    // Pairs with the fence a waiter issues between publishing its wait
+    // kind and checking the flag: one of the two sides sees the other.
+    core::sync::atomic::fence(Ordering::SeqCst);
+    if let Some(hook) = WAKE_HOOK.get() {
+        hook(meta);
+    }
+}
 
 /// Token for checking and triggering cancellation
 ///
@@ -25,8 +95,8 @@ pub struct CancellationToken {
 enum CancellationInner {
     /// Heap-allocated token with Arc (for tokens created outside GVThread)
     Owned(Arc<OwnedCancellation>),
-    /// Reference to metadata's cancelled field (no allocation)
-    Metadata(*const AtomicU8),
+    /// Reference to a GVThread's metadata (no allocation)
+    Metadata(*const GVThreadMetadata),
     /// Dummy token that never cancels
     Dummy,
 }
@@ -37,6 +107,54 @@ struct OwnedCancellation {
     
     /// Parent token (if any)
     parent: Option<CancellationToken>,
+
+    /// Registered children, cancelled along with this token
+    children: SpinLock<Vec<Weak<OwnedCancellation>>>,
+
+    /// GVThreads bound to this token: (metadata address, generation)
+    bound: SpinLock<Vec<(usize, u32)>>,
+}
+
+impl OwnedCancellation {
+    fn new(parent: Option<CancellationToken>) -> Self {
+        Self {
+            cancelled: AtomicBool::new(false),
+            parent,
+            children: SpinLock::new(Vec::new()),
+            bound: SpinLock::new(Vec::new()),
+        }
+    }
+
+    /// Set the flag and push cancellation to bound GVThreads and children
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+
+        let bound = std::mem::take(&mut *self.bound.lock());
+        for (addr, generation) in bound {
+            cancel_gvthread(unsafe { &*(addr as *const GVThreadMetadata) }, generation);
+        }
+
+        let children: Vec<_> = self.children.lock().iter().filter_map(Weak::upgrade).collect();
+        for child in children {
+            child.cancel();
+        }
+    }
+}
+
+impl Drop for OwnedCancellation {
+    fn drop(&mut self) {
+        // Deregister from the parent's registry
+        let me = self as *const OwnedCancellation;
+        match self.parent.as_ref().map(|p| &p.inner) {
+            Some(CancellationInner::Owned(parent)) => {
+                parent.children.lock().retain(|w| w.as_ptr() != me);
+            }
+            Some(CancellationInner::Metadata(_)) => {
+                METADATA_CHILDREN.lock().retain(|(_, _, w)| w.as_ptr() != me);
+            }
+            _ => {}
+        }
+    }
 }
 
 // Safety: CancellationInner::Metadata points to GVThreadMetadata which is thread-safe
@@ -49,10 +167,7 @@ impl CancellationToken {
     /// WARNING: Do not call from GVThread stack! Use from_metadata instead.
     pub fn new() -> Self {
         Self {
-            inner: CancellationInner::Owned(Arc::new(OwnedCancellation {
-                cancelled: AtomicBool::new(false),
-                parent: None,
-            })),
+            inner: CancellationInner::Owned(Arc::new(OwnedCancellation::new(None))),
         }
     }
     
@@ -61,7 +176,7 @@ impl CancellationToken {
     /// This does NOT allocate and is safe to call from GVThread stack.
     pub fn from_metadata(meta: &GVThreadMetadata) -> Self {
         Self {
-            inner: CancellationInner::Metadata(&meta.cancelled as *const AtomicU8),
+            inner: CancellationInner::Metadata(meta as *const GVThreadMetadata),
         }
     }
     
@@ -81,10 +196,60 @@ impl CancellationToken {
     /// WARNING: This allocates! Do not call from GVThread stack.
     pub fn child(&self) -> Self {
         Self {
-            inner: CancellationInner::Owned(Arc::new(OwnedCancellation {
-                cancelled: AtomicBool::new(false),
-                parent: Some(self.clone()),
-            })),
+            inner: CancellationInner::Owned(Arc::new(OwnedCancellation::new(Some(self.clone())))),
+        }
+    }
+    
+    /// Create a child token registered with this one
+    ///
+    /// Unlike `child()`, cancelling this token actively cancels the child:
+    /// GVThreads bound to it get their metadata flag set and are woken.
+    /// The child deregisters itself when its last clone is dropped.
+    ///
+    /// WARNING: This allocates! Do not call from GVThread stack.
+    pub fn child_token(&self) -> Self {
+        let child = Arc::new(OwnedCancellation::new(Some(self.clone())));
+        match &self.inner {
+            CancellationInner::Owned(parent) => {
+                parent.children.lock().push(Arc::downgrade(&child));
+            }
+            CancellationInner::Metadata(ptr) => {
+                let meta = unsafe { &**ptr };
+                METADATA_CHILDREN.lock().push((
+                    meta.get_id().as_u32(),
+                    meta.get_generation(),
+                    Arc::downgrade(&child),
+                ));
+            }
+            CancellationInner::Dummy => {}
+        }
+        // Parent may already be cancelled
+        if self.is_cancelled() {
+            child.cancelled.store(true, Ordering::Release);
+        }
+        Self {
+            inner: CancellationInner::Owned(child),
+        }
+    }
+    
+    /// Bind a GVThread to this token
+    ///
+    /// When this token (or any ancestor linked via `child_token()`) is
+    /// cancelled, the GVThread's metadata `cancelled` flag is set and it
+    /// is woken from cancellable waits. Binds are one-shot: they are
+    /// consumed by the first cancellation. Only owned tokens keep binds;
+    /// for a metadata token this flags the GVThread only if already
+    /// cancelled.
+    ///
+    /// Used by the runtime when spawning with a token.
+    pub fn bind_gvthread(&self, meta: &GVThreadMetadata) {
+        let generation = meta.get_generation();
+        if let CancellationInner::Owned(arc) = &self.inner {
+            arc.bound.lock().push((meta as *const GVThreadMetadata as usize, generation));
+        }
+        // Close the race with a concurrent cancel(): re-check after binding
+        if self.is_cancelled() {
+            cancel_gvthread(meta, generation);
         }
     }
     
@@ -107,7 +272,7 @@ impl CancellationToken {
             }
             CancellationInner::Metadata(ptr) => {
                 // Read from metadata's cancelled field
-                unsafe { (**ptr).load(Ordering::Acquire) != 0 }
+                unsafe { (**ptr).is_cancelled() }
             }
             CancellationInner::Dummy => false,
         }
@@ -116,14 +281,24 @@ impl CancellationToken {
     /// Request cancellation
     ///
     /// This only sets this token's flag, not parent's.
-    /// Child tokens will see cancellation when they check.
+    /// Children from `child()` see cancellation when they check; children
+    /// from `child_token()` are cancelled (and their GVThreads woken) now.
     pub fn cancel(&self) {
         match &self.inner {
-            CancellationInner::Owned(arc) => {
-                arc.cancelled.store(true, Ordering::Release);
-            }
+            CancellationInner::Owned(arc) => arc.cancel(),
             CancellationInner::Metadata(ptr) => {
-                unsafe { (**ptr).store(1, Ordering::Release); }
+                let meta = unsafe { &**ptr };
+                let (id, generation) = (meta.get_id().as_u32(), meta.get_generation());
+                cancel_gvthread(meta, generation);
+
+                let children: Vec<_> = METADATA_CHILDREN.lock()
+                    .iter()
+                    .filter(|(i, g, _)| *i == id && *g == generation)
+                    .filter_map(|(_, _, w)| w.upgrade())
+                    .collect();
+                for child in children {
+                    child.cancel();
+                }
             }
             CancellationInner::Dummy => {}
         }
@@ -168,7 +343,7 @@ impl CancellationToken {
                 arc.cancelled.store(false, Ordering::Release);
             }
             CancellationInner::Metadata(ptr) => {
-                unsafe { (**ptr).store(0, Ordering::Release); }
+                unsafe { (**ptr).cancelled.store(0, Ordering::Release); }
             }
             CancellationInner::Dummy => {}
         }
@@ -264,6 +439,57 @@ mod tests {
         assert!(token2.is_cancelled());
     }
     
+    #[test]
+    fn test_child_token_propagates_to_bound_gvthread() {
+        let parent = CancellationToken::new();
+        let child = parent.child_token();
+        let grandchild = child.child_token();
+        
+        let meta = GVThreadMetadata::new();
+        meta.init(crate::id::GVThreadId::new(3), 1, crate::id::GVThreadId::NONE, crate::state::Priority::Normal);
+        grandchild.bind_gvthread(&meta);
+        assert!(!meta.is_cancelled());
+        
+        parent.cancel();
+        assert!(child.is_cancelled());
+        assert!(meta.is_cancelled());
+    }
+    
+    #[test]
+    fn test_child_token_deregisters_on_drop() {
+        let parent = CancellationToken::new();
+        let child = parent.child_token();
+        let clone = child.clone();
+        let CancellationInner::Owned(arc) = &parent.inner else { unreachable!() };
+        assert_eq!(arc.children.lock().len(), 1);
+        drop(child);
+        assert_eq!(arc.children.lock().len(), 1);
+        drop(clone);
+        assert_eq!(arc.children.lock().len(), 0);
+    }
+    
+    #[test]
+    fn test_bind_after_cancel_flags_immediately() {
+        let token = CancellationToken::new().child_token();
+        token.cancel();
+        let meta = GVThreadMetadata::new();
+        meta.init(crate::id::GVThreadId::new(4), 1, crate::id::GVThreadId::NONE, crate::state::Priority::Normal);
+        token.bind_gvthread(&meta);
+        assert!(meta.is_cancelled());
+    }
+    
+    #[test]
+    fn test_bind_ignores_reused_slot() {
+        let token = CancellationToken::new();
+        let meta = GVThreadMetadata::new();
+        meta.init(crate::id::GVThreadId::new(5), 1, crate::id::GVThreadId::NONE, crate::state::Priority::Normal);
+        token.bind_gvthread(&meta);
+        // Slot reused by another GVThread before the cancel
+        meta.init(crate::id::GVThreadId::new(5), 2, crate::id::GVThreadId::NONE, crate::state::Priority::Normal);
+        token.cancel();
+        assert!(!meta.is_cancelled());
+    }
+    
     #[test]
     fn test_dummy_token() {
         let token = CancellationToken::dummy();
@@ -3,17 +3,42 @@
 //! This channel is designed to work with the GVThread scheduler.
 //! When a send or receive would block, the calling GVThread yields
 //! to the scheduler instead of blocking the OS thread.
+//!
+//! Senders and receivers may run on different workers at once: every
+//! piece of shared state sits behind a `SpinLock`, so `Sender<T>` and
+//! `Receiver<T>` are `Send + Sync` exactly when `T: Send`, derived by
+//! the compiler rather than asserted.
 
 use std::collections::VecDeque;
 use std::sync::Arc;
+use crate::cancel;
 use crate::id::GVThreadId;
 use crate::spinlock::SpinLock;
 use crate::error::{SchedError, SchedResult, TrySendError, TryRecvError};
 
 /// Create a new bounded channel with the specified capacity
+///
+/// A full channel pushes back: `send` waits for room and `try_send`
+/// fails, so a slow consumer throttles its producers.
 pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    with_buffer(VecDeque::with_capacity(capacity), capacity)
+}
+
+/// Create a channel with no capacity limit
+///
+/// `send` never waits and `try_send` only fails once every receiver is
+/// gone: the buffer grows instead. That trades backpressure for memory —
+/// a consumer that falls behind lets the queue grow without bound, so
+/// use this only where something else limits the producers (e.g. event
+/// aggregation, where blocking or dropping an event is worse than
+/// growing). `capacity()` reports `usize::MAX`.
+pub fn channel_unbounded<T>() -> (Sender<T>, Receiver<T>) {
+    with_buffer(VecDeque::new(), usize::MAX)
+}
+
+fn with_buffer<T>(buffer: VecDeque<T>, capacity: usize) -> (Sender<T>, Receiver<T>) {
     let inner = Arc::new(ChannelInner {
-        buffer: SpinLock::new(VecDeque::with_capacity(capacity)),
+        buffer: SpinLock::new(buffer),
         capacity,
         send_waiters: SpinLock::new(VecDeque::new()),
         recv_waiters: SpinLock::new(VecDeque::new()),
@@ -65,15 +90,23 @@ struct ChannelInner<T> {
 impl<T> Sender<T> {
     /// Send a value, blocking (yielding) if the channel is full
     ///
-    /// Returns `Err(Cancelled)` if the token is cancelled while waiting.
+    /// Returns `Err(Cancelled)` if the calling GVThread is cancelled while
+    /// waiting (the value is dropped).
     /// Returns `Err(ChannelClosed)` if all receivers have been dropped.
-    pub fn send(&self, value: T) -> SchedResult<()> {
+    pub fn send(&self, mut value: T) -> SchedResult<()> {
         loop {
             // Check if channel is closed
             if *self.inner.closed.lock() {
                 return Err(SchedError::ChannelClosed);
             }
             
+            // Only give up if we'd have to wait; a free slot wins
+            if self.inner.buffer.lock().len() >= self.inner.capacity
+                && cancel::current_cancelled()
+            {
+                return Err(SchedError::Cancelled);
+            }
+            
             // Try to send without blocking
             match self.try_send_inner(value) {
                 Ok(()) => {
@@ -101,7 +134,7 @@ impl<T> Sender<T> {
                             // Still full, continue loop
                             // This is a placeholder - real impl yields to scheduler
                             std::hint::spin_loop();
-                            return self.send(v); // Recursive retry (will be proper yield)
+                            value = v;
                         }
                     }
                 }
@@ -155,7 +188,7 @@ impl<T> Sender<T> {
         self.inner.buffer.lock().is_empty()
     }
     
-    /// Get channel capacity
+    /// Get channel capacity (`usize::MAX` if unbounded)
     pub fn capacity(&self) -> usize {
         self.inner.capacity
     }
@@ -164,7 +197,8 @@ impl<T> Sender<T> {
 impl<T> Receiver<T> {
     /// Receive a value, blocking (yielding) if the channel is empty
     ///
-    /// Returns `Err(Cancelled)` if the token is cancelled while waiting.
+    /// Returns `Err(Cancelled)` if the calling GVThread is cancelled while
+    /// waiting (buffered values are still delivered first).
     /// Returns `Err(ChannelClosed)` if all senders have been dropped and buffer is empty.
     pub fn recv(&self) -> SchedResult<T> {
         loop {
@@ -177,9 +211,13 @@ impl<T> Receiver<T> {
                 }
                 Err(TryRecvError) => {
                     // Buffer empty
-                    // Check if all senders are gone
+                    // Check if all senders are gone. The last one may
+                    // have sent right before dropping: look once more
                     if *self.inner.sender_count.lock() == 0 {
-                        return Err(SchedError::ChannelClosed);
+                        return self.try_recv().map_err(|_| SchedError::ChannelClosed);
+                    }
+                    if cancel::current_cancelled() {
+                        return Err(SchedError::Cancelled);
                     }
                     
                     // In real implementation, this would:
@@ -231,6 +269,75 @@ impl<T> Receiver<T> {
     pub fn is_empty(&self) -> bool {
         self.inner.buffer.lock().is_empty()
     }
+    
+    /// Iterate with blocking `recv`: ends once the channel is closed and
+    /// drained (or the calling GVThread is cancelled)
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { rx: self }
+    }
+    
+    /// Iterate over the values available right now, stopping at the
+    /// first empty `try_recv`
+    pub fn try_iter(&self) -> TryIter<'_, T> {
+        TryIter { rx: self }
+    }
+}
+
+/// Blocking iterator over a `Receiver`, from `Receiver::iter`
+pub struct Iter<'a, T> {
+    rx: &'a Receiver<T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = T;
+    
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+/// Non-blocking iterator over a `Receiver`, from `Receiver::try_iter`
+pub struct TryIter<'a, T> {
+    rx: &'a Receiver<T>,
+}
+
+impl<'a, T> Iterator for TryIter<'a, T> {
+    type Item = T;
+    
+    fn next(&mut self) -> Option<T> {
+        self.rx.try_recv().ok()
+    }
+}
+
+/// Owning blocking iterator, from `Receiver::into_iter`
+pub struct IntoIter<T> {
+    rx: Receiver<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+impl<T> IntoIterator for Receiver<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+    
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { rx: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+    
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
 }
 
 impl<T> Clone for Sender<T> {
@@ -277,12 +384,6 @@ impl<T> Drop for Receiver<T> {
     }
 }
 
-// Safety: Channel is safe to share between threads
-unsafe impl<T: Send> Send for Sender<T> {}
-unsafe impl<T: Send> Sync for Sender<T> {}
-unsafe impl<T: Send> Send for Receiver<T> {}
-unsafe impl<T: Send> Sync for Receiver<T> {}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -348,6 +449,113 @@ mod tests {
         assert!(rx.is_closed());
     }
     
+    thread_local! {
+        static CANCELLED: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+    }
+    
+    #[test]
+    fn test_cancelled_waiters_give_up() {
+        cancel::set_current_cancelled_hook(|| CANCELLED.with(|c| c.get()));
+        CANCELLED.with(|c| c.set(true));
+        
+        let (tx, rx) = channel::<i32>(1);
+        assert!(matches!(rx.recv(), Err(SchedError::Cancelled)));
+        tx.send(1).unwrap(); // room available: not a wait
+        assert!(matches!(tx.send(2), Err(SchedError::Cancelled)));
+        assert_eq!(rx.recv().unwrap(), 1); // buffered value still delivered
+        
+        CANCELLED.with(|c| c.set(false));
+    }
+    
+    #[test]
+    fn test_iter_drains_until_closed() {
+        let (tx, rx) = channel(10);
+        for i in 0..5 {
+            tx.try_send(i).unwrap();
+        }
+        drop(tx);
+        assert_eq!(rx.iter().collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+        assert_eq!(rx.iter().next(), None);
+        
+        let (tx, rx) = channel(10);
+        tx.try_send("a").unwrap();
+        drop(tx);
+        let mut got = Vec::new();
+        for v in rx {
+            got.push(v);
+        }
+        assert_eq!(got, ["a"]);
+    }
+    
+    #[test]
+    fn test_try_iter_stops_at_empty() {
+        let (tx, rx) = channel(10);
+        tx.try_send(1).unwrap();
+        tx.try_send(2).unwrap();
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), [1, 2]);
+        // Still open: more may come later
+        tx.try_send(3).unwrap();
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), [3]);
+    }
+    
+    #[test]
+    fn test_unbounded_never_fills() {
+        let (tx, rx) = channel_unbounded();
+        assert_eq!(tx.capacity(), usize::MAX);
+        for i in 0..10_000 {
+            tx.try_send(i).unwrap();
+        }
+        tx.send(10_000).unwrap();
+        assert_eq!(rx.len(), 10_001);
+        drop(tx);
+        assert!(rx.iter().eq(0..=10_000));
+    }
+    
+    #[test]
+    fn test_send_sync_follow_t() {
+        fn assert_send_sync<S: Send + Sync>() {}
+        // Send but not Sync is enough: values are only ever moved through
+        assert_send_sync::<Sender<std::cell::Cell<u32>>>();
+        assert_send_sync::<Receiver<std::cell::Cell<u32>>>();
+    }
+    
+    #[test]
+    fn test_mpmc_threads_lose_and_duplicate_nothing() {
+        const PRODUCERS: u64 = 4;
+        const PER_PRODUCER: u64 = 20_000;
+        
+        let (tx, rx) = channel(16);
+        let producers: Vec<_> = (0..PRODUCERS)
+            .map(|p| {
+                let tx = tx.clone();
+                std::thread::spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        let mut value = p * PER_PRODUCER + i;
+                        while let Err(TrySendError(v)) = tx.try_send(value) {
+                            value = v;
+                            std::thread::yield_now();
+                        }
+                    }
+                })
+            })
+            .collect();
+        drop(tx);
+        let consumers: Vec<_> = (0..3)
+            .map(|_| {
+                let rx = rx.clone();
+                std::thread::spawn(move || rx.iter().collect::<Vec<u64>>())
+            })
+            .collect();
+        drop(rx);
+        
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let mut got: Vec<u64> = consumers.into_iter().flat_map(|c| c.join().unwrap()).collect();
+        got.sort_unstable();
+        assert!(got.into_iter().eq(0..PRODUCERS * PER_PRODUCER));
+    }
+    
     #[test]
     fn test_clone_sender() {
         let (tx1, rx) = channel(10);
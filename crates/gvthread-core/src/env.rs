@@ -14,9 +14,14 @@
 //!
 //! // Boolean helper (accepts "1", "true", "yes", "on")
 //! let debug: bool = env_get_bool("GVT_DEBUG", false);
+//!
+//! // Values with units ("10ms", "1s" / "64KB", "16MB")
+//! let slice = env_get_duration("GVT_TIME_SLICE", Duration::from_millis(10));
+//! let stack = env_get_bytes("GVT_STACK_SIZE", 16 * 1024 * 1024);
 //! ```
 
 use std::str::FromStr;
+use std::time::Duration;
 
 /// Get environment variable parsed as type T, or return default
 ///
@@ -88,6 +93,85 @@ pub fn env_get_str(key: &str, default: &str) -> String {
     std::env::var(key).unwrap_or_else(|_| default.to_string())
 }
 
+/// Get environment variable as a duration with a unit suffix
+///
+/// Accepts an integer followed by `ns`, `us`, `ms`, `s`, `m` or `h`
+/// (e.g. `"10ms"`, `"5us"`, `"1s"`; whitespace before the unit is
+/// allowed). A bare number, unknown unit or overflow returns the default.
+///
+/// # Examples
+///
+/// ```ignore
+/// let slice = env_get_duration("GVT_TIME_SLICE", Duration::from_millis(10));
+/// ```
+#[inline]
+pub fn env_get_duration(key: &str, default: Duration) -> Duration {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| parse_duration(&v))
+        .unwrap_or(default)
+}
+
+/// Get environment variable as a byte size with an optional unit suffix
+///
+/// Accepts an integer, optionally followed by `B`, `K`/`KB`/`KiB`,
+/// `M`/`MB`/`MiB` or `G`/`GB`/`GiB` (case-insensitive). Units are powers
+/// of 1024, as for stack and buffer sizes. Anything else, or overflow,
+/// returns the default.
+///
+/// # Examples
+///
+/// ```ignore
+/// let stack = env_get_bytes("GVT_STACK_SIZE", 16 * 1024 * 1024); // "64KB" → 65536
+/// ```
+#[inline]
+pub fn env_get_bytes(key: &str, default: usize) -> usize {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| parse_bytes(&v))
+        .unwrap_or(default)
+}
+
+/// Split `"16 MB"` into `(16, "MB")`
+fn split_number(s: &str) -> Option<(u64, &str)> {
+    let s = s.trim();
+    let digits = s.bytes().take_while(u8::is_ascii_digit).count();
+    if digits == 0 {
+        return None;
+    }
+    let value = s[..digits].parse().ok()?;
+    Some((value, s[digits..].trim_start()))
+}
+
+/// Parse `"10ms"`-style durations (see `env_get_duration`)
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let (value, unit) = split_number(s)?;
+    let nanos_per_unit: u64 = match unit {
+        "ns" => 1,
+        "us" | "µs" => 1_000,
+        "ms" => 1_000_000,
+        "s" => 1_000_000_000,
+        "m" => 60_000_000_000,
+        "h" => 3_600_000_000_000,
+        _ => return None,
+    };
+    value.checked_mul(nanos_per_unit).map(Duration::from_nanos)
+}
+
+/// Parse `"64KB"`-style sizes (see `env_get_bytes`)
+pub fn parse_bytes(s: &str) -> Option<usize> {
+    let (value, unit) = split_number(s)?;
+    let shift = match unit.to_ascii_lowercase().as_str() {
+        "" | "b" => 0,
+        "k" | "kb" | "kib" => 10,
+        "m" | "mb" | "mib" => 20,
+        "g" | "gb" | "gib" => 30,
+        _ => return None,
+    };
+    let bytes = value.checked_mul(1 << shift)?;
+    usize::try_from(bytes).ok()
+}
+
 /// Check if environment variable is set (regardless of value)
 #[inline]
 pub fn env_is_set(key: &str) -> bool {
@@ -182,4 +266,59 @@ mod tests {
         assert_eq!(val, 99); // Should return default on parse failure
         std::env::remove_var("__TEST_INVALID__");
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("10ms"), Some(Duration::from_millis(10)));
+        assert_eq!(parse_duration("5us"), Some(Duration::from_micros(5)));
+        assert_eq!(parse_duration("1s"), Some(Duration::from_secs(1)));
+        assert_eq!(parse_duration(" 250 ns "), Some(Duration::from_nanos(250)));
+        assert_eq!(parse_duration("2m"), Some(Duration::from_secs(120)));
+        
+        // Malformed
+        assert_eq!(parse_duration("10"), None); // no unit
+        assert_eq!(parse_duration("ms"), None);
+        assert_eq!(parse_duration("1.5s"), None);
+        assert_eq!(parse_duration("-1s"), None);
+        assert_eq!(parse_duration("10 fortnights"), None);
+        assert_eq!(parse_duration("99999999999999999999h"), None);
+    }
+    
+    #[test]
+    fn test_parse_bytes() {
+        assert_eq!(parse_bytes("64KB"), Some(64 * 1024));
+        assert_eq!(parse_bytes("16MB"), Some(16 * 1024 * 1024));
+        assert_eq!(parse_bytes("1g"), Some(1 << 30));
+        assert_eq!(parse_bytes("4096"), Some(4096));
+        assert_eq!(parse_bytes("8 KiB"), Some(8192));
+        
+        // Malformed
+        assert_eq!(parse_bytes(""), None);
+        assert_eq!(parse_bytes("KB"), None);
+        assert_eq!(parse_bytes("12XB"), None);
+        assert_eq!(parse_bytes("0x100"), None);
+        assert_eq!(parse_bytes("18446744073709551615KB"), None);
+    }
+    
+    #[test]
+    fn test_env_get_units() {
+        std::env::set_var("__TEST_DURATION__", "20ms");
+        assert_eq!(
+            env_get_duration("__TEST_DURATION__", Duration::ZERO),
+            Duration::from_millis(20)
+        );
+        std::env::set_var("__TEST_DURATION__", "twenty");
+        assert_eq!(
+            env_get_duration("__TEST_DURATION__", Duration::from_secs(3)),
+            Duration::from_secs(3)
+        );
+        std::env::remove_var("__TEST_DURATION__");
+        
+        std::env::set_var("__TEST_BYTES__", "64KB");
+        assert_eq!(env_get_bytes("__TEST_BYTES__", 1), 65536);
+        std::env::set_var("__TEST_BYTES__", "64 parsecs");
+        assert_eq!(env_get_bytes("__TEST_BYTES__", 1), 1);
+        std::env::remove_var("__TEST_BYTES__");
+        assert_eq!(env_get_bytes("__TEST_BYTES__", 7), 7);
+    }
+}
@@ -26,6 +26,9 @@ pub enum SchedError {
     /// No GVThread slots available
     NoSlotsAvailable,
     
+    /// Scheduler is shutting down and accepts no new GVThreads
+    ShuttingDown,
+    
     /// GVThread not found
     GVThreadNotFound,
     
@@ -38,6 +41,12 @@ pub enum SchedError {
     /// Scheduler already initialized
     AlreadyInitialized,
     
+    /// Scheduler configuration failed validation
+    InvalidConfig(&'static str),
+    
+    /// Runtime started or `block_on` called from inside a GVThread
+    InsideGVThread,
+    
     /// Memory allocation/mapping failed
     MemoryError(MemoryError),
     
@@ -57,10 +66,16 @@ impl fmt::Display for SchedError {
             SchedError::ChannelFull => write!(f, "channel full"),
             SchedError::ChannelEmpty => write!(f, "channel empty"),
             SchedError::NoSlotsAvailable => write!(f, "no GVThread slots available"),
+            SchedError::ShuttingDown => write!(f, "scheduler is shutting down"),
             SchedError::GVThreadNotFound => write!(f, "GVThread not found"),
             SchedError::InvalidState => write!(f, "invalid GVThread state"),
             SchedError::NotInitialized => write!(f, "scheduler not initialized"),
             SchedError::AlreadyInitialized => write!(f, "scheduler already initialized"),
+            SchedError::InvalidConfig(msg) => write!(f, "invalid config: {}", msg),
+            SchedError::InsideGVThread => write!(
+                f,
+                "cannot block_on from within a GVThread; spawn the work and join its JoinHandle instead"
+            ),
             SchedError::MemoryError(e) => write!(f, "memory error: {}", e),
             SchedError::WorkerError(e) => write!(f, "worker error: {}", e),
             SchedError::PlatformError(code) => write!(f, "platform error: {}", code),
@@ -88,6 +103,9 @@ pub enum MemoryError {
     /// Too many slots requested
     TooManySlots,
     
+    /// Slots × slot size past the address space the region may reserve
+    RegionTooLarge,
+    
     /// Invalid slot ID
     InvalidSlot,
 }
@@ -100,6 +118,9 @@ impl fmt::Display for MemoryError {
             MemoryError::AdviseFailed => write!(f, "memory advise failed"),
             MemoryError::AlreadyInitialized => write!(f, "memory region already initialized"),
             MemoryError::TooManySlots => write!(f, "too many slots requested"),
+            MemoryError::RegionTooLarge => {
+                write!(f, "max_gvthreads × stack_size exceeds the reservable address space")
+            }
             MemoryError::InvalidSlot => write!(f, "invalid slot ID"),
         }
     }
@@ -175,6 +196,9 @@ mod tests {
         
         let e = SchedError::MemoryError(MemoryError::AllocationFailed);
         assert_eq!(format!("{}", e), "memory error: memory allocation failed");
+        
+        let e = SchedError::InvalidConfig("num_workers must be > 0");
+        assert_eq!(format!("{}", e), "invalid config: num_workers must be > 0");
     }
     
     #[test]
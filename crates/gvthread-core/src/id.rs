@@ -1,6 +1,7 @@
-//! GVThread identifier type
+//! GVThread identifier types
 
 use core::fmt;
+use core::str::FromStr;
 
 /// Unique identifier for a GVThread
 ///
@@ -53,6 +54,13 @@ impl GVThreadId {
             Some(self)
         }
     }
+    
+    /// This id as of slot `generation`: names one GVThread even after
+    /// the slot is reused
+    #[inline]
+    pub const fn at(self, generation: u32) -> GenerationalId {
+        GenerationalId { id: self, generation }
+    }
 }
 
 impl From<u32> for GVThreadId {
@@ -95,6 +103,70 @@ impl Default for GVThreadId {
     }
 }
 
+/// Parses what `Display` prints: `42` or `none`. A `42@3` form (see
+/// `GenerationalId`) is accepted and its generation dropped.
+impl FromStr for GVThreadId {
+    type Err = ParseIdError;
+    
+    fn from_str(s: &str) -> Result<Self, ParseIdError> {
+        if s == "none" {
+            return Ok(GVThreadId::NONE);
+        }
+        let slot = s.split_once('@').map_or(s, |(slot, _)| slot);
+        slot.parse().map(GVThreadId).map_err(|_| ParseIdError)
+    }
+}
+
+/// A `GVThreadId` plus the generation of its slot
+///
+/// Slots are recycled, so the same numeric id names different GVThreads
+/// over time; the pair tells them apart in logs. Displays as `42@3`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GenerationalId {
+    pub id: GVThreadId,
+    pub generation: u32,
+}
+
+impl fmt::Debug for GenerationalId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GVThreadId")
+            .field("slot", &self.id.as_u32())
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
+
+impl fmt::Display for GenerationalId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.id, self.generation)
+    }
+}
+
+/// Parses `42@3`
+impl FromStr for GenerationalId {
+    type Err = ParseIdError;
+    
+    fn from_str(s: &str) -> Result<Self, ParseIdError> {
+        let (slot, generation) = s.split_once('@').ok_or(ParseIdError)?;
+        Ok(GenerationalId {
+            id: slot.parse()?,
+            generation: generation.parse().map_err(|_| ParseIdError)?,
+        })
+    }
+}
+
+/// A string that isn't a GVThread id (`42`, `none` or `42@3`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseIdError;
+
+impl fmt::Display for ParseIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid GVThread id")
+    }
+}
+
+impl std::error::Error for ParseIdError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,4 +194,20 @@ mod tests {
         let raw: u32 = id.into();
         assert_eq!(raw, 100);
     }
+    
+    #[test]
+    fn test_gvthread_id_round_trips_through_strings() {
+        for id in [GVThreadId::new(0), GVThreadId::new(42), GVThreadId::NONE] {
+            assert_eq!(id.to_string().parse::<GVThreadId>(), Ok(id));
+        }
+        assert_eq!("42@3".parse::<GVThreadId>(), Ok(GVThreadId::new(42)));
+        assert!("x".parse::<GVThreadId>().is_err());
+        
+        let tagged = GVThreadId::new(42).at(3);
+        assert_eq!(tagged.to_string(), "42@3");
+        assert_eq!(format!("{:?}", tagged), "GVThreadId { slot: 42, generation: 3 }");
+        assert_eq!("42@3".parse::<GenerationalId>(), Ok(tagged));
+        assert_eq!("42".parse::<GenerationalId>(), Err(ParseIdError));
+        assert_eq!("42@".parse::<GenerationalId>(), Err(ParseIdError));
+    }
 }
@@ -8,6 +8,7 @@
 //! - `GVT_FLUSH_EPRINT=1` - Flush stderr after each print (useful for debugging crashes)
 //! - `GVT_LOG_LEVEL=<level>` - Set log level: 0=off, 1=error, 2=warn, 3=info, 4=debug, 5=trace
 //! - `GVT_KPRINT_TIME=1` - Include nanosecond timestamp in output
+//! - `GVT_LOG_FORMAT=json` - One JSON object per log line (default: `human`)
 //!
 //! # Output Format
 //!
@@ -19,6 +20,33 @@
 //! - `[INFO]  [12345678] [w2:g--] Worker idle`
 //! - `[ERROR] [w--:g--] Not in runtime context`
 //!
+//! # Sampling
+//!
+//! `kdebug_every!(n, ...)` / `ktrace_every!(n, ...)` emit only the 1st,
+//! (n+1)th, (2n+1)th... call of each call site, so diagnostics can stay
+//! in hot paths (context switches, yields) without flooding the output.
+//! Each site has its own counter; it only advances while the level is
+//! enabled.
+//!
+//! # JSON Output
+//!
+//! `set_format(LogFormat::Json)` (or `GVT_LOG_FORMAT=json`) turns each
+//! leveled line into one JSON object for log pipelines:
+//!
+//! `{"ts":12345678,"level":"debug","worker":0,"gvt":5,"msg":"Started processing"}`
+//!
+//! `ts` is the same nanosecond clock as `GVT_KPRINT_TIME` (always
+//! present), and `worker`/`gvt` are `null` outside a worker/GVThread.
+//! `kprint!`/`kprintln!` output is written as-is in both formats.
+//!
+//! # Output Sink
+//!
+//! Everything goes to stderr unless redirected with `set_sink()` (any
+//! `Write + Send`, e.g. a `File` or an in-memory ring buffer) or
+//! `set_sink_fd()` (an already open descriptor); `reset_sink()` goes back
+//! to stderr. The sink is called under a lock, from worker, timer and
+//! application threads alike, so it must not log through `kprint` itself.
+//!
 //! # Usage
 //!
 //! ```ignore
@@ -32,7 +60,8 @@
 //! ```
 
 use std::io::Write;
-use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering};
+use std::sync::Mutex;
 use std::time::Instant;
 use crate::env::env_get_bool;
 
@@ -60,6 +89,18 @@ impl LogLevel {
         }
     }
     
+    /// Lowercase name, as used in JSON output
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogLevel::Off => "off",
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+    
     pub fn prefix(&self) -> &'static str {
         match self {
             LogLevel::Off => "",
@@ -72,15 +113,31 @@ impl LogLevel {
     }
 }
 
+/// Line format for leveled logs
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// `[LEVEL] [w<worker>:g<gvthread>] message`
+    #[default]
+    Human = 0,
+    /// `{"ts":..,"level":..,"worker":..,"gvt":..,"msg":..}`
+    Json = 1,
+}
+
 // Global configuration (initialized once)
 static FLUSH_ENABLED: AtomicBool = AtomicBool::new(false);
 static TIME_ENABLED: AtomicBool = AtomicBool::new(false);
 static LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Info as u8);
+static LOG_FORMAT: AtomicU8 = AtomicU8::new(LogFormat::Human as u8);
 static INITIALIZED: AtomicBool = AtomicBool::new(false);
 
 // Start time for relative timestamps
 static START_TIME: std::sync::OnceLock<Instant> = std::sync::OnceLock::new();
 
+// Custom output sink (None = stderr); SINK_SET skips the lock for stderr
+static SINK: Mutex<Option<Box<dyn Write + Send>>> = Mutex::new(None);
+static SINK_SET: AtomicBool = AtomicBool::new(false);
+
 /// Initialize logging from environment variables
 /// 
 /// Called automatically on first log, but can be called explicitly for
@@ -99,6 +156,13 @@ pub fn init() {
     // Check GVT_KPRINT_TIME
     TIME_ENABLED.store(env_get_bool("GVT_KPRINT_TIME", false), Ordering::Relaxed);
     
+    // Check GVT_LOG_FORMAT
+    if let Ok(val) = std::env::var("GVT_LOG_FORMAT") {
+        if val.eq_ignore_ascii_case("json") {
+            LOG_FORMAT.store(LogFormat::Json as u8, Ordering::Relaxed);
+        }
+    }
+    
     // Check GVT_LOG_LEVEL
     if let Ok(val) = std::env::var("GVT_LOG_LEVEL") {
         let level = match val.to_lowercase().as_str() {
@@ -141,6 +205,23 @@ pub fn log_level() -> LogLevel {
     LogLevel::from_u8(LOG_LEVEL.load(Ordering::Relaxed))
 }
 
+/// Get current line format
+#[inline]
+pub fn log_format() -> LogFormat {
+    if !INITIALIZED.load(Ordering::Relaxed) {
+        init();
+    }
+    match LOG_FORMAT.load(Ordering::Relaxed) {
+        1 => LogFormat::Json,
+        _ => LogFormat::Human,
+    }
+}
+
+/// Set line format programmatically
+pub fn set_format(format: LogFormat) {
+    LOG_FORMAT.store(format as u8, Ordering::Relaxed);
+}
+
 /// Set log level programmatically
 pub fn set_log_level(level: LogLevel) {
     LOG_LEVEL.store(level as u8, Ordering::Relaxed);
@@ -156,6 +237,67 @@ pub fn set_time_enabled(enabled: bool) {
     TIME_ENABLED.store(enabled, Ordering::Relaxed);
 }
 
+/// Send all output to `sink` instead of stderr
+///
+/// Replaces (and drops) any previous sink. Context prefixes, levels and
+/// flushing behave as with stderr.
+pub fn set_sink(sink: Box<dyn Write + Send>) {
+    *SINK.lock().unwrap_or_else(|e| e.into_inner()) = Some(sink);
+    SINK_SET.store(true, Ordering::Release);
+}
+
+/// Send all output to an open file descriptor (Unix)
+///
+/// The descriptor is borrowed, not owned: it is never closed here, and
+/// must stay open until the sink is replaced or reset.
+#[cfg(unix)]
+pub fn set_sink_fd(fd: std::os::unix::io::RawFd) {
+    use std::os::unix::io::FromRawFd;
+    let file = unsafe { std::fs::File::from_raw_fd(fd) };
+    set_sink(Box::new(FdSink(std::mem::ManuallyDrop::new(file))));
+}
+
+/// Go back to writing to stderr
+pub fn reset_sink() {
+    SINK_SET.store(false, Ordering::Release);
+    *SINK.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Borrowed descriptor used by `set_sink_fd`
+#[cfg(unix)]
+struct FdSink(std::mem::ManuallyDrop<std::fs::File>);
+
+#[cfg(unix)]
+impl Write for FdSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf)
+    }
+    
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+/// Run `f` with the current sink locked, flushing after if enabled
+fn with_sink(f: impl FnOnce(&mut dyn Write)) {
+    if SINK_SET.load(Ordering::Acquire) {
+        let mut sink = SINK.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(w) = sink.as_mut() {
+            f(w.as_mut());
+            if flush_enabled() {
+                let _ = w.flush();
+            }
+            return;
+        }
+    }
+    let stderr = std::io::stderr();
+    let mut handle = stderr.lock();
+    f(&mut handle);
+    if flush_enabled() {
+        let _ = handle.flush();
+    }
+}
+
 /// Check if a log level is enabled
 #[inline]
 pub fn level_enabled(level: LogLevel) -> bool {
@@ -208,7 +350,7 @@ pub fn get_gvthread_id() -> Option<u32> {
 }
 
 /// Write context string [w<id>:g<id>] directly to handle (no heap allocation)
-fn write_context(handle: &mut std::io::StderrLock<'_>) -> std::io::Result<()> {
+fn write_context(handle: &mut dyn Write) -> std::io::Result<()> {
     let _ = handle.write_all(b"[w");
     match get_worker_id() {
         Some(id) => { let _ = write!(handle, "{}", id); }
@@ -222,39 +364,16 @@ fn write_context(handle: &mut std::io::StderrLock<'_>) -> std::io::Result<()> {
     handle.write_all(b"] ")
 }
 
-/// Internal: Write and optionally flush (no context)
-#[doc(hidden)]
-pub fn _kprint_impl(args: std::fmt::Arguments<'_>) {
-    let stderr = std::io::stderr();
-    let mut handle = stderr.lock();
-    let _ = handle.write_fmt(args);
-    if flush_enabled() {
-        let _ = handle.flush();
-    }
-}
-
-/// Internal: Write with newline and optionally flush (no context)
-#[doc(hidden)]
-pub fn _kprintln_impl(args: std::fmt::Arguments<'_>) {
-    let stderr = std::io::stderr();
-    let mut handle = stderr.lock();
-    let _ = handle.write_fmt(args);
-    let _ = handle.write_all(b"\n");
-    if flush_enabled() {
-        let _ = handle.flush();
+/// Write one leveled log line in the current format
+fn write_record(handle: &mut dyn Write, level: LogLevel, args: std::fmt::Arguments<'_>) {
+    match log_format() {
+        LogFormat::Human => write_human(handle, level, args),
+        LogFormat::Json => write_json(handle, level, args),
     }
 }
 
-/// Internal: Leveled print with context
-#[doc(hidden)]
-pub fn _klog_impl(level: LogLevel, args: std::fmt::Arguments<'_>) {
-    if !level_enabled(level) {
-        return;
-    }
-    
-    let stderr = std::io::stderr();
-    let mut handle = stderr.lock();
-    
+/// `[LEVEL] [ns] [w<id>:g<id>] message`
+fn write_human(handle: &mut dyn Write, level: LogLevel, args: std::fmt::Arguments<'_>) {
     // Level prefix
     let _ = write!(handle, "{} ", level.prefix());
     
@@ -264,22 +383,114 @@ pub fn _klog_impl(level: LogLevel, args: std::fmt::Arguments<'_>) {
     }
     
     // Context [worker:gvthread] - written directly, no heap allocation
-    let _ = write_context(&mut handle);
+    let _ = write_context(handle);
     
     // User message
     let _ = handle.write_fmt(args);
     let _ = handle.write_all(b"\n");
+}
+
+/// `{"ts":..,"level":..,"worker":..,"gvt":..,"msg":..}`
+fn write_json(handle: &mut dyn Write, level: LogLevel, args: std::fmt::Arguments<'_>) {
+    let _ = write!(handle, "{{\"ts\":{},\"level\":\"{}\",\"worker\":", elapsed_ns(), level.name());
+    let _ = match get_worker_id() {
+        Some(id) => write!(handle, "{}", id),
+        None => handle.write_all(b"null"),
+    };
+    let _ = handle.write_all(b",\"gvt\":");
+    let _ = match get_gvthread_id() {
+        Some(id) => write!(handle, "{}", id),
+        None => handle.write_all(b"null"),
+    };
+    let _ = handle.write_all(b",\"msg\":\"");
+    // Escaped as it is formatted - no intermediate String
+    let _ = std::fmt::write(&mut JsonEscape(handle), args);
+    let _ = handle.write_all(b"\"}\n");
+}
+
+/// Escapes formatted text for a JSON string literal
+struct JsonEscape<'a>(&'a mut dyn Write);
+
+impl std::fmt::Write for JsonEscape<'_> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        let mut start = 0;
+        for (i, b) in s.bytes().enumerate() {
+            let escaped: Option<&[u8]> = match b {
+                b'"' => Some(b"\\\""),
+                b'\\' => Some(b"\\\\"),
+                b'\n' => Some(b"\\n"),
+                b'\r' => Some(b"\\r"),
+                b'\t' => Some(b"\\t"),
+                0x00..=0x1F => None,
+                _ => continue,
+            };
+            self.0.write_all(&s.as_bytes()[start..i]).map_err(|_| std::fmt::Error)?;
+            match escaped {
+                Some(e) => self.0.write_all(e),
+                None => write!(self.0, "\\u{:04x}", b),
+            }
+            .map_err(|_| std::fmt::Error)?;
+            start = i + 1;
+        }
+        self.0.write_all(&s.as_bytes()[start..]).map_err(|_| std::fmt::Error)
+    }
+}
+
+/// Per-call-site counter behind the `*_every!` macros
+#[doc(hidden)]
+pub struct LogSampler {
+    calls: AtomicU64,
+}
+
+impl LogSampler {
+    pub const fn new() -> Self {
+        Self { calls: AtomicU64::new(0) }
+    }
     
-    if flush_enabled() {
-        let _ = handle.flush();
+    /// Count a call; `true` for every `n`th one, starting with the first
+    #[inline]
+    pub fn sample(&self, n: u64) -> bool {
+        self.calls.fetch_add(1, Ordering::Relaxed) % n.max(1) == 0
+    }
+}
+
+impl Default for LogSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Internal: Write and optionally flush (no context)
+#[doc(hidden)]
+pub fn _kprint_impl(args: std::fmt::Arguments<'_>) {
+    with_sink(|handle| {
+        let _ = handle.write_fmt(args);
+    });
+}
+
+/// Internal: Write with newline and optionally flush (no context)
+#[doc(hidden)]
+pub fn _kprintln_impl(args: std::fmt::Arguments<'_>) {
+    with_sink(|handle| {
+        let _ = handle.write_fmt(args);
+        let _ = handle.write_all(b"\n");
+    });
+}
+
+/// Internal: Leveled print with context
+#[doc(hidden)]
+pub fn _klog_impl(level: LogLevel, args: std::fmt::Arguments<'_>) {
+    if !level_enabled(level) {
+        return;
     }
+    with_sink(|handle| write_record(handle, level, args));
 }
 
 // ============================================================================
 // Public Macros
 // ============================================================================
 
-/// Print to stderr (no newline, no context)
+/// Print to the sink, stderr by default (no newline, no context)
 /// 
 /// Like `eprint!` but with optional auto-flush and mutex protection.
 #[macro_export]
@@ -289,7 +500,7 @@ macro_rules! kprint {
     }};
 }
 
-/// Print to stderr with newline (no context)
+/// Print to the sink, stderr by default, with newline (no context)
 /// 
 /// Like `eprintln!` but with optional auto-flush and mutex protection.
 #[macro_export]
@@ -357,6 +568,42 @@ macro_rules! ktrace {
     }};
 }
 
+/// Debug level log with context, emitted once every `n` calls per site
+///
+/// ```ignore
+/// kdebug_every!(1000, "switched to {}", id);
+/// ```
+#[macro_export]
+macro_rules! kdebug_every {
+    ($n:expr, $($arg:tt)*) => {{
+        if $crate::kprint::level_enabled($crate::kprint::LogLevel::Debug) {
+            static SITE: $crate::kprint::LogSampler = $crate::kprint::LogSampler::new();
+            if SITE.sample($n) {
+                $crate::kprint::_klog_impl(
+                    $crate::kprint::LogLevel::Debug,
+                    format_args!($($arg)*)
+                );
+            }
+        }
+    }};
+}
+
+/// Trace level log with context, emitted once every `n` calls per site
+#[macro_export]
+macro_rules! ktrace_every {
+    ($n:expr, $($arg:tt)*) => {{
+        if $crate::kprint::level_enabled($crate::kprint::LogLevel::Trace) {
+            static SITE: $crate::kprint::LogSampler = $crate::kprint::LogSampler::new();
+            if SITE.sample($n) {
+                $crate::kprint::_klog_impl(
+                    $crate::kprint::LogLevel::Trace,
+                    format_args!($($arg)*)
+                );
+            }
+        }
+    }};
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -410,6 +657,78 @@ mod tests {
         assert!(t2 > t1);
     }
     
+    #[test]
+    fn test_record_format() {
+        set_worker_id(3);
+        let mut out = Vec::new();
+        write_record(&mut out, LogLevel::Warn, format_args!("queue at {}%", 90));
+        clear_worker_id();
+        
+        let line = String::from_utf8(out).unwrap();
+        assert!(line.starts_with("[WARN]  "));
+        assert!(line.ends_with("[w3:g--] queue at 90%\n"), "{:?}", line);
+    }
+    
+    #[test]
+    fn test_json_record() {
+        set_gvthread_id(9);
+        let mut out = Vec::new();
+        write_json(&mut out, LogLevel::Debug, format_args!("say \"{}\"\n\ttab \\ {}", "hi", '\u{1}'));
+        clear_gvthread_id();
+        
+        let line = String::from_utf8(out).unwrap();
+        assert!(line.starts_with("{\"ts\":"));
+        assert!(line.ends_with(
+            "\"level\":\"debug\",\"worker\":null,\"gvt\":9,\"msg\":\"say \\\"hi\\\"\\n\\ttab \\\\ \\u0001\"}\n"
+        ), "{:?}", line);
+    }
+    
+    #[test]
+    fn test_custom_sink() {
+        #[derive(Clone)]
+        struct Shared(std::sync::Arc<Mutex<Vec<u8>>>);
+        impl Write for Shared {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+        
+        let captured = Shared(Default::default());
+        set_sink(Box::new(captured.clone()));
+        kprintln!("to the sink {}", 7);
+        reset_sink();
+        kprintln!("back to stderr");
+        
+        let text = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(text.contains("to the sink 7\n"));
+        assert!(!text.contains("back to stderr"));
+    }
+    
+    #[test]
+    fn test_sampler() {
+        let site = LogSampler::new();
+        let hits: Vec<u64> = (0..10).filter(|_| site.sample(4)).collect();
+        assert_eq!(hits.len(), 3); // calls 0, 4, 8
+        
+        // n = 0 behaves like 1 rather than dividing by zero
+        assert!(LogSampler::new().sample(0));
+        
+        // Shared across threads, still exactly one in n
+        let site = std::sync::Arc::new(LogSampler::new());
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let site = site.clone();
+                std::thread::spawn(move || (0..1000).filter(|_| site.sample(10)).count())
+            })
+            .collect();
+        let total: usize = threads.into_iter().map(|t| t.join().unwrap()).sum();
+        assert_eq!(total, 400);
+    }
+    
     #[test]
     fn test_macros_compile() {
         // Just verify macros compile - actual output tested manually
@@ -422,5 +741,7 @@ mod tests {
         kinfo!("info");
         kdebug!("debug");
         ktrace!("trace");
+        kdebug_every!(10, "debug {}", 1);
+        ktrace_every!(10, "trace");
     }
 }
\ No newline at end of file
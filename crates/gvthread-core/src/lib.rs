@@ -17,6 +17,7 @@
 //! - `cancel` - Cancellation token for cooperative cancellation
 //! - `error` - Error types
 //! - `spinlock` - Internal spinlock primitive
+//! - `preempt` - Sections forced preemption must not interrupt
 //! - `traits` - Platform and architecture traits
 //! - `kprint` - Kernel-style debug printing macros
 //! - `env` - Environment variable utilities
@@ -33,28 +34,31 @@ pub mod mutex;
 pub mod cancel;
 pub mod error;
 pub mod spinlock;
+pub mod preempt;
 pub mod traits;
 pub mod kprint;
 pub mod env;
 
 // Re-exports for convenience
-pub use id::GVThreadId;
+pub use id::{GVThreadId, GenerationalId, ParseIdError};
 pub use state::{GVThreadState, Priority};
-pub use metadata::{GVThreadMetadata, WorkerState, WORKER_STATE_SIZE};
+pub use metadata::{GVThreadMetadata, GVThreadStats, WorkerState, WORKER_STATE_SIZE};
 pub use bitmap::ReadyBitmaps;
 pub use slot::SlotAllocator;
-pub use channel::{channel, Sender, Receiver};
+pub use channel::{channel, channel_unbounded, Sender, Receiver};
 pub use mutex::SchedMutex;
 pub use cancel::CancellationToken;
 pub use error::{SchedError, SchedResult};
 pub use spinlock::SpinLock;
-pub use env::{env_get, env_get_bool, env_get_opt, env_get_str, env_is_set};
+pub use env::{env_get, env_get_bool, env_get_bytes, env_get_duration, env_get_opt, env_get_str, env_is_set};
 
 /// Constants for memory layout
 pub mod constants {
-    /// Slot size - configurable via feature flag
-    /// Default: 16KB (4 pages) for debugging, gives ~8KB usable stack
-    /// For production: rebuild with larger size or use GVT_SLOT_PAGES env
+    /// Compile-time slot size (`large-stack` feature)
+    ///
+    /// The runtime does not use it: its slots are sized from
+    /// `SchedulerConfig::stack_size` (`GVT_STACK_SIZE`) plus the metadata
+    /// and guard pages.
     #[cfg(feature = "large-stack")]
     pub const SLOT_SIZE: usize = 16 * 1024 * 1024;  // 16 MB
     
@@ -67,7 +71,7 @@ pub mod constants {
     /// Metadata size at start of slot (4 KB, one page)
     pub const METADATA_SIZE: usize = 4096;
     
-    /// Stack size within slot (slot - metadata - guard)
+    /// Stack size within a `SLOT_SIZE` slot (slot - metadata - guard)
     pub const STACK_SIZE: usize = SLOT_SIZE - METADATA_SIZE - GUARD_SIZE;
     
     /// Maximum workers (OS threads)
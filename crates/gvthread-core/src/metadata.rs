@@ -3,8 +3,9 @@
 //! These structures have fixed layouts (repr(C)) for direct memory access
 //! from assembly code and signal handlers.
 
-use core::sync::atomic::{AtomicU8, AtomicU32, AtomicU64, AtomicBool, Ordering};
-use crate::id::GVThreadId;
+use core::sync::atomic::{AtomicU8, AtomicU16, AtomicU32, AtomicU64, AtomicBool, AtomicPtr, Ordering};
+use std::sync::OnceLock;
+use crate::id::{GVThreadId, GenerationalId};
 use crate::state::{GVThreadState, Priority};
 use crate::constants::{CACHE_LINE_SIZE, GVTHREAD_NONE};
 
@@ -17,6 +18,15 @@ pub const VOLUNTARY_SAVE_SIZE: usize = 64;
 /// Size of forced saved registers (all registers for SIGURG)
 pub const FORCED_SAVE_SIZE: usize = 256;
 
+/// Size of the saved x87/SSE state (FXSAVE format)
+pub const FPU_SAVE_SIZE: usize = 512;
+
+/// Offset of `GVThreadMetadata::voluntary_regs`, fixed for the context
+/// switch assembly; use `GVThreadMetadata::saved_regs_ptr`
+pub const VOLUNTARY_REGS_OFFSET: usize = 0x40;
+
+const _: () = assert!(core::mem::offset_of!(GVThreadMetadata, voluntary_regs) == VOLUNTARY_REGS_OFFSET);
+
 /// GVThread metadata at the start of each slot
 ///
 /// Layout (offsets are stable for ASM access):
@@ -24,7 +34,7 @@ pub const FORCED_SAVE_SIZE: usize = 256;
 /// 0x00: preempt_flag    (u8)  - Set by timer, checked at safepoints
 /// 0x01: cancelled       (u8)  - Cancellation flag
 /// 0x02: state           (u8)  - GVThreadState
-/// 0x03: priority        (u8)  - Priority level
+/// 0x03: priority        (u8)  - Effective priority (may be boosted)
 /// 0x04: gvthread_id     (u32) - Self ID
 /// 0x08: parent_id       (u32) - Parent GVThread ID
 /// 0x0C: worker_id       (u32) - Current/last worker ID
@@ -34,9 +44,18 @@ pub const FORCED_SAVE_SIZE: usize = 256;
 /// 0x28: generation      (u32) - Generation counter for slot reuse detection
 /// 0x2C: sleep_flag      (u32) - Non-zero if sleeping (needs timer processing)
 /// 0x30: wake_time_ns    (u64) - Absolute wake time in nanoseconds
-/// 0x38: reserved        (8 bytes)
+/// 0x38: base_priority   (u8)  - Priority given at spawn
+/// 0x39: switching       (u8)  - On its way to a context switch (see below)
+/// 0x3A: preempt_off     (u16) - Open `preempt::disable` sections
+/// 0x3C: reserved        (4 bytes)
 /// 0x40: voluntary_regs  (64 bytes)  - Callee-saved registers
 /// 0x80: forced_regs     (256 bytes) - All registers (SIGURG)
+/// 0x180: fpu_state      (512 bytes) - x87/SSE state (SIGURG)
+/// 0x380: voluntary_yields   (u64) - yield_now / yield_to calls
+/// 0x388: forced_preemptions (u64) - Times the runtime signalled it off
+/// 0x390: total_run_ns       (u64) - Time spent running on a worker
+/// 0x398: times_scheduled    (u64) - Times a worker switched into it
+/// 0x3A0: locals             (ptr) - `gvthread_local!` values (runtime-owned)
 /// ```
 #[repr(C, align(64))]
 pub struct GVThreadMetadata {
@@ -65,8 +84,23 @@ pub struct GVThreadMetadata {
     /// Absolute wake time in nanoseconds (valid when sleep_flag != 0)
     pub wake_time_ns: AtomicU64,
     
-    // Reserved for future use (offset 0x38-0x3F)
-    _reserved: [u8; 8],
+    // Priority given at spawn; `priority` returns here when a boost
+    // (priority inheritance) ends (offset 0x38)
+    pub base_priority: AtomicU8,
+    
+    // Non-zero while the GVThread is on its way to a context switch:
+    // committed to blocking, inside a yield, or forcibly switched out and
+    // not yet restored. SIGURG never switches it then (offset 0x39).
+    pub switching: AtomicU8,
+    
+    // `preempt::disable` sections the GVThread has open. The worker's own
+    // count is per OS thread, and a GVThread switched out halfway through
+    // updating it would finish on another worker's; counted here too, it
+    // can't be switched out there in the first place (offset 0x3A).
+    pub preempt_off: AtomicU16,
+    
+    // Reserved for future use (offset 0x3C-0x3F)
+    _reserved: [u8; 4],
     
     // Saved registers for voluntary yield (offset 0x40-0x7F)
     // rsp, rip, rbx, rbp, r12, r13, r14, r15
@@ -75,6 +109,56 @@ pub struct GVThreadMetadata {
     // Saved registers for forced preemption (offset 0x80-0x17F)
     // All general purpose + flags + FPU state pointer
     pub forced_regs: ForcedSavedRegs,
+    
+    // x87/SSE registers at forced preemption (offset 0x180-0x37F);
+    // `forced_regs.fpu_state_ptr` points here once captured
+    pub fpu_state: FpuState,
+    
+    // Scheduling counters (offset 0x380-0x39F), relaxed; see `stats()`
+    pub voluntary_yields: AtomicU64,
+    pub forced_preemptions: AtomicU64,
+    pub total_run_ns: AtomicU64,
+    pub times_scheduled: AtomicU64,
+    
+    // Per-GVThread storage (offset 0x3A0): the runtime's map of
+    // `gvthread_local!` values, null until first used. Only the
+    // GVThread itself and its finish path touch it.
+    pub locals: AtomicPtr<()>,
+}
+
+/// Per-GVThread scheduling counters (see `GVThreadMetadata::stats`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GVThreadStats {
+    /// `yield_now` / `yield_to` calls
+    pub voluntary_yields: u64,
+    /// Times the runtime sent SIGURG to take its worker: it ignored the
+    /// yield flag past the grace period, or a Critical GVThread needed
+    /// the worker. A flag it honoured at a safepoint isn't counted.
+    pub forced_preemptions: u64,
+    /// Time spent running on a worker, in nanoseconds
+    pub total_run_ns: u64,
+    /// Times a worker switched into it
+    pub times_scheduled: u64,
+}
+
+/// Returns the calling GVThread's metadata (`None` outside a GVThread)
+///
+/// Installed once by the runtime; lets primitives in this crate (which
+/// can't see the scheduler's TLS) find out who is calling.
+pub type CurrentMetadataHook = fn() -> Option<&'static GVThreadMetadata>;
+
+static CURRENT_HOOK: OnceLock<CurrentMetadataHook> = OnceLock::new();
+
+/// Install the runtime's current-GVThread lookup (first call wins)
+pub fn set_current_metadata_hook(hook: CurrentMetadataHook) {
+    let _ = CURRENT_HOOK.set(hook);
+}
+
+/// The calling GVThread's metadata (`None` outside a GVThread or before
+/// the runtime started)
+#[inline]
+pub fn current() -> Option<&'static GVThreadMetadata> {
+    CURRENT_HOOK.get().and_then(|hook| hook())
 }
 
 /// Saved registers for voluntary yield (callee-saved per System V AMD64 ABI)
@@ -135,13 +219,31 @@ pub struct ForcedSavedRegs {
     pub cs: u64,
     pub ss: u64,
     
-    // FPU/SSE state pointer (points to separate storage if needed)
+    // Saved x87/SSE state (`GVThreadMetadata::fpu_state`), 0 if none
     pub fpu_state_ptr: u64,
     
     // Padding to 256 bytes
     _padding: [u64; 11],
 }
 
+/// x87/SSE register state in FXSAVE layout (the same layout as the
+/// kernel's `fpstate` in a signal frame): control/status words, MXCSR,
+/// ST0-7 at 0x20 and XMM0-15 at 0xA0
+///
+/// A forcibly preempted GVThread may be in the middle of floating-point
+/// work, and any XMM register may be live there — not only the ones the
+/// ABI preserves across calls — so all of them are saved.
+#[repr(C, align(16))]
+pub struct FpuState {
+    pub bytes: [u8; FPU_SAVE_SIZE],
+}
+
+impl Default for FpuState {
+    fn default() -> Self {
+        Self { bytes: [0; FPU_SAVE_SIZE] }
+    }
+}
+
 impl Default for ForcedSavedRegs {
     fn default() -> Self {
         Self {
@@ -174,7 +276,10 @@ impl GVThreadMetadata {
             generation: AtomicU32::new(0),
             sleep_flag: AtomicU32::new(0),
             wake_time_ns: AtomicU64::new(0),
-            _reserved: [0; 8],
+            base_priority: AtomicU8::new(Priority::Normal as u8),
+            switching: AtomicU8::new(0),
+            preempt_off: AtomicU16::new(0),
+            _reserved: [0; 4],
             voluntary_regs: VoluntarySavedRegs {
                 rsp: 0, rip: 0, rbx: 0, rbp: 0,
                 r12: 0, r13: 0, r14: 0, r15: 0,
@@ -188,20 +293,70 @@ impl GVThreadMetadata {
                 fpu_state_ptr: 0,
                 _padding: [0; 11],
             },
+            fpu_state: FpuState { bytes: [0; FPU_SAVE_SIZE] },
+            voluntary_yields: AtomicU64::new(0),
+            forced_preemptions: AtomicU64::new(0),
+            total_run_ns: AtomicU64::new(0),
+            times_scheduled: AtomicU64::new(0),
+            locals: AtomicPtr::new(core::ptr::null_mut()),
         }
     }
     
+    /// Retire the slot's previous occupant before the slot is handed out
+    ///
+    /// Publishes `generation` first, so from here on anyone checking the
+    /// previous occupant's generation sees a mismatch, then clears what
+    /// wakers and the signal handler read without one: state, flags and
+    /// counters. A released slot's page may still hold all of it
+    /// (MADV_FREE, or not yet reclaimed); `init` fills in the rest.
+    pub fn reset(&self, generation: u32) {
+        self.generation.store(generation, Ordering::SeqCst);
+        // Raw store: Finished -> Created is no transition of a GVThread
+        self.state.store(GVThreadState::Created as u8, Ordering::Release);
+        self.preempt_flag.store(0, Ordering::Relaxed);
+        self.cancelled.store(0, Ordering::Relaxed);
+        self.switching.store(0, Ordering::Relaxed);
+        self.preempt_off.store(0, Ordering::Relaxed);
+        self.sleep_flag.store(0, Ordering::Relaxed);
+        self.wake_time_ns.store(0, Ordering::Relaxed);
+        self.voluntary_yields.store(0, Ordering::Relaxed);
+        self.forced_preemptions.store(0, Ordering::Relaxed);
+        self.total_run_ns.store(0, Ordering::Relaxed);
+        self.times_scheduled.store(0, Ordering::Relaxed);
+    }
+
     /// Initialize metadata for a new GVThread
-    pub fn init(&self, id: GVThreadId, parent: GVThreadId, priority: Priority) {
+    ///
+    /// `generation` is the one `SlotAllocator::allocate` handed out with
+    /// `id`; stale wakes for the slot's previous occupants check against it.
+    pub fn init(&self, id: GVThreadId, generation: u32, parent: GVThreadId, priority: Priority) {
         self.preempt_flag.store(0, Ordering::Relaxed);
         self.cancelled.store(0, Ordering::Relaxed);
         self.state.store(GVThreadState::Created as u8, Ordering::Relaxed);
         self.priority.store(priority as u8, Ordering::Relaxed);
+        self.base_priority.store(priority as u8, Ordering::Relaxed);
+        self.switching.store(0, Ordering::Relaxed);
         self.gvthread_id.store(id.as_u32(), Ordering::Relaxed);
         self.parent_id.store(parent.as_u32(), Ordering::Relaxed);
         self.worker_id.store(GVTHREAD_NONE, Ordering::Relaxed);
-        // Increment generation on each reuse for stale wake detection
-        self.generation.fetch_add(1, Ordering::Relaxed);
+        self.result_ptr.store(0, Ordering::Relaxed);
+        self.sleep_flag.store(0, Ordering::Relaxed);
+        self.wake_time_ns.store(0, Ordering::Relaxed);
+        self.voluntary_yields.store(0, Ordering::Relaxed);
+        self.forced_preemptions.store(0, Ordering::Relaxed);
+        self.total_run_ns.store(0, Ordering::Relaxed);
+        self.times_scheduled.store(0, Ordering::Relaxed);
+        self.locals.store(core::ptr::null_mut(), Ordering::Relaxed);
+        self.generation.store(generation, Ordering::Release);
+    }
+    
+    /// The voluntary-switch register save area of the metadata at `meta`
+    ///
+    /// Takes the raw slot pointer rather than `&self`: the context switch
+    /// writes through the result.
+    #[inline]
+    pub const fn saved_regs_ptr(meta: *mut Self) -> *mut VoluntarySavedRegs {
+        meta.cast::<u8>().wrapping_add(VOLUNTARY_REGS_OFFSET).cast()
     }
     
     // Accessor methods
@@ -211,16 +366,55 @@ impl GVThreadMetadata {
         GVThreadState::from(self.state.load(Ordering::Acquire))
     }
     
+    /// Publish a new state
+    ///
+    /// Debug builds check the move against
+    /// `GVThreadState::can_transition_to` and fail loudly on an illegal
+    /// one; release builds store unchecked.
     #[inline]
     pub fn set_state(&self, state: GVThreadState) {
+        #[cfg(debug_assertions)]
+        {
+            // swap, not load + store: the check sees the exact state replaced
+            let from = GVThreadState::from(self.state.swap(state as u8, Ordering::AcqRel));
+            if !from.can_transition_to(state) {
+                crate::kwarn!(
+                    "GVThread {}: illegal state transition {:?} -> {:?}",
+                    self.generational_id(), from, state
+                );
+                debug_assert!(false, "illegal GVThread state transition {:?} -> {:?}", from, state);
+            }
+        }
+        #[cfg(not(debug_assertions))]
         self.state.store(state as u8, Ordering::Release);
     }
     
+    /// Effective priority: the base priority, or higher while boosted
     #[inline]
     pub fn get_priority(&self) -> Priority {
         Priority::from(self.priority.load(Ordering::Relaxed))
     }
     
+    #[inline]
+    pub fn get_base_priority(&self) -> Priority {
+        Priority::from(self.base_priority.load(Ordering::Relaxed))
+    }
+    
+    /// Raise the effective priority to at least `priority`
+    ///
+    /// Returns `true` if it changed. Never lowers it.
+    #[inline]
+    pub fn boost_priority(&self, priority: Priority) -> bool {
+        // Lower value = higher priority
+        self.priority.fetch_min(priority as u8, Ordering::AcqRel) > priority as u8
+    }
+    
+    /// End any boost: effective priority returns to the base priority
+    #[inline]
+    pub fn restore_priority(&self) {
+        self.priority.store(self.base_priority.load(Ordering::Relaxed), Ordering::Release);
+    }
+    
     #[inline]
     pub fn is_preempt_requested(&self) -> bool {
         self.preempt_flag.load(Ordering::Acquire) != 0
@@ -236,6 +430,29 @@ impl GVThreadMetadata {
         self.preempt_flag.store(0, Ordering::Relaxed);
     }
     
+    /// Called by the GVThread itself before it commits to switching out
+    /// (registering as a waiter, yielding): forced preemption leaves it
+    /// alone until `end_switch`, after it is resumed. Left set by a wait
+    /// that never blocked, it only delays forced preemption to the next
+    /// switch.
+    #[inline]
+    pub fn begin_switch(&self) {
+        self.switching.store(1, Ordering::Relaxed);
+        // Ordered against the signal handler on this same thread
+        core::sync::atomic::compiler_fence(Ordering::SeqCst);
+    }
+    
+    #[inline]
+    pub fn end_switch(&self) {
+        core::sync::atomic::compiler_fence(Ordering::SeqCst);
+        self.switching.store(0, Ordering::Relaxed);
+    }
+    
+    #[inline]
+    pub fn is_switching(&self) -> bool {
+        self.switching.load(Ordering::Relaxed) != 0
+    }
+    
     #[inline]
     pub fn is_cancelled(&self) -> bool {
         self.cancelled.load(Ordering::Acquire) != 0
@@ -255,6 +472,44 @@ impl GVThreadMetadata {
     pub fn get_generation(&self) -> u32 {
         self.generation.load(Ordering::Acquire)
     }
+    
+    /// Id and generation together (`42@3` in logs)
+    #[inline]
+    pub fn generational_id(&self) -> GenerationalId {
+        self.get_id().at(self.get_generation())
+    }
+    
+    #[inline]
+    pub fn record_voluntary_yield(&self) {
+        self.voluntary_yields.fetch_add(1, Ordering::Relaxed);
+    }
+    
+    #[inline]
+    pub fn record_forced_preemption(&self) {
+        self.forced_preemptions.fetch_add(1, Ordering::Relaxed);
+    }
+    
+    /// A worker switched into this GVThread
+    #[inline]
+    pub fn record_scheduled(&self) {
+        self.times_scheduled.fetch_add(1, Ordering::Relaxed);
+    }
+    
+    /// This GVThread ran for `ns` before switching out
+    #[inline]
+    pub fn record_run_ns(&self, ns: u64) {
+        self.total_run_ns.fetch_add(ns, Ordering::Relaxed);
+    }
+    
+    /// Snapshot of the scheduling counters (each read individually)
+    pub fn stats(&self) -> GVThreadStats {
+        GVThreadStats {
+            voluntary_yields: self.voluntary_yields.load(Ordering::Relaxed),
+            forced_preemptions: self.forced_preemptions.load(Ordering::Relaxed),
+            total_run_ns: self.total_run_ns.load(Ordering::Relaxed),
+            times_scheduled: self.times_scheduled.load(Ordering::Relaxed),
+        }
+    }
 }
 
 /// Worker state - stored in contiguous array for cache efficiency
@@ -267,7 +522,7 @@ impl GVThreadMetadata {
 /// 0x00: current_gthread   (u32) - Currently running GVThread ID
 /// 0x04: activity_counter  (u32) - Incremented at safepoints
 /// 0x08: run_start_ns      (u64) - When current GVThread started
-/// 0x10: last_activity_ns  (u64) - Last safepoint/yield time
+/// 0x10: last_activity_ns  (u64) - Last safepoint/yield time (coarse clock)
 /// 0x18: thread_id         (u64) - pthread_t / OS thread ID
 /// 0x20: is_parked         (u8)  - Worker is parked (no work)
 /// 0x21: is_low_priority   (u8)  - Dedicated LOW priority worker
@@ -374,6 +629,8 @@ const _: () = {
     assert!(core::mem::align_of::<WorkerState>() == 64);
     assert!(core::mem::size_of::<VoluntarySavedRegs>() == 64);
     assert!(core::mem::size_of::<ForcedSavedRegs>() == 256);
+    assert!(core::mem::size_of::<FpuState>() == FPU_SAVE_SIZE);
+    assert!(core::mem::size_of::<GVThreadMetadata>() <= crate::constants::METADATA_SIZE);
 };
 
 /// Verify voluntary_regs offset at compile time
@@ -387,10 +644,12 @@ const _VOLUNTARY_REGS_OFFSET_CHECK: () = {
     // 0x10: entry_fn (8) = 8
     // 0x18: entry_arg (8) = 8
     // 0x20: result_ptr (8) = 8
-    // 0x28: _reserved (24) = 24
+    // 0x28: generation (4) + sleep_flag (4) = 8
+    // 0x30: wake_time_ns (8) = 8
+    // 0x38: base_priority (1) + switching (1) + preempt_off (2) + _reserved (4) = 8
     // 0x40: voluntary_regs
     //
-    // Total before voluntary_regs = 4 + 4 + 4 + 4 + 8 + 8 + 8 + 24 = 64 = 0x40 ✓
+    // Total before voluntary_regs = 4 + 4 + 4 + 4 + 8 + 8 + 8 + 8 + 8 + 8 = 64 = 0x40 ✓
 };
 
 #[cfg(test)]
@@ -414,11 +673,78 @@ mod tests {
         assert_eq!(&meta.state as *const _ as usize - base, 0x02);
         assert_eq!(&meta.priority as *const _ as usize - base, 0x03);
         assert_eq!(&meta.gvthread_id as *const _ as usize - base, 0x04);
+        assert_eq!(&meta.base_priority as *const _ as usize - base, 0x38);
+        assert_eq!(&meta.switching as *const _ as usize - base, 0x39);
+        assert_eq!(&meta.preempt_off as *const _ as usize - base, 0x3A);
         
         // CRITICAL: voluntary_regs must be at 0x40 for context switch assembly!
         let vol_regs_offset = &meta.voluntary_regs as *const _ as usize - base;
         assert_eq!(vol_regs_offset, 0x40, 
             "voluntary_regs must be at offset 0x40, but found 0x{:x}", vol_regs_offset);
+        assert_eq!(&meta.forced_regs as *const _ as usize - base, 0x80);
+        assert_eq!(&meta.fpu_state as *const _ as usize - base, 0x180);
+        assert_eq!(&meta.voluntary_yields as *const _ as usize - base, 0x380);
+        assert_eq!(&meta.times_scheduled as *const _ as usize - base, 0x398);
+        assert_eq!(&meta.locals as *const _ as usize - base, 0x3A0);
+    }
+    
+    #[test]
+    fn test_stats_counters_reset_on_init() {
+        let meta = GVThreadMetadata::new();
+        meta.init(GVThreadId::new(1), 1, GVThreadId::NONE, Priority::Normal);
+        meta.record_scheduled();
+        meta.record_scheduled();
+        meta.record_voluntary_yield();
+        meta.record_forced_preemption();
+        meta.record_run_ns(1_500);
+        meta.record_run_ns(500);
+        assert_eq!(meta.stats(), GVThreadStats {
+            voluntary_yields: 1,
+            forced_preemptions: 1,
+            total_run_ns: 2_000,
+            times_scheduled: 2,
+        });
+        
+        // Slot reused by the next GVThread
+        meta.init(GVThreadId::new(1), 2, GVThreadId::NONE, Priority::Normal);
+        assert_eq!(meta.stats(), GVThreadStats::default());
+    }
+
+    #[test]
+    fn test_reset_retires_previous_occupant() {
+        let meta = GVThreadMetadata::new();
+        meta.init(GVThreadId::new(1), 1, GVThreadId::NONE, Priority::Normal);
+        meta.set_state(GVThreadState::Ready);
+        meta.set_state(GVThreadState::Running);
+        meta.set_state(GVThreadState::Blocked);
+        meta.request_preempt();
+        meta.begin_switch();
+        meta.sleep_flag.store(1, Ordering::Relaxed);
+        meta.record_scheduled();
+
+        meta.reset(2);
+        assert_eq!(meta.get_generation(), 2);
+        assert_eq!(meta.get_state(), GVThreadState::Created);
+        assert!(!meta.is_preempt_requested());
+        assert_eq!(meta.switching.load(Ordering::Relaxed), 0);
+        assert_eq!(meta.sleep_flag.load(Ordering::Relaxed), 0);
+        assert_eq!(meta.stats(), GVThreadStats::default());
+    }
+    
+    #[test]
+    fn test_priority_boost_and_restore() {
+        let meta = GVThreadMetadata::new();
+        meta.init(GVThreadId::new(1), 1, GVThreadId::NONE, Priority::Low);
+        assert_eq!(meta.get_priority(), Priority::Low);
+        
+        assert!(meta.boost_priority(Priority::High));
+        // A lower boost never undoes a higher one
+        assert!(!meta.boost_priority(Priority::Normal));
+        assert_eq!(meta.get_priority(), Priority::High);
+        assert_eq!(meta.get_base_priority(), Priority::Low);
+        
+        meta.restore_priority();
+        assert_eq!(meta.get_priority(), Priority::Low);
     }
     
     #[test]
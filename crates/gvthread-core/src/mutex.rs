@@ -1,16 +1,69 @@
 //! GVThread-aware mutex
 //!
 //! Unlike std::sync::Mutex, this mutex yields to the scheduler
-//! when contended instead of blocking the OS thread.
+//! when contended instead of blocking the OS thread: a waiting GVThread
+//! retries between `yield_now`s (through the hook the runtime installs),
+//! so its worker runs other GVThreads — the holder among them.
+//!
+//! # Priority inheritance
+//!
+//! A waiter lends its priority to the GVThread holding the lock: while
+//! a High waiter waits, a Low holder runs (and is requeued) at High, so
+//! Normal work can't keep it from releasing. A holder already queued is
+//! moved up at once (through the runtime's boost hook). The boost ends
+//! on unlock.
+//! A holder of several contended mutexes drops to its base priority
+//! when it releases any of them; the remaining waiters re-apply theirs
+//! on their next attempt.
 
 use core::cell::UnsafeCell;
 use core::ops::{Deref, DerefMut};
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
 use std::collections::VecDeque;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 use crate::id::GVThreadId;
+use crate::metadata::{self, GVThreadMetadata};
 use crate::spinlock::SpinLock;
 use crate::error::SchedResult;
 
+/// Yields the calling GVThread to its worker
+///
+/// Installed once by the runtime; lets waiters in this crate (which
+/// can't see the scheduler) give up their worker between attempts.
+/// Outside a GVThread it must yield the OS thread instead.
+pub type YieldHook = fn();
+
+static YIELD_HOOK: OnceLock<YieldHook> = OnceLock::new();
+
+/// Install the runtime's yield (first call wins)
+pub fn set_yield_hook(hook: YieldHook) {
+    let _ = YIELD_HOOK.set(hook);
+}
+
+/// Called after a waiter raised a holder's effective priority
+///
+/// Installed once by the runtime; moves the holder up if it is queued
+/// at its old priority.
+pub type BoostHook = fn(&GVThreadMetadata);
+
+static BOOST_HOOK: OnceLock<BoostHook> = OnceLock::new();
+
+/// Install the runtime's boost hook (first call wins)
+pub fn set_boost_hook(hook: BoostHook) {
+    let _ = BOOST_HOOK.set(hook);
+}
+
+/// Let others run before the next attempt: the scheduler's `yield_now`,
+/// or the OS thread's before the runtime started
+#[inline]
+fn yield_waiter() {
+    match YIELD_HOOK.get() {
+        Some(hook) => hook(),
+        None => std::thread::yield_now(),
+    }
+}
+
 /// A mutex that yields to the scheduler when contended
 ///
 /// This mutex is designed for use within GVThreads. When a GVThread
@@ -36,7 +89,14 @@ pub struct SchedMutex<T> {
     data: UnsafeCell<T>,
     
     /// Queue of waiting GVThreads (FIFO for fairness)
+    ///
+    /// Its lock also orders boosts of `owner` against the unlock that
+    /// restores it.
     waiters: SpinLock<VecDeque<GVThreadId>>,
+    
+    /// Holder's metadata, for priority inheritance (null if the holder
+    /// is not a GVThread)
+    owner: AtomicPtr<GVThreadMetadata>,
 }
 
 // Safety: SchedMutex provides exclusive access to T
@@ -50,6 +110,7 @@ impl<T> SchedMutex<T> {
             locked: AtomicBool::new(false),
             data: UnsafeCell::new(value),
             waiters: SpinLock::new(VecDeque::new()),
+            owner: AtomicPtr::new(core::ptr::null_mut()),
         }
     }
     
@@ -62,6 +123,7 @@ impl<T> SchedMutex<T> {
             .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
             .is_ok()
         {
+            self.set_owner(metadata::current());
             return Ok(SchedMutexGuard { mutex: self });
         }
         
@@ -70,23 +132,47 @@ impl<T> SchedMutex<T> {
     }
     
     fn lock_slow(&self) -> SchedResult<SchedMutexGuard<'_, T>> {
-        // In real implementation:
-        // 1. Add current GVThread to waiters
-        // 2. Yield to scheduler
-        // 3. When woken, we own the lock
-        
-        // For now, spin with yield (placeholder until scheduler integration)
+        Ok(self.wait_until(None).expect("no deadline to miss"))
+    }
+    
+    /// Acquire the lock, giving up after `timeout`
+    ///
+    /// Waits like `lock()`; `None` if the lock was still held when the
+    /// timeout ran out. The last attempt comes after the deadline, so a
+    /// lock released just as the timeout fires is still taken.
+    pub fn lock_timeout(&self, timeout: Duration) -> Option<SchedMutexGuard<'_, T>> {
+        if let Some(guard) = self.try_lock() {
+            return Some(guard);
+        }
+        self.wait_until(Some(Instant::now() + timeout))
+    }
+    
+    /// Wait for the lock until `deadline` (forever with `None`)
+    ///
+    /// Waiters don't queue, so giving up just stops trying: there is no
+    /// wait-list entry to withdraw or hand-off to lose.
+    fn wait_until(&self, deadline: Option<Instant>) -> Option<SchedMutexGuard<'_, T>> {
+        let me = metadata::current();
         loop {
+            let expired = deadline.is_some_and(|d| Instant::now() >= d);
             if self.locked
                 .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
                 .is_ok()
             {
-                return Ok(SchedMutexGuard { mutex: self });
+                self.set_owner(me);
+                return Some(SchedMutexGuard { mutex: self });
+            }
+            if expired {
+                return None;
+            }
+            
+            // Lend our priority to the holder
+            if let Some(me) = me {
+                self.boost_owner(me.get_priority());
             }
             
-            // Yield to scheduler (placeholder: just yield OS thread)
-            std::thread::yield_now();
-            std::hint::spin_loop();
+            // Requeued at our priority: others, the holder included, run
+            yield_waiter();
         }
     }
     
@@ -96,6 +182,7 @@ impl<T> SchedMutex<T> {
             .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
             .is_ok()
         {
+            self.set_owner(metadata::current());
             Some(SchedMutexGuard { mutex: self })
         } else {
             None
@@ -120,10 +207,37 @@ impl<T> SchedMutex<T> {
         self.data.into_inner()
     }
     
+    fn set_owner(&self, owner: Option<&'static GVThreadMetadata>) {
+        let ptr = owner.map_or(core::ptr::null_mut(), |m| m as *const _ as *mut _);
+        self.owner.store(ptr, Ordering::Release);
+    }
+    
+    /// Raise the holder's effective priority to at least `priority`
+    fn boost_owner(&self, priority: crate::state::Priority) {
+        let _waiters = self.waiters.lock();
+        let owner = self.owner.load(Ordering::Acquire);
+        if !owner.is_null() {
+            // Safety: the holder is alive while it owns the lock, and
+            // can't release it while we hold `waiters`
+            let owner = unsafe { &*owner };
+            if owner.boost_priority(priority) {
+                if let Some(hook) = BOOST_HOOK.get() {
+                    hook(owner);
+                }
+            }
+        }
+    }
+    
     fn unlock(&self) {
         // Check if there are waiters
         let waiter = {
             let mut waiters = self.waiters.lock();
+            // End any boost lent while we held the lock. Under `waiters`,
+            // so a waiter's boost can't land after this.
+            let owner = self.owner.swap(core::ptr::null_mut(), Ordering::AcqRel);
+            if !owner.is_null() {
+                unsafe { &*owner }.restore_priority();
+            }
             waiters.pop_front()
         };
         
@@ -163,6 +277,14 @@ pub struct SchedMutexGuard<'a, T> {
     mutex: &'a SchedMutex<T>,
 }
 
+impl<'a, T> SchedMutexGuard<'a, T> {
+    /// The mutex this guard holds (for condition variables that must
+    /// release and re-acquire it)
+    pub fn mutex(this: &Self) -> &'a SchedMutex<T> {
+        this.mutex
+    }
+}
+
 impl<'a, T> Deref for SchedMutexGuard<'a, T> {
     type Target = T;
     
@@ -188,8 +310,11 @@ impl<'a, T> Drop for SchedMutexGuard<'a, T> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::Priority;
+    use std::cell::Cell;
     use std::sync::Arc;
     use std::thread;
+    use std::time::{Duration, Instant};
     
     #[test]
     fn test_basic_lock() {
@@ -224,6 +349,31 @@ mod tests {
         assert!(guard3.is_some());
     }
     
+    #[test]
+    fn test_lock_timeout() {
+        let mutex = Arc::new(SchedMutex::new(0));
+        
+        // Uncontended, or zero timeout on a free lock: taken at once
+        drop(mutex.lock_timeout(Duration::ZERO).expect("free lock"));
+        
+        let guard = mutex.lock().unwrap();
+        let start = Instant::now();
+        assert!(mutex.lock_timeout(Duration::from_millis(20)).is_none());
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        
+        // Released while a waiter still has time: it gets the lock
+        let m = Arc::clone(&mutex);
+        let waiter = thread::spawn(move || {
+            let mut guard = m.lock_timeout(Duration::from_secs(5)).expect("released in time");
+            *guard += 1;
+        });
+        thread::sleep(Duration::from_millis(10));
+        drop(guard);
+        waiter.join().unwrap();
+        assert_eq!(*mutex.lock().unwrap(), 1);
+        assert!(!mutex.is_locked());
+    }
+    
     #[test]
     fn test_concurrent() {
         let mutex = Arc::new(SchedMutex::new(0));
@@ -247,6 +397,49 @@ mod tests {
         assert_eq!(*guard, 4000);
     }
     
+    thread_local! {
+        static CURRENT: Cell<Option<&'static GVThreadMetadata>> = const { Cell::new(None) };
+    }
+    
+    fn leaked_meta(id: u32, priority: Priority) -> &'static GVThreadMetadata {
+        let meta: &'static GVThreadMetadata = Box::leak(Box::new(GVThreadMetadata::new()));
+        meta.init(GVThreadId::new(id), 1, GVThreadId::NONE, priority);
+        meta
+    }
+    
+    #[test]
+    fn test_priority_inheritance() {
+        metadata::set_current_metadata_hook(|| CURRENT.with(|c| c.get()));
+        
+        let low = leaked_meta(1, Priority::Low);
+        let high = leaked_meta(2, Priority::High);
+        let mutex = Arc::new(SchedMutex::new(0));
+        
+        CURRENT.with(|c| c.set(Some(low)));
+        let guard = mutex.lock().unwrap();
+        
+        let m = Arc::clone(&mutex);
+        let waiter = thread::spawn(move || {
+            CURRENT.with(|c| c.set(Some(high)));
+            *m.lock().unwrap() += 1;
+            // The waiter's own priority is untouched
+            high.get_priority()
+        });
+        
+        // The High waiter boosts the Low holder while it waits
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while low.get_priority() != Priority::High {
+            assert!(Instant::now() < deadline, "holder was never boosted");
+            thread::yield_now();
+        }
+        
+        drop(guard);
+        assert_eq!(waiter.join().unwrap(), Priority::High);
+        assert_eq!(low.get_priority(), Priority::Low);
+        assert_eq!(*mutex.lock().unwrap(), 1);
+        CURRENT.with(|c| c.set(None));
+    }
+    
     #[test]
     fn test_into_inner() {
         let mutex = SchedMutex::new(42);
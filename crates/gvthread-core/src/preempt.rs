@@ -0,0 +1,123 @@
+//! Sections forced preemption must not interrupt
+//!
+//! Forced preemption (SIGURG) switches away from a GVThread wherever it
+//! happens to be. That is only safe in code that holds nothing another
+//! GVThread on the same worker could need: switching away from a held
+//! `SpinLock`, or from inside the allocator, leaves the next GVThread on
+//! that worker spinning or deadlocked on it.
+//!
+//! `disable()` marks such a section for the current OS thread (the
+//! worker). The signal handler checks `is_preemptible()` and, if the
+//! worker is inside one, leaves the GVThread's preempt flag set and
+//! returns; the GVThread then yields at its next safepoint.
+//!
+//! On a GVThread the section is also counted in its metadata
+//! (`preempt_off`), before the worker's count is touched and until after
+//! it is restored: a TLS update is an address lookup and then a store,
+//! and a GVThread switched out between the two would resume on another
+//! worker and store into the first one's count. The handler checks both
+//! (`is_preemptible_in`).
+//!
+//! Sections nest. A marked section must not yield or block the GVThread
+//! (it would end on whichever worker resumes it), which holds for every
+//! lock-holding section in the runtime.
+
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::sync::atomic::{compiler_fence, Ordering};
+
+use crate::metadata::{self, GVThreadMetadata};
+
+thread_local! {
+    /// Depth of `disable()` sections on this OS thread
+    ///
+    /// Const-initialized and without a destructor, so reading it from a
+    /// signal handler is a plain TLS load.
+    static PREEMPT_OFF: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Marks a section forced preemption must not interrupt; ends on drop
+#[must_use = "preemption is re-enabled as soon as the guard is dropped"]
+pub struct NoPreemptGuard {
+    /// The GVThread whose count it bumped, if any
+    gvthread: Option<&'static GVThreadMetadata>,
+    /// Tied to the OS thread whose counter it bumped
+    _not_send: PhantomData<*const ()>,
+}
+
+/// Disable forced preemption on this OS thread until the guard drops
+#[inline]
+pub fn disable() -> NoPreemptGuard {
+    // First: from here on the GVThread stays on this worker
+    let gvthread = metadata::current();
+    if let Some(meta) = gvthread {
+        meta.preempt_off.fetch_add(1, Ordering::Relaxed);
+    }
+    // The handler runs on this thread: only the compiler can reorder
+    compiler_fence(Ordering::SeqCst);
+    PREEMPT_OFF.with(|depth| depth.set(depth.get() + 1));
+    compiler_fence(Ordering::SeqCst);
+    NoPreemptGuard { gvthread, _not_send: PhantomData }
+}
+
+/// True unless this OS thread is inside a `disable()` section
+///
+/// Async-signal-safe: meant for the preemption signal handler.
+#[inline]
+pub fn is_preemptible() -> bool {
+    PREEMPT_OFF.with(|depth| depth.get() == 0)
+}
+
+/// `is_preemptible()`, and `gvthread` (the one interrupted, if any) has
+/// no section open either
+///
+/// Async-signal-safe: meant for the preemption signal handler.
+#[inline]
+pub fn is_preemptible_in(gvthread: Option<&GVThreadMetadata>) -> bool {
+    is_preemptible()
+        && gvthread.map_or(true, |meta| meta.preempt_off.load(Ordering::Relaxed) == 0)
+}
+
+impl Drop for NoPreemptGuard {
+    #[inline]
+    fn drop(&mut self) {
+        compiler_fence(Ordering::SeqCst);
+        PREEMPT_OFF.with(|depth| {
+            debug_assert!(depth.get() > 0, "NoPreemptGuard dropped on another thread");
+            depth.set(depth.get().saturating_sub(1));
+        });
+        compiler_fence(Ordering::SeqCst);
+        if let Some(meta) = self.gvthread {
+            meta.preempt_off.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SpinLock;
+
+    #[test]
+    fn sections_nest_and_cover_spinlocks() {
+        assert!(is_preemptible());
+        let outer = disable();
+        let inner = disable();
+        drop(inner);
+        assert!(!is_preemptible());
+        drop(outer);
+        assert!(is_preemptible());
+
+        let lock = SpinLock::new(0);
+        {
+            let _held = lock.lock();
+            assert!(!is_preemptible());
+        }
+        assert!(is_preemptible());
+        let held = lock.try_lock().unwrap();
+        assert!(lock.try_lock().is_none());
+        assert!(!is_preemptible());
+        drop(held);
+        assert!(is_preemptible());
+    }
+}
@@ -2,6 +2,10 @@
 //!
 //! Manages allocation and deallocation of fixed-size slots.
 //! Uses a LIFO free stack for cache-friendly reuse of recently freed slots.
+//!
+//! Each slot also carries a generation, bumped on every release, so a
+//! `(id, generation)` pair names one GVThread even after its slot is
+//! reused. Generations start at 1: a zeroed metadata page matches none.
 
 use core::sync::atomic::{AtomicU32, Ordering};
 use crate::id::GVThreadId;
@@ -21,6 +25,9 @@ pub struct SlotAllocator {
     
     /// Number of currently allocated slots
     allocated_count: AtomicU32,
+    
+    /// Per-slot generation (source of truth; copied into the metadata)
+    generations: Box<[AtomicU32]>,
 }
 
 impl SlotAllocator {
@@ -32,20 +39,21 @@ impl SlotAllocator {
             next_fresh: AtomicU32::new(0),
             max_slots: max_slots as u32,
             allocated_count: AtomicU32::new(0),
+            generations: (0..max_slots).map(|_| AtomicU32::new(1)).collect(),
         }
     }
     
-    /// Allocate a slot, returning its ID
+    /// Allocate a slot, returning its ID and generation
     ///
     /// Prefers reusing recently freed slots (LIFO) for better cache behavior.
     /// Falls back to fresh slot IDs if free stack is empty.
-    pub fn allocate(&self) -> SchedResult<GVThreadId> {
+    pub fn allocate(&self) -> SchedResult<(GVThreadId, u32)> {
         // First, try to get a recycled slot from free stack
         {
             let mut free = self.free_stack.lock();
             if let Some(id) = free.pop() {
                 self.allocated_count.fetch_add(1, Ordering::Relaxed);
-                return Ok(GVThreadId::new(id));
+                return Ok(self.with_generation(id));
             }
         }
         
@@ -65,20 +73,28 @@ impl SlotAllocator {
             ) {
                 Ok(_) => {
                     self.allocated_count.fetch_add(1, Ordering::Relaxed);
-                    return Ok(GVThreadId::new(current));
+                    return Ok(self.with_generation(current));
                 }
                 Err(_) => continue, // Another thread claimed it, retry
             }
         }
     }
     
+    #[inline]
+    fn with_generation(&self, id: u32) -> (GVThreadId, u32) {
+        (GVThreadId::new(id), self.generations[id as usize].load(Ordering::Acquire))
+    }
+    
     /// Release a slot back to the allocator
     ///
-    /// The slot will be reused by subsequent allocations.
+    /// Bumps the slot's generation first, so waiters still holding the
+    /// old one are stale from here on. The slot will be reused by
+    /// subsequent allocations.
     pub fn release(&self, id: GVThreadId) {
         if id.is_none() {
             return;
         }
+        self.generations[id.as_usize()].fetch_add(1, Ordering::AcqRel);
         
         let mut free = self.free_stack.lock();
         free.push(id.as_u32());
@@ -94,6 +110,7 @@ impl SlotAllocator {
         let mut free = self.free_stack.lock();
         for id in ids {
             if !id.is_none() {
+                self.generations[id.as_usize()].fetch_add(1, Ordering::AcqRel);
                 free.push(id.as_u32());
             }
         }
@@ -124,6 +141,19 @@ impl SlotAllocator {
         self.free_stack.lock().len()
     }
     
+    /// Current generation of slot `id`
+    #[inline]
+    pub fn generation(&self, id: GVThreadId) -> u32 {
+        self.generations[id.as_usize()].load(Ordering::Acquire)
+    }
+    
+    /// Whether `(id, generation)` still names the slot's occupant, i.e.
+    /// the slot hasn't been released since that generation was handed out
+    #[inline]
+    pub fn is_current(&self, id: GVThreadId, generation: u32) -> bool {
+        self.is_valid(id) && self.generation(id) == generation
+    }
+    
     /// Check if a slot ID is valid (within range)
     #[inline]
     pub fn is_valid(&self, id: GVThreadId) -> bool {
@@ -139,9 +169,9 @@ mod tests {
     fn test_allocate_sequential() {
         let alloc = SlotAllocator::new(100);
         
-        let id1 = alloc.allocate().unwrap();
-        let id2 = alloc.allocate().unwrap();
-        let id3 = alloc.allocate().unwrap();
+        let (id1, _) = alloc.allocate().unwrap();
+        let (id2, _) = alloc.allocate().unwrap();
+        let (id3, _) = alloc.allocate().unwrap();
         
         assert_eq!(id1.as_u32(), 0);
         assert_eq!(id2.as_u32(), 1);
@@ -153,8 +183,8 @@ mod tests {
     fn test_allocate_release_reuse() {
         let alloc = SlotAllocator::new(100);
         
-        let id1 = alloc.allocate().unwrap();
-        let id2 = alloc.allocate().unwrap();
+        let (id1, _) = alloc.allocate().unwrap();
+        let (id2, _) = alloc.allocate().unwrap();
         
         assert_eq!(alloc.allocated_count(), 2);
         
@@ -163,7 +193,7 @@ mod tests {
         assert_eq!(alloc.allocated_count(), 1);
         
         // Next allocation should reuse id1's slot (LIFO)
-        let id3 = alloc.allocate().unwrap();
+        let (id3, _) = alloc.allocate().unwrap();
         assert_eq!(id3, id1);
         assert_eq!(alloc.allocated_count(), 2);
     }
@@ -181,11 +211,32 @@ mod tests {
         assert!(matches!(result, Err(SchedError::NoSlotsAvailable)));
     }
     
+    #[test]
+    fn test_release_bumps_generation() {
+        let alloc = SlotAllocator::new(4);
+        
+        let (id, gen1) = alloc.allocate().unwrap();
+        assert!(alloc.is_current(id, gen1));
+        alloc.release(id);
+        // A waiter holding gen1 is stale as soon as the slot is freed
+        assert!(!alloc.is_current(id, gen1));
+        
+        let (reused, gen2) = alloc.allocate().unwrap();
+        assert_eq!(reused, id);
+        assert_ne!(gen2, gen1);
+        assert!(alloc.is_current(id, gen2));
+        assert!(!alloc.is_current(id, gen1));
+        
+        alloc.release_batch(&[id]);
+        assert!(!alloc.is_current(id, gen2));
+        assert!(!alloc.is_current(GVThreadId::new(4), 1)); // out of range
+    }
+    
     #[test]
     fn test_release_batch() {
         let alloc = SlotAllocator::new(100);
         
-        let ids: Vec<_> = (0..10).map(|_| alloc.allocate().unwrap()).collect();
+        let ids: Vec<_> = (0..10).map(|_| alloc.allocate().unwrap().0).collect();
         assert_eq!(alloc.allocated_count(), 10);
         
         alloc.release_batch(&ids);
@@ -207,7 +258,7 @@ mod tests {
             handles.push(thread::spawn(move || {
                 let mut ids = vec![];
                 for _ in 0..1000 {
-                    ids.push(alloc.allocate().unwrap());
+                    ids.push(alloc.allocate().unwrap().0);
                 }
                 ids
             }));
@@ -6,11 +6,36 @@
 use core::cell::UnsafeCell;
 use core::ops::{Deref, DerefMut};
 use core::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::preempt::{self, NoPreemptGuard};
+
+/// Failed attempts spent spinning (with `spin_loop` backoff) before a
+/// waiter starts yielding its core
+pub const SPIN_LIMIT: u32 = 64;
+
+/// Further failed attempts spent in `thread::yield_now` before a waiter
+/// falls back to sleeping
+pub const YIELD_LIMIT: u32 = 32;
+
+/// Sleep between attempts once spinning and yielding didn't help
+pub const BACKOFF_SLEEP: Duration = Duration::from_micros(50);
 
 /// A simple spinlock
 ///
 /// This spinlock is designed for short critical sections in the scheduler.
-/// It spins in a loop waiting for the lock, with a pause instruction hint.
+/// A waiter spins with pause hints for `SPIN_LIMIT` attempts, then
+/// yields its core for `YIELD_LIMIT` attempts, then sleeps
+/// `BACKOFF_SLEEP` between attempts, so a preempted holder gets the CPU
+/// back instead of waiters burning it.
+///
+/// Both fallbacks are plain syscalls on the caller's stack: taken on a
+/// GVThread, they park the worker thread with the GVThread still on it,
+/// never switching or moving its stack.
+///
+/// Holding (or waiting for) the lock disables forced preemption on the
+/// thread (`preempt::disable`), so a GVThread is never switched away with
+/// it held.
 ///
 /// # Warning
 ///
@@ -38,36 +63,38 @@ impl<T> SpinLock<T> {
     /// Acquire the lock, spinning until it's available
     #[inline]
     pub fn lock(&self) -> SpinLockGuard<'_, T> {
+        // Before the CAS: there must be no instant we hold it preemptibly
+        let no_preempt = preempt::disable();
         loop {
             // Try to acquire with weak CAS (can spuriously fail, but faster)
             if self.locked
                 .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
                 .is_ok()
             {
-                return SpinLockGuard { lock: self };
+                return SpinLockGuard { lock: self, _no_preempt: no_preempt };
             }
             
-            // Spin with backoff
-            let mut spin_count = 0u32;
+            // Wait for it to look free, backing off
+            let mut attempts = 0u32;
             while self.locked.load(Ordering::Relaxed) {
-                spin_count = spin_count.wrapping_add(1);
-                
-                // Exponential backoff with pause hints
-                for _ in 0..spin_count.min(64) {
-                    core::hint::spin_loop();
-                }
+                attempts = attempts.saturating_add(1);
+                backoff(attempts);
             }
         }
     }
     
     /// Try to acquire the lock without spinning
+    ///
+    /// `None` if it is held: for callers that would rather skip the work
+    /// than wait for it.
     #[inline]
     pub fn try_lock(&self) -> Option<SpinLockGuard<'_, T>> {
+        let no_preempt = preempt::disable();
         if self.locked
             .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
             .is_ok()
         {
-            Some(SpinLockGuard { lock: self })
+            Some(SpinLockGuard { lock: self, _no_preempt: no_preempt })
         } else {
             None
         }
@@ -90,6 +117,21 @@ impl<T> SpinLock<T> {
     }
 }
 
+/// Wait after the `attempts`-th failed look at a held lock: growing
+/// pause-hint spins, then `yield_now`, then short sleeps
+#[inline]
+fn backoff(attempts: u32) {
+    if attempts <= SPIN_LIMIT {
+        for _ in 0..attempts {
+            core::hint::spin_loop();
+        }
+    } else if attempts <= SPIN_LIMIT + YIELD_LIMIT {
+        std::thread::yield_now();
+    } else {
+        std::thread::sleep(BACKOFF_SLEEP);
+    }
+}
+
 impl<T: Default> Default for SpinLock<T> {
     fn default() -> Self {
         SpinLock::new(T::default())
@@ -97,8 +139,14 @@ impl<T: Default> Default for SpinLock<T> {
 }
 
 /// Guard that releases the spinlock when dropped
+///
+/// Also dropped when a panic unwinds through the critical section: the
+/// lock is simply released, never poisoned, and the data keeps whatever
+/// writes happened before the panic.
 pub struct SpinLockGuard<'a, T> {
     lock: &'a SpinLock<T>,
+    /// Dropped after `drop` released the lock
+    _no_preempt: NoPreemptGuard,
 }
 
 impl<'a, T> Deref for SpinLockGuard<'a, T> {
@@ -185,4 +233,56 @@ mod tests {
         let guard = lock.lock();
         assert_eq!(*guard, 4000);
     }
+    
+    #[test]
+    fn test_spinlock_try_lock_fails_while_another_thread_holds_it() {
+        let lock = Arc::new(SpinLock::new(0u32));
+        let (held_tx, held_rx) = std::sync::mpsc::channel();
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let holder = {
+            let lock = Arc::clone(&lock);
+            thread::spawn(move || {
+                let mut guard = lock.lock();
+                *guard = 1;
+                held_tx.send(()).unwrap();
+                release_rx.recv().unwrap();
+            })
+        };
+        held_rx.recv().unwrap();
+        assert!(lock.try_lock().is_none());
+        release_tx.send(()).unwrap();
+        holder.join().unwrap();
+        assert_eq!(*lock.try_lock().expect("released after holder exit"), 1);
+    }
+    
+    #[test]
+    fn test_spinlock_released_when_critical_section_panics() {
+        let lock = SpinLock::new(vec![1u32]);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let mut guard = lock.lock();
+            guard.push(2);
+            panic!("inside the critical section");
+        }));
+        assert!(result.is_err());
+        assert!(!lock.is_locked());
+        // Not poisoned: writes before the panic are kept
+        assert_eq!(*lock.try_lock().unwrap(), [1, 2]);
+    }
+    
+    #[test]
+    fn test_spinlock_waiter_outlasts_a_slow_holder() {
+        // Long enough that the waiter reaches the sleeping phase
+        let lock = Arc::new(SpinLock::new(0u32));
+        let guard = lock.lock();
+        let waiter = {
+            let lock = Arc::clone(&lock);
+            thread::spawn(move || {
+                *lock.lock() += 1;
+            })
+        };
+        thread::sleep(Duration::from_millis(20));
+        drop(guard);
+        waiter.join().unwrap();
+        assert_eq!(*lock.lock(), 1);
+    }
 }
@@ -3,6 +3,26 @@
 use core::fmt;
 
 /// State of a GVThread
+///
+/// Legal transitions (checked by `GVThreadMetadata::set_state` in debug
+/// builds, see `can_transition_to`):
+///
+/// ```text
+///   Created ──spawn──▶ Ready ◀──────── wake ──────── Blocked
+///                      │  ▲                            ▲
+///              schedule│  │yield                       │block
+///                      ▼  │                            │
+///                     Running ─────────────────────────┘
+///                      │   │
+///               SIGURG │   │ entry returns
+///                      ▼   ▼
+///              Preempted   Finished ◀── Cancelled
+/// ```
+///
+/// `Preempted` goes back to `Ready` (or straight to `Running`).
+/// `Created`, `Ready` and `Blocked` GVThreads may be `Cancelled`.
+/// A finished slot only leaves `Finished` through re-initialisation,
+/// which resets it to `Created` without going through `set_state`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum GVThreadState {
@@ -46,6 +66,25 @@ impl GVThreadState {
     pub const fn needs_full_restore(&self) -> bool {
         matches!(self, GVThreadState::Preempted)
     }
+
+    /// Check if a GVThread in this state may move to `next`
+    ///
+    /// `Ready -> Ready` (re-queue) and `Finished -> Finished` (finish
+    /// recorded twice) are allowed; other self-transitions are not.
+    #[inline]
+    pub const fn can_transition_to(&self, next: GVThreadState) -> bool {
+        use GVThreadState::*;
+        matches!(
+            (*self, next),
+            (Created, Ready | Cancelled)
+                | (Ready, Ready | Running | Cancelled)
+                | (Running, Ready | Blocked | Preempted | Finished)
+                | (Blocked, Ready | Cancelled)
+                | (Preempted, Ready | Running)
+                | (Finished, Finished)
+                | (Cancelled, Finished)
+        )
+    }
 }
 
 impl From<u8> for GVThreadState {
@@ -172,6 +211,38 @@ mod tests {
         assert!(!GVThreadState::Ready.needs_full_restore());
     }
     
+    #[test]
+    fn test_legal_transitions() {
+        use GVThreadState::*;
+        // The normal lifecycle
+        for (from, to) in [
+            (Created, Ready),
+            (Ready, Running),
+            (Running, Blocked),
+            (Blocked, Ready),
+            (Running, Preempted),
+            (Preempted, Ready),
+            (Running, Ready),
+            (Running, Finished),
+            (Blocked, Cancelled),
+            (Cancelled, Finished),
+        ] {
+            assert!(from.can_transition_to(to), "{:?} -> {:?}", from, to);
+        }
+        // Skipped steps and resurrection
+        for (from, to) in [
+            (Created, Running),
+            (Ready, Blocked),
+            (Blocked, Running),
+            (Running, Running),
+            (Running, Created),
+            (Finished, Ready),
+            (Cancelled, Running),
+        ] {
+            assert!(!from.can_transition_to(to), "{:?} -> {:?}", from, to);
+        }
+    }
+
     #[test]
     fn test_priority_ordering() {
         assert!(Priority::Critical < Priority::High);
@@ -57,11 +57,21 @@ const CONFIG_PARAMS: &[ConfigParam] = &[
         rust_type: "u64",
         default_value: "10",
     },
+    ConfigParam {
+        name: "TIMER_MIN_SLEEP_US",
+        rust_type: "u64",
+        default_value: "100",
+    },
     ConfigParam {
         name: "ENABLE_FORCED_PREEMPT",
         rust_type: "bool",
         default_value: "true",
     },
+    ConfigParam {
+        name: "FORCED_SWITCH",
+        rust_type: "bool",
+        default_value: "false",
+    },
     ConfigParam {
         name: "DEBUG_LOGGING",
         rust_type: "bool",
@@ -92,6 +102,26 @@ const CONFIG_PARAMS: &[ConfigParam] = &[
         rust_type: "u64",
         default_value: "100",
     },
+    ConfigParam {
+        name: "BLOCKING_THREADS",
+        rust_type: "usize",
+        default_value: "4",
+    },
+    ConfigParam {
+        name: "PIN_WORKERS",
+        rust_type: "bool",
+        default_value: "false",
+    },
+    ConfigParam {
+        name: "LAZY_STACK_FREE",
+        rust_type: "bool",
+        default_value: "false",
+    },
+    ConfigParam {
+        name: "SUPERVISE_WORKERS",
+        rust_type: "bool",
+        default_value: "false",
+    },
 ];
 
 fn main() {
@@ -2,7 +2,8 @@
 //!
 //! TODO: Implement for ARM64 (macOS Apple Silicon, Linux ARM, etc.)
 
-use gvthread_core::metadata::{VoluntarySavedRegs, ForcedSavedRegs};
+use gvthread_core::metadata::{VoluntarySavedRegs, ForcedSavedRegs, FpuState};
+use std::sync::atomic::AtomicU8;
 
 /// Initialize a new GVThread's context
 pub unsafe fn init_context(
@@ -22,7 +23,29 @@ pub unsafe extern "C" fn context_switch_voluntary(
     todo!("aarch64 context_switch_voluntary not yet implemented")
 }
 
+/// Capture an interrupted GVThread's registers from a signal context
+pub unsafe fn save_forced_context(
+    _regs: *mut ForcedSavedRegs,
+    _fpu: *mut FpuState,
+    _ucontext: *const libc::ucontext_t,
+) {
+    todo!("aarch64 save_forced_context not yet implemented")
+}
+
+/// Interrupted stack and instruction pointers from a signal context
+pub unsafe fn interrupted_sp_ip(_ucontext: *const libc::ucontext_t) -> (usize, usize) {
+    todo!("aarch64 interrupted_sp_ip not yet implemented")
+}
+
+/// Make the signal handler return into the forced switch path
+pub unsafe fn redirect_to_forced_switch(_ucontext: *mut libc::ucontext_t) {
+    todo!("aarch64 redirect_to_forced_switch not yet implemented")
+}
+
 /// Restore from forced preemption
-pub unsafe extern "C" fn context_restore_forced(_regs: *const ForcedSavedRegs) {
+pub unsafe extern "C" fn context_restore_forced(
+    _regs: *const ForcedSavedRegs,
+    _switching: *const AtomicU8,
+) {
     todo!("aarch64 context_restore_forced not yet implemented")
 }
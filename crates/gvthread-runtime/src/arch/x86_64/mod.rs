@@ -3,8 +3,9 @@
 //! Uses inline assembly for context switch.
 //! Now stable in Rust 1.88+
 
-use gvthread_core::metadata::{VoluntarySavedRegs, ForcedSavedRegs};
+use gvthread_core::metadata::{VoluntarySavedRegs, ForcedSavedRegs, FpuState};
 use std::arch::naked_asm;
+use std::sync::atomic::AtomicU8;
 
 /// Initialize a new GVThread's context
 ///
@@ -42,13 +43,20 @@ pub unsafe fn init_context(
 }
 
 /// Trampoline that calls the entry function with its argument
+///
+/// Its unwind info marks the return address undefined: this is the
+/// outermost frame of a GVThread stack, so backtraces and unwinding stop
+/// here instead of reading past the stack top.
 #[unsafe(naked)]
 pub unsafe extern "C" fn gvthread_entry_trampoline() {
     naked_asm!(
+        ".cfi_startproc",
+        ".cfi_undefined rip",
         "mov rdi, r13",
         "call r12",
         "call {cleanup}",
         "ud2",
+        ".cfi_endproc",
         cleanup = sym gvthread_finished,
     );
 }
@@ -89,18 +97,128 @@ pub unsafe extern "C" fn context_switch_voluntary(
     );
 }
 
+/// Capture an interrupted GVThread's registers from a signal context
+///
+/// Called from the SIGURG handler with the `ucontext_t` it was given.
+/// Copies the general purpose registers into `regs` and, when the
+/// kernel saved it, the x87/SSE state into `fpu` (pointed to by
+/// `regs.fpu_state_ptr` so `context_restore_forced` reloads it).
+///
+/// # Safety
+///
+/// `ucontext` must be the `ucontext_t` passed to an SA_SIGINFO handler;
+/// `regs` and `fpu` must be valid for writes.
+#[cfg(target_os = "linux")]
+pub unsafe fn save_forced_context(
+    regs: *mut ForcedSavedRegs,
+    fpu: *mut FpuState,
+    ucontext: *const libc::ucontext_t,
+) {
+    let mcontext = &(*ucontext).uc_mcontext;
+    let greg = |r: libc::c_int| mcontext.gregs[r as usize] as u64;
+    let regs = &mut *regs;
+    
+    regs.rax = greg(libc::REG_RAX);
+    regs.rbx = greg(libc::REG_RBX);
+    regs.rcx = greg(libc::REG_RCX);
+    regs.rdx = greg(libc::REG_RDX);
+    regs.rsi = greg(libc::REG_RSI);
+    regs.rdi = greg(libc::REG_RDI);
+    regs.rbp = greg(libc::REG_RBP);
+    regs.rsp = greg(libc::REG_RSP);
+    regs.r8 = greg(libc::REG_R8);
+    regs.r9 = greg(libc::REG_R9);
+    regs.r10 = greg(libc::REG_R10);
+    regs.r11 = greg(libc::REG_R11);
+    regs.r12 = greg(libc::REG_R12);
+    regs.r13 = greg(libc::REG_R13);
+    regs.r14 = greg(libc::REG_R14);
+    regs.r15 = greg(libc::REG_R15);
+    regs.rip = greg(libc::REG_RIP);
+    regs.rflags = greg(libc::REG_EFL);
+    
+    // The signal frame's fpstate starts with the FXSAVE image
+    let fpregs = mcontext.fpregs as *const u8;
+    if fpregs.is_null() {
+        regs.fpu_state_ptr = 0;
+    } else {
+        std::ptr::copy_nonoverlapping(fpregs, (*fpu).bytes.as_mut_ptr(), (*fpu).bytes.len());
+        regs.fpu_state_ptr = fpu as u64;
+    }
+}
+
+/// Interrupted stack and instruction pointers, `(rsp, rip)`, from a
+/// signal context
+///
+/// # Safety
+///
+/// `ucontext` must be the `ucontext_t` passed to an SA_SIGINFO handler.
+#[cfg(target_os = "linux")]
+#[inline]
+pub unsafe fn interrupted_sp_ip(ucontext: *const libc::ucontext_t) -> (usize, usize) {
+    let gregs = &(*ucontext).uc_mcontext.gregs;
+    (gregs[libc::REG_RSP as usize] as usize, gregs[libc::REG_RIP as usize] as usize)
+}
+
+/// Make the signal handler return into `forced_switch_entry` instead of
+/// the interrupted code, on the same stack below its red zone
+///
+/// # Safety
+///
+/// `ucontext` must be the `ucontext_t` passed to an SA_SIGINFO handler
+/// that interrupted a GVThread, after `save_forced_context` captured it.
+#[cfg(target_os = "linux")]
+pub unsafe fn redirect_to_forced_switch(ucontext: *mut libc::ucontext_t) {
+    let gregs = &mut (*ucontext).uc_mcontext.gregs;
+    let rsp = (gregs[libc::REG_RSP as usize] as u64 - 128) & !0xF;
+    gregs[libc::REG_RSP as usize] = rsp as i64;
+    gregs[libc::REG_RIP as usize] = forced_switch_entry as *const () as i64;
+}
+
+/// Where a forcibly preempted GVThread resumes after the signal handler
+///
+/// Calls `scheduler::forced_yield`, which switches out and, once the
+/// GVThread is resumed, restores the interrupted registers; it never
+/// returns. Unwinding stops here, as at `gvthread_entry_trampoline`.
+#[unsafe(naked)]
+unsafe extern "C" fn forced_switch_entry() {
+    naked_asm!(
+        ".cfi_startproc",
+        ".cfi_undefined rip",
+        "call {forced_yield}",
+        "ud2",
+        ".cfi_endproc",
+        forced_yield = sym crate::scheduler::forced_yield,
+    );
+}
+
 /// Restore from forced preemption (all registers)
+///
+/// Reloads the x87/SSE state first (if `fpu_state_ptr` is set), then the
+/// general purpose registers, flags and RIP. RIP and RFLAGS are staged
+/// below the 128-byte red zone, which the interrupted code may be using.
+///
+/// Clears `switching` once nothing more is read from `regs`: a SIGURG
+/// from then on may save over them for the next forced switch.
 #[unsafe(naked)]
-pub unsafe extern "C" fn context_restore_forced(_regs: *const ForcedSavedRegs) {
+pub unsafe extern "C" fn context_restore_forced(
+    _regs: *const ForcedSavedRegs,
+    _switching: *const AtomicU8,
+) {
     naked_asm!(
-        // RDI contains pointer to ForcedSavedRegs
+        // RDI contains pointer to ForcedSavedRegs, RSI the flag
+        "mov rax, [rdi + 0xA0]",
+        "test rax, rax",
+        "jz 2f",
+        "fxrstor64 [rax]",
+        "2:",
         "mov rax, [rdi + 0x00]",
         "mov rbx, [rdi + 0x08]",
         "mov rcx, [rdi + 0x10]",
         "mov rdx, [rdi + 0x18]",
-        "mov rsi, [rdi + 0x20]",
         "mov rbp, [rdi + 0x30]",
         "mov rsp, [rdi + 0x38]",
+        "lea rsp, [rsp - 128]",
         "mov r8,  [rdi + 0x40]",
         "mov r9,  [rdi + 0x48]",
         "mov r10, [rdi + 0x50]",
@@ -109,14 +227,17 @@ pub unsafe extern "C" fn context_restore_forced(_regs: *const ForcedSavedRegs) {
         "mov r13, [rdi + 0x68]",
         "mov r14, [rdi + 0x70]",
         "mov r15, [rdi + 0x78]",
-        // Push RIP and RFLAGS for return
+        // Push RIP, RFLAGS, RSI and RDI for return
         "push qword ptr [rdi + 0x80]",
         "push qword ptr [rdi + 0x88]",
-        // Now restore RDI
-        "mov rdi, [rdi + 0x28]",
-        // Restore flags and return
+        "push qword ptr [rdi + 0x20]",
+        "push qword ptr [rdi + 0x28]",
+        "mov byte ptr [rsi], 0",
+        // Restore RDI, RSI, flags, return and step back over the red zone
+        "pop rdi",
+        "pop rsi",
         "popfq",
-        "ret",
+        "ret 128",
     );
 }
 
@@ -132,22 +253,23 @@ extern "C" fn gvthread_finished() {
     use gvthread_core::metadata::GVThreadMetadata;
     
     // Get current GVThread info
-    let meta_base = tls::current_gvthread_base();
-    let worker_id = current_worker_id();
+    let meta_ptr = tls::current_metadata_ptr();
     
-    if meta_base.is_null() {
+    if meta_ptr.is_null() {
         // Something went wrong - spin forever (will trigger SIGURG eventually)
         loop { std::hint::spin_loop(); }
     }
     
+    // No forced switch from here on: it could move us off `worker_id`
+    let meta = unsafe { &*meta_ptr };
+    meta.begin_switch();
+    let worker_id = current_worker_id();
+    
     // Mark as finished
-    let meta = unsafe { &*(meta_base as *const GVThreadMetadata) };
     meta.set_state(GVThreadState::Finished);
     
     // Get our saved registers and scheduler context
-    let gvthread_regs = unsafe {
-        (meta_base).add(0x40) as *mut VoluntarySavedRegs
-    };
+    let gvthread_regs = GVThreadMetadata::saved_regs_ptr(meta_ptr);
     let sched_ctx = get_worker_sched_context(worker_id);
     
     // Switch back to scheduler - we will NOT return from this
@@ -159,3 +281,103 @@ extern "C" fn gvthread_finished() {
     // Should never reach here, but just in case
     unreachable!("gvthread_finished returned after context switch");
 }
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::arch::asm;
+    use std::os::unix::thread::JoinHandleExt;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    static mut SAVED_REGS: ForcedSavedRegs = unsafe { std::mem::zeroed() };
+    static mut SAVED_FPU: FpuState = FpuState { bytes: [0; 512] };
+    static SWITCHING: AtomicU8 = AtomicU8::new(0);
+    static PREEMPTIONS: AtomicUsize = AtomicUsize::new(0);
+    static STARTED: AtomicBool = AtomicBool::new(false);
+
+    /// Stand-in for the SIGURG handler: save the interrupted context,
+    /// trash the registers the kernel would restore, and resume through
+    /// `context_restore_forced` instead
+    extern "C" fn preempt(_sig: libc::c_int, _info: *mut libc::siginfo_t, uc: *mut libc::c_void) {
+        unsafe {
+            let uc = uc as *mut libc::ucontext_t;
+            save_forced_context(&raw mut SAVED_REGS, &raw mut SAVED_FPU, uc);
+
+            let mcontext = &mut (*uc).uc_mcontext;
+            // ST0-7 and XMM0-15 (the control words and the kernel's
+            // trailer must stay valid for sigreturn)
+            std::ptr::write_bytes((mcontext.fpregs as *mut u8).add(0x20), 0xAB, 0x180);
+            for r in [libc::REG_RAX, libc::REG_RCX, libc::REG_RDX, libc::REG_RSI, libc::REG_RDI,
+                      libc::REG_R8, libc::REG_R9, libc::REG_R10, libc::REG_R11] {
+                mcontext.gregs[r as usize] = 0x5A5A_5A5A;
+            }
+            mcontext.gregs[libc::REG_RIP as usize] = resume as *const () as i64;
+        }
+        PREEMPTIONS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[unsafe(naked)]
+    unsafe extern "C" fn resume() {
+        naked_asm!(
+            "lea rdi, [rip + {regs}]",
+            "lea rsi, [rip + {switching}]",
+            "jmp {restore}",
+            regs = sym SAVED_REGS,
+            switching = sym SWITCHING,
+            restore = sym context_restore_forced,
+        );
+    }
+
+    /// Sum 0.5 `n` times, keeping the accumulator in an XMM register
+    fn fp_loop(n: u64) -> f64 {
+        let mut acc = 0.0f64;
+        unsafe {
+            asm!(
+                "2:",
+                "addsd {acc}, {inc}",
+                "dec {n}",
+                "jnz 2b",
+                acc = inout(xmm_reg) acc,
+                inc = in(xmm_reg) 0.5f64,
+                n = inout(reg) n => _,
+            );
+        }
+        acc
+    }
+
+    #[test]
+    fn test_forced_restore_preserves_fp_state() {
+        const N: u64 = 1 << 28;
+        unsafe {
+            let mut sa: libc::sigaction = std::mem::zeroed();
+            sa.sa_sigaction = preempt as *const () as usize;
+            sa.sa_flags = libc::SA_SIGINFO;
+            assert_eq!(libc::sigaction(libc::SIGUSR1, &sa, std::ptr::null_mut()), 0);
+        }
+
+        let victim = std::thread::spawn(|| {
+            STARTED.store(true, Ordering::SeqCst);
+            fp_loop(N)
+        });
+        while !STARTED.load(Ordering::SeqCst) {
+            std::thread::yield_now();
+        }
+        // One preemption at a time: the next signal only after the
+        // previous one has been handled and (shortly after) resumed
+        for _ in 0..10 {
+            if victim.is_finished() {
+                break;
+            }
+            let before = PREEMPTIONS.load(Ordering::SeqCst);
+            unsafe { libc::pthread_kill(victim.as_pthread_t(), libc::SIGUSR1) };
+            while PREEMPTIONS.load(Ordering::SeqCst) == before && !victim.is_finished() {
+                std::thread::yield_now();
+            }
+            std::thread::sleep(Duration::from_millis(2));
+        }
+
+        assert_eq!(victim.join().unwrap(), 0.5 * N as f64);
+        assert!(PREEMPTIONS.load(Ordering::SeqCst) > 0, "loop finished before any preemption");
+    }
+}
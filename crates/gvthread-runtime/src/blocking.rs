@@ -0,0 +1,133 @@
+//! Blocking thread pool for `spawn_blocking`
+//!
+//! GVThreads share a small, fixed set of worker threads; a GVThread that
+//! blocks the OS thread (synchronous file I/O, heavy computation) stalls
+//! every other GVThread on that worker. `spawn_blocking` moves such work
+//! onto a separate pool of plain OS threads and hands back a
+//! `JoinHandle` whose `join()` parks only the calling GVThread.
+//!
+//! The pool is started lazily on first use, sized from
+//! `SchedulerConfig::blocking_threads` (`GVT_BLOCKING_THREADS`).
+
+use crate::config::SchedulerConfig;
+use crate::join::{join_pair, JoinError, JoinHandle};
+use crate::scheduler;
+
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::thread;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+struct PoolShared {
+    queue: Mutex<VecDeque<Job>>,
+    cond: Condvar,
+}
+
+/// Fixed pool of OS threads running blocking jobs
+struct BlockingPool {
+    shared: Arc<PoolShared>,
+    num_threads: usize,
+}
+
+static BLOCKING_POOL: OnceLock<BlockingPool> = OnceLock::new();
+
+impl BlockingPool {
+    fn new(num_threads: usize) -> Self {
+        let num_threads = num_threads.max(1);
+        let shared = Arc::new(PoolShared {
+            queue: Mutex::new(VecDeque::new()),
+            cond: Condvar::new(),
+        });
+        for i in 0..num_threads {
+            let shared = Arc::clone(&shared);
+            thread::Builder::new()
+                .name(format!("gvthread-blocking-{}", i))
+                .spawn(move || blocking_thread_loop(shared))
+                .expect("Failed to spawn blocking pool thread");
+        }
+        Self { shared, num_threads }
+    }
+
+    fn submit(&self, job: Job) {
+        self.shared.queue.lock().unwrap().push_back(job);
+        self.shared.cond.notify_one();
+    }
+}
+
+fn blocking_thread_loop(shared: Arc<PoolShared>) {
+    loop {
+        let job = {
+            let mut queue = shared.queue.lock().unwrap();
+            loop {
+                if let Some(job) = queue.pop_front() {
+                    break job;
+                }
+                queue = shared.cond.wait(queue).unwrap();
+            }
+        };
+        job();
+    }
+}
+
+fn pool() -> &'static BlockingPool {
+    BLOCKING_POOL.get_or_init(|| {
+        let n = scheduler::global_scheduler()
+            .map(|s| s.config().blocking_threads)
+            .unwrap_or_else(|| SchedulerConfig::from_env().blocking_threads);
+        BlockingPool::new(n)
+    })
+}
+
+/// Run `f` on the blocking thread pool
+///
+/// Returns immediately. `join()` on the handle yields the result (or
+/// `JoinError::Panic` if `f` panicked). Safe to call from inside or
+/// outside a GVThread.
+pub fn spawn_blocking<F, T>(f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let (handle, completer) = join_pair();
+    pool().submit(Box::new(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(f)).map_err(JoinError::Panic);
+        completer.complete(result);
+    }));
+    handle
+}
+
+/// Number of threads in the blocking pool (0 if not yet started)
+pub fn blocking_threads() -> usize {
+    BLOCKING_POOL.get().map(|p| p.num_threads).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::join::panic_message;
+
+    #[test]
+    fn join_returns_the_value() {
+        let handle = spawn_blocking(|| {
+            thread::sleep(std::time::Duration::from_millis(10));
+            thread::current().name().map(str::to_owned)
+        });
+        let name = handle.join().unwrap().unwrap();
+        assert!(name.starts_with("gvthread-blocking-"), "ran on {}", name);
+        assert!(blocking_threads() >= 1);
+    }
+
+    #[test]
+    fn panic_becomes_join_error() {
+        let handle = spawn_blocking(|| -> u32 { panic!("blocking boom") });
+        match handle.join() {
+            Err(JoinError::Panic(payload)) => assert_eq!(panic_message(&*payload), "blocking boom"),
+            Err(e) => panic!("expected a panic, got {}", e),
+            Ok(v) => panic!("expected a panic, got {}", v),
+        }
+        // The pool thread survived it
+        assert_eq!(spawn_blocking(|| 7).join().unwrap(), 7);
+    }
+}
@@ -0,0 +1,344 @@
+//! Broadcast channel: every receiver sees every message
+//!
+//! `channel(capacity)` returns a `Sender` and a `Receiver`. Unlike the
+//! point-to-point `gvthread_core::channel`, each value sent is delivered
+//! to every receiver alive at the time (fan-out of config updates,
+//! shutdown signals, ...).
+//!
+//! Values live in a ring of `capacity` slots; each receiver keeps its own
+//! cursor into it. The sender never waits: once the ring is full it
+//! overwrites the oldest value, and a receiver that had not read it yet
+//! gets `RecvError::Lagged(n)` (n values skipped) and resumes at the
+//! oldest value still held.
+//!
+//! An empty `recv()` blocks the calling GVThread via `block_current()`
+//! (or parks the OS thread outside a GVThread) until the next `send` or
+//! the last sender's drop.
+
+use crate::waiter::WaitNode;
+
+use gvthread_core::spinlock::SpinLock;
+
+use std::fmt;
+use std::sync::Arc;
+
+/// Create a broadcast channel holding the last `capacity` values
+///
+/// # Panics
+///
+/// If `capacity` is 0.
+pub fn channel<T: Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "broadcast channel capacity must be at least 1");
+    let shared = Arc::new(Shared {
+        state: SpinLock::new(State {
+            slots: (0..capacity).map(|_| None).collect(),
+            next: 0,
+            senders: 1,
+            receivers: 1,
+            waiters: Vec::new(),
+        }),
+    });
+    (
+        Sender { shared: Arc::clone(&shared) },
+        Receiver { shared, cursor: 0 },
+    )
+}
+
+struct Shared<T> {
+    state: SpinLock<State<T>>,
+}
+
+struct State<T> {
+    /// Ring: message `seq` lives in `slots[seq % len]`
+    slots: Box<[Option<T>]>,
+    /// Sequence number of the next message sent
+    next: u64,
+    senders: usize,
+    receivers: usize,
+    /// Receivers blocked on an empty channel
+    waiters: Vec<Arc<WaitNode>>,
+}
+
+impl<T> State<T> {
+    /// Oldest sequence number still in the ring
+    fn oldest(&self) -> u64 {
+        self.next.saturating_sub(self.slots.len() as u64)
+    }
+}
+
+impl<T: Clone> State<T> {
+    /// Read the value at `cursor` and advance it (or skip it forward
+    /// past overwritten values)
+    fn take(&self, cursor: &mut u64) -> Result<T, TryRecvError> {
+        let oldest = self.oldest();
+        if *cursor < oldest {
+            let missed = oldest - *cursor;
+            *cursor = oldest;
+            return Err(TryRecvError::Lagged(missed));
+        }
+        if *cursor == self.next {
+            return Err(if self.senders == 0 {
+                TryRecvError::Closed
+            } else {
+                TryRecvError::Empty
+            });
+        }
+        let slot = (*cursor % self.slots.len() as u64) as usize;
+        *cursor += 1;
+        Ok(self.slots[slot].clone().expect("slot below `next` is filled"))
+    }
+}
+
+/// Sending half; clone for more senders
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Receiving half; each clone reads every message on its own
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+    /// Sequence number of the next message to read
+    cursor: u64,
+}
+
+/// `send` failed: no receiver is left. Holds the value back.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+/// Error from `Receiver::recv`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvError {
+    /// Every sender is gone and this receiver has read everything
+    Closed,
+    /// This receiver fell behind: the oldest `n` values it had not read
+    /// were overwritten. The next `recv` returns the oldest value held.
+    Lagged(u64),
+}
+
+/// Error from `Receiver::try_recv`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// Nothing new yet
+    Empty,
+    /// Every sender is gone and this receiver has read everything
+    Closed,
+    /// See `RecvError::Lagged`
+    Lagged(u64),
+}
+
+impl<T> Sender<T> {
+    /// Send `value` to every receiver, without waiting
+    ///
+    /// Returns the number of receivers it went to, or the value back if
+    /// there are none. With the ring full, the oldest value is dropped.
+    pub fn send(&self, value: T) -> Result<usize, SendError<T>> {
+        let (receivers, waiters) = {
+            let mut state = self.shared.state.lock();
+            if state.receivers == 0 {
+                return Err(SendError(value));
+            }
+            let slot = (state.next % state.slots.len() as u64) as usize;
+            state.slots[slot] = Some(value);
+            state.next += 1;
+            (state.receivers, std::mem::take(&mut state.waiters))
+        };
+        for node in waiters {
+            node.notify();
+        }
+        Ok(receivers)
+    }
+
+    /// A new receiver that sees values sent from now on
+    pub fn subscribe(&self) -> Receiver<T> {
+        let mut state = self.shared.state.lock();
+        state.receivers += 1;
+        Receiver { shared: Arc::clone(&self.shared), cursor: state.next }
+    }
+
+    /// Number of live receivers
+    pub fn receiver_count(&self) -> usize {
+        self.shared.state.lock().receivers
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.state.lock().senders += 1;
+        Self { shared: Arc::clone(&self.shared) }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let waiters = {
+            let mut state = self.shared.state.lock();
+            state.senders -= 1;
+            if state.senders > 0 {
+                return;
+            }
+            std::mem::take(&mut state.waiters)
+        };
+        // Wake blocked receivers so they see `Closed`
+        for node in waiters {
+            node.notify();
+        }
+    }
+}
+
+impl<T: Clone> Receiver<T> {
+    /// Receive the next value, blocking while there is none
+    ///
+    /// `Err(Lagged(n))` if `n` values were overwritten before this
+    /// receiver got to them; `Err(Closed)` once every sender is gone and
+    /// the rest has been read.
+    pub fn recv(&mut self) -> Result<T, RecvError> {
+        loop {
+            let node = {
+                let mut state = self.shared.state.lock();
+                match state.take(&mut self.cursor) {
+                    Err(TryRecvError::Empty) => {}
+                    Err(TryRecvError::Closed) => return Err(RecvError::Closed),
+                    Err(TryRecvError::Lagged(n)) => return Err(RecvError::Lagged(n)),
+                    Ok(value) => return Ok(value),
+                }
+                // Registered under the lock: the next send finds us
+                let node = Arc::new(WaitNode::current());
+                state.waiters.push(Arc::clone(&node));
+                node
+            };
+            node.wait();
+        }
+    }
+
+    /// Receive the next value if there is one, without blocking
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        self.shared.state.lock().take(&mut self.cursor)
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Values sent that this receiver has not read yet (including ones
+    /// already overwritten)
+    pub fn len(&self) -> usize {
+        (self.shared.state.lock().next - self.cursor) as usize
+    }
+
+    /// True if there is nothing new to read
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A receiver at the same position: it reads the same values from here
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        self.shared.state.lock().receivers += 1;
+        Self { shared: Arc::clone(&self.shared), cursor: self.cursor }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.state.lock().receivers -= 1;
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("broadcast::Sender")
+            .field("receivers", &self.receiver_count())
+            .finish()
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("broadcast::Receiver")
+            .field("cursor", &self.cursor)
+            .finish()
+    }
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecvError::Closed => write!(f, "broadcast channel closed"),
+            RecvError::Lagged(n) => write!(f, "receiver lagged by {} values", n),
+        }
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn every_receiver_sees_every_value() {
+        let (tx, mut a) = channel(8);
+        let mut b = a.clone();
+        assert_eq!(tx.send(1), Ok(2));
+        let mut c = tx.subscribe(); // sees only what comes next
+        assert_eq!(tx.send(2), Ok(3));
+
+        assert_eq!((a.recv(), a.recv()), (Ok(1), Ok(2)));
+        assert_eq!((b.recv(), b.recv()), (Ok(1), Ok(2)));
+        assert_eq!(c.recv(), Ok(2));
+        assert_eq!(a.try_recv(), Err(TryRecvError::Empty));
+
+        drop(tx);
+        assert_eq!(a.recv(), Err(RecvError::Closed));
+        assert_eq!(c.try_recv(), Err(TryRecvError::Closed));
+    }
+
+    #[test]
+    fn lagging_receiver_skips_to_the_oldest_value() {
+        let (tx, mut rx) = channel(3);
+        for i in 0..5 {
+            tx.send(i).unwrap();
+        }
+        assert_eq!(rx.len(), 5);
+        assert_eq!(rx.recv(), Err(RecvError::Lagged(2)));
+        assert_eq!((rx.recv(), rx.recv(), rx.recv()), (Ok(2), Ok(3), Ok(4)));
+        assert!(rx.is_empty());
+    }
+
+    #[test]
+    fn send_without_receivers_hands_the_value_back() {
+        let (tx, rx) = channel(2);
+        drop(rx);
+        assert_eq!(tx.send("config"), Err(SendError("config")));
+    }
+
+    #[test]
+    fn blocked_receivers_wake_on_send_and_on_close() {
+        let (tx, rx) = channel::<u32>(4);
+        let readers: Vec<_> = (0..3)
+            .map(|_| {
+                let mut rx = rx.clone();
+                thread::spawn(move || {
+                    let mut got = Vec::new();
+                    while let Ok(v) = rx.recv() {
+                        got.push(v);
+                    }
+                    got
+                })
+            })
+            .collect();
+        drop(rx);
+
+        for i in 0..100 {
+            tx.send(i).unwrap();
+            // Keep within capacity so nobody lags
+            while tx.shared.state.lock().waiters.len() < 3 {
+                thread::yield_now();
+            }
+        }
+        drop(tx);
+        for reader in readers {
+            assert_eq!(reader.join().unwrap(), (0..100).collect::<Vec<_>>());
+        }
+    }
+}
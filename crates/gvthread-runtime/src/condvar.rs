@@ -0,0 +1,197 @@
+//! GVThread-aware condition variable
+//!
+//! `SchedCondvar` pairs with `SchedMutex`. `wait()` releases the mutex,
+//! blocks the calling GVThread via `block_current()` (or parks the OS
+//! thread, when called outside a GVThread), and re-acquires the mutex
+//! once notified.
+//!
+//! The waiter enqueues its `WaitNode` *before* releasing the mutex, so a
+//! notifier that changes the shared state under the mutex always finds
+//! it (see `waiter` for how the wake itself avoids being lost).
+//!
+//! As with any condition variable, callers must re-check their predicate
+//! after `wait()` returns — or use `wait_while()`.
+
+use crate::waiter::WaitNode;
+
+use gvthread_core::error::SchedResult;
+use gvthread_core::mutex::{SchedMutex, SchedMutexGuard};
+use gvthread_core::spinlock::SpinLock;
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// A condition variable for use with `SchedMutex`
+///
+/// # Example
+///
+/// ```ignore
+/// let (lock, cond) = (SchedMutex::new(false), SchedCondvar::new());
+///
+/// // Waiter (in a GVThread):
+/// let ready = cond.wait_while(lock.lock()?, |ready| !*ready)?;
+///
+/// // Notifier:
+/// *lock.lock()? = true;
+/// cond.notify_one();
+/// ```
+pub struct SchedCondvar {
+    waiters: SpinLock<VecDeque<Arc<WaitNode>>>,
+}
+
+impl SchedCondvar {
+    /// Create a condition variable with no waiters
+    pub const fn new() -> Self {
+        Self {
+            waiters: SpinLock::new(VecDeque::new()),
+        }
+    }
+
+    /// Release `guard`, block until notified, and re-acquire the mutex
+    ///
+    /// May return without the predicate holding (another waiter got there
+    /// first); re-check it, or use `wait_while()`.
+    pub fn wait<'a, T>(&self, guard: SchedMutexGuard<'a, T>) -> SchedResult<SchedMutexGuard<'a, T>> {
+        let mutex: &'a SchedMutex<T> = SchedMutexGuard::mutex(&guard);
+        let node = Arc::new(WaitNode::current());
+        self.waiters.lock().push_back(Arc::clone(&node));
+        drop(guard);
+
+        node.wait();
+        mutex.lock()
+    }
+
+    /// Wait while `condition` returns `true`
+    ///
+    /// Returns with the mutex held and `condition` false.
+    pub fn wait_while<'a, T, F>(
+        &self,
+        mut guard: SchedMutexGuard<'a, T>,
+        mut condition: F,
+    ) -> SchedResult<SchedMutexGuard<'a, T>>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        while condition(&mut *guard) {
+            guard = self.wait(guard)?;
+        }
+        Ok(guard)
+    }
+
+    /// Wake one waiter, if any (FIFO)
+    pub fn notify_one(&self) {
+        let node = self.waiters.lock().pop_front();
+        if let Some(node) = node {
+            node.notify();
+        }
+    }
+
+    /// Wake all current waiters
+    pub fn notify_all(&self) {
+        let nodes = std::mem::take(&mut *self.waiters.lock());
+        for node in nodes {
+            node.notify();
+        }
+    }
+
+    /// Number of waiters not yet notified (diagnostics)
+    pub fn waiter_count(&self) -> usize {
+        self.waiters.lock().len()
+    }
+}
+
+impl Default for SchedCondvar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for SchedCondvar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SchedCondvar")
+            .field("waiters", &self.waiter_count())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::thread;
+
+    const CAPACITY: usize = 4;
+
+    struct BoundedBuffer {
+        items: SchedMutex<VecDeque<u32>>,
+        not_empty: SchedCondvar,
+        not_full: SchedCondvar,
+    }
+
+    impl BoundedBuffer {
+        fn push(&self, v: u32) {
+            let mut q = self.not_full
+                .wait_while(self.items.lock().unwrap(), |q| q.len() >= CAPACITY)
+                .unwrap();
+            q.push_back(v);
+            drop(q);
+            self.not_empty.notify_one();
+        }
+
+        fn pop(&self) -> u32 {
+            let mut q = self.not_empty
+                .wait_while(self.items.lock().unwrap(), |q| q.is_empty())
+                .unwrap();
+            let v = q.pop_front().unwrap();
+            drop(q);
+            self.not_full.notify_one();
+            v
+        }
+    }
+
+    #[test]
+    fn bounded_buffer_no_lost_wakeups() {
+        const PRODUCERS: u32 = 4;
+        const PER_PRODUCER: u32 = 2000;
+
+        let buf = Arc::new(BoundedBuffer {
+            items: SchedMutex::new(VecDeque::new()),
+            not_empty: SchedCondvar::new(),
+            not_full: SchedCondvar::new(),
+        });
+
+        let producers: Vec<_> = (0..PRODUCERS)
+            .map(|p| {
+                let buf = Arc::clone(&buf);
+                thread::spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        buf.push(p * PER_PRODUCER + i);
+                    }
+                })
+            })
+            .collect();
+        let consumers: Vec<_> = (0..PRODUCERS)
+            .map(|_| {
+                let buf = Arc::clone(&buf);
+                thread::spawn(move || (0..PER_PRODUCER).map(|_| buf.pop() as u64).sum::<u64>())
+            })
+            .collect();
+
+        for p in producers {
+            p.join().unwrap();
+        }
+        let total: u64 = consumers.into_iter().map(|c| c.join().unwrap()).sum();
+        let n = (PRODUCERS * PER_PRODUCER) as u64;
+        assert_eq!(total, n * (n - 1) / 2);
+        assert_eq!(buf.not_empty.waiter_count(), 0);
+        assert_eq!(buf.not_full.waiter_count(), 0);
+    }
+
+    #[test]
+    fn notify_without_waiters_is_noop() {
+        let cv = SchedCondvar::new();
+        cv.notify_one();
+        cv.notify_all();
+        assert_eq!(cv.waiter_count(), 0);
+    }
+}
@@ -24,13 +24,19 @@ mod tests {
         let _ = GRACE_PERIOD_MS;
         let _ = TIMER_INTERVAL_MS;
         let _ = TIMER_MAX_SLEEP_MS;
+        let _ = TIMER_MIN_SLEEP_US;
         let _ = ENABLE_FORCED_PREEMPT;
+        let _ = FORCED_SWITCH;
         let _ = DEBUG_LOGGING;
         let _ = STACK_SIZE;
         let _ = LOCAL_QUEUE_CAPACITY;
         let _ = GLOBAL_QUEUE_CAPACITY;
         let _ = IDLE_SPINS;
         let _ = PARK_TIMEOUT_MS;
+        let _ = BLOCKING_THREADS;
+        let _ = PIN_WORKERS;
+        let _ = LAZY_STACK_FREE;
+        let _ = SUPERVISE_WORKERS;
     }
 
     #[test]
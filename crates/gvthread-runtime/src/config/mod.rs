@@ -21,44 +21,113 @@
 //!     .num_workers(8)
 //!     .time_slice(Duration::from_millis(5));
 //! ```
+//!
+//! With the `serde` feature, `SchedulerConfig` can also be read from a
+//! config file section; durations are integer milliseconds, as in the
+//! `*_MS` variables, and missing fields come from `from_env()`:
+//!
+//! ```rust,ignore
+//! #[derive(serde::Deserialize)]
+//! struct ServiceConfig {
+//!     scheduler: SchedulerConfig,
+//!     // ...
+//! }
+//! ```
 
 pub mod defaults;
 
+use std::str::FromStr;
 use std::time::Duration;
-use gvthread_core::env::env_get;
+use gvthread_core::env::{env_get, env_get_bytes, env_get_duration};
+use gvthread_core::error::SchedError;
+
+/// Ready queue implementation used by the scheduler
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum ReadyQueueKind {
+    /// Go-like per-worker local queues + global queue; priority ignored
+    #[default]
+    Simple,
+    /// One ready bitmap per priority level; the highest priority runs first
+    Priority,
+}
+
+impl FromStr for ReadyQueueKind {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "simple" => Ok(Self::Simple),
+            "priority" => Ok(Self::Priority),
+            _ => Err(ConfigError::InvalidValue("ready queue must be simple or priority")),
+        }
+    }
+}
 
 /// Scheduler configuration with builder pattern.
 ///
 /// Use `from_env()` to start with compile-time defaults and apply
 /// any environment variable overrides.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct SchedulerConfig {
     /// Number of worker threads
     pub num_workers: usize,
     /// Number of workers dedicated to low priority GVThreads
     pub num_low_priority_workers: usize,
-    /// Maximum concurrent GVThreads
+    /// Maximum concurrent GVThreads; with `stack_size`, sets the address
+    /// space reserved up front (at most `memory::MAX_REGION_SIZE`)
     pub max_gvthreads: usize,
     /// Time slice before setting preempt flag
+    #[cfg_attr(feature = "serde", serde(with = "duration_ms"))]
     pub time_slice: Duration,
     /// Grace period before forced preemption (SIGURG)
+    #[cfg_attr(feature = "serde", serde(with = "duration_ms"))]
     pub grace_period: Duration,
     /// Timer thread check interval
+    #[cfg_attr(feature = "serde", serde(with = "duration_ms"))]
     pub timer_interval: Duration,
     /// Enable SIGURG-based forced preemption
+    ///
+    /// SIGURG sets the GVThread's preempt flag and interrupts a blocking
+    /// syscall; it yields at its next safepoint. Switching it out from
+    /// the handler as well takes `forced_switch`, which is unsafe with
+    /// thread-affine state the runtime can't see: std's stdout lock
+    /// (`println!` from the next GVThread on the worker panics "already
+    /// borrowed"), other thread-local caches keyed by OS thread, and
+    /// allocators linked statically into the executable (jemalloc,
+    /// mimalloc, musl's malloc).
     pub enable_forced_preempt: bool,
+    /// Let SIGURG switch a CPU-bound GVThread out, not only flag it
+    /// (needs `enable_forced_preempt`; see its hazards)
+    pub forced_switch: bool,
     /// Enable debug logging
     pub debug_logging: bool,
-    /// Virtual stack size per GVThread
+    /// Virtual stack size per GVThread; only touched pages use memory
     pub stack_size: usize,
-    /// Per-worker local queue capacity
+    /// Per-worker local queue capacity; pushes past it spill to the
+    /// global queue
     pub local_queue_capacity: usize,
     /// Global queue capacity
     pub global_queue_capacity: usize,
     /// Spins before parking worker
     pub idle_spins: u32,
     /// Worker park timeout
+    #[cfg_attr(feature = "serde", serde(with = "duration_ms"))]
     pub park_timeout: Duration,
+    /// OS threads in the `spawn_blocking` pool
+    pub blocking_threads: usize,
+    /// Ready queue implementation
+    pub ready_queue: ReadyQueueKind,
+    /// Pin worker i to CPU i (modulo the CPUs available)
+    pub pin_workers: bool,
+    /// Release finished stacks with MADV_FREE (lazy) instead of MADV_DONTNEED
+    pub lazy_stack_free: bool,
+    /// Restart a worker whose scheduler loop panics, re-queuing the
+    /// GVThread it was running
+    pub supervise_workers: bool,
 }
 
 impl Default for SchedulerConfig {
@@ -70,20 +139,28 @@ impl Default for SchedulerConfig {
 impl SchedulerConfig {
     /// Create config from compile-time defaults with environment overrides.
     ///
-    /// Environment variables (all optional):
+    /// Environment variables (all optional). Durations can be given with a
+    /// unit (`GVT_TIME_SLICE=500us`), which takes precedence over the older
+    /// `*_MS` form:
     /// - `GVT_NUM_WORKERS` - Number of worker threads
     /// - `GVT_NUM_LOW_PRIORITY_WORKERS` - Low priority workers
     /// - `GVT_MAX_GVTHREADS` - Max concurrent GVThreads
-    /// - `GVT_TIME_SLICE_MS` - Time slice in milliseconds
-    /// - `GVT_GRACE_PERIOD_MS` - Grace period in milliseconds
-    /// - `GVT_TIMER_INTERVAL_MS` - Timer interval in milliseconds
+    /// - `GVT_TIME_SLICE` / `GVT_TIME_SLICE_MS` - Time slice (`"10ms"` / milliseconds)
+    /// - `GVT_GRACE_PERIOD` / `GVT_GRACE_PERIOD_MS` - Grace period
+    /// - `GVT_TIMER_INTERVAL` / `GVT_TIMER_INTERVAL_MS` - Timer interval
     /// - `GVT_ENABLE_FORCED_PREEMPT` - Enable SIGURG (0/1)
+    /// - `GVT_FORCED_SWITCH` - Switch GVThreads out on SIGURG (0/1)
     /// - `GVT_DEBUG` - Enable debug logging (0/1)
-    /// - `GVT_STACK_SIZE` - Stack size per GVThread
+    /// - `GVT_STACK_SIZE` - Stack size per GVThread (bytes, or `"64KB"`, `"16MB"`)
     /// - `GVT_LOCAL_QUEUE_CAPACITY` - Per-worker queue size
     /// - `GVT_GLOBAL_QUEUE_CAPACITY` - Global queue size
     /// - `GVT_IDLE_SPINS` - Spins before parking
-    /// - `GVT_PARK_TIMEOUT_MS` - Park timeout in milliseconds
+    /// - `GVT_PARK_TIMEOUT` / `GVT_PARK_TIMEOUT_MS` - Park timeout
+    /// - `GVT_BLOCKING_THREADS` - `spawn_blocking` pool size
+    /// - `GVT_READY_QUEUE` - Ready queue: `simple` or `priority`
+    /// - `GVT_PIN_WORKERS` - Pin workers to CPUs (0/1)
+    /// - `GVT_LAZY_STACK_FREE` - Release finished stacks with MADV_FREE (0/1)
+    /// - `GVT_SUPERVISE_WORKERS` - Restart workers whose loop panics (0/1)
     pub fn from_env() -> Self {
         Self {
             num_workers: env_get("GVT_NUM_WORKERS", defaults::NUM_WORKERS),
@@ -92,27 +169,31 @@ impl SchedulerConfig {
                 defaults::NUM_LOW_PRIORITY_WORKERS,
             ),
             max_gvthreads: env_get("GVT_MAX_GVTHREADS", defaults::MAX_GVTHREADS),
-            time_slice: Duration::from_millis(env_get(
-                "GVT_TIME_SLICE_MS",
-                defaults::TIME_SLICE_MS,
-            )),
-            grace_period: Duration::from_millis(env_get(
-                "GVT_GRACE_PERIOD_MS",
-                defaults::GRACE_PERIOD_MS,
-            )),
-            timer_interval: Duration::from_millis(env_get(
-                "GVT_TIMER_INTERVAL_MS",
-                defaults::TIMER_INTERVAL_MS,
-            )),
+            time_slice: env_get_duration(
+                "GVT_TIME_SLICE",
+                Duration::from_millis(env_get("GVT_TIME_SLICE_MS", defaults::TIME_SLICE_MS)),
+            ),
+            grace_period: env_get_duration(
+                "GVT_GRACE_PERIOD",
+                Duration::from_millis(env_get("GVT_GRACE_PERIOD_MS", defaults::GRACE_PERIOD_MS)),
+            ),
+            timer_interval: env_get_duration(
+                "GVT_TIMER_INTERVAL",
+                Duration::from_millis(env_get("GVT_TIMER_INTERVAL_MS", defaults::TIMER_INTERVAL_MS)),
+            ),
             enable_forced_preempt: env_get(
                 "GVT_ENABLE_FORCED_PREEMPT",
                 if defaults::ENABLE_FORCED_PREEMPT { 1usize } else { 0 },
             ) != 0,
+            forced_switch: env_get(
+                "GVT_FORCED_SWITCH",
+                if defaults::FORCED_SWITCH { 1usize } else { 0 },
+            ) != 0,
             debug_logging: env_get(
                 "GVT_DEBUG",
                 if defaults::DEBUG_LOGGING { 1usize } else { 0 },
             ) != 0,
-            stack_size: env_get("GVT_STACK_SIZE", defaults::STACK_SIZE),
+            stack_size: env_get_bytes("GVT_STACK_SIZE", defaults::STACK_SIZE),
             local_queue_capacity: env_get(
                 "GVT_LOCAL_QUEUE_CAPACITY",
                 defaults::LOCAL_QUEUE_CAPACITY,
@@ -122,10 +203,24 @@ impl SchedulerConfig {
                 defaults::GLOBAL_QUEUE_CAPACITY,
             ),
             idle_spins: env_get("GVT_IDLE_SPINS", defaults::IDLE_SPINS as usize) as u32,
-            park_timeout: Duration::from_millis(env_get(
-                "GVT_PARK_TIMEOUT_MS",
-                defaults::PARK_TIMEOUT_MS,
-            )),
+            park_timeout: env_get_duration(
+                "GVT_PARK_TIMEOUT",
+                Duration::from_millis(env_get("GVT_PARK_TIMEOUT_MS", defaults::PARK_TIMEOUT_MS)),
+            ),
+            blocking_threads: env_get("GVT_BLOCKING_THREADS", defaults::BLOCKING_THREADS),
+            ready_queue: env_get("GVT_READY_QUEUE", ReadyQueueKind::default()),
+            pin_workers: env_get(
+                "GVT_PIN_WORKERS",
+                if defaults::PIN_WORKERS { 1usize } else { 0 },
+            ) != 0,
+            lazy_stack_free: env_get(
+                "GVT_LAZY_STACK_FREE",
+                if defaults::LAZY_STACK_FREE { 1usize } else { 0 },
+            ) != 0,
+            supervise_workers: env_get(
+                "GVT_SUPERVISE_WORKERS",
+                if defaults::SUPERVISE_WORKERS { 1usize } else { 0 },
+            ) != 0,
         }
     }
 
@@ -140,12 +235,18 @@ impl SchedulerConfig {
             grace_period: Duration::from_millis(defaults::GRACE_PERIOD_MS),
             timer_interval: Duration::from_millis(defaults::TIMER_INTERVAL_MS),
             enable_forced_preempt: defaults::ENABLE_FORCED_PREEMPT,
+            forced_switch: defaults::FORCED_SWITCH,
             debug_logging: defaults::DEBUG_LOGGING,
             stack_size: defaults::STACK_SIZE,
             local_queue_capacity: defaults::LOCAL_QUEUE_CAPACITY,
             global_queue_capacity: defaults::GLOBAL_QUEUE_CAPACITY,
             idle_spins: defaults::IDLE_SPINS,
             park_timeout: Duration::from_millis(defaults::PARK_TIMEOUT_MS),
+            blocking_threads: defaults::BLOCKING_THREADS,
+            ready_queue: ReadyQueueKind::default(),
+            pin_workers: defaults::PIN_WORKERS,
+            lazy_stack_free: defaults::LAZY_STACK_FREE,
+            supervise_workers: defaults::SUPERVISE_WORKERS,
         }
     }
 
@@ -186,6 +287,11 @@ impl SchedulerConfig {
         self
     }
 
+    pub fn forced_switch(mut self, enable: bool) -> Self {
+        self.forced_switch = enable;
+        self
+    }
+
     pub fn debug_logging(mut self, enable: bool) -> Self {
         self.debug_logging = enable;
         self
@@ -216,6 +322,31 @@ impl SchedulerConfig {
         self
     }
 
+    pub fn blocking_threads(mut self, n: usize) -> Self {
+        self.blocking_threads = n;
+        self
+    }
+
+    pub fn ready_queue(mut self, kind: ReadyQueueKind) -> Self {
+        self.ready_queue = kind;
+        self
+    }
+
+    pub fn pin_workers(mut self, enable: bool) -> Self {
+        self.pin_workers = enable;
+        self
+    }
+
+    pub fn lazy_stack_free(mut self, enable: bool) -> Self {
+        self.lazy_stack_free = enable;
+        self
+    }
+
+    pub fn supervise_workers(mut self, enable: bool) -> Self {
+        self.supervise_workers = enable;
+        self
+    }
+
     /// Validate configuration and return errors if invalid.
     pub fn validate(&self) -> Result<(), ConfigError> {
         if self.num_workers == 0 {
@@ -235,31 +366,145 @@ impl SchedulerConfig {
         if self.stack_size < 64 * 1024 {
             return Err(ConfigError::InvalidValue("stack_size must be >= 64KB"));
         }
+        if crate::memory::region_size_for(self.max_gvthreads, self.stack_size).is_none() {
+            return Err(ConfigError::InvalidValue(
+                "max_gvthreads * stack_size must fit in 64 TB of address space",
+            ));
+        }
         if self.local_queue_capacity == 0 {
             return Err(ConfigError::InvalidValue("local_queue_capacity must be > 0"));
         }
         if self.global_queue_capacity == 0 {
             return Err(ConfigError::InvalidValue("global_queue_capacity must be > 0"));
         }
+        if self.blocking_threads == 0 {
+            return Err(ConfigError::InvalidValue("blocking_threads must be > 0"));
+        }
         Ok(())
     }
 
     /// Print configuration (for debugging)
+    ///
+    /// Each value is followed by where it came from (see `sources`).
+    /// `init_global_scheduler` calls this when `GVT_PRINT_CONFIG=1`.
     pub fn print(&self) {
         eprintln!("GVThread Configuration:");
-        eprintln!("  num_workers:            {}", self.num_workers);
-        eprintln!("  num_low_priority:       {}", self.num_low_priority_workers);
-        eprintln!("  max_gvthreads:          {}", self.max_gvthreads);
-        eprintln!("  time_slice:             {:?}", self.time_slice);
-        eprintln!("  grace_period:           {:?}", self.grace_period);
-        eprintln!("  timer_interval:         {:?}", self.timer_interval);
-        eprintln!("  enable_forced_preempt:  {}", self.enable_forced_preempt);
-        eprintln!("  debug_logging:          {}", self.debug_logging);
-        eprintln!("  stack_size:             {}", self.stack_size);
-        eprintln!("  local_queue_capacity:   {}", self.local_queue_capacity);
-        eprintln!("  global_queue_capacity:  {}", self.global_queue_capacity);
-        eprintln!("  idle_spins:             {}", self.idle_spins);
-        eprintln!("  park_timeout:           {:?}", self.park_timeout);
+        for ((name, value), (_, source)) in self.fields().iter().zip(self.sources()) {
+            let label = format!("{}:", name);
+            eprintln!("  {:<24}{:<16}({})", label, value, source);
+        }
+    }
+
+    /// Where each setting's current value came from, in `print` order
+    ///
+    /// A value equal to the compiled-in default counts as `Default`, even
+    /// if an environment variable spells out the same value; a value that
+    /// matches neither the default nor the environment was set in code.
+    pub fn sources(&self) -> Vec<(&'static str, ConfigSource)> {
+        let defaults = Self::new().fields();
+        let env = Self::from_env().fields();
+        self.fields()
+            .into_iter()
+            .zip(ENV_VARS)
+            .enumerate()
+            .map(|(i, ((name, value), vars))| {
+                // The first one set is the one `from_env` prefers
+                let var = vars.iter().copied().find(|v| std::env::var_os(v).is_some());
+                let source = match var {
+                    _ if value == defaults[i].1 => ConfigSource::Default,
+                    Some(var) if value == env[i].1 => ConfigSource::Env(var),
+                    _ => ConfigSource::Code,
+                };
+                (name, source)
+            })
+            .collect()
+    }
+
+    /// Each setting as `(name, formatted value)`; `ENV_VARS` follows the
+    /// same order
+    fn fields(&self) -> [(&'static str, String); 19] {
+        [
+            ("num_workers", self.num_workers.to_string()),
+            ("num_low_priority", self.num_low_priority_workers.to_string()),
+            ("max_gvthreads", self.max_gvthreads.to_string()),
+            ("time_slice", format!("{:?}", self.time_slice)),
+            ("grace_period", format!("{:?}", self.grace_period)),
+            ("timer_interval", format!("{:?}", self.timer_interval)),
+            ("enable_forced_preempt", self.enable_forced_preempt.to_string()),
+            ("forced_switch", self.forced_switch.to_string()),
+            ("debug_logging", self.debug_logging.to_string()),
+            ("stack_size", self.stack_size.to_string()),
+            ("local_queue_capacity", self.local_queue_capacity.to_string()),
+            ("global_queue_capacity", self.global_queue_capacity.to_string()),
+            ("idle_spins", self.idle_spins.to_string()),
+            ("park_timeout", format!("{:?}", self.park_timeout)),
+            ("blocking_threads", self.blocking_threads.to_string()),
+            ("ready_queue", format!("{:?}", self.ready_queue)),
+            ("pin_workers", self.pin_workers.to_string()),
+            ("lazy_stack_free", self.lazy_stack_free.to_string()),
+            ("supervise_workers", self.supervise_workers.to_string()),
+        ]
+    }
+}
+
+/// Environment variables read by `from_env` for each of
+/// `SchedulerConfig::fields`, highest precedence first
+const ENV_VARS: [&[&str]; 19] = [
+    &["GVT_NUM_WORKERS"],
+    &["GVT_NUM_LOW_PRIORITY_WORKERS"],
+    &["GVT_MAX_GVTHREADS"],
+    &["GVT_TIME_SLICE", "GVT_TIME_SLICE_MS"],
+    &["GVT_GRACE_PERIOD", "GVT_GRACE_PERIOD_MS"],
+    &["GVT_TIMER_INTERVAL", "GVT_TIMER_INTERVAL_MS"],
+    &["GVT_ENABLE_FORCED_PREEMPT"],
+    &["GVT_FORCED_SWITCH"],
+    &["GVT_DEBUG"],
+    &["GVT_STACK_SIZE"],
+    &["GVT_LOCAL_QUEUE_CAPACITY"],
+    &["GVT_GLOBAL_QUEUE_CAPACITY"],
+    &["GVT_IDLE_SPINS"],
+    &["GVT_PARK_TIMEOUT", "GVT_PARK_TIMEOUT_MS"],
+    &["GVT_BLOCKING_THREADS"],
+    &["GVT_READY_QUEUE"],
+    &["GVT_PIN_WORKERS"],
+    &["GVT_LAZY_STACK_FREE"],
+    &["GVT_SUPERVISE_WORKERS"],
+];
+
+/// Where a configuration value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// Compiled-in default (library defaults or the user's gvt_config.rs)
+    Default,
+    /// The named environment variable
+    Env(&'static str),
+    /// Set in code, by a builder method or field assignment
+    Code,
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::Env(var) => write!(f, "env {}", var),
+            ConfigSource::Code => write!(f, "code"),
+        }
+    }
+}
+
+/// (De)serialize a `Duration` as whole milliseconds, like the `*_MS`
+/// environment variables (anything below a millisecond is dropped)
+#[cfg(feature = "serde")]
+mod duration_ms {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(d: &Duration, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_u64(d.as_millis().try_into().unwrap_or(u64::MAX))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Duration, D::Error> {
+        u64::deserialize(d).map(Duration::from_millis)
     }
 }
 
@@ -279,6 +524,14 @@ impl std::fmt::Display for ConfigError {
 
 impl std::error::Error for ConfigError {}
 
+impl From<ConfigError> for SchedError {
+    fn from(e: ConfigError) -> Self {
+        match e {
+            ConfigError::InvalidValue(msg) => SchedError::InvalidConfig(msg),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -309,5 +562,62 @@ mod tests {
 
         let config = SchedulerConfig::from_env().num_workers(1000);
         assert!(config.validate().is_err());
+
+        let config = SchedulerConfig::from_env()
+            .max_gvthreads(8 << 20)
+            .stack_size(16 << 20);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_ready_queue_kind() {
+        assert_eq!("priority".parse::<ReadyQueueKind>().unwrap(), ReadyQueueKind::Priority);
+        assert_eq!(" Simple ".parse::<ReadyQueueKind>().unwrap(), ReadyQueueKind::Simple);
+        assert!("bitmap".parse::<ReadyQueueKind>().is_err());
+
+        let config = SchedulerConfig::new().ready_queue(ReadyQueueKind::Priority);
+        assert_eq!(config.ready_queue, ReadyQueueKind::Priority);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let config = SchedulerConfig::new()
+            .num_workers(3)
+            .time_slice(Duration::from_millis(7))
+            .park_timeout(Duration::from_secs(2))
+            .ready_queue(ReadyQueueKind::Priority)
+            .pin_workers(true)
+            .supervise_workers(true);
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains("\"time_slice\":7"));
+        assert!(json.contains("\"ready_queue\":\"priority\""));
+
+        let back: SchedulerConfig = serde_json::from_str(&json).unwrap();
+        // Every field, formatted
+        assert_eq!(back.fields(), config.fields());
+
+        // A partial block keeps the rest at from_env()
+        let partial: SchedulerConfig = serde_json::from_str(r#"{"num_workers": 2}"#).unwrap();
+        assert_eq!(partial.num_workers, 2);
+        assert_eq!(partial.stack_size, SchedulerConfig::from_env().stack_size);
+    }
+
+    #[test]
+    fn test_sources() {
+        // Not read by any other test in this binary
+        std::env::set_var("GVT_IDLE_SPINS", "77");
+        let config = SchedulerConfig::from_env()
+            .blocking_threads(defaults::BLOCKING_THREADS + 1)
+            .ready_queue(ReadyQueueKind::default());
+        let sources = config.sources();
+        let source = |name| sources.iter().find(|(n, _)| *n == name).unwrap().1;
+
+        assert_eq!(source("idle_spins"), ConfigSource::Env("GVT_IDLE_SPINS"));
+        assert_eq!(source("blocking_threads"), ConfigSource::Code);
+        assert_eq!(source("ready_queue"), ConfigSource::Default);
+        // The builder wins over the environment
+        assert_eq!(config.idle_spins(5).sources()[12], ("idle_spins", ConfigSource::Code));
+        std::env::remove_var("GVT_IDLE_SPINS");
     }
 }
\ No newline at end of file
@@ -0,0 +1,157 @@
+//! Driving a `Future` to completion from blocking-style code
+//!
+//! `block_on_future(fut)` is a minimal single-future executor: it polls
+//! `fut` on the calling GVThread and, while it is pending, blocks the
+//! GVThread via `block_current()` (or parks the OS thread, outside a
+//! GVThread) until the future's waker fires, then polls again.
+//!
+//! The waker is one `Arc` per call. Each time the future is pending the
+//! executor hands it a fresh `WaitNode` (see `waiter`) and waits on that,
+//! so the wake itself never gets lost or delivered to a GVThread that has
+//! moved on. A wake that lands while the executor is still polling finds
+//! no node and just leaves `notified` set: the executor polls again
+//! instead of blocking.
+
+use crate::waiter::WaitNode;
+
+use gvthread_core::spinlock::SpinLock;
+
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+#[derive(Default)]
+struct WakeState {
+    /// Woken since the executor last started polling
+    notified: bool,
+    /// The executor is blocked (or about to block) on this
+    parked: Option<Arc<WaitNode>>,
+}
+
+#[derive(Default)]
+struct FutureWaker {
+    state: SpinLock<WakeState>,
+}
+
+impl Wake for FutureWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        let parked = {
+            let mut state = self.state.lock();
+            state.notified = true;
+            state.parked.take()
+        };
+        if let Some(node) = parked {
+            node.notify();
+        }
+    }
+}
+
+/// Run `fut` to completion on the current GVThread (or OS thread)
+///
+/// Only the calling GVThread is blocked while the future is pending;
+/// the worker runs other GVThreads. A future that is ready on the first
+/// poll returns without blocking.
+///
+/// # Example
+///
+/// ```ignore
+/// spawn(|_| {
+///     let frame = block_on_future(decoder.next_frame());
+///     handle(frame);
+/// });
+/// ```
+pub fn block_on_future<F: Future>(fut: F) -> F::Output {
+    let inner = Arc::new(FutureWaker::default());
+    let waker = Waker::from(Arc::clone(&inner));
+    let mut cx = Context::from_waker(&waker);
+    let mut fut = pin!(fut);
+
+    loop {
+        inner.state.lock().notified = false;
+        if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+            return output;
+        }
+        let node = Arc::new(WaitNode::current());
+        {
+            let mut state = inner.state.lock();
+            // Woken during the poll: poll again without blocking
+            if state.notified {
+                continue;
+            }
+            state.parked = Some(Arc::clone(&node));
+        }
+        // Other wakes (timer, cancellation) are absorbed by the node:
+        // this returns once the future's waker fired
+        node.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn ready_future_does_not_block() {
+        assert_eq!(block_on_future(async { 6 * 7 }), 42);
+    }
+
+    /// Pending until another thread sets the value and wakes it
+    struct Slot {
+        value: Mutex<(Option<u32>, Option<Waker>)>,
+    }
+
+    struct SlotFuture<'a>(&'a Slot);
+
+    impl Future for SlotFuture<'_> {
+        type Output = u32;
+
+        fn poll(self: std::pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+            let mut guard = self.0.value.lock().unwrap();
+            match guard.0 {
+                Some(v) => Poll::Ready(v),
+                None => {
+                    guard.1 = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pending_future_woken_from_other_thread() {
+        let slot = Arc::new(Slot { value: Mutex::new((None, None)) });
+
+        let setter = {
+            let slot = Arc::clone(&slot);
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(20));
+                let waker = {
+                    let mut guard = slot.value.lock().unwrap();
+                    guard.0 = Some(7);
+                    guard.1.take()
+                };
+                // Extra wakes are harmless
+                if let Some(w) = waker {
+                    w.wake_by_ref();
+                    w.wake();
+                }
+            })
+        };
+
+        let total = block_on_future(async {
+            let a = SlotFuture(&slot).await;
+            let b = async { 1 }.await;
+            a + b
+        });
+        setter.join().unwrap();
+        assert_eq!(total, 8);
+    }
+}
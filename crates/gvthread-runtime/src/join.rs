@@ -0,0 +1,187 @@
+//! Join handles for work that completes on another thread
+//!
+//! A `JoinHandle<T>` is the receiving side of a one-shot result slot.
+//! The slot is allocated with the handle, by whoever spawns the work;
+//! the producer just moves its `T` in, and the slot (with any unjoined
+//! value) is freed when the last of handle and completer is dropped.
+//! `join()` blocks the calling GVThread via `block_current()` (or the OS
+//! thread, when called outside a GVThread) until the producer completes.
+//!
+//! The joiner registers itself as waiter under the lock and then blocks;
+//! the completer wakes it with `scheduler::wake_waiter`, which tolerates
+//! the joiner not having switched out yet (no lost wakeup).
+//!
+//! A completer dropped without completing (its closure never ran, e.g.
+//! the scheduler shut down first) publishes `JoinError::Cancelled`, so a
+//! joiner never waits forever.
+
+use crate::scheduler;
+
+use gvthread_core::id::GVThreadId;
+
+use std::any::Any;
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex};
+
+/// Why a joined task produced no value
+pub enum JoinError {
+    /// The task panicked; holds the panic payload
+    Panic(Box<dyn Any + Send + 'static>),
+    /// The task was dropped before it ran (scheduler shut down)
+    Cancelled,
+}
+
+impl JoinError {
+    /// True if the task panicked
+    pub fn is_panic(&self) -> bool {
+        matches!(self, JoinError::Panic(_))
+    }
+
+    /// True if the task never ran
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, JoinError::Cancelled)
+    }
+
+    /// The panic payload, for `std::panic::resume_unwind`
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not `JoinError::Panic`.
+    pub fn into_panic(self) -> Box<dyn Any + Send + 'static> {
+        match self {
+            JoinError::Panic(payload) => payload,
+            JoinError::Cancelled => panic!("JoinError::Cancelled has no panic payload"),
+        }
+    }
+}
+
+impl fmt::Debug for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JoinError::Panic(payload) => write!(f, "Panic({:?})", panic_message(payload.as_ref())),
+            JoinError::Cancelled => write!(f, "Cancelled"),
+        }
+    }
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JoinError::Panic(payload) => write!(f, "task panicked: {}", panic_message(payload.as_ref())),
+            JoinError::Cancelled => write!(f, "task cancelled before it ran"),
+        }
+    }
+}
+
+impl std::error::Error for JoinError {}
+
+/// The message of a panic payload (`panic!` with a literal or format)
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(s) = payload.downcast_ref::<&'static str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s
+    } else {
+        "<non-string panic payload>"
+    }
+}
+
+struct JoinState<T> {
+    /// Set once by the completer
+    result: Option<Result<T, JoinError>>,
+    /// GVThread blocked in `join()`: (id, generation)
+    waiter: Option<(GVThreadId, u32)>,
+}
+
+struct JoinInner<T> {
+    state: Mutex<JoinState<T>>,
+    /// For joiners that are plain OS threads
+    cond: Condvar,
+}
+
+/// Handle to a result produced on another thread
+pub struct JoinHandle<T> {
+    inner: Arc<JoinInner<T>>,
+}
+
+/// Producer side of a `JoinHandle`
+pub(crate) struct Completer<T> {
+    inner: Arc<JoinInner<T>>,
+    completed: bool,
+}
+
+/// Create a connected (handle, completer) pair
+pub(crate) fn join_pair<T>() -> (JoinHandle<T>, Completer<T>) {
+    let inner = Arc::new(JoinInner {
+        state: Mutex::new(JoinState { result: None, waiter: None }),
+        cond: Condvar::new(),
+    });
+    (JoinHandle { inner: Arc::clone(&inner) }, Completer { inner, completed: false })
+}
+
+impl<T> JoinHandle<T> {
+    /// True once the result is available (`join()` won't block)
+    pub fn is_finished(&self) -> bool {
+        self.inner.state.lock().unwrap().result.is_some()
+    }
+
+    /// Wait for the result
+    ///
+    /// Inside a GVThread this blocks only the GVThread; the worker keeps
+    /// running others. Outside a GVThread it blocks the OS thread.
+    pub fn join(self) -> Result<T, JoinError> {
+        let me = scheduler::current_waiter();
+        let mut state = self.inner.state.lock().unwrap();
+        loop {
+            if let Some(result) = state.result.take() {
+                return result;
+            }
+            if me.is_none() {
+                state = self.inner.cond.wait(state).unwrap();
+                continue;
+            }
+
+            state.waiter = me;
+            drop(state);
+
+            scheduler::block_current();
+
+            // Woken (or spurious) — re-check
+            state = self.inner.state.lock().unwrap();
+        }
+    }
+}
+
+impl<T> Completer<T> {
+    /// Publish the result and wake the joiner (if any)
+    pub(crate) fn complete(mut self, result: Result<T, JoinError>) {
+        self.completed = true;
+        self.publish(result);
+    }
+
+    fn publish(&self, result: Result<T, JoinError>) {
+        let waiter = {
+            let mut state = self.inner.state.lock().unwrap();
+            state.result = Some(result);
+            state.waiter.take()
+        };
+        self.inner.cond.notify_all();
+
+        if let Some((id, generation)) = waiter {
+            scheduler::wake_waiter(id, generation);
+        }
+    }
+}
+
+impl<T> Drop for Completer<T> {
+    fn drop(&mut self) {
+        if !self.completed {
+            self.publish(Err(JoinError::Cancelled));
+        }
+    }
+}
+
+// Safety: the result crosses threads exactly once, guarded by the mutex
+unsafe impl<T: Send> Send for JoinHandle<T> {}
+unsafe impl<T: Send> Sync for JoinHandle<T> {}
+unsafe impl<T: Send> Send for Completer<T> {}
@@ -24,14 +24,35 @@ pub mod scheduler;
 pub mod tls;
 pub mod parking;
 pub mod ready_queue;
+pub mod stats;
+pub mod join;
+pub mod local;
+pub mod blocking;
+pub mod condvar;
+pub mod broadcast;
+pub mod oneshot;
+pub mod rwlock;
+pub mod timeout;
+pub mod future;
+pub mod scope;
+mod waiter;
 
 // Re-exports
-pub use config::SchedulerConfig;
+pub use config::{ConfigSource, ReadyQueueKind, SchedulerConfig};
 pub use scheduler::Scheduler;
 pub use worker::{WorkerPool, worker_states};
-pub use timer::{sleep, sleep_ms, sleep_us};
+pub use timer::{sleep, sleep_cancellable, sleep_ms, sleep_us};
 pub use parking::{WorkerParking, new_parking};
-pub use ready_queue::{ReadyQueue, SimpleQueue};
+pub use ready_queue::{PriorityQueue, ReadyQueue, SimpleQueue};
+pub use stats::RuntimeStats;
+pub use join::{JoinError, JoinHandle};
+pub use local::{AccessError, LocalKey};
+pub use blocking::spawn_blocking;
+pub use condvar::SchedCondvar;
+pub use rwlock::SchedRwLock;
+pub use timeout::{timeout, Timeout};
+pub use future::block_on_future;
+pub use scope::{scope, Scope, ScopedJoinHandle};
 
 // Platform detection
 cfg_if::cfg_if! {
@@ -0,0 +1,193 @@
+//! Per-GVThread storage: `gvthread_local!`
+//!
+//! OS thread-locals don't follow a GVThread around: it may resume on a
+//! different worker after any yield or block. A `gvthread_local!` value
+//! belongs to the GVThread instead, wherever it runs:
+//!
+//! ```rust,ignore
+//! use std::cell::Cell;
+//!
+//! gvthread_local! {
+//!     static REQUEST_ID: Cell<u64> = Cell::new(0);
+//! }
+//!
+//! spawn(|_| {
+//!     REQUEST_ID.with(|id| id.set(42));
+//!     yield_now(); // may move to another worker
+//!     assert_eq!(REQUEST_ID.with(|id| id.get()), 42);
+//! }, Priority::Normal);
+//! ```
+//!
+//! Each GVThread gets its own value, created by the initializer on first
+//! access. The values hang off the GVThread's metadata (`locals`) and
+//! are dropped when it finishes, on the worker's stack: a destructor
+//! that touches a `gvthread_local!` is no longer inside a GVThread.
+//!
+//! Outside a GVThread there is nothing to attach a value to: `try_with`
+//! returns `AccessError`, `with` panics.
+
+use crate::scheduler;
+
+use gvthread_core::metadata::GVThreadMetadata;
+
+use std::any::Any;
+use std::fmt;
+use std::ptr;
+use std::sync::atomic::Ordering;
+
+/// Declare per-GVThread statics (`LocalKey`)
+///
+/// Same syntax as `std::thread_local!`; the type must be `Send`, since
+/// the GVThread carries it from worker to worker.
+#[macro_export]
+macro_rules! gvthread_local {
+    () => {};
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty = $init:expr; $($rest:tt)*) => {
+        $crate::gvthread_local!($(#[$attr])* $vis static $name: $t = $init);
+        $crate::gvthread_local!($($rest)*);
+    };
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty = $init:expr) => {
+        $(#[$attr])*
+        $vis static $name: $crate::local::LocalKey<$t> = {
+            fn __init() -> $t {
+                $init
+            }
+            $crate::local::LocalKey::new(__init)
+        };
+    };
+}
+
+/// A per-GVThread value, declared with `gvthread_local!`
+pub struct LocalKey<T: Send + 'static> {
+    init: fn() -> T,
+}
+
+/// `LocalKey::try_with` outside a GVThread
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccessError;
+
+/// One GVThread's values, keyed by `LocalKey` address
+type Locals = Vec<(usize, Box<dyn Any + Send>)>;
+
+impl<T: Send + 'static> LocalKey<T> {
+    #[doc(hidden)]
+    pub const fn new(init: fn() -> T) -> Self {
+        Self { init }
+    }
+
+    /// Run `f` with the calling GVThread's value
+    ///
+    /// # Panics
+    ///
+    /// Outside a GVThread.
+    pub fn with<F, R>(&'static self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.try_with(f)
+            .expect("gvthread_local! value accessed outside a GVThread")
+    }
+
+    /// Run `f` with the calling GVThread's value, or `Err(AccessError)`
+    /// outside a GVThread
+    pub fn try_with<F, R>(&'static self, f: F) -> Result<R, AccessError>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        let meta = scheduler::current_metadata().ok_or(AccessError)?;
+        let value = match self.find(meta) {
+            Some(value) => value,
+            None => {
+                // No borrow of the map is held here: the initializer may
+                // use other keys
+                let value = Box::new((self.init)());
+                let value_ptr: *const T = &*value;
+                unsafe { (*locals(meta)).push((self.id(), value)) };
+                value_ptr
+            }
+        };
+        // Boxed, so stable while the map grows; dropped only once the
+        // GVThread has finished
+        Ok(f(unsafe { &*value }))
+    }
+
+    fn id(&'static self) -> usize {
+        self as *const Self as usize
+    }
+
+    fn find(&'static self, meta: &GVThreadMetadata) -> Option<*const T> {
+        let locals = meta.locals.load(Ordering::Relaxed) as *const Locals;
+        let locals = unsafe { locals.as_ref()? };
+        let (_, value) = locals.iter().find(|(id, _)| *id == self.id())?;
+        value.downcast_ref::<T>().map(|v| v as *const T)
+    }
+}
+
+/// The calling GVThread's map, created on first use
+fn locals(meta: &GVThreadMetadata) -> *mut Locals {
+    let mut map = meta.locals.load(Ordering::Relaxed) as *mut Locals;
+    if map.is_null() {
+        map = Box::into_raw(Box::<Locals>::default());
+        meta.locals.store(map as *mut (), Ordering::Relaxed);
+    }
+    map
+}
+
+/// Drop a finished GVThread's values (runs on the worker's stack)
+pub(crate) fn drop_locals(meta: &GVThreadMetadata) {
+    let map = meta.locals.swap(ptr::null_mut(), Ordering::Relaxed) as *mut Locals;
+    if !map.is_null() {
+        drop(unsafe { Box::from_raw(map) });
+    }
+}
+
+impl<T: Send + 'static> fmt::Debug for LocalKey<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalKey").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "gvthread_local! value accessed outside a GVThread")
+    }
+}
+
+impl std::error::Error for AccessError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    gvthread_local! {
+        static COUNTER: Cell<u32> = Cell::new(7);
+    }
+
+    #[test]
+    fn access_outside_a_gvthread_is_an_error() {
+        assert_eq!(COUNTER.try_with(|c| c.get()), Err(AccessError));
+        assert!(std::panic::catch_unwind(|| COUNTER.with(|c| c.get())).is_err());
+    }
+
+    #[test]
+    fn values_are_found_by_key_and_dropped_at_finish() {
+        use std::sync::Arc;
+
+        gvthread_local! {
+            static SHARED: Arc<()> = Arc::new(());
+            static NAME: String = String::from("request");
+        }
+
+        let meta = GVThreadMetadata::new();
+        let witness = Arc::new(());
+        unsafe { (*locals(&meta)).push((SHARED.id(), Box::new(Arc::clone(&witness)))) };
+        assert!(SHARED.find(&meta).is_some());
+        assert!(NAME.find(&meta).is_none());
+
+        drop_locals(&meta);
+        assert!(meta.locals.load(Ordering::Relaxed).is_null());
+        assert_eq!(Arc::strong_count(&witness), 1);
+        drop_locals(&meta); // nothing left
+    }
+}
@@ -1,6 +1,21 @@
 //! Memory region management for GVThread slots
 //!
 //! Platform-specific implementations handle virtual memory allocation.
+//!
+//! Stacks are committed lazily: the whole region is reserved up front,
+//! a slot is made accessible the first time it is used, and its pages
+//! are only backed by physical memory once the GVThread touches them.
+//! When a GVThread finishes, the slot's pages are handed back to the
+//! kernel; only its generation counter is kept, in a side table, so
+//! stale wakes are still told apart after the slot is reused.
+//!
+//! Every slot is laid out the same way, sized from
+//! `SchedulerConfig::stack_size` when the region is created:
+//!
+//! ```text
+//! slot_base                                          slot_base + slot_size
+//! | metadata (METADATA_SIZE) | stack (stack_size, ↓) | guard (GUARD_SIZE) |
+//! ```
 
 cfg_if::cfg_if! {
     if #[cfg(unix)] {
@@ -12,9 +27,9 @@ cfg_if::cfg_if! {
     }
 }
 
-use gvthread_core::constants::{SLOT_SIZE, METADATA_SIZE, GUARD_SIZE};
+use gvthread_core::constants::{METADATA_SIZE, GUARD_SIZE};
 
-use std::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicU64, Ordering};
 use std::ptr;
 
 /// Memory region for all GVThread slots
@@ -28,8 +43,17 @@ pub struct MemoryRegion {
     /// Number of slots
     max_slots: usize,
     
+    /// Bytes per slot: metadata + stack + guard
+    slot_size: usize,
+    
     /// Whether region is initialized
     initialized: AtomicBool,
+    
+    /// One bit per slot, set once the slot has been made accessible
+    activated: Vec<AtomicU64>,
+    
+    /// Release finished stacks lazily (MADV_FREE) instead of at once
+    lazy_free: bool,
 }
 
 impl MemoryRegion {
@@ -39,7 +63,10 @@ impl MemoryRegion {
             base: AtomicPtr::new(ptr::null_mut()),
             total_size: 0,
             max_slots: 0,
+            slot_size: 0,
             initialized: AtomicBool::new(false),
+            activated: Vec::new(),
+            lazy_free: false,
         }
     }
     
@@ -61,11 +88,40 @@ impl MemoryRegion {
         self.max_slots
     }
     
+    /// Bytes per slot, metadata and guard page included
+    #[inline]
+    pub fn slot_size(&self) -> usize {
+        self.slot_size
+    }
+    
+    /// Usable stack per GVThread (the configured `stack_size`, rounded
+    /// up to whole pages)
+    #[inline]
+    pub fn stack_size(&self) -> usize {
+        self.slot_size - METADATA_SIZE - GUARD_SIZE
+    }
+    
+    /// Whether a slot's pages were ever made accessible (its metadata
+    /// can be read; a released slot's reads back as zeroes or stale)
+    #[inline]
+    pub fn is_activated(&self, slot_id: u32) -> bool {
+        let bit = 1u64 << (slot_id % 64);
+        (slot_id as usize) < self.max_slots
+            && self.activated[slot_id as usize / 64].load(Ordering::Acquire) & bit != 0
+    }
+    
+    /// Mark a slot activated; returns `true` if it already was
+    #[inline]
+    fn mark_activated(&self, slot_id: u32) -> bool {
+        let bit = 1u64 << (slot_id % 64);
+        self.activated[slot_id as usize / 64].fetch_or(bit, Ordering::AcqRel) & bit != 0
+    }
+    
     /// Calculate the base address of a slot
     #[inline]
     pub fn slot_base(&self, slot_id: u32) -> *mut u8 {
         debug_assert!((slot_id as usize) < self.max_slots);
-        unsafe { self.base().add(slot_id as usize * SLOT_SIZE) }
+        unsafe { self.base().add(slot_id as usize * self.slot_size) }
     }
     
     /// Calculate the metadata address for a slot
@@ -79,7 +135,7 @@ impl MemoryRegion {
     pub fn stack_top(&self, slot_id: u32) -> *mut u8 {
         unsafe {
             self.slot_base(slot_id)
-                .add(SLOT_SIZE)
+                .add(self.slot_size)
                 .sub(GUARD_SIZE)
         }
     }
@@ -94,6 +150,52 @@ impl MemoryRegion {
     }
 }
 
+/// Most address space the region may reserve: 64 TB, half of the
+/// x86-64 user address space
+///
+/// Reserving costs no memory, but `max_gvthreads` slots add up: the
+/// defaults (1M GVThreads with 16 MB stacks) reserve about 16 TB.
+/// Lower `max_gvthreads` or `stack_size` on hosts with less address
+/// space (e.g. 39-bit aarch64 kernels), where even that fails to map.
+pub const MAX_REGION_SIZE: usize = 64 << 40;
+
+/// Address space for `max_slots` slots with `stack_size` stacks (`None`
+/// on overflow or past `MAX_REGION_SIZE`)
+pub fn region_size_for(max_slots: usize, stack_size: usize) -> Option<usize> {
+    slot_size_for(stack_size)?
+        .checked_mul(max_slots)
+        .filter(|&size| size <= MAX_REGION_SIZE)
+}
+
+/// Slot size holding a stack of at least `stack_size` bytes (`None` on
+/// overflow)
+pub fn slot_size_for(stack_size: usize) -> Option<usize> {
+    stack_size
+        .checked_next_multiple_of(GUARD_SIZE)?
+        .checked_add(METADATA_SIZE + GUARD_SIZE)
+}
+
+/// Resident set size in KB, less pages released with MADV_FREE
+/// (`GVT_LAZY_STACK_FREE=1`), which the kernel reclaims on demand
+///
+/// VmRSS alone still counts those pages until memory gets tight, so a
+/// lazily freed stack would look leaked. 0 where /proc is unavailable.
+pub fn resident_kb() -> u64 {
+    proc_kb("/proc/self/status", "VmRSS:")
+        .saturating_sub(proc_kb("/proc/self/smaps_rollup", "LazyFree:"))
+}
+
+/// A `Field:   123 kB` line of a /proc file (0 if missing)
+fn proc_kb(path: &str, field: &str) -> u64 {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| {
+            s.lines()
+                .find_map(|line| line.strip_prefix(field)?.split_whitespace().next()?.parse().ok())
+        })
+        .unwrap_or(0)
+}
+
 // Global memory region instance
 static mut MEMORY_REGION: MemoryRegion = MemoryRegion::new();
 
@@ -116,3 +218,24 @@ pub fn memory_region() -> &'static MemoryRegion {
 pub unsafe fn memory_region_mut() -> &'static mut MemoryRegion {
     &mut MEMORY_REGION
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slot_size_follows_the_configured_stack() {
+        assert_eq!(slot_size_for(64 * 1024), Some(METADATA_SIZE + 64 * 1024 + GUARD_SIZE));
+        // Rounded up to whole pages
+        assert_eq!(slot_size_for(64 * 1024 + 1), Some(METADATA_SIZE + 68 * 1024 + GUARD_SIZE));
+        assert_eq!(slot_size_for(usize::MAX), None);
+    }
+
+    #[test]
+    fn region_size_is_capped() {
+        let default = region_size_for(1 << 20, 16 << 20).unwrap();
+        assert_eq!(default, (1 << 20) * slot_size_for(16 << 20).unwrap());
+        assert_eq!(region_size_for(4 << 20, 16 << 20), None);
+        assert_eq!(region_size_for(usize::MAX, 64 * 1024), None);
+    }
+}
@@ -1,9 +1,9 @@
 //! Unix memory implementation using mmap
 
-use super::MemoryRegion;
-use gvthread_core::constants::{SLOT_SIZE, METADATA_SIZE, GUARD_SIZE};
+use super::{region_size_for, slot_size_for, MemoryRegion, MAX_REGION_SIZE};
+use gvthread_core::constants::{METADATA_SIZE, GUARD_SIZE};
 use gvthread_core::error::{MemoryError, SchedResult};
-use std::sync::atomic::Ordering;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Hint for region start address (high address to avoid conflicts)
 const REGION_START_HINT: usize = 0x7000_0000_0000;
@@ -11,15 +11,18 @@ const REGION_START_HINT: usize = 0x7000_0000_0000;
 impl MemoryRegion {
     /// Initialize the memory region
     ///
-    /// Reserves virtual address space for `max_slots` GVThread slots.
+    /// Reserves virtual address space for `max_slots` GVThread slots,
+    /// each with a stack of `stack_size` bytes (rounded up to pages).
     /// Memory is reserved with PROT_NONE (no access) initially.
-    pub fn init(&mut self, max_slots: usize) -> SchedResult<()> {
+    /// With `lazy_free`, finished stacks are released with MADV_FREE.
+    pub fn init(&mut self, max_slots: usize, stack_size: usize, lazy_free: bool) -> SchedResult<()> {
         if self.initialized.load(Ordering::SeqCst) {
             return Err(MemoryError::AlreadyInitialized.into());
         }
         
-        let total_size = max_slots.checked_mul(SLOT_SIZE)
-            .ok_or(MemoryError::TooManySlots)?;
+        let slot_size = slot_size_for(stack_size).ok_or(MemoryError::TooManySlots)?;
+        let total_size = region_size_for(max_slots, stack_size)
+            .ok_or(MemoryError::RegionTooLarge)?;
         
         // Reserve virtual address space with PROT_NONE
         let base = unsafe {
@@ -34,21 +37,39 @@ impl MemoryRegion {
         };
         
         if base == libc::MAP_FAILED {
+            gvthread_core::kerror!(
+                "reserving {} GB of address space for {} GVThreads failed (limit {} GB); \
+                 lower max_gvthreads or stack_size",
+                total_size >> 30,
+                max_slots,
+                MAX_REGION_SIZE >> 30,
+            );
             return Err(MemoryError::AllocationFailed.into());
         }
         
         self.base.store(base as *mut u8, Ordering::Release);
         self.total_size = total_size;
         self.max_slots = max_slots;
+        self.slot_size = slot_size;
+        self.activated = (0..max_slots.div_ceil(64)).map(|_| AtomicU64::new(0)).collect();
+        self.lazy_free = lazy_free;
         self.initialized.store(true, Ordering::SeqCst);
         
         Ok(())
     }
     
-    /// Activate a slot (make it readable/writable)
+    /// Activate a slot (make it readable/writable) for the GVThread
+    /// the allocator handed it out to with `generation`
     ///
-    /// Called when allocating a new GVThread.
-    pub fn activate_slot(&self, slot_id: u32) -> SchedResult<()> {
+    /// Called when allocating a new GVThread. Only the first use of a slot
+    /// needs the mprotect: deactivation releases pages but keeps the
+    /// protection. No physical memory is committed here; stack pages are
+    /// faulted in as the GVThread touches them.
+    ///
+    /// The metadata is reset (`GVThreadMetadata::reset`) before the slot
+    /// goes back to `spawn`: until then it may still hold the previous
+    /// occupant's, and a racing stale wake must not take it for current.
+    pub fn activate_slot(&self, slot_id: u32, generation: u32) -> SchedResult<()> {
         if !self.is_initialized() {
             return Err(MemoryError::AllocationFailed.into());
         }
@@ -57,6 +78,16 @@ impl MemoryRegion {
             return Err(MemoryError::InvalidSlot.into());
         }
         
+        if !self.mark_activated(slot_id) {
+            self.make_accessible(slot_id)?;
+        }
+        
+        unsafe { &*get_metadata_ptr(slot_id) }.reset(generation);
+        Ok(())
+    }
+    
+    /// First use of a slot: open its metadata and stack
+    fn make_accessible(&self, slot_id: u32) -> SchedResult<()> {
         let base = self.slot_base(slot_id);
         
         // Make metadata region accessible
@@ -73,7 +104,7 @@ impl MemoryRegion {
         
         // Make stack region accessible (between metadata and guard)
         let stack_base = unsafe { base.add(METADATA_SIZE) };
-        let stack_size = SLOT_SIZE - METADATA_SIZE - GUARD_SIZE;
+        let stack_size = self.stack_size();
         let ret = unsafe {
             libc::mprotect(
                 stack_base as *mut libc::c_void,
@@ -94,6 +125,15 @@ impl MemoryRegion {
     /// Deactivate a slot (release physical memory)
     ///
     /// Called when a GVThread is finished and its slot is being recycled.
+    /// The generation counter lives on in the slot allocator, and the
+    /// next `activate_slot` writes it back first, so slot reuse still
+    /// bumps it past any stale wake.
+    ///
+    /// MADV_DONTNEED drops the pages at once, so RSS falls immediately.
+    /// MADV_FREE (`lazy_free`) is cheaper under churn — the kernel only
+    /// reclaims the pages under memory pressure, and a reused slot may
+    /// get them back without a fault — but RSS stays high until then.
+    /// Kernels without MADV_FREE (< 4.5) fall back to MADV_DONTNEED.
     pub fn deactivate_slot(&self, slot_id: u32) -> SchedResult<()> {
         if !self.is_initialized() {
             return Err(MemoryError::AllocationFailed.into());
@@ -104,17 +144,14 @@ impl MemoryRegion {
         }
         
         let base = self.slot_base(slot_id);
-        let usable_size = SLOT_SIZE - GUARD_SIZE;
+        let usable_size = self.slot_size - GUARD_SIZE;
         
         // Tell kernel we don't need the physical pages
-        let ret = unsafe {
-            libc::madvise(
-                base as *mut libc::c_void,
-                usable_size,
-                libc::MADV_DONTNEED,
-            )
+        let advise = |advice| unsafe {
+            libc::madvise(base as *mut libc::c_void, usable_size, advice)
         };
-        if ret != 0 {
+        let freed = self.lazy_free && advise(libc::MADV_FREE) == 0;
+        if !freed && advise(libc::MADV_DONTNEED) != 0 {
             return Err(MemoryError::AdviseFailed.into());
         }
         
@@ -140,6 +177,8 @@ impl MemoryRegion {
         self.base.store(std::ptr::null_mut(), Ordering::Release);
         self.total_size = 0;
         self.max_slots = 0;
+        self.slot_size = 0;
+        self.activated = Vec::new();
         self.initialized.store(false, Ordering::SeqCst);
         
         Ok(())
@@ -147,9 +186,9 @@ impl MemoryRegion {
 }
 
 /// Initialize the global memory region
-pub fn init_memory_region(max_slots: usize) -> SchedResult<()> {
+pub fn init_memory_region(max_slots: usize, stack_size: usize, lazy_free: bool) -> SchedResult<()> {
     unsafe {
-        super::memory_region_mut().init(max_slots)
+        super::memory_region_mut().init(max_slots, stack_size, lazy_free)
     }
 }
 
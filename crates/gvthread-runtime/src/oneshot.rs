@@ -0,0 +1,221 @@
+//! Oneshot channel: a single value, sent once
+//!
+//! `channel()` returns a `OneshotSender` and a `OneshotReceiver` sharing
+//! one slot. `send` consumes the sender and never waits; `recv` blocks
+//! the calling GVThread via `block_current()` (or parks the OS thread
+//! outside a GVThread) until the value arrives, or fails with `Canceled`
+//! once the sender is dropped without sending.
+//!
+//! The usual request/response shape: hand the sender to the GVThread
+//! doing the work, keep the receiver to wait for its answer.
+
+use crate::waiter::WaitNode;
+
+use gvthread_core::spinlock::SpinLock;
+
+use std::fmt;
+use std::sync::Arc;
+
+/// Create a connected oneshot sender/receiver pair
+pub fn channel<T>() -> (OneshotSender<T>, OneshotReceiver<T>) {
+    let shared = Arc::new(SpinLock::new(Slot {
+        value: None,
+        sender_gone: false,
+        receiver_gone: false,
+        waiter: None,
+    }));
+    (
+        OneshotSender { shared: Arc::clone(&shared) },
+        OneshotReceiver { shared },
+    )
+}
+
+struct Slot<T> {
+    value: Option<T>,
+    /// Sender sent or was dropped: nothing more will arrive
+    sender_gone: bool,
+    receiver_gone: bool,
+    /// Receiver blocked in `recv`
+    waiter: Option<Arc<WaitNode>>,
+}
+
+/// Sending half: `send` once
+pub struct OneshotSender<T> {
+    shared: Arc<SpinLock<Slot<T>>>,
+}
+
+/// Receiving half: `recv` once
+pub struct OneshotReceiver<T> {
+    shared: Arc<SpinLock<Slot<T>>>,
+}
+
+/// The sender was dropped without sending
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Canceled;
+
+/// Error from `OneshotReceiver::try_recv`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// No value yet
+    Empty,
+    /// The sender was dropped without sending
+    Canceled,
+}
+
+impl<T> OneshotSender<T> {
+    /// Hand `value` to the receiver, waking it if it waits
+    ///
+    /// Never blocks. Fails only if the receiver is gone, giving the value
+    /// back.
+    pub fn send(self, value: T) -> Result<(), T> {
+        let waiter = {
+            let mut slot = self.shared.lock();
+            if slot.receiver_gone {
+                return Err(value);
+            }
+            slot.value = Some(value);
+            slot.waiter.take()
+        };
+        // `Drop` marks the sender gone
+        drop(self);
+        if let Some(node) = waiter {
+            node.notify();
+        }
+        Ok(())
+    }
+
+    /// True if the receiver was dropped (a `send` would fail)
+    pub fn is_closed(&self) -> bool {
+        self.shared.lock().receiver_gone
+    }
+}
+
+impl<T> Drop for OneshotSender<T> {
+    fn drop(&mut self) {
+        let waiter = {
+            let mut slot = self.shared.lock();
+            slot.sender_gone = true;
+            slot.waiter.take()
+        };
+        if let Some(node) = waiter {
+            node.notify();
+        }
+    }
+}
+
+impl<T> OneshotReceiver<T> {
+    /// Wait for the value
+    ///
+    /// Inside a GVThread this blocks only the GVThread. `Err(Canceled)`
+    /// if the sender was dropped without sending.
+    pub fn recv(self) -> Result<T, Canceled> {
+        loop {
+            let node = {
+                let mut slot = self.shared.lock();
+                match take(&mut slot) {
+                    Err(TryRecvError::Empty) => {}
+                    Err(TryRecvError::Canceled) => return Err(Canceled),
+                    Ok(value) => return Ok(value),
+                }
+                // Registered under the lock: `send` or the sender's drop
+                // finds us
+                let node = Arc::new(WaitNode::current());
+                slot.waiter = Some(Arc::clone(&node));
+                node
+            };
+            node.wait();
+        }
+    }
+
+    /// Take the value if it has arrived, without blocking
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        take(&mut self.shared.lock())
+    }
+}
+
+fn take<T>(slot: &mut Slot<T>) -> Result<T, TryRecvError> {
+    match slot.value.take() {
+        Some(value) => Ok(value),
+        None if slot.sender_gone => Err(TryRecvError::Canceled),
+        None => Err(TryRecvError::Empty),
+    }
+}
+
+impl<T> Drop for OneshotReceiver<T> {
+    fn drop(&mut self) {
+        // Not received: drop it now (outside the lock) rather than with
+        // the last handle
+        let _unreceived = {
+            let mut slot = self.shared.lock();
+            slot.receiver_gone = true;
+            slot.value.take()
+        };
+    }
+}
+
+impl<T> fmt::Debug for OneshotSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OneshotSender")
+            .field("closed", &self.is_closed())
+            .finish()
+    }
+}
+
+impl<T> fmt::Debug for OneshotReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OneshotReceiver").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for Canceled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "oneshot sender dropped without sending")
+    }
+}
+
+impl std::error::Error for Canceled {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn value_sent_before_recv() {
+        let (tx, rx) = channel();
+        assert_eq!(tx.send(7), Ok(()));
+        assert_eq!(rx.recv(), Ok(7));
+    }
+
+    #[test]
+    fn recv_waits_for_a_later_send() {
+        let (tx, rx) = channel();
+        let responder = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            tx.send("response").unwrap();
+        });
+        assert_eq!(rx.recv(), Ok("response"));
+        responder.join().unwrap();
+    }
+
+    #[test]
+    fn dropped_sender_cancels_a_waiting_receiver() {
+        let (tx, mut rx) = channel::<u32>();
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+        let dropper = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            drop(tx);
+        });
+        assert_eq!(rx.recv(), Err(Canceled));
+        dropper.join().unwrap();
+    }
+
+    #[test]
+    fn send_to_a_dropped_receiver_returns_the_value() {
+        let (tx, rx) = channel();
+        drop(rx);
+        assert!(tx.is_closed());
+        assert_eq!(tx.send(vec![1, 2]), Err(vec![1, 2]));
+    }
+}
@@ -9,6 +9,34 @@ impl LinuxPlatform {
     pub fn new() -> Self {
         Self
     }
+    
+    /// CPUs the calling thread may run on, ascending
+    ///
+    /// Read from its affinity mask, so taskset/cgroup limits are honored.
+    /// Empty if the mask can't be read.
+    pub fn allowed_cpus() -> Vec<usize> {
+        unsafe {
+            let mut set: libc::cpu_set_t = std::mem::zeroed();
+            if libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set) != 0 {
+                return Vec::new();
+            }
+            (0..libc::CPU_SETSIZE as usize)
+                .filter(|&cpu| libc::CPU_ISSET(cpu, &set))
+                .collect()
+        }
+    }
+    
+    /// Pin the calling thread to a single CPU
+    pub fn pin_current_thread(cpu: usize) -> std::io::Result<()> {
+        unsafe {
+            let mut set: libc::cpu_set_t = std::mem::zeroed();
+            libc::CPU_SET(cpu, &mut set);
+            if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Default for LinuxPlatform {
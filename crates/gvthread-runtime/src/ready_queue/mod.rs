@@ -3,10 +3,15 @@
 //! Provides a trait-based abstraction allowing different scheduling strategies.
 //!
 //! # Implementations
-//! - `SimpleQueue` - Go-like per-worker + global queue (MVP)
+//! - `SimpleQueue` - Go-like per-worker + global queue (MVP, default)
+//! - `PriorityQueue` - one ready bitmap per priority level, highest first
+//!
+//! `SchedulerConfig::ready_queue` selects one.
 
+mod priority;
 mod simple;
 
+pub use priority::PriorityQueue;
 pub use simple::SimpleQueue;
 
 use gvthread_core::id::GVThreadId;
@@ -20,7 +25,7 @@ pub trait ReadyQueue: Send + Sync {
     ///
     /// # Arguments
     /// * `id` - GVThread ID
-    /// * `priority` - Priority level (ignored in MVP)
+    /// * `priority` - Priority level (ignored by `SimpleQueue`)
     /// * `hint_worker` - Preferred worker's local queue (None = global)
     fn push(&self, id: GVThreadId, priority: Priority, hint_worker: Option<usize>);
     
@@ -33,7 +38,25 @@ pub trait ReadyQueue: Send + Sync {
     /// * `None` - No work (worker should park)
     fn pop(&self, worker_id: usize) -> Option<(GVThreadId, Priority)>;
     
+    /// Remove `id` from `worker_id`'s local queue, if it is queued there
+    ///
+    /// Used by `yield_to()` for a directed handoff. Returns `false` if
+    /// the GVThread is elsewhere (or the queue has no local queues).
+    fn take_local(&self, _worker_id: usize, _id: GVThreadId) -> bool {
+        false
+    }
+    
+    /// Move `id` up to `priority` if it is queued at a lower one
+    ///
+    /// Used when priority inheritance boosts a GVThread that is already
+    /// queued. Queues that ignore priority leave it where it is.
+    fn reprioritize(&self, _id: GVThreadId, _priority: Priority) {}
+    
     /// Park worker until work available or timeout
+    ///
+    /// Must register the worker as parked, then re-check for queued work
+    /// (returning at once if there is any) in a way `push` can't slip
+    /// between: the worker loop's own empty `pop` may be stale by now.
     fn park(&self, worker_id: usize, timeout_ms: u64);
     
     /// Wake one parked worker
@@ -49,4 +72,25 @@ pub trait ReadyQueue: Send + Sync {
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
+    
+    /// Entries in the shared (global) queue (for diagnostics)
+    fn global_len(&self) -> usize {
+        self.len()
+    }
+    
+    /// Entries in a worker's local queue (for diagnostics)
+    fn local_len(&self, _worker_id: usize) -> usize {
+        0
+    }
+    
+    /// Workers currently parked in `park()` (for diagnostics)
+    fn parked_count(&self) -> usize {
+        0
+    }
+    
+    /// Up to `max` queued GVThreads, in no particular order (for
+    /// diagnostics)
+    fn queued(&self, _max: usize) -> Vec<GVThreadId> {
+        Vec::new()
+    }
 }
\ No newline at end of file
@@ -0,0 +1,203 @@
+//! Priority ready queue
+//!
+//! Design:
+//! - Ready GVThreads live in the core `ReadyBitmaps`: one bitmap per
+//!   priority level (Critical, High, Normal, Low), claimed lock-free
+//! - Its one-word summary of non-empty levels gives the highest ready
+//!   priority in O(1), so an idle `pop` never scans
+//! - Dedicated low-priority workers look at Low first, so background
+//!   work still progresses while higher levels are saturated
+//!
+//! Within a level, order is the bitmaps' (random start block and bit),
+//! not FIFO. The only lock is the one parked workers wait
+//! on; push and pop don't take it.
+//!
+//! There are no per-worker queues, so a push's worker hint is ignored
+//! and nothing is stolen.
+
+use super::ReadyQueue;
+use gvthread_core::bitmap::ReadyBitmaps;
+use gvthread_core::id::GVThreadId;
+use gvthread_core::state::Priority;
+
+use std::sync::atomic::{fence, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+/// Ready queue that always runs the highest priority GVThread first
+pub struct PriorityQueue {
+    ready: ReadyBitmaps,
+    /// Parked workers wait here
+    park_lock: Mutex<()>,
+    cond: Condvar,
+    parked: AtomicUsize,
+    /// Workers at or above this index are dedicated low-priority workers
+    first_low_worker: usize,
+}
+
+impl PriorityQueue {
+    /// Create a queue for `num_workers` workers, the last
+    /// `num_low_priority_workers` of which prefer Low priority work,
+    /// and GVThread ids below `max_gvthreads`
+    pub fn new(num_workers: usize, num_low_priority_workers: usize, max_gvthreads: usize) -> Self {
+        Self {
+            ready: ReadyBitmaps::new(max_gvthreads, num_workers),
+            park_lock: Mutex::new(()),
+            cond: Condvar::new(),
+            parked: AtomicUsize::new(0),
+            first_low_worker: num_workers.saturating_sub(num_low_priority_workers),
+        }
+    }
+
+    /// Entries at one priority level (for diagnostics)
+    pub fn len_at(&self, priority: Priority) -> usize {
+        self.ready.ready_at(priority)
+    }
+}
+
+impl ReadyQueue for PriorityQueue {
+    fn push(&self, id: GVThreadId, priority: Priority, _hint_worker: Option<usize>) {
+        self.ready.set_ready(id, priority);
+        self.wake_one();
+    }
+
+    fn pop(&self, worker_id: usize) -> Option<(GVThreadId, Priority)> {
+        self.ready.highest_nonempty()?;
+        if worker_id >= self.first_low_worker {
+            if let Some(found) = self.ready.find_and_claim(worker_id, true) {
+                return Some(found);
+            }
+        }
+        self.ready.find_and_claim(worker_id, false)
+    }
+
+    /// No per-worker queues: any queued GVThread can be handed off to
+    fn take_local(&self, _worker_id: usize, id: GVThreadId) -> bool {
+        (0..Priority::COUNT)
+            .filter_map(Priority::from_index)
+            .any(|priority| self.ready.clear_ready(id, priority))
+    }
+
+    /// A claim racing with this wins: the GVThread is running, not moved
+    fn reprioritize(&self, id: GVThreadId, priority: Priority) {
+        let lower = (priority.as_index() + 1..Priority::COUNT).filter_map(Priority::from_index);
+        for level in lower {
+            if self.ready.clear_ready(id, level) {
+                self.push(id, priority, None);
+                return;
+            }
+        }
+    }
+
+    /// The fence pairs with the one in `wake_one`: either the re-check
+    /// sees the pusher's GVThread, or the pusher sees `parked` and
+    /// notifies (under the lock, so not between re-check and wait).
+    fn park(&self, _worker_id: usize, timeout_ms: u64) {
+        self.parked.fetch_add(1, Ordering::SeqCst);
+        fence(Ordering::SeqCst);
+        let guard = self.park_lock.lock().unwrap();
+        if self.ready.highest_nonempty().is_none() {
+            let _ = self.cond.wait_timeout(guard, Duration::from_millis(timeout_ms));
+        }
+        self.parked.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    fn wake_one(&self) {
+        fence(Ordering::SeqCst);
+        if self.parked.load(Ordering::SeqCst) > 0 {
+            let _guard = self.park_lock.lock().unwrap();
+            self.cond.notify_one();
+        }
+    }
+
+    fn wake_all(&self) {
+        self.cond.notify_all();
+    }
+
+    fn len(&self) -> usize {
+        (0..Priority::COUNT)
+            .filter_map(Priority::from_index)
+            .map(|priority| self.ready.ready_at(priority))
+            .sum()
+    }
+
+    fn parked_count(&self) -> usize {
+        self.parked.load(Ordering::Acquire)
+    }
+
+    fn queued(&self, max: usize) -> Vec<GVThreadId> {
+        self.ready.ready_ids(max).into_iter().map(|(id, _)| id).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(q: &PriorityQueue, worker_id: usize) -> Vec<u32> {
+        std::iter::from_fn(|| q.pop(worker_id).map(|(id, _)| id.as_u32())).collect()
+    }
+
+    #[test]
+    fn test_highest_priority_first() {
+        let q = PriorityQueue::new(2, 0, 16);
+        q.push(GVThreadId::new(1), Priority::Low, None);
+        q.push(GVThreadId::new(2), Priority::Normal, None);
+        q.push(GVThreadId::new(3), Priority::Normal, None);
+        q.push(GVThreadId::new(4), Priority::High, Some(1));
+        q.push(GVThreadId::new(5), Priority::Critical, None);
+        assert_eq!(q.len(), 5);
+        assert_eq!(q.len_at(Priority::Normal), 2);
+        assert_eq!(q.queued(2), vec![GVThreadId::new(5), GVThreadId::new(4)]);
+
+        assert_eq!(q.pop(0), Some((GVThreadId::new(5), Priority::Critical)));
+        let mut order = ids(&q, 0);
+        // Within a level, in no particular order
+        order[1..3].sort_unstable();
+        assert_eq!(order, vec![4, 2, 3, 1]);
+        assert!(q.is_empty());
+    }
+
+    #[test]
+    fn test_low_priority_worker_prefers_low() {
+        // Worker 1 of 2 is the dedicated low-priority worker
+        let q = PriorityQueue::new(2, 1, 16);
+        q.push(GVThreadId::new(1), Priority::High, None);
+        q.push(GVThreadId::new(2), Priority::Low, None);
+        q.push(GVThreadId::new(3), Priority::Normal, None);
+
+        assert_eq!(q.pop(1), Some((GVThreadId::new(2), Priority::Low)));
+        // No Low work left: it helps with the rest
+        assert_eq!(q.pop(1), Some((GVThreadId::new(1), Priority::High)));
+        assert_eq!(q.pop(0), Some((GVThreadId::new(3), Priority::Normal)));
+        assert_eq!(q.pop(0), None);
+    }
+
+    #[test]
+    fn test_reprioritize_moves_a_queued_gvthread_up() {
+        let q = PriorityQueue::new(1, 0, 16);
+        q.push(GVThreadId::new(1), Priority::High, None);
+        q.push(GVThreadId::new(2), Priority::Low, None);
+
+        q.reprioritize(GVThreadId::new(2), Priority::Critical);
+        // Not queued, or already higher: left alone
+        q.reprioritize(GVThreadId::new(3), Priority::Critical);
+        q.reprioritize(GVThreadId::new(1), Priority::Normal);
+
+        assert_eq!(q.len(), 2);
+        assert_eq!(q.pop(0), Some((GVThreadId::new(2), Priority::Critical)));
+        assert_eq!(q.pop(0), Some((GVThreadId::new(1), Priority::High)));
+    }
+
+    #[test]
+    fn test_take_local() {
+        let q = PriorityQueue::new(1, 0, 16);
+        q.push(GVThreadId::new(1), Priority::High, None);
+        q.push(GVThreadId::new(2), Priority::Normal, None);
+
+        assert!(q.take_local(0, GVThreadId::new(1)));
+        assert!(!q.take_local(0, GVThreadId::new(1)));
+        assert_eq!(q.len_at(Priority::High), 0);
+        assert_eq!(ids(&q, 0), vec![2]);
+    }
+}
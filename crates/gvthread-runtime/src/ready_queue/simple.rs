@@ -5,6 +5,18 @@
 //! - Global queue (VecDeque, Mutex + Condvar)
 //! - Work stealing from random victim
 //! - Single priority (all treated as Normal)
+//!
+//! Overflow: a push hinted at a worker whose local queue is full goes to
+//! the global queue instead, so it never blocks or drops work. Spilled
+//! GVThreads lose their affinity and FIFO position relative to that
+//! worker's local queue: any worker may pick them up, possibly before
+//! GVThreads queued locally earlier.
+//!
+//! Parking: a worker registers as parked, then re-checks every queue
+//! (local ones included) under the global lock before waiting; a pusher
+//! that sees a parked worker takes the same lock before notifying. So a
+//! push either lands before the re-check, and the worker doesn't sleep,
+//! or its notify finds the worker waiting — it can't fall in between.
 
 use super::ReadyQueue;
 use gvthread_core::id::GVThreadId;
@@ -12,13 +24,16 @@ use gvthread_core::state::Priority;
 use gvthread_core::SpinLock;
 
 use std::collections::VecDeque;
-use std::sync::atomic::{AtomicUsize, AtomicBool, Ordering};
+use std::sync::atomic::{fence, AtomicUsize, AtomicBool, Ordering};
 use std::sync::{Mutex, Condvar};
 use std::time::Duration;
 
-/// Local queue capacity per worker
+/// Default local queue capacity per worker (`local_queue_capacity`)
 const LOCAL_CAPACITY: usize = 256;
 
+/// Default global queue capacity (`global_queue_capacity`)
+const GLOBAL_CAPACITY: usize = 65536;
+
 /// Check global every N pops (Go uses 61)
 const GLOBAL_CHECK_INTERVAL: u32 = 61;
 
@@ -26,20 +41,22 @@ const GLOBAL_CHECK_INTERVAL: u32 = 61;
 struct LocalQueue {
     queue: SpinLock<VecDeque<u32>>,
     len: AtomicUsize,
+    capacity: usize,
 }
 
 impl LocalQueue {
-    fn new() -> Self {
+    fn new(capacity: usize) -> Self {
         Self {
-            queue: SpinLock::new(VecDeque::with_capacity(LOCAL_CAPACITY)),
+            queue: SpinLock::new(VecDeque::with_capacity(capacity)),
             len: AtomicUsize::new(0),
+            capacity,
         }
     }
     
     /// Push to back. Returns false if full.
     fn push(&self, id: u32) -> bool {
         let mut q = self.queue.lock();
-        if q.len() >= LOCAL_CAPACITY {
+        if q.len() >= self.capacity {
             return false;
         }
         q.push_back(id);
@@ -58,13 +75,32 @@ impl LocalQueue {
         item
     }
     
-    /// Steal half from front (for work stealing)
+    /// Remove a specific entry (directed handoff)
+    fn remove(&self, id: u32) -> bool {
+        if self.len.load(Ordering::Acquire) == 0 {
+            return false;
+        }
+        let mut q = self.queue.lock();
+        match q.iter().position(|&x| x == id) {
+            Some(pos) => {
+                q.remove(pos);
+                self.len.store(q.len(), Ordering::Release);
+                true
+            }
+            None => false,
+        }
+    }
+    
+    /// Steal half from front, rounded up (for work stealing)
+    ///
+    /// Rounding up matters: a single GVThread queued behind a busy
+    /// owner must still be stealable by an idle worker.
     fn steal_half(&self) -> Vec<u32> {
         if self.len.load(Ordering::Acquire) == 0 {
             return Vec::new();
         }
         let mut q = self.queue.lock();
-        let n = q.len() / 2;
+        let n = q.len().div_ceil(2);
         if n == 0 {
             return Vec::new();
         }
@@ -108,9 +144,7 @@ impl GlobalQueue {
             self.len.store(q.len(), Ordering::Release);
         }
         // Wake one parked worker
-        if self.parked.load(Ordering::Acquire) > 0 {
-            self.cond.notify_one();
-        }
+        self.wake_one();
     }
     
     fn pop(&self) -> Option<u32> {
@@ -140,17 +174,31 @@ impl GlobalQueue {
         batch
     }
     
-    fn park(&self, timeout_ms: u64) {
-        self.parked.fetch_add(1, Ordering::AcqRel);
+    /// Wait for a wake or the timeout, unless `has_work()` once we're
+    /// registered as parked
+    ///
+    /// The fence pairs with the one in `wake_one`: either this re-check
+    /// sees the pusher's work, or the pusher sees `parked` and notifies.
+    fn park(&self, timeout_ms: u64, has_work: impl Fn() -> bool) {
+        self.parked.fetch_add(1, Ordering::SeqCst);
+        fence(Ordering::SeqCst);
         let guard = self.queue.lock().unwrap();
-        if guard.is_empty() {
+        if !has_work() {
             let _ = self.cond.wait_timeout(guard, Duration::from_millis(timeout_ms));
         }
-        self.parked.fetch_sub(1, Ordering::AcqRel);
+        self.parked.fetch_sub(1, Ordering::SeqCst);
     }
     
+    /// Wake one parked worker, if any; call after making work visible
+    ///
+    /// Notifying under the lock means a worker between its re-check and
+    /// its wait still gets the signal.
     fn wake_one(&self) {
-        self.cond.notify_one();
+        fence(Ordering::SeqCst);
+        if self.parked.load(Ordering::SeqCst) > 0 {
+            let _guard = self.queue.lock().unwrap();
+            self.cond.notify_one();
+        }
     }
     
     fn wake_all(&self) {
@@ -169,6 +217,7 @@ impl GlobalQueue {
 /// Simple Go-like scheduler (MVP)
 pub struct SimpleQueue {
     local: Vec<LocalQueue>,
+    local_capacity: usize,
     global: GlobalQueue,
     num_workers: AtomicUsize,
     /// Per-worker counter for periodic global check
@@ -181,9 +230,17 @@ pub struct SimpleQueue {
 
 impl SimpleQueue {
     pub fn new() -> Self {
+        Self::with_capacity(LOCAL_CAPACITY, GLOBAL_CAPACITY)
+    }
+    
+    /// Queue whose per-worker local queues hold up to `local_capacity`
+    /// GVThreads; `global_capacity` only presizes the (unbounded) global
+    /// queue
+    pub fn with_capacity(local_capacity: usize, global_capacity: usize) -> Self {
         Self {
             local: Vec::new(),
-            global: GlobalQueue::new(65536),
+            local_capacity,
+            global: GlobalQueue::new(global_capacity),
             num_workers: AtomicUsize::new(0),
             counters: Vec::new(),
             rng: Vec::new(),
@@ -197,7 +254,7 @@ impl SimpleQueue {
             return; // Already initialized
         }
         
-        self.local = (0..num_workers).map(|_| LocalQueue::new()).collect();
+        self.local = (0..num_workers).map(|_| LocalQueue::new(self.local_capacity)).collect();
         self.counters = (0..num_workers).map(|_| AtomicUsize::new(0)).collect();
         self.rng = (0..num_workers)
             .map(|i| AtomicUsize::new(i.wrapping_mul(2654435761) + 1))
@@ -257,24 +314,19 @@ impl ReadyQueue for SimpleQueue {
     fn push(&self, id: GVThreadId, _priority: Priority, hint_worker: Option<usize>) {
         let gid = id.as_u32();
         
-        // Try local queue if hint provided
+        // Try local queue if hint provided; spill to global when it's full
         if let Some(w) = hint_worker {
             let num = self.num_workers.load(Ordering::Relaxed);
             if w < num && self.local[w].push(gid) {
-                // Wake a worker since we added work
-                if self.global.parked_count() > 0 {
-                    self.global.wake_one();
-                }
+                // Wake a worker since we added work (it may be `w` itself,
+                // on its way into `park`)
+                self.global.wake_one();
                 return;
             }
         }
         
-        // Fall back to global
+        // Fall back to global (wakes a parked worker)
         self.global.push(gid);
-        // Wake a parked worker to process this
-        if self.global.parked_count() > 0 {
-            self.global.wake_one();
-        }
     }
     
     fn pop(&self, worker_id: usize) -> Option<(GVThreadId, Priority)> {
@@ -300,10 +352,12 @@ impl ReadyQueue for SimpleQueue {
         
         // 2. Try global + batch
         if let Some(id) = self.global.pop() {
-            // Grab a batch for local
-            let batch = self.global.pop_batch(LOCAL_CAPACITY / 2);
+            // Grab a batch for local; what doesn't fit goes back
+            let batch = self.global.pop_batch(self.local_capacity / 2);
             for bid in batch {
-                let _ = self.local[worker_id].push(bid);
+                if !self.local[worker_id].push(bid) {
+                    self.global.push(bid);
+                }
             }
             return Some((GVThreadId::new(id), Priority::Normal));
         }
@@ -316,8 +370,14 @@ impl ReadyQueue for SimpleQueue {
         None
     }
     
+    fn take_local(&self, worker_id: usize, id: GVThreadId) -> bool {
+        worker_id < self.num_workers.load(Ordering::Relaxed)
+            && self.local[worker_id].remove(id.as_u32())
+    }
+    
     fn park(&self, _worker_id: usize, timeout_ms: u64) {
-        self.global.park(timeout_ms);
+        // Any queued GVThread will do: a neighbour's can be stolen
+        self.global.park(timeout_ms, || self.len() > 0);
     }
     
     fn wake_one(&self) {
@@ -335,6 +395,27 @@ impl ReadyQueue for SimpleQueue {
         }
         total
     }
+    
+    fn global_len(&self) -> usize {
+        self.global.len()
+    }
+    
+    fn local_len(&self, worker_id: usize) -> usize {
+        self.local.get(worker_id).map(|lq| lq.len()).unwrap_or(0)
+    }
+    
+    fn parked_count(&self) -> usize {
+        self.global.parked_count()
+    }
+    
+    fn queued(&self, max: usize) -> Vec<GVThreadId> {
+        let mut ids: Vec<u32> = self.global.queue.lock().unwrap().iter().take(max).copied().collect();
+        for lq in &self.local {
+            let room = max - ids.len();
+            ids.extend(lq.queue.lock().iter().take(room));
+        }
+        ids.into_iter().map(GVThreadId::new).collect()
+    }
 }
 
 #[cfg(test)]
@@ -343,7 +424,7 @@ mod tests {
     
     #[test]
     fn test_local_queue() {
-        let lq = LocalQueue::new();
+        let lq = LocalQueue::new(LOCAL_CAPACITY);
         assert_eq!(lq.len(), 0);
         
         assert!(lq.push(1));
@@ -387,6 +468,49 @@ mod tests {
         assert_eq!(r1.map(|(id, _)| id.as_u32()), Some(20));
     }
     
+    #[test]
+    fn test_queued_covers_global_and_local() {
+        let mut sq = SimpleQueue::new();
+        sq.init(2);
+        
+        sq.push(GVThreadId::new(1), Priority::Normal, None);
+        sq.push(GVThreadId::new(2), Priority::Normal, Some(1));
+        sq.push(GVThreadId::new(3), Priority::Normal, Some(0));
+        let mut ids: Vec<u32> = sq.queued(8).iter().map(|id| id.as_u32()).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert_eq!(sq.queued(2).len(), 2);
+        assert_eq!(sq.len(), 3);
+    }
+    
+    #[test]
+    fn test_take_local() {
+        let mut sq = SimpleQueue::new();
+        sq.init(2);
+        
+        sq.push(GVThreadId::new(1), Priority::Normal, Some(0));
+        sq.push(GVThreadId::new(2), Priority::Normal, Some(0));
+        sq.push(GVThreadId::new(3), Priority::Normal, Some(1));
+        
+        // Only found in the named worker's queue
+        assert!(!sq.take_local(0, GVThreadId::new(3)));
+        assert!(sq.take_local(0, GVThreadId::new(2)));
+        assert!(!sq.take_local(0, GVThreadId::new(2)));
+        assert_eq!(sq.local_len(0), 1);
+        assert_eq!(sq.pop(0).map(|(id, _)| id.as_u32()), Some(1));
+    }
+    
+    #[test]
+    fn test_steal_single_item() {
+        let mut sq = SimpleQueue::new();
+        sq.init(2);
+        
+        // Worker 0 is busy; its lone queued item must go to worker 1
+        sq.push(GVThreadId::new(7), Priority::Normal, Some(0));
+        let r1 = sq.pop(1);
+        assert_eq!(r1.map(|(id, _)| id.as_u32()), Some(7));
+    }
+    
     #[test]
     fn test_work_stealing() {
         let mut sq = SimpleQueue::new();
@@ -401,4 +525,86 @@ mod tests {
         let r = sq.pop(1);
         assert!(r.is_some());
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_local_overflow_spills_to_global() {
+        let mut sq = SimpleQueue::with_capacity(4, 16);
+        sq.init(2);
+        
+        // Worker 0 produces faster than it consumes
+        for i in 0..10 {
+            sq.push(GVThreadId::new(i), Priority::Normal, Some(0));
+        }
+        assert_eq!(sq.local_len(0), 4);
+        assert_eq!(sq.global_len(), 6);
+        
+        // Every GVThread still runs, overflow included, on either worker
+        let mut seen: Vec<u32> = std::iter::from_fn(|| sq.pop(1).or_else(|| sq.pop(0)))
+            .map(|(id, _)| id.as_u32())
+            .collect();
+        seen.sort_unstable();
+        assert_eq!(seen, (0..10).collect::<Vec<_>>());
+        assert_eq!(sq.len(), 0);
+    }
+    
+    #[test]
+    fn test_park_rechecks_local_queue() {
+        let mut sq = SimpleQueue::new();
+        sq.init(2);
+        
+        // Queued for worker 0 after its last pop: it must not sleep
+        sq.push(GVThreadId::new(5), Priority::Normal, Some(0));
+        let start = std::time::Instant::now();
+        sq.park(0, 5_000);
+        assert!(start.elapsed() < Duration::from_secs(1), "parked with local work queued");
+        assert_eq!(sq.pop(0).map(|(id, _)| id.as_u32()), Some(5));
+    }
+    
+    #[test]
+    fn test_park_no_lost_wakeup() {
+        let mut sq = SimpleQueue::new();
+        sq.init(2);
+        let sq = &sq;
+        
+        // A pusher racing a worker between its empty pop and its park
+        for round in 0..200u32 {
+            let start = std::time::Instant::now();
+            std::thread::scope(|s| {
+                s.spawn(move || {
+                    for _ in 0..round % 8 {
+                        std::thread::yield_now();
+                    }
+                    sq.push(GVThreadId::new(round + 1), Priority::Normal, Some(0));
+                });
+                while sq.pop(0).is_none() {
+                    // Widen the window between the empty pop and parking
+                    for _ in 0..round % 5 {
+                        std::thread::yield_now();
+                    }
+                    sq.park(0, 2_000);
+                }
+            });
+            assert!(
+                start.elapsed() < Duration::from_secs(1),
+                "round {}: wakeup lost, worker slept out its park timeout", round
+            );
+        }
+        assert_eq!(sq.parked_count(), 0);
+    }
+    
+    #[test]
+    fn test_diagnostic_lengths() {
+        let mut sq = SimpleQueue::new();
+        sq.init(2);
+        
+        sq.push(GVThreadId::new(1), Priority::Normal, Some(1));
+        sq.push(GVThreadId::new(2), Priority::Normal, Some(1));
+        sq.push(GVThreadId::new(3), Priority::Normal, None);
+        
+        assert_eq!(sq.local_len(0), 0);
+        assert_eq!(sq.local_len(1), 2);
+        assert_eq!(sq.global_len(), 1);
+        assert_eq!(sq.len(), 3);
+        assert_eq!(sq.parked_count(), 0);
+    }
+}
@@ -0,0 +1,341 @@
+//! GVThread-aware reader-writer lock
+//!
+//! `SchedRwLock` lets any number of readers or one writer hold the lock.
+//! A contended `read()`/`write()` blocks the calling GVThread via
+//! `block_current()` (or parks the OS thread outside a GVThread).
+//!
+//! # Fairness
+//!
+//! Waiters queue in FIFO order. A new reader only joins the current
+//! readers if nobody is queued, so a waiting writer is never starved by a
+//! steady stream of readers. On release the lock is handed off directly:
+//! either to the writer at the head of the queue, or to every reader up to
+//! the next queued writer. A woken waiter already owns the lock.
+
+use crate::waiter::WaitNode;
+
+use gvthread_core::error::SchedResult;
+use gvthread_core::spinlock::SpinLock;
+
+use core::cell::UnsafeCell;
+use core::ops::{Deref, DerefMut};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Access {
+    Read,
+    Write,
+}
+
+struct RwState {
+    /// Number of readers holding the lock
+    readers: usize,
+    /// A writer holds the lock
+    writer: bool,
+    /// Blocked waiters, FIFO
+    queue: VecDeque<(Access, Arc<WaitNode>)>,
+}
+
+/// A reader-writer lock that blocks the GVThread when contended
+///
+/// # Example
+///
+/// ```ignore
+/// let routes = SchedRwLock::new(HashMap::new());
+///
+/// // Many GVThreads:
+/// let r = routes.read()?;
+/// let target = r.get(&path);
+///
+/// // Occasionally:
+/// routes.write()?.insert(path, target);
+/// ```
+pub struct SchedRwLock<T> {
+    state: SpinLock<RwState>,
+    data: UnsafeCell<T>,
+}
+
+// Safety: readers share &T across threads, writers get &mut T exclusively
+unsafe impl<T: Send> Send for SchedRwLock<T> {}
+unsafe impl<T: Send + Sync> Sync for SchedRwLock<T> {}
+
+impl<T> SchedRwLock<T> {
+    /// Create a new unlocked lock containing `value`
+    pub const fn new(value: T) -> Self {
+        Self {
+            state: SpinLock::new(RwState {
+                readers: 0,
+                writer: false,
+                queue: VecDeque::new(),
+            }),
+            data: UnsafeCell::new(value),
+        }
+    }
+
+    /// Acquire shared access, blocking while a writer holds or awaits it
+    pub fn read(&self) -> SchedResult<SchedRwLockReadGuard<'_, T>> {
+        self.acquire(Access::Read);
+        Ok(SchedRwLockReadGuard { lock: self })
+    }
+
+    /// Acquire exclusive access, blocking while anyone else holds it
+    pub fn write(&self) -> SchedResult<SchedRwLockWriteGuard<'_, T>> {
+        self.acquire(Access::Write);
+        Ok(SchedRwLockWriteGuard { lock: self })
+    }
+
+    /// Try to acquire shared access without blocking
+    pub fn try_read(&self) -> Option<SchedRwLockReadGuard<'_, T>> {
+        let mut state = self.state.lock();
+        if state.writer || !state.queue.is_empty() {
+            return None;
+        }
+        state.readers += 1;
+        Some(SchedRwLockReadGuard { lock: self })
+    }
+
+    /// Try to acquire exclusive access without blocking
+    pub fn try_write(&self) -> Option<SchedRwLockWriteGuard<'_, T>> {
+        let mut state = self.state.lock();
+        if state.writer || state.readers > 0 || !state.queue.is_empty() {
+            return None;
+        }
+        state.writer = true;
+        Some(SchedRwLockWriteGuard { lock: self })
+    }
+
+    /// Number of readers currently holding the lock
+    pub fn reader_count(&self) -> usize {
+        self.state.lock().readers
+    }
+
+    /// Whether a writer currently holds the lock
+    pub fn is_write_locked(&self) -> bool {
+        self.state.lock().writer
+    }
+
+    /// Get mutable access to the underlying data
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
+    /// Consume the lock and return the inner value
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+
+    /// Acquire if free and nobody is queued, else queue and block until a
+    /// releasing holder hands the lock to us. Either way the caller owns
+    /// the lock on return.
+    fn acquire(&self, access: Access) {
+        let node = {
+            let mut state = self.state.lock();
+            let free = match access {
+                Access::Read => !state.writer,
+                Access::Write => !state.writer && state.readers == 0,
+            };
+            if free && state.queue.is_empty() {
+                match access {
+                    Access::Read => state.readers += 1,
+                    Access::Write => state.writer = true,
+                }
+                return;
+            }
+            let node = Arc::new(WaitNode::current());
+            state.queue.push_back((access, Arc::clone(&node)));
+            node
+        };
+        // Ownership is transferred by `handoff()` before the wake.
+        node.wait();
+    }
+
+    fn read_unlock(&self) {
+        let woken = {
+            let mut state = self.state.lock();
+            state.readers -= 1;
+            if state.readers > 0 {
+                return;
+            }
+            Self::handoff(&mut state)
+        };
+        for node in woken {
+            node.notify();
+        }
+    }
+
+    fn write_unlock(&self) {
+        let woken = {
+            let mut state = self.state.lock();
+            state.writer = false;
+            Self::handoff(&mut state)
+        };
+        for node in woken {
+            node.notify();
+        }
+    }
+
+    /// Grant the lock to the head of the queue: one writer, or every
+    /// reader up to the next writer. Called with the lock free.
+    fn handoff(state: &mut RwState) -> Vec<Arc<WaitNode>> {
+        let mut woken = Vec::new();
+        match state.queue.front() {
+            Some((Access::Write, _)) => {
+                let (_, node) = state.queue.pop_front().unwrap();
+                state.writer = true;
+                woken.push(node);
+            }
+            Some((Access::Read, _)) => {
+                while let Some((Access::Read, _)) = state.queue.front() {
+                    let (_, node) = state.queue.pop_front().unwrap();
+                    state.readers += 1;
+                    woken.push(node);
+                }
+            }
+            None => {}
+        }
+        woken
+    }
+}
+
+impl<T: Default> Default for SchedRwLock<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for SchedRwLock<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.try_read() {
+            Some(guard) => f.debug_struct("SchedRwLock").field("data", &&*guard).finish(),
+            None => f.debug_struct("SchedRwLock").field("data", &"<locked>").finish(),
+        }
+    }
+}
+
+/// Shared access guard; releases on drop
+pub struct SchedRwLockReadGuard<'a, T> {
+    lock: &'a SchedRwLock<T>,
+}
+
+impl<'a, T> Deref for SchedRwLockReadGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<'a, T> Drop for SchedRwLockReadGuard<'a, T> {
+    fn drop(&mut self) {
+        self.lock.read_unlock();
+    }
+}
+
+/// Exclusive access guard; releases on drop
+pub struct SchedRwLockWriteGuard<'a, T> {
+    lock: &'a SchedRwLock<T>,
+}
+
+impl<'a, T> Deref for SchedRwLockWriteGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<'a, T> DerefMut for SchedRwLockWriteGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<'a, T> Drop for SchedRwLockWriteGuard<'a, T> {
+    fn drop(&mut self) {
+        self.lock.write_unlock();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Barrier;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn readers_share_writer_excludes() {
+        let lock = SchedRwLock::new(5);
+        let r1 = lock.read().unwrap();
+        let r2 = lock.read().unwrap();
+        assert_eq!(*r1 + *r2, 10);
+        assert_eq!(lock.reader_count(), 2);
+        assert!(lock.try_write().is_none());
+        drop((r1, r2));
+
+        let mut w = lock.write().unwrap();
+        *w = 7;
+        assert!(lock.try_read().is_none());
+        drop(w);
+        assert_eq!(*lock.read().unwrap(), 7);
+    }
+
+    #[test]
+    fn concurrent_readers_then_writer() {
+        const READERS: usize = 4;
+        let lock = Arc::new(SchedRwLock::new(0u32));
+        let inside = Arc::new(AtomicUsize::new(0));
+        let max_inside = Arc::new(AtomicUsize::new(0));
+        let barrier = Arc::new(Barrier::new(READERS));
+
+        let readers: Vec<_> = (0..READERS)
+            .map(|_| {
+                let (lock, inside, max_inside, barrier) =
+                    (lock.clone(), inside.clone(), max_inside.clone(), barrier.clone());
+                thread::spawn(move || {
+                    let g = lock.read().unwrap();
+                    let now = inside.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_inside.fetch_max(now, Ordering::SeqCst);
+                    // Every reader must get in before any leaves.
+                    barrier.wait();
+                    inside.fetch_sub(1, Ordering::SeqCst);
+                    *g
+                })
+            })
+            .collect();
+        for r in readers {
+            assert_eq!(r.join().unwrap(), 0);
+        }
+        assert_eq!(max_inside.load(Ordering::SeqCst), READERS);
+
+        *lock.write().unwrap() += 1;
+        assert_eq!(*lock.read().unwrap(), 1);
+    }
+
+    #[test]
+    fn queued_writer_blocks_new_readers() {
+        let lock = Arc::new(SchedRwLock::new(0u32));
+        let r = lock.read().unwrap();
+
+        let l2 = lock.clone();
+        let writer = thread::spawn(move || {
+            *l2.write().unwrap() = 1;
+        });
+        while lock.state.lock().queue.is_empty() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        // Writer is queued: a new reader must not overtake it.
+        assert!(lock.try_read().is_none());
+
+        let l3 = lock.clone();
+        let late_reader = thread::spawn(move || *l3.read().unwrap());
+
+        drop(r);
+        writer.join().unwrap();
+        assert_eq!(late_reader.join().unwrap(), 1);
+        assert_eq!(lock.reader_count(), 0);
+        assert!(!lock.is_write_locked());
+    }
+}
@@ -1,29 +1,39 @@
 //! Main scheduler implementation
 //!
 //! Orchestrates all components: memory, workers, timers, ready queue.
+//!
+//! There is one scheduler per process: the slot memory region, worker
+//! states and sleep queue are process-wide, and the free functions
+//! (`spawn`, `stats`, ...) reach it through `global_scheduler()`.
 
-use crate::config::SchedulerConfig;
+use crate::config::{ReadyQueueKind, SchedulerConfig};
 use crate::memory;
 use crate::worker::{WorkerPool, set_current_worker_id, current_worker_state, worker_states};
 use crate::timer::TimerThread;
 use crate::tls;
 use crate::current_arch;
-use crate::ready_queue::{ReadyQueue, SimpleQueue};
+use crate::ready_queue::{PriorityQueue, ReadyQueue, SimpleQueue};
+use crate::stats::RuntimeStats;
+use crate::join::{join_pair, panic_message, JoinError, JoinHandle};
 
 use gvthread_core::id::GVThreadId;
 use gvthread_core::state::{GVThreadState, Priority};
-use gvthread_core::metadata::{GVThreadMetadata, VoluntarySavedRegs};
+use gvthread_core::metadata::{GVThreadMetadata, GVThreadStats, VoluntarySavedRegs};
 use gvthread_core::constants::GVTHREAD_NONE;
 
 use gvthread_core::slot::SlotAllocator;
 use gvthread_core::cancel::CancellationToken;
 use gvthread_core::error::{SchedError, SchedResult};
+use gvthread_core::preempt;
 
 // Use kprint macros for debug output
-use gvthread_core::{kprintln, kdebug, kwarn};
+use gvthread_core::{kprintln, kdebug, kdebug_every, kerror, kwarn};
+
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
 
-use std::sync::atomic::{AtomicBool, Ordering};
 
+/// Per-context-switch debug lines are sampled: one in this many
+const SWITCH_LOG_EVERY: u64 = 1000;
 
 /// Global scheduler instance
 pub(crate) static mut SCHEDULER: Option<Scheduler> = None;
@@ -61,6 +71,93 @@ pub fn set_worker_io_hooks(
     }
 }
 
+// ── Blocked-wait kinds ──────────────────────────────────────────────
+//
+// `GVThreadMetadata::sleep_flag` records what a GVThread is blocked on,
+// so cancellation knows how to interrupt it:
+//
+//   WAIT_SLEEP — on the sleep queue: wake it directly
+//   WAIT_IO    — on an I/O layer: ask the layer (io cancel hook) to
+//                cancel the operation; its completion wakes the GVThread
+
+/// Not in an interruptible wait
+pub const WAIT_NONE: u32 = 0;
+/// Sleeping (`sleep` / `sleep_cancellable`)
+pub const WAIT_SLEEP: u32 = 1;
+/// Waiting for an I/O completion
+pub const WAIT_IO: u32 = 2;
+
+static IO_CANCEL_FN: std::sync::OnceLock<fn(GVThreadId)> = std::sync::OnceLock::new();
+
+/// Install the I/O layer's cancel hook (first call wins)
+///
+/// Called when a GVThread in a `WAIT_IO` wait is cancelled. The hook
+/// must make the pending operation complete (e.g. `IORING_OP_ASYNC_CANCEL`)
+/// and wake the GVThread through its normal completion path; it must not
+/// wake it directly while the kernel may still use its buffers.
+pub fn set_io_cancel_hook(cancel: fn(GVThreadId)) {
+    let _ = IO_CANCEL_FN.set(cancel);
+}
+
+/// Publish what `meta`'s GVThread is about to block on
+///
+/// Entering a wait (`kind != WAIT_NONE`) is followed by a full fence, so
+/// a subsequent `is_cancelled()` check cannot miss a concurrent cancel
+/// that also missed the wait kind.
+#[inline]
+pub fn set_wait_kind(meta: &gvthread_core::metadata::GVThreadMetadata, kind: u32) {
+    if kind != WAIT_NONE {
+        // Committed to block_current (see `prepare_block`)
+        meta.begin_switch();
+    }
+    meta.sleep_flag.store(kind, Ordering::Release);
+    if kind != WAIT_NONE {
+        std::sync::atomic::fence(Ordering::SeqCst);
+    }
+}
+
+/// `set_wait_kind` for the calling GVThread (no-op outside one)
+#[inline]
+pub fn set_current_wait_kind(kind: u32) {
+    if let Some(meta) = tls::current_metadata() {
+        set_wait_kind(meta, kind);
+    }
+}
+
+/// Whether GVThread `id` has been cancelled
+pub fn is_gvthread_cancelled(id: GVThreadId) -> bool {
+    unsafe { &*memory::get_metadata_ptr(id.as_u32()) }.is_cancelled()
+}
+
+/// Cancellation wake hook (installed by `Scheduler::start`)
+///
+/// Interrupts the wait a cancelled GVThread is blocked in, per its wait
+/// kind. A sleeper may still be on its way into `block_current`, so wait
+/// for it to leave `Running` before waking it.
+fn wake_cancelled(meta: &gvthread_core::metadata::GVThreadMetadata) {
+    let generation = meta.get_generation();
+    match meta.sleep_flag.load(Ordering::Acquire) {
+        WAIT_SLEEP => {
+            while meta.get_generation() == generation
+                && meta.sleep_flag.load(Ordering::Acquire) == WAIT_SLEEP
+                && meta.get_state() == GVThreadState::Running
+            {
+                std::hint::spin_loop();
+                std::thread::yield_now();
+            }
+            if meta.sleep_flag.load(Ordering::Acquire) == WAIT_SLEEP {
+                wake_gvthread_checked(meta.get_id(), meta.get_priority(), generation);
+            }
+        }
+        WAIT_IO => {
+            if let Some(cancel) = IO_CANCEL_FN.get() {
+                cancel(meta.get_id());
+            }
+        }
+        _ => {}
+    }
+}
+
 /// Per-worker scheduler context
 /// 
 /// Each worker stores its "scheduler context" here - the register state
@@ -120,6 +217,33 @@ pub fn get_worker_sched_context(worker_id: usize) -> *mut VoluntarySavedRegs {
     unsafe { WORKER_SCHED_CONTEXTS.get(worker_id) }
 }
 
+/// Per-worker GVThread that `yield_to()` switched away from, waiting to
+/// be requeued by whichever GVThread resumes next (GVTHREAD_NONE = none)
+static HANDOFF_PENDING: [AtomicU32; gvthread_core::constants::MAX_WORKERS] =
+    [const { AtomicU32::new(GVTHREAD_NONE) }; gvthread_core::constants::MAX_WORKERS];
+
+/// Per-worker flag: the GVThread that just switched back to the scheduler
+/// from `block_current()` still has to be marked `Blocked`
+static BLOCK_PENDING: [AtomicBool; gvthread_core::constants::MAX_WORKERS] =
+    [const { AtomicBool::new(false) }; gvthread_core::constants::MAX_WORKERS];
+
+/// Requeue the GVThread a `yield_to()` on this worker switched away from
+///
+/// Called first thing wherever a GVThread resumes. By then the yielder's
+/// registers are saved, so another worker may safely pick it up.
+#[inline]
+fn complete_handoff(worker_id: usize) {
+    let prev = HANDOFF_PENDING[worker_id].swap(GVTHREAD_NONE, Ordering::Relaxed);
+    if prev == GVTHREAD_NONE {
+        return;
+    }
+    let meta = unsafe { &*memory::get_metadata_ptr(prev) };
+    if let Some(sched) = global_scheduler() {
+        let _no_preempt = preempt::disable();
+        sched.ready_queue.push(GVThreadId::new(prev), meta.get_priority(), Some(worker_id));
+    }
+}
+
 /// Main scheduler
 pub struct Scheduler {
     /// Configuration
@@ -139,25 +263,74 @@ pub struct Scheduler {
     
     /// Scheduler is running
     running: AtomicBool,
+
+    /// Live (spawned, not yet Finished) GVThreads
+    active_count: AtomicUsize,
+    
+    /// Signalled when `active_count` drops to zero (see `wait_idle`)
+    idle_lock: std::sync::Mutex<()>,
+    idle_cond: std::sync::Condvar,
+    
+    /// GVThreads spawned since start
+    spawned_total: AtomicU64,
+    
+    /// GVThreads finished since start
+    finished_total: AtomicU64,
+    
+    /// Scheduler → GVThread context switches (incremented in run_gvthread)
+    pub(crate) context_switches: AtomicU64,
+    
+    /// New spawns accepted (cleared by graceful shutdown)
+    accepting: AtomicBool,
 }
 
 impl Scheduler {
     /// Create a new scheduler with the given configuration
+    ///
+    /// # Panics
+    ///
+    /// If `config` is invalid; see `try_new`.
     pub fn new(config: SchedulerConfig) -> Self {
-        config.validate().expect("Invalid scheduler configuration");
+        Self::try_new(config).unwrap_or_else(|e| panic!("Invalid scheduler configuration: {}", e))
+    }
+    
+    /// Create a new scheduler, or `Err(InvalidConfig)` if `config` fails
+    /// validation
+    pub fn try_new(config: SchedulerConfig) -> SchedResult<Self> {
+        config.validate()?;
         
         // Create and initialize ready queue
-        let mut ready_queue = SimpleQueue::new();
-        ready_queue.init(config.num_workers);
+        let ready_queue: Box<dyn ReadyQueue> = match config.ready_queue {
+            ReadyQueueKind::Simple => {
+                let mut queue = SimpleQueue::with_capacity(
+                    config.local_queue_capacity,
+                    config.global_queue_capacity,
+                );
+                queue.init(config.num_workers);
+                Box::new(queue)
+            }
+            ReadyQueueKind::Priority => Box::new(PriorityQueue::new(
+                config.num_workers,
+                config.num_low_priority_workers,
+                config.max_gvthreads,
+            )),
+        };
         
-        Self {
+        Ok(Self {
             slot_allocator: SlotAllocator::new(config.max_gvthreads),
-            ready_queue: Box::new(ready_queue),
+            ready_queue,
             worker_pool: None,
             timer_thread: None,
             running: AtomicBool::new(false),
+            active_count: AtomicUsize::new(0),
+            idle_lock: std::sync::Mutex::new(()),
+            idle_cond: std::sync::Condvar::new(),
+            spawned_total: AtomicU64::new(0),
+            finished_total: AtomicU64::new(0),
+            context_switches: AtomicU64::new(0),
+            accepting: AtomicBool::new(true),
             config,
-        }
+        })
     }
     
     /// Initialize and start the scheduler
@@ -166,8 +339,22 @@ impl Scheduler {
             return Err(SchedError::AlreadyInitialized);
         }
         
-        // Initialize memory region
-        memory::init_memory_region(self.config.max_gvthreads)?;
+        // Map the memory region, unless pre-start spawns already needed it
+        self.init_memory()?;
+        
+        // Let linked cancellation wake GVThreads in cancellable waits
+        gvthread_core::cancel::set_wake_hook(wake_cancelled);
+        gvthread_core::cancel::set_current_cancelled_hook(current_cancelled);
+        // Let SchedMutex find its holder for priority inheritance (and
+        // move it up if queued), and give up the worker while it waits
+        gvthread_core::metadata::set_current_metadata_hook(current_metadata);
+        gvthread_core::mutex::set_boost_hook(requeue_boosted);
+        gvthread_core::mutex::set_yield_hook(yield_now);
+        install_panic_hook();
+        #[cfg(unix)]
+        if self.config.enable_forced_preempt {
+            crate::signal::init_preemption(self.config.forced_switch)?;
+        }
         
         // Set the global running flag BEFORE starting workers
         SCHEDULER_RUNNING.store(true, Ordering::Release);
@@ -182,12 +369,16 @@ impl Scheduler {
             self.config.num_workers,
             self.config.num_low_priority_workers,
         );
+        if self.config.supervise_workers {
+            workers = workers.supervised(std::sync::Arc::new(recover_worker));
+        }
         
         // Clone values needed by worker closure
         let debug = self.config.debug_logging;
+        let pin = self.config.pin_workers;
         
         workers.start(move |worker_id, is_low_priority| {
-            worker_main_loop(worker_id, is_low_priority, debug);
+            worker_main_loop(worker_id, is_low_priority, pin, debug);
         });
         
         self.worker_pool = Some(workers);
@@ -195,18 +386,118 @@ impl Scheduler {
         Ok(())
     }
     
+    /// Reserve the GVThread memory region if it isn't already
+    ///
+    /// Spawning needs the region, not the workers: GVThreads spawned
+    /// before `start` wait in the ready queue until workers pick them up.
+    fn init_memory(&self) -> SchedResult<()> {
+        if memory::memory_region().is_initialized() {
+            return Ok(());
+        }
+        memory::init_memory_region(
+            self.config.max_gvthreads,
+            self.config.stack_size,
+            self.config.lazy_stack_free,
+        )
+    }
+    
     /// Spawn a new GVThread
+    ///
+    /// Valid before `start`: the GVThread is queued and runs once the
+    /// workers start.
+    ///
+    /// Returns `GVThreadId::NONE` (dropping `f`) once a graceful
+    /// shutdown has begun.
+    ///
+    /// # Panics
+    ///
+    /// If every slot is in use; see `try_spawn`.
     pub fn spawn<F>(&self, f: F, priority: Priority) -> GVThreadId
     where
         F: FnOnce(&CancellationToken) + Send + 'static,
     {
+        infallible(self.spawn_inner(f, priority, None))
+    }
+    
+    /// Spawn a new GVThread bound to `token`
+    ///
+    /// Cancelling `token` (or an ancestor it was created from with
+    /// `child_token()`) cancels the GVThread: its own token reports
+    /// cancelled and cancellable waits return early.
+    ///
+    /// # Panics
+    ///
+    /// If every slot is in use; see `try_spawn_with_token`.
+    pub fn spawn_with_token<F>(&self, f: F, priority: Priority, token: &CancellationToken) -> GVThreadId
+    where
+        F: FnOnce(&CancellationToken) + Send + 'static,
+    {
+        infallible(self.spawn_inner(f, priority, Some(token)))
+    }
+    
+    /// Spawn a new GVThread, or `Err(NoSlotsAvailable)` once
+    /// `max_gvthreads` are live (`f` is dropped)
+    ///
+    /// Fails with `ShuttingDown` once a graceful shutdown has begun.
+    pub fn try_spawn<F>(&self, f: F, priority: Priority) -> SchedResult<GVThreadId>
+    where
+        F: FnOnce(&CancellationToken) + Send + 'static,
+    {
+        self.spawn_inner(f, priority, None)
+    }
+    
+    /// `try_spawn` bound to `token` (see `spawn_with_token`)
+    pub fn try_spawn_with_token<F>(
+        &self,
+        f: F,
+        priority: Priority,
+        token: &CancellationToken,
+    ) -> SchedResult<GVThreadId>
+    where
+        F: FnOnce(&CancellationToken) + Send + 'static,
+    {
+        self.spawn_inner(f, priority, Some(token))
+    }
+    
+    /// Slots in use and the maximum (`max_gvthreads`)
+    ///
+    /// Cheaper than `stats()`; meant for callers that throttle before
+    /// they run out.
+    pub fn slot_usage(&self) -> (usize, usize) {
+        (
+            self.slot_allocator.allocated_count() as usize,
+            self.slot_allocator.max_slots() as usize,
+        )
+    }
+    
+    fn spawn_inner<F>(
+        &self,
+        f: F,
+        priority: Priority,
+        token: Option<&CancellationToken>,
+    ) -> SchedResult<GVThreadId>
+    where
+        F: FnOnce(&CancellationToken) + Send + 'static,
+    {
+        // Slot allocation, boxing `f` and queueing (see `signal`)
+        let _no_preempt = preempt::disable();
+        
+        // Refuse new work once a graceful shutdown has begun
+        if !self.accepting.load(Ordering::Acquire) {
+            kwarn!("spawn rejected: scheduler is shutting down");
+            return Err(SchedError::ShuttingDown);
+        }
+        
         // Allocate a slot
-        let id = self.slot_allocator.allocate()
-            .expect("No slots available");
+        let (id, generation) = self.slot_allocator.allocate().inspect_err(|_| {
+            kwarn!("spawn rejected: all {} GVThread slots in use", self.slot_allocator.max_slots());
+        })?;
         
         // Activate the slot's memory
-        memory::memory_region().activate_slot(id.as_u32())
-            .expect("Failed to activate slot");
+        if let Err(e) = memory::memory_region().activate_slot(id.as_u32(), generation) {
+            self.slot_allocator.release(id);
+            return Err(e);
+        }
         
         // Get metadata pointer
         let meta_ptr = memory::get_metadata_ptr(id.as_u32());
@@ -220,19 +511,21 @@ impl Scheduler {
         };
         
         // Initialize metadata
-        meta.init(id, parent, priority);
+        meta.init(id, generation, parent, priority);
+        if let Some(token) = token {
+            token.bind_gvthread(meta);
+        }
         
-        // Box the closure and store pointer in metadata
-        let boxed: Box<dyn FnOnce(&CancellationToken) + Send> = Box::new(f);
-        let closure_ptr = Box::into_raw(Box::new(boxed));
+        // Box the closure (thin pointer) and store it in metadata.
+        // Allocating here is fine even when the caller is a GVThread:
+        // see `gvthread_entry`.
+        let closure_ptr = SpawnedClosure::into_raw_header(f);
         meta.entry_fn.store(gvthread_entry as usize as u64, Ordering::Relaxed);
         meta.entry_arg.store(closure_ptr as usize as u64, Ordering::Relaxed);
         
         // Initialize stack context
         let stack_top = memory::get_stack_top(id.as_u32());
-        let regs_ptr = unsafe {
-            (meta_ptr as *mut u8).add(0x40) as *mut gvthread_core::metadata::VoluntarySavedRegs
-        };
+        let regs_ptr = GVThreadMetadata::saved_regs_ptr(meta_ptr);
         
         unsafe {
             crate::current_arch::init_context(
@@ -244,10 +537,16 @@ impl Scheduler {
         }
         
         // Mark as ready and add to queue
+        self.active_count.fetch_add(1, Ordering::AcqRel);
+        self.spawned_total.fetch_add(1, Ordering::Relaxed);
         meta.set_state(GVThreadState::Ready);
         self.ready_queue.push(id, priority, None);  // No worker hint for spawn
         
-        id
+        if priority == Priority::Critical && self.is_running() {
+            crate::timer::preempt_for_critical(self.config.num_workers, self.config.enable_forced_preempt);
+        }
+        
+        Ok(id)
     }
     
     /// Get next ready GVThread for a worker
@@ -262,6 +561,7 @@ impl Scheduler {
         meta.set_state(GVThreadState::Ready);
         // Use current worker as hint if available
         let hint = tls::try_current_worker_id();
+        let _no_preempt = preempt::disable();
         self.ready_queue.push(id, priority, hint);
     }
     
@@ -278,11 +578,20 @@ impl Scheduler {
         let meta_ptr = memory::get_metadata_ptr(id.as_u32());
         let meta = unsafe { &*meta_ptr };
         
-        // Only wake if currently blocked
-        if meta.get_state() == GVThreadState::Blocked {
-            meta.set_state(GVThreadState::Ready);
+        // Only wake if currently blocked. CAS so that concurrent wakers
+        // (timer, cancellation, a notifier) push it at most once.
+        if meta.state
+            .compare_exchange(
+                GVThreadState::Blocked as u8,
+                GVThreadState::Ready as u8,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            )
+            .is_ok()
+        {
             // Use current worker as hint for locality
             let hint = tls::try_current_worker_id();
+            let _no_preempt = preempt::disable();
             self.ready_queue.push(id, priority, hint);
         }
     }
@@ -295,11 +604,69 @@ impl Scheduler {
         meta.set_state(GVThreadState::Finished);
         // Queue-based: no need to remove, it was already popped
         
+        // Before the metadata page is released with the slot
+        crate::local::drop_locals(meta);
+        
         // Deactivate slot memory
         let _ = memory::memory_region().deactivate_slot(id.as_u32());
         
         // Return slot to allocator
         self.slot_allocator.release(id);
+        
+        self.finished_total.fetch_add(1, Ordering::Relaxed);
+        if self.active_count.fetch_sub(1, Ordering::AcqRel) == 1 {
+            // Under the lock, so a waiter between its check and its
+            // wait can't miss this
+            let _guard = self.idle_lock.lock().unwrap();
+            self.idle_cond.notify_all();
+        }
+    }
+    
+    /// Scheduler configuration
+    pub fn config(&self) -> &SchedulerConfig {
+        &self.config
+    }
+    
+    /// Number of live GVThreads (Ready, Running or Blocked — not Finished)
+    pub fn active_count(&self) -> usize {
+        self.active_count.load(Ordering::Acquire)
+    }
+    
+    /// True when no GVThread is live and the ready queue is empty
+    pub fn is_idle(&self) -> bool {
+        self.active_count() == 0 && self.ready_queue.is_empty()
+    }
+    
+    /// Block the calling OS thread until no GVThread is live
+    ///
+    /// Woken by the last GVThread to finish, not by polling. Must not be
+    /// called from a GVThread: it would wait for itself.
+    pub fn wait_idle(&self) {
+        debug_assert!(!tls::is_in_gvthread(), "wait_idle called from a GVThread");
+        let mut guard = self.idle_lock.lock().unwrap();
+        while self.active_count() > 0 {
+            guard = self.idle_cond.wait(guard).unwrap();
+        }
+    }
+    
+    /// Snapshot of scheduler counters (cheap; relaxed loads only)
+    pub fn stats(&self) -> RuntimeStats {
+        let num_workers = self.config.num_workers;
+        RuntimeStats {
+            slots_allocated: self.slot_allocator.allocated_count(),
+            max_slots: self.slot_allocator.max_slots(),
+            active_gvthreads: self.active_count(),
+            spawned_total: self.spawned_total.load(Ordering::Relaxed),
+            finished_total: self.finished_total.load(Ordering::Relaxed),
+            global_queue_len: self.ready_queue.global_len(),
+            local_queue_lens: (0..num_workers)
+                .map(|w| self.ready_queue.local_len(w))
+                .collect(),
+            parked_workers: self.ready_queue.parked_count(),
+            context_switches: self.context_switches.load(Ordering::Relaxed),
+            live_workers: self.worker_pool.as_ref().map_or(0, |w| w.live_workers()),
+            worker_restarts: self.worker_pool.as_ref().map_or(0, |w| w.restarts()),
+        }
     }
     
     /// Check if scheduler is running
@@ -307,10 +674,81 @@ impl Scheduler {
         self.running.load(Ordering::Acquire)
     }
     
+    /// Stop accepting new spawns (`spawn` returns `GVThreadId::NONE`,
+    /// `try_spawn` fails with `ShuttingDown`)
+    pub fn stop_accepting(&self) {
+        self.accepting.store(false, Ordering::Release);
+    }
+    
+    /// Drain, then shut down
+    ///
+    /// Stops accepting spawns, waits up to `timeout` for every live
+    /// GVThread to finish and the ready queue to drain, then stops the
+    /// workers. Returns the number of GVThreads still live when the
+    /// workers were stopped (0 = clean drain).
+    pub fn shutdown_graceful(&mut self, timeout: std::time::Duration) -> usize {
+        self.stop_accepting();
+        
+        // Woken by the last GVThread to finish (`mark_finished`); no live
+        // GVThread also means nothing left in the ready queue
+        let deadline = std::time::Instant::now() + timeout;
+        let mut guard = self.idle_lock.lock().unwrap();
+        while self.is_running() && self.active_count() > 0 {
+            let left = deadline.saturating_duration_since(std::time::Instant::now());
+            if left.is_zero() {
+                break;
+            }
+            guard = self.idle_cond.wait_timeout(guard, left).unwrap().0;
+        }
+        drop(guard);
+        
+        let remaining = self.active_count();
+        if remaining > 0 {
+            kwarn!("graceful shutdown timed out with {} GVThreads active", remaining);
+        }
+        self.shutdown();
+        remaining
+    }
+    
+    /// Free the boxed closures of GVThreads that never started
+    ///
+    /// `gvthread_entry` takes ownership of the closure (and clears
+    /// `entry_arg`) when a GVThread first runs. Any non-zero `entry_arg`
+    /// left after the workers have stopped belongs to a GVThread that
+    /// was still queued — drop it here instead of leaking it. GVThreads
+    /// abandoned mid-execution own their closure on their (discarded)
+    /// stack and cannot be reclaimed.
+    ///
+    /// Must only be called once no worker can run GVThreads.
+    fn reclaim_abandoned(&self) -> usize {
+        if !memory::memory_region().is_initialized() {
+            return 0;
+        }
+        
+        // Slots below the high-water mark stay mapped. A released slot
+        // may keep its old contents (MADV_FREE doesn't zero the page),
+        // but its entry_arg is 0: `gvthread_entry` clears it on taking
+        // the closure, and this swap on reclaiming it.
+        let high_water = self.slot_allocator.max_slots() - self.slot_allocator.fresh_remaining();
+        let mut reclaimed = 0;
+        for slot in 0..high_water {
+            let meta = unsafe { &*memory::get_metadata_ptr(slot) };
+            let closure_ptr = meta.entry_arg.swap(0, Ordering::AcqRel);
+            if closure_ptr != 0 {
+                unsafe { ClosureHeader::drop(closure_ptr as usize as *mut ClosureHeader) };
+                reclaimed += 1;
+            }
+        }
+        reclaimed
+    }
+    
     /// Shutdown the scheduler
     pub fn shutdown(&mut self) {
         if !self.running.swap(false, Ordering::SeqCst) {
-            return; // Already stopped
+            // Already stopped, or never started: nothing will run what
+            // was spawned before `start`
+            self.reclaim_abandoned();
+            return;
         }
         
         // Clear the global running flag FIRST to signal workers to exit
@@ -333,23 +771,148 @@ impl Scheduler {
             workers.shutdown();
             workers.join();
         }
+        
+        // No worker can run GVThreads now — free never-started closures
+        let reclaimed = self.reclaim_abandoned();
+        if reclaimed > 0 {
+            kdebug!("reclaimed {} closures of never-started GVThreads", reclaimed);
+        }
+    }
+}
+
+/// The infallible spawns' contract: `NONE` while shutting down, panic
+/// on any other failure
+fn infallible(spawned: SchedResult<GVThreadId>) -> GVThreadId {
+    match spawned {
+        Ok(id) => id,
+        Err(SchedError::ShuttingDown) => GVThreadId::NONE,
+        Err(e) => panic!("spawn failed: {}", e),
+    }
+}
+
+/// Stack below which a GVThread can't run the previous panic hook itself
+///
+/// With `RUST_BACKTRACE` set, std symbolizes the backtrace on the
+/// panicking stack, which takes far more than a small slot's stack and
+/// overflows into the guard page.
+const PANIC_HOOK_MIN_STACK: usize = 1024 * 1024;
+
+/// Run the previous panic hook for GVThreads without being preempted
+///
+/// The hook state std keeps (the panic count, the stderr lock) is per OS
+/// thread: a GVThread switched out mid-hook would hand it to the next one
+/// on the worker, and a panic there aborts the process. On stacks below
+/// `PANIC_HOOK_MIN_STACK` the previous hook runs on a short-lived OS
+/// thread named after the GVThread, so user hooks still see every panic;
+/// its backtrace is that thread's. If the thread can't be spawned the
+/// panic is logged instead.
+fn install_panic_hook() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if !tls::is_in_gvthread() {
+                return previous(info);
+            }
+            let _marked = gvthread_core::preempt::disable();
+            if memory::memory_region().stack_size() >= PANIC_HOOK_MIN_STACK {
+                return previous(info);
+            }
+
+            /// The hook info isn't `Send`, but the worker waits for the thread
+            struct Info<'a, T: ?Sized>(&'a T);
+            unsafe impl<T: ?Sized> Send for Info<'_, T> {}
+
+            let id = tls::current_gvthread_id().as_u32();
+            let info_ref = Info(info);
+            let ran = std::thread::scope(|scope| {
+                std::thread::Builder::new()
+                    .name(format!("gvthread-{}", id))
+                    .spawn_scoped(scope, || {
+                        let info_ref = info_ref;
+                        previous(info_ref.0)
+                    })
+                    .map(|thread| thread.join().is_ok())
+                    .unwrap_or(false)
+            });
+            if !ran {
+                let location = info.location()
+                    .map(|l| format!("{}:{}", l.file(), l.line()))
+                    .unwrap_or_default();
+                kerror!("GVThread {} panicked at {}: {}", id, location, panic_message(info.payload()));
+            }
+        }));
+    });
+}
+
+/// A spawned closure, boxed once so `entry_arg` holds a thin pointer
+///
+/// `repr(C)` puts the header first: code that doesn't know `F`
+/// (`gvthread_entry`, `reclaim_abandoned`) reaches it through a
+/// `*mut ClosureHeader`.
+#[repr(C)]
+struct SpawnedClosure<F> {
+    header: ClosureHeader,
+    f: F,
+}
+
+struct ClosureHeader {
+    /// Free the allocation and run the closure
+    call: unsafe fn(*mut ClosureHeader, &CancellationToken),
+    /// Free the allocation and the closure without running it
+    drop: unsafe fn(*mut ClosureHeader),
+}
+
+impl<F: FnOnce(&CancellationToken) + Send + 'static> SpawnedClosure<F> {
+    fn into_raw_header(f: F) -> *mut ClosureHeader {
+        let closure = Box::new(Self {
+            header: ClosureHeader { call: Self::call, drop: Self::drop },
+            f,
+        });
+        Box::into_raw(closure).cast()
+    }
+    
+    unsafe fn call(this: *mut ClosureHeader, token: &CancellationToken) {
+        let closure = Box::from_raw(this.cast::<Self>());
+        (closure.f)(token)
+    }
+    
+    unsafe fn drop(this: *mut ClosureHeader) {
+        drop(Box::from_raw(this.cast::<Self>()));
+    }
+}
+
+impl ClosureHeader {
+    /// Run the closure at `this`, consuming it
+    unsafe fn call(this: *mut ClosureHeader, token: &CancellationToken) {
+        ((*this).call)(this, token)
+    }
+    
+    /// Drop the closure at `this` unrun
+    unsafe fn drop(this: *mut ClosureHeader) {
+        ((*this).drop)(this)
     }
 }
 
 /// Entry point for GVThread execution
+///
+/// Runs on the GVThread's own stack, like all GVThread code, and may
+/// allocate like it: malloc's state is per OS thread (arenas, tcache),
+/// not per stack. What allocation needs is that no context switch
+/// happens inside malloc while it holds an arena lock — true as long as
+/// GVThreads only switch at yield/block points, or forcibly outside
+/// libc (see `signal`).
 extern "C" fn gvthread_entry(closure_ptr: usize) {
-    // CRITICAL: No heap allocations in this function!
-    // We're running on GVThread's custom stack, malloc gets confused.
+    let closure = closure_ptr as *mut ClosureHeader;
     
-    // Reconstruct the boxed closure (this is from_raw, not allocating)
-    let boxed: Box<Box<dyn FnOnce(&CancellationToken) + Send>> = 
-        unsafe { Box::from_raw(closure_ptr as *mut _) };
+    // We may have been switched into by yield_to()
+    complete_handoff(crate::worker::current_worker_id());
     
     // Get cancellation token from metadata (no allocation!)
     // The token was created in spawn() and stored in metadata
-    let meta_base = crate::tls::current_gvthread_base();
-    let token = if !meta_base.is_null() {
-        let meta = unsafe { &*(meta_base as *const GVThreadMetadata) };
+    let token = if let Some(meta) = tls::current_metadata() {
+        // We own the closure now — shutdown must not reclaim it
+        meta.entry_arg.store(0, Ordering::Release);
         // Create a lightweight token that reads from metadata's cancelled field
         CancellationToken::from_metadata(meta)
     } else {
@@ -357,20 +920,30 @@ extern "C" fn gvthread_entry(closure_ptr: usize) {
         CancellationToken::dummy()
     };
     
-    // Run the closure
-    (*boxed)(&token);
+    // Run the closure. A panic must not unwind past this frame: the
+    // trampoline's unwind info ends the stack there, so the unwinder
+    // would find no handler and abort. Catching needs `panic = "unwind"`
+    // (the default); under `panic = "abort"` a panicking GVThread still
+    // takes the process down. The panic hook has already reported it.
+    let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+        ClosureHeader::call(closure, &token)
+    }));
     
     // GVThread finished - will be handled by trampoline cleanup
 }
 
 /// Main worker loop
-fn worker_main_loop(worker_id: usize, is_low_priority: bool, debug: bool) {
+fn worker_main_loop(worker_id: usize, is_low_priority: bool, pin: bool, debug: bool) {
     // Set up TLS
     set_current_worker_id(worker_id);
     
     // Set kprint context for this worker thread
     gvthread_core::kprint::set_worker_id(worker_id as u32);
     
+    if pin {
+        pin_worker(worker_id, debug);
+    }
+    
     // Store thread ID in worker state
     let worker = current_worker_state();
     worker.thread_id.store(
@@ -378,6 +951,13 @@ fn worker_main_loop(worker_id: usize, is_low_priority: bool, debug: bool) {
         Ordering::Relaxed,
     );
     
+    // The timer's SIGURG targets this thread; don't inherit a mask that
+    // blocks it
+    #[cfg(unix)]
+    if let Err(e) = crate::signal::unblock_sigurg() {
+        kwarn!("Failed to unblock SIGURG: {}", e);
+    }
+    
     if debug {
         kdebug!("Started (low_priority={})", is_low_priority);
     }
@@ -446,7 +1026,9 @@ fn worker_main_loop(worker_id: usize, is_low_priority: bool, debug: bool) {
                     }
                     std::thread::yield_now();
                 } else {
-                    // Park via ready_queue's condvar
+                    // Park via ready_queue's condvar. `park` re-checks the
+                    // queues after registering, so a push since our empty
+                    // get_next() cancels the park instead of being lost.
                     worker.is_parked.store(true, Ordering::Relaxed);
                     unsafe {
                         if let Some(ref sched) = SCHEDULER {
@@ -464,6 +1046,60 @@ fn worker_main_loop(worker_id: usize, is_low_priority: bool, debug: bool) {
     gvthread_core::kprint::clear_worker_id();
 }
 
+/// Supervisor hook: `worker_main_loop` panicked on this worker
+///
+/// The panic unwound on the worker's own stack, so a GVThread it was
+/// switched into is switched out again with its registers saved. Put it
+/// back where `run_gvthread` would have: re-queue it unless it blocked
+/// or finished.
+fn recover_worker(worker_id: usize) {
+    let worker = current_worker_state();
+    let id = worker.get_current_gthread();
+    worker.stop_running();
+    worker.is_parked.store(false, Ordering::Relaxed);
+    tls::clear_current_gvthread();
+    gvthread_core::kprint::clear_gvthread_id();
+    let block_pending = BLOCK_PENDING[worker_id].swap(false, Ordering::Relaxed);
+    
+    if id.is_none() {
+        return;
+    }
+    let meta = unsafe { &*memory::get_metadata_ptr(id.as_u32()) };
+    let state = meta.get_state();
+    match state {
+        GVThreadState::Running if block_pending => meta.set_state(GVThreadState::Blocked),
+        GVThreadState::Running | GVThreadState::Ready | GVThreadState::Preempted => {
+            kerror!("re-queuing GVThread {} stranded by worker {}", id, worker_id);
+            unsafe {
+                if let Some(ref sched) = SCHEDULER {
+                    sched.mark_ready(id, meta.get_priority());
+                }
+            }
+        }
+        // A waker or mark_finished owns it
+        _ => {}
+    }
+}
+
+/// Pin this worker to CPU `worker_id`, wrapping modulo the CPUs the
+/// process may use (so more workers than CPUs share them round-robin)
+fn pin_worker(worker_id: usize, debug: bool) {
+    let cpus = crate::CurrentPlatform::allowed_cpus();
+    if cpus.is_empty() {
+        kwarn!("CPU affinity unavailable, not pinning");
+        return;
+    }
+    let cpu = cpus[worker_id % cpus.len()];
+    match crate::CurrentPlatform::pin_current_thread(cpu) {
+        Ok(()) => {
+            if debug {
+                kdebug!("Pinned to CPU {}", cpu);
+            }
+        }
+        Err(e) => kwarn!("Failed to pin to CPU {}: {}", cpu, e),
+    }
+}
+
 /// Run a GVThread on this worker
 /// 
 /// This is the core of the scheduler. We:
@@ -474,38 +1110,27 @@ fn worker_main_loop(worker_id: usize, is_low_priority: bool, debug: bool) {
 /// When the GVThread yields or finishes, context_switch_voluntary
 /// will restore our scheduler context and we return here.
 fn run_gvthread(worker_id: usize, id: GVThreadId, priority: Priority, debug: bool) {
-    let worker = current_worker_state();
-    
     // Get GVThread metadata
     let meta_ptr = memory::get_metadata_ptr(id.as_u32());
-    let meta = unsafe { &*meta_ptr };
-    
-    // Update worker state (now_ns is safe here - we're on worker stack)
-    let now_ns = crate::timer::now_ns();
-    worker.start_running(id, now_ns);
-    
-    // Update TLS
-    tls::set_current_gvthread(id, meta_ptr as *mut u8);
-    
-    // Set kprint gvthread context
-    gvthread_core::kprint::set_gvthread_id(id.as_u32());
     
-    // Update GVThread state
-    meta.set_state(GVThreadState::Running);
-    meta.worker_id.store(worker_id as u32, Ordering::Relaxed);
-    meta.clear_preempt();
+    enter_gvthread(worker_id, id, meta_ptr);
     
     if debug {
-        kdebug!("Running GVThread {} ({:?})", id, priority);
+        let tagged = unsafe { &*meta_ptr }.generational_id();
+        kdebug_every!(SWITCH_LOG_EVERY, "Running GVThread {} ({:?})", tagged, priority);
     }
     
     // Get scheduler context save area for this worker
     let sched_ctx = get_worker_sched_context(worker_id);
     
-    // Get GVThread's saved registers (at offset 0x40 in metadata)
-    let gvthread_regs = unsafe {
-        (meta_ptr as *mut u8).add(0x40) as *mut VoluntarySavedRegs
-    };
+    // Get GVThread's saved registers
+    let gvthread_regs = GVThreadMetadata::saved_regs_ptr(meta_ptr);
+    
+    unsafe {
+        if let Some(ref sched) = SCHEDULER {
+            sched.context_switches.fetch_add(1, Ordering::Relaxed);
+        }
+    }
     
     // Perform the context switch!
     unsafe {
@@ -515,11 +1140,31 @@ fn run_gvthread(worker_id: usize, id: GVThreadId, priority: Priority, debug: boo
     // We're back from GVThread - clear gvthread context for kprint
     gvthread_core::kprint::clear_gvthread_id();
     
-    // Handle based on GVThread state
-    let state = meta.get_state();
+    // A yield_to() chain may have handed this worker to other GVThreads;
+    // the one that switched back to us is the one to account for.
+    let worker = current_worker_state();
+    let current = tls::current_gvthread_id();
+    let id = if current.is_none() { id } else { current };
+    let meta = unsafe { &*memory::get_metadata_ptr(id.as_u32()) };
+    record_run_time(meta);
+    
+    // block_current() left publishing Blocked to us: only now are its
+    // registers saved, so a waker may requeue it
+    let blocked = BLOCK_PENDING[worker_id].swap(false, Ordering::Relaxed);
+    if blocked {
+        meta.set_state(GVThreadState::Blocked);
+    }
+    
+    // Requeue at the effective priority (it may be boosted by a SchedMutex)
+    let priority = meta.get_priority();
+    
+    // Handle based on GVThread state. Once published Blocked it belongs
+    // to its waker: reading the state back could see the waker's Ready
+    // and queue it a second time.
+    let state = if blocked { GVThreadState::Blocked } else { meta.get_state() };
     
     if debug {
-        kdebug!("GVThread {} returned ({:?})", id, state);
+        kdebug_every!(SWITCH_LOG_EVERY, "GVThread {} returned ({:?})", meta.generational_id(), state);
     }
     
     match state {
@@ -563,6 +1208,36 @@ fn run_gvthread(worker_id: usize, id: GVThreadId, priority: Priority, debug: boo
     tls::clear_current_gvthread();
 }
 
+/// Make `id` the GVThread running on this worker: worker state, TLS,
+/// kprint context and the GVThread's own state
+fn enter_gvthread(worker_id: usize, id: GVThreadId, meta_ptr: *mut GVThreadMetadata) {
+    let worker = current_worker_state();
+    let meta = unsafe { &*meta_ptr };
+    
+    // Update worker state
+    let now_ns = crate::timer::now_ns();
+    worker.start_running(id, now_ns);
+    
+    // Update TLS
+    tls::set_current_gvthread(id, meta_ptr as *mut u8);
+    
+    // Set kprint gvthread context
+    gvthread_core::kprint::set_gvthread_id(id.as_u32());
+    
+    // Update GVThread state
+    meta.set_state(GVThreadState::Running);
+    meta.worker_id.store(worker_id as u32, Ordering::Relaxed);
+    meta.clear_preempt();
+    meta.record_scheduled();
+}
+
+/// Charge `meta` for the time since this worker switched into it
+#[inline]
+fn record_run_time(meta: &GVThreadMetadata) {
+    let started = current_worker_state().run_start_ns.load(Ordering::Relaxed);
+    meta.record_run_ns(crate::timer::now_ns().saturating_sub(started));
+}
+
 /// Yield the current GVThread
 /// 
 /// Saves the GVThread's context, marks it as Ready, and switches
@@ -581,29 +1256,31 @@ pub fn yield_now() {
     
     // Get current GVThread info from TLS
     let gvthread_id = tls::current_gvthread_id();
-    let meta_base = tls::current_gvthread_base();
-    let worker_id = crate::worker::current_worker_id();
+    let meta_ptr = tls::current_metadata_ptr();
     
     // Safety check
-    if meta_base.is_null() || gvthread_id.is_none() {
+    if meta_ptr.is_null() || gvthread_id.is_none() {
         std::thread::yield_now();
         return;
     }
     
-    let meta = unsafe { &*(meta_base as *const GVThreadMetadata) };
+    // A forced switch past this point would move us off `worker_id`
+    let meta = unsafe { &*meta_ptr };
+    meta.begin_switch();
+    let worker_id = crate::worker::current_worker_id();
     
     // Mark as Ready - but do NOT add to bitmap yet!
     // run_gvthread() will add us after context switch returns.
     meta.set_state(GVThreadState::Ready);
+    meta.record_voluntary_yield();
     
-    // Bump activity counter for preemption tracking
+    // Bump activity counter for preemption tracking. The coarse clock
+    // is enough here and spares the hot path a clock read.
     let worker = current_worker_state();
-    worker.record_activity(crate::timer::now_ns());
+    worker.record_activity(crate::timer::coarse_now_ns());
     
-    // Get our saved registers (at offset 0x40 in metadata)
-    let gvthread_regs = unsafe {
-        (meta_base).add(0x40) as *mut VoluntarySavedRegs
-    };
+    // Get our saved registers
+    let gvthread_regs = GVThreadMetadata::saved_regs_ptr(meta_ptr);
     
     // Get scheduler context for this worker
     let sched_ctx = get_worker_sched_context(worker_id);
@@ -614,10 +1291,106 @@ pub fn yield_now() {
     }
     
     // When we get here, we've been resumed by a worker.
+    complete_handoff(crate::worker::current_worker_id());
+    meta.end_switch();
     // Clear preempt flag in case it was set
     meta.clear_preempt();
 }
 
+/// Switch out a GVThread the SIGURG handler interrupted
+///
+/// Entered through `forced_switch_entry`, on the GVThread's stack, after
+/// the handler saved its registers and set `switching`. Yields like
+/// `yield_now` (as `Preempted`, which `run_gvthread` requeues), then
+/// restores the interrupted registers, clearing `switching` on the way.
+pub(crate) extern "C" fn forced_yield() -> ! {
+    let meta_ptr = tls::current_metadata_ptr();
+    let meta = unsafe { &*meta_ptr };
+    let worker_id = crate::worker::current_worker_id();
+    
+    meta.set_state(GVThreadState::Preempted);
+    current_worker_state().record_activity(crate::timer::coarse_now_ns());
+    
+    let gvthread_regs = GVThreadMetadata::saved_regs_ptr(meta_ptr);
+    let sched_ctx = get_worker_sched_context(worker_id);
+    unsafe {
+        current_arch::context_switch_voluntary(gvthread_regs, sched_ctx);
+    }
+    
+    // Resumed, possibly on another worker
+    complete_handoff(crate::worker::current_worker_id());
+    meta.clear_preempt();
+    unsafe {
+        current_arch::context_restore_forced(&meta.forced_regs, &meta.switching);
+    }
+    unreachable!("context_restore_forced returned");
+}
+
+/// Yield directly to `target`
+///
+/// If `target` is Ready in this worker's local queue, it is taken out
+/// and switched into straight from the caller, skipping the scheduler
+/// loop; the caller goes back on this worker's local queue. Otherwise
+/// (target running, blocked, queued elsewhere, or not called from a
+/// GVThread) this is a plain `yield_now()`.
+///
+/// Meant for tightly coupled pairs, e.g. a producer handing each item
+/// to its consumer.
+pub fn yield_to(target: GVThreadId) {
+    if !tls::is_in_gvthread() {
+        std::thread::yield_now();
+        return;
+    }
+    
+    let gvthread_id = tls::current_gvthread_id();
+    let meta_ptr = tls::current_metadata_ptr();
+    
+    if meta_ptr.is_null() || gvthread_id.is_none() || target.is_none() || target == gvthread_id {
+        yield_now();
+        return;
+    }
+    
+    let meta = unsafe { &*meta_ptr };
+    meta.begin_switch();
+    let worker_id = crate::worker::current_worker_id();
+    
+    // Claim the target; only Ready GVThreads with saved context are queued
+    let sched = match global_scheduler() {
+        Some(sched) if sched.ready_queue.take_local(worker_id, target) => sched,
+        _ => {
+            yield_now();
+            return;
+        }
+    };
+    
+    // Like yield_now(), we must not be queued before our context is
+    // saved: the target requeues us once it is running.
+    meta.set_state(GVThreadState::Ready);
+    meta.record_voluntary_yield();
+    HANDOFF_PENDING[worker_id].store(gvthread_id.as_u32(), Ordering::Relaxed);
+    
+    let worker = current_worker_state();
+    worker.record_activity(crate::timer::coarse_now_ns());
+    record_run_time(meta);
+    
+    let target_ptr = memory::get_metadata_ptr(target.as_u32());
+    enter_gvthread(worker_id, target, target_ptr);
+    sched.context_switches.fetch_add(1, Ordering::Relaxed);
+    
+    let gvthread_regs = GVThreadMetadata::saved_regs_ptr(meta_ptr);
+    let target_regs = GVThreadMetadata::saved_regs_ptr(target_ptr);
+    
+    // Switch straight into the target
+    unsafe {
+        current_arch::context_switch_voluntary(gvthread_regs, target_regs);
+    }
+    
+    // Resumed, possibly on another worker
+    complete_handoff(crate::worker::current_worker_id());
+    meta.end_switch();
+    meta.clear_preempt();
+}
+
 /// Block the current GVThread
 /// 
 /// Marks the GVThread as Blocked and yields to the scheduler.
@@ -631,22 +1404,25 @@ pub fn block_current() {
     }
     
     let gvthread_id = tls::current_gvthread_id();
-    let meta_base = tls::current_gvthread_base();
-    let worker_id = crate::worker::current_worker_id();
+    let meta_ptr = tls::current_metadata_ptr();
     
-    if meta_base.is_null() || gvthread_id.is_none() {
+    if meta_ptr.is_null() || gvthread_id.is_none() {
         return;
     }
     
-    let meta = unsafe { &*(meta_base as *const GVThreadMetadata) };
+    // Usually already set by the wait's registration
+    let meta = unsafe { &*meta_ptr };
+    meta.begin_switch();
+    let worker_id = crate::worker::current_worker_id();
     
-    // Mark as Blocked - scheduler will NOT re-add to ready queue
-    meta.set_state(GVThreadState::Blocked);
+    // Stay Running until our registers are saved: the scheduler loop
+    // marks us Blocked after the switch. Blocked any earlier, a waker
+    // could requeue us and another worker resume a stale context.
+    // (Wakers of a committed waiter wait for it to leave Running.)
+    BLOCK_PENDING[worker_id].store(true, Ordering::Relaxed);
     
     // Get our saved registers
-    let gvthread_regs = unsafe {
-        (meta_base).add(0x40) as *mut VoluntarySavedRegs
-    };
+    let gvthread_regs = GVThreadMetadata::saved_regs_ptr(meta_ptr);
     
     // Get scheduler context for this worker
     let sched_ctx = get_worker_sched_context(worker_id);
@@ -657,6 +1433,8 @@ pub fn block_current() {
     }
     
     // When we get here, we've been woken and resumed
+    complete_handoff(crate::worker::current_worker_id());
+    meta.end_switch();
     meta.clear_preempt();
 }
 
@@ -672,21 +1450,113 @@ pub fn wake_gvthread(id: GVThreadId, priority: Priority) {
     }
 }
 
+/// Scheduling counters of a live GVThread (`None` if `id` isn't one)
+///
+/// High `forced_preemptions` next to few `voluntary_yields` marks a
+/// GVThread that hogs its worker.
+pub fn gvthread_stats(id: GVThreadId) -> Option<GVThreadStats> {
+    if id.is_none() || !memory::memory_region().is_activated(id.as_u32()) {
+        return None;
+    }
+    let meta = unsafe { &*memory::get_metadata_ptr(id.as_u32()) };
+    let state = meta.get_state();
+    let live = state != GVThreadState::Created && !state.is_terminated();
+    (live && meta.get_id() == id).then(|| meta.stats())
+}
+
 /// Wake a blocked GVThread with generation check
 /// 
 /// Only wakes if the current generation matches. This prevents stale
 /// wakes after a slot has been reused for a new GVThread.
 pub fn wake_gvthread_checked(id: GVThreadId, priority: Priority, expected_generation: u32) {
-    let meta_ptr = memory::get_metadata_ptr(id.as_u32());
-    let meta = unsafe { &*meta_ptr };
-    
-    // Check generation to avoid waking wrong GVThread after slot reuse
-    if meta.get_generation() != expected_generation {
-        return; // Stale wake - slot was reused
+    unsafe {
+        if let Some(ref sched) = SCHEDULER {
+            // Check and wake back to back: a waker switched out in between
+            // could come back to the slot's next occupant
+            let _no_preempt = preempt::disable();
+            // The allocator bumps the generation on release, so this also
+            // catches a slot that is finished but not yet reused
+            if !sched.slot_allocator.is_current(id, expected_generation) {
+                return; // Stale wake - slot was released
+            }
+            sched.wake_gvthread(id, priority);
+        }
     }
-    
-    // Delegate to normal wake
-    wake_gvthread(id, priority);
+}
+
+/// Priority inheritance boosted `meta`'s GVThread: if it is queued,
+/// move it to its new priority (installed by `Scheduler::start`)
+fn requeue_boosted(meta: &GVThreadMetadata) {
+    if let Some(sched) = global_scheduler() {
+        sched.ready_queue.reprioritize(meta.get_id(), meta.get_priority());
+    }
+}
+
+/// The calling GVThread's metadata (`None` outside a GVThread)
+pub fn current_metadata() -> Option<&'static GVThreadMetadata> {
+    tls::current_metadata()
+}
+
+/// Whether the calling GVThread's cancellation flag is set
+///
+/// `false` outside a GVThread.
+pub fn current_cancelled() -> bool {
+    tls::current_metadata().is_some_and(|meta| meta.is_cancelled())
+}
+
+/// The calling GVThread's base priority (`Normal` outside a GVThread)
+///
+/// What GVThreads spawned on its behalf (scoped, `timeout`) inherit.
+pub(crate) fn current_base_priority() -> Priority {
+    tls::current_metadata().map(|m| m.get_base_priority()).unwrap_or(Priority::Normal)
+}
+
+/// Identify the current GVThread for a wait queue: `(id, generation)`
+///
+/// Returns `None` outside a GVThread. The caller is committed to
+/// `block_current()` (see `prepare_block`).
+pub fn current_waiter() -> Option<(GVThreadId, u32)> {
+    if !tls::is_in_gvthread() {
+        return None;
+    }
+    let id = tls::current_gvthread_id();
+    if id.is_none() {
+        return None;
+    }
+    let meta = unsafe { &*memory::get_metadata_ptr(id.as_u32()) };
+    meta.begin_switch();
+    Some((id, meta.get_generation()))
+}
+
+/// Commit the calling GVThread to `block_current()`: until it has
+/// switched out and back, forced preemption leaves it alone
+///
+/// Wakers wait for a committed waiter to leave `Running` and then
+/// expect it `Blocked`; forcibly switched out in between, it would miss
+/// the wake. `current_waiter()` and a wait kind commit implicitly; waits
+/// registered some other way call this before publishing themselves.
+pub fn prepare_block() {
+    if let Some(meta) = tls::current_metadata() {
+        meta.begin_switch();
+    }
+}
+
+/// Wake a waiter that registered itself (via `current_waiter()`) and is
+/// committed to calling `block_current()`
+///
+/// `wake_gvthread` ignores a GVThread that is not yet `Blocked`, so a
+/// waiter that registered but hasn't switched out would lose the wake.
+/// Wait (briefly — it's on its way into `block_current`) until it has
+/// left `Running`, then wake it with its own priority.
+pub fn wake_waiter(id: GVThreadId, generation: u32) {
+    let meta = unsafe { &*memory::get_metadata_ptr(id.as_u32()) };
+    while meta.get_generation() == generation
+        && meta.get_state() == GVThreadState::Running
+    {
+        std::hint::spin_loop();
+        std::thread::yield_now();
+    }
+    wake_gvthread_checked(id, meta.get_priority(), generation);
 }
 
 /// Spawn a new GVThread (uses global scheduler)
@@ -705,17 +1575,112 @@ where
     id
 }
 
+/// Spawn a new GVThread and get a handle to its return value (uses
+/// global scheduler)
+///
+/// `join()` returns `Ok` with what `f` returned, `Err(JoinError::Panic)`
+/// with the payload if `f` panicked, or `Err(JoinError::Cancelled)` if
+/// shutdown dropped it unrun.
+///
+/// The result slot is allocated here, with the handle, on the spawning
+/// side; the GVThread only moves `f`'s value into it, so returning a value
+/// costs the GVThread no allocation. If the handle is dropped unjoined the
+/// value is dropped with the slot once the GVThread has finished.
+pub fn spawn_with_handle<F, T>(f: F, priority: Priority) -> JoinHandle<T>
+where
+    F: FnOnce(&CancellationToken) -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let (handle, completer) = join_pair();
+    spawn(
+        move |token| {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(token)));
+            completer.complete(result.map_err(JoinError::Panic));
+        },
+        priority,
+    );
+    handle
+}
+
+/// Spawn a new GVThread bound to a cancellation token (uses global scheduler)
+pub fn spawn_with_token<F>(f: F, priority: Priority, token: &CancellationToken) -> GVThreadId
+where
+    F: FnOnce(&CancellationToken) + Send + 'static,
+{
+    global_scheduler()
+        .expect("Scheduler not initialized")
+        .spawn_with_token(f, priority, token)
+}
+
+/// Spawn a new GVThread, failing with `NoSlotsAvailable` instead of
+/// panicking when `max_gvthreads` are live (uses global scheduler)
+pub fn try_spawn<F>(f: F, priority: Priority) -> SchedResult<GVThreadId>
+where
+    F: FnOnce(&CancellationToken) + Send + 'static,
+{
+    global_scheduler()
+        .ok_or(SchedError::NotInitialized)?
+        .try_spawn(f, priority)
+}
+
+/// `try_spawn` bound to a cancellation token (uses global scheduler)
+pub fn try_spawn_with_token<F>(f: F, priority: Priority, token: &CancellationToken) -> SchedResult<GVThreadId>
+where
+    F: FnOnce(&CancellationToken) + Send + 'static,
+{
+    global_scheduler()
+        .ok_or(SchedError::NotInitialized)?
+        .try_spawn_with_token(f, priority, token)
+}
+
+/// Slots in use and the maximum in the global scheduler (`(0, 0)` if not
+/// initialized)
+pub fn slot_usage() -> (usize, usize) {
+    global_scheduler().map(|s| s.slot_usage()).unwrap_or((0, 0))
+}
+
+/// Snapshot of the global scheduler's counters (all zero if not initialized)
+pub fn stats() -> RuntimeStats {
+    global_scheduler().map(|s| s.stats()).unwrap_or_default()
+}
+
+/// Number of live GVThreads in the global scheduler (0 if not initialized)
+pub fn active_gvthreads() -> usize {
+    global_scheduler().map(|s| s.active_count()).unwrap_or(0)
+}
+
+/// Block the calling OS thread until the global scheduler has no live
+/// GVThreads (see `Scheduler::wait_idle`)
+///
+/// Must not be called from inside a GVThread — it would count itself.
+pub fn wait_until_idle() {
+    if let Some(sched) = global_scheduler() {
+        sched.wait_idle();
+    }
+}
+
 /// Initialize the global scheduler
+///
+/// With `GVT_PRINT_CONFIG=1` the configuration, and where each value
+/// came from, is printed to stderr first.
 pub fn init_global_scheduler(config: SchedulerConfig) -> SchedResult<()> {
+    // Validate first so a bad config doesn't use up the one-time init
+    config.validate()?;
     if SCHEDULER_INIT.swap(true, Ordering::SeqCst) {
         return Err(SchedError::AlreadyInitialized);
     }
+    if gvthread_core::env::env_get_bool("GVT_PRINT_CONFIG", false) {
+        config.print();
+    }
     
     // Initialize the sleep queue with capacity for all possible GVThreads
     crate::timer::init_sleep_queue_with_capacity(config.max_gvthreads);
     
+    // Map the memory region now so GVThreads can be spawned before start
+    let sched = Scheduler::try_new(config)?;
+    sched.init_memory()?;
     unsafe {
-        SCHEDULER = Some(Scheduler::new(config));
+        SCHEDULER = Some(sched);
     }
     
     Ok(())
@@ -737,6 +1702,19 @@ pub fn start_global_scheduler() -> SchedResult<()> {
     }
 }
 
+/// Gracefully shut down the global scheduler (see `Scheduler::shutdown_graceful`)
+///
+/// Returns the number of GVThreads still active when workers stopped.
+pub fn shutdown_global_scheduler_graceful(timeout: std::time::Duration) -> usize {
+    unsafe {
+        if let Some(ref mut sched) = SCHEDULER {
+            sched.shutdown_graceful(timeout)
+        } else {
+            0
+        }
+    }
+}
+
 /// Shutdown the global scheduler
 pub fn shutdown_global_scheduler() {
     unsafe {
@@ -744,4 +1722,33 @@ pub fn shutdown_global_scheduler() {
             sched.shutdown();
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn spawned_closure_runs_or_drops_exactly_once() {
+        let witness = Arc::new(AtomicUsize::new(0));
+        let token = CancellationToken::dummy();
+
+        let ran = Arc::clone(&witness);
+        let closure = SpawnedClosure::into_raw_header(move |_: &CancellationToken| {
+            ran.fetch_add(1, Ordering::SeqCst);
+        });
+        unsafe { ClosureHeader::call(closure, &token) };
+        assert_eq!(witness.load(Ordering::SeqCst), 1);
+        assert_eq!(Arc::strong_count(&witness), 1);
+
+        // Never started: freed along with its captures, unrun
+        let unrun = Arc::clone(&witness);
+        let closure = SpawnedClosure::into_raw_header(move |_: &CancellationToken| {
+            unrun.fetch_add(1, Ordering::SeqCst);
+        });
+        unsafe { ClosureHeader::drop(closure) };
+        assert_eq!(witness.load(Ordering::SeqCst), 1);
+        assert_eq!(Arc::strong_count(&witness), 1);
+    }
+}
@@ -0,0 +1,178 @@
+//! Scoped GVThreads that may borrow from the spawning stack
+//!
+//! `scope(|s| { s.spawn(|_| ...); })` works like `std::thread::scope`:
+//! every GVThread spawned through `s` is joined before `scope` returns,
+//! so its closure only needs to outlive the scope, not be `'static`.
+//!
+//! Each scoped spawn gets two join pairs: one for the caller's
+//! `ScopedJoinHandle` (the closure's result or panic payload), and one
+//! the scope keeps to wait for the GVThread at the end. The scope joins
+//! all of them even if the scope closure or a GVThread panicked.
+
+use crate::join::{join_pair, Completer, JoinError, JoinHandle};
+use crate::scheduler;
+
+use gvthread_core::cancel::CancellationToken;
+
+use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// A spawned GVThread the scope still has to wait for
+struct Spawned {
+    /// Completes with `true` if the closure panicked
+    done: JoinHandle<bool>,
+    /// Set when the panic was handed to a `ScopedJoinHandle::join`
+    observed: Arc<AtomicBool>,
+}
+
+/// What a scoped GVThread owns, in drop order
+///
+/// Reclaimed without running, the GVThread drops this unrun: `done`
+/// comes last, so the scope only sees `Cancelled` once `f` and its
+/// borrows are gone.
+struct ScopedBody<F, T> {
+    f: F,
+    completer: Completer<T>,
+    done: Completer<bool>,
+}
+
+/// Spawner passed to the `scope` closure
+///
+/// The lifetimes mirror `std::thread::Scope`: `'scope` is the scope
+/// itself, `'env` anything borrowed from outside it.
+pub struct Scope<'scope, 'env: 'scope> {
+    spawned: Mutex<Vec<Spawned>>,
+    scope: PhantomData<&'scope mut &'scope ()>,
+    env: PhantomData<&'env mut &'env ()>,
+}
+
+/// Handle to a scoped GVThread's result
+pub struct ScopedJoinHandle<'scope, T> {
+    handle: JoinHandle<T>,
+    observed: Arc<AtomicBool>,
+    scope: PhantomData<&'scope ()>,
+}
+
+/// Run `f` with a scope for spawning GVThreads that borrow local data
+///
+/// All GVThreads spawned through the scope are joined before this
+/// returns. If `f` panics, the remaining GVThreads are still joined and
+/// the panic is then resumed. If a scoped GVThread panicked and its
+/// handle was not joined, this panics after joining the rest.
+///
+/// Blocks only the calling GVThread (or the OS thread, when called
+/// outside one). Requires the global scheduler to be running.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut counts = vec![0; 4];
+/// gvthread::scope(|s| {
+///     for (i, c) in counts.iter_mut().enumerate() {
+///         s.spawn(move |_| *c = i * 10);
+///     }
+/// });
+/// assert_eq!(counts, [0, 10, 20, 30]);
+/// ```
+pub fn scope<'env, F, T>(f: F) -> T
+where
+    F: for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> T,
+{
+    let scope = Scope {
+        spawned: Mutex::new(Vec::new()),
+        scope: PhantomData,
+        env: PhantomData,
+    };
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| f(&scope)));
+    let unhandled_panic = scope.join_all();
+
+    match result {
+        Err(payload) => panic::resume_unwind(payload),
+        Ok(_) if unhandled_panic => panic!("a scoped GVThread panicked"),
+        Ok(value) => value,
+    }
+}
+
+impl<'scope, 'env> Scope<'scope, 'env> {
+    /// Spawn a GVThread that may borrow anything outliving the scope
+    ///
+    /// Runs at the calling GVThread's priority (normal outside one).
+    /// Panics if no GVThread slot is free or the scheduler is shutting
+    /// down; already-spawned GVThreads are still joined by `scope`.
+    pub fn spawn<F, T>(&'scope self, f: F) -> ScopedJoinHandle<'scope, T>
+    where
+        F: FnOnce(&CancellationToken) -> T + Send + 'scope,
+        T: Send + 'scope,
+    {
+        let (handle, completer) = join_pair();
+        let (done, done_completer) = join_pair();
+        let observed = Arc::new(AtomicBool::new(false));
+
+        let body = ScopedBody { f, completer, done: done_completer };
+        let body: Box<dyn FnOnce(&CancellationToken) + Send + 'scope> = Box::new(move |token| {
+            let ScopedBody { f, completer, done } = body;
+            let result = panic::catch_unwind(AssertUnwindSafe(|| f(token)));
+            let panicked = result.is_err();
+            completer.complete(result.map_err(JoinError::Panic));
+            // Last: `f` and the result are gone, nothing borrowed is left
+            done.complete(Ok(panicked));
+        });
+        // Safety: `scope` joins this GVThread (via `done`) before 'scope
+        // ends, and `done` completes only after everything the closure
+        // borrows is dropped, run or not.
+        let body: Box<dyn FnOnce(&CancellationToken) + Send + 'static> =
+            unsafe { std::mem::transmute(body) };
+
+        if let Err(e) = scheduler::try_spawn(move |token| body(token), scheduler::current_base_priority()) {
+            panic!("scoped spawn failed: {}", e);
+        }
+
+        self.spawned.lock().unwrap().push(Spawned {
+            done,
+            observed: Arc::clone(&observed),
+        });
+        ScopedJoinHandle { handle, observed, scope: PhantomData }
+    }
+
+    /// Join every spawned GVThread; true if one had a panic nobody joined
+    fn join_all(&self) -> bool {
+        let mut unhandled_panic = false;
+        // GVThreads may spawn more into the scope while we wait
+        loop {
+            let batch = std::mem::take(&mut *self.spawned.lock().unwrap());
+            if batch.is_empty() {
+                return unhandled_panic;
+            }
+            for spawned in batch {
+                let panicked = spawned.done.join().unwrap_or(false);
+                if panicked && !spawned.observed.load(Ordering::Acquire) {
+                    unhandled_panic = true;
+                }
+            }
+        }
+    }
+}
+
+impl<T> ScopedJoinHandle<'_, T> {
+    /// True once the GVThread's closure has returned (or panicked)
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Wait for the GVThread and return its result
+    ///
+    /// Returns `Err` with the panic payload if the closure panicked; the
+    /// panic then no longer makes `scope` panic.
+    pub fn join(self) -> thread::Result<T> {
+        self.observed.store(true, Ordering::Release);
+        self.handle.join().map_err(|e| match e {
+            JoinError::Panic(payload) => payload,
+            // Shutdown reclaimed the GVThread before it ran
+            JoinError::Cancelled => Box::new("scoped GVThread cancelled before it ran"),
+        })
+    }
+}
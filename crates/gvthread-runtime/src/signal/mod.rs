@@ -1,6 +1,41 @@
 //! Signal handling for preemption
 //!
 //! Uses SIGURG on Unix systems to force preemption of CPU-bound GVThreads.
+//!
+//! `init_preemption` installs the handler once per process; each worker
+//! unblocks SIGURG as it starts, and the timer sends it (`send_sigurg`)
+//! to a worker whose GVThread ignored its preempt flag for the grace
+//! period. The handler always sets that flag. With
+//! `SchedulerConfig::forced_switch` (off by default) it also switches
+//! the GVThread out, if the interrupted code is safe to leave
+//! mid-instruction stream:
+//!
+//! - not inside a `gvthread_core::preempt::disable()` section on this
+//!   worker: every `SpinLock` critical section (the sleep queue, the
+//!   timer thread handle, the local ready queues), `spawn_inner`, and
+//!   the ready-queue pushes in `mark_ready`, `wake_gvthread` and the
+//!   yield handoff
+//! - not on its way to a switch (`GVThreadMetadata::switching`): from
+//!   registering as a waiter (`current_waiter`, a wait kind,
+//!   `prepare_block`) or entering `yield_now`, `yield_to`,
+//!   `block_current` until it is resumed, so it never leaves a worker
+//!   whose id or ring it already holds, nor misses a wake
+//! - on its own stack, in the main executable's code: never inside libc
+//!   (malloc, stdio, pthread locks) or another shared library
+//!
+//! Otherwise the flag alone applies, at the next safepoint. To switch,
+//! the handler saves every register into the GVThread's metadata and
+//! returns into `forced_switch_entry`, which yields as `Preempted`; once
+//! resumed, `context_restore_forced` reloads the saved registers.
+//!
+//! Not covered: locks and thread-affine state held by user code, and
+//! allocators linked into the executable. std's stdout lock records its
+//! owner by OS thread, so a GVThread switched out inside `println!`
+//! leaves the next `println!` on that worker re-entering it: "already
+//! borrowed". A statically linked jemalloc or mimalloc (or musl's
+//! malloc) keeps per-thread caches the next GVThread would corrupt.
+//! That is why the switch is opt-in; code that must not be switched
+//! away from can call `preempt::disable()` itself.
 
 cfg_if::cfg_if! {
     if #[cfg(unix)] {
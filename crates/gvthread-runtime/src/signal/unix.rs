@@ -1,29 +1,185 @@
 //! Unix signal handling for SIGURG preemption
 
 
-use gvthread_core::error::SchedResult;
-use std::sync::atomic::{AtomicBool, Ordering};
+use crate::tls;
+
+use gvthread_core::error::{SchedError, SchedResult, WorkerError};
+use gvthread_core::metadata::GVThreadMetadata;
+use gvthread_core::preempt;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 
 static HANDLER_INSTALLED: AtomicBool = AtomicBool::new(false);
 
+/// SIGURGs that arrived inside a `preempt::disable()` section
+static DEFERRED: AtomicU64 = AtomicU64::new(0);
+
+/// SIGURGs that switched a GVThread out
+static FORCED: AtomicU64 = AtomicU64::new(0);
+
+/// Whether the handler may switch a GVThread out (`forced_switch`)
+static SWITCH_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The main executable's code, `[start, end)`; 0/0 until resolved
+static PROGRAM_TEXT: [AtomicUsize; 2] = [AtomicUsize::new(0), AtomicUsize::new(0)];
+
+/// Set up forced preemption for the process
+///
+/// Installs the SIGURG handler (once, however many schedulers start) and,
+/// with `forced_switch`, lets it switch GVThreads out of the code found
+/// safe to leave. Workers unblock SIGURG themselves (`unblock_sigurg`).
+pub fn init_preemption(forced_switch: bool) -> SchedResult<()> {
+    #[cfg(target_os = "linux")]
+    if PROGRAM_TEXT[1].load(Ordering::Acquire) == 0 {
+        let (start, end) = program_text();
+        PROGRAM_TEXT[0].store(start, Ordering::Relaxed);
+        PROGRAM_TEXT[1].store(end, Ordering::Release);
+    }
+    SWITCH_ENABLED.store(forced_switch, Ordering::Relaxed);
+    install_sigurg_handler()
+}
+
 /// Install the SIGURG handler for forced preemption
-pub fn install_sigurg_handler() -> SchedResult<()> {
+fn install_sigurg_handler() -> SchedResult<()> {
     if HANDLER_INSTALLED.swap(true, Ordering::SeqCst) {
         return Ok(()); // Already installed
     }
     
-    // TODO: Implement actual signal handler
-    // For now, just a stub
-    
+    let installed = unsafe {
+        let mut sa: libc::sigaction = std::mem::zeroed();
+        sa.sa_sigaction = on_sigurg as *const () as usize;
+        // SA_SIGINFO for the ucontext to save. No SA_RESTART: a GVThread
+        // stuck in a blocking syscall gets EINTR back instead of keeping
+        // the worker.
+        sa.sa_flags = libc::SA_SIGINFO;
+        libc::sigemptyset(&mut sa.sa_mask);
+        libc::sigaction(libc::SIGURG, &sa, std::ptr::null_mut()) == 0
+    };
+    if !installed {
+        HANDLER_INSTALLED.store(false, Ordering::SeqCst);
+        return Err(SchedError::WorkerError(WorkerError::SignalSetupFailed));
+    }
     Ok(())
 }
 
+/// SIGURGs so far that found the worker in a non-preemptible section
+/// and were left to the safepoint instead
+pub fn deferred_preemptions() -> u64 {
+    DEFERRED.load(Ordering::Relaxed)
+}
+
+/// SIGURGs so far that switched a GVThread out
+pub fn forced_switches() -> u64 {
+    FORCED.load(Ordering::Relaxed)
+}
+
+/// The SIGURG handler
+///
+/// Only touches atomics and const-initialized TLS (and, to switch, the
+/// interrupted GVThread's metadata), so it is async-signal-safe wherever
+/// it lands.
+extern "C" fn on_sigurg(_sig: libc::c_int, _info: *mut libc::siginfo_t, uc: *mut libc::c_void) {
+    // Whatever happens below, the GVThread yields at its next safepoint
+    let meta = tls::current_metadata_ptr();
+    if !meta.is_null() {
+        unsafe { (*meta).preempt_flag.store(1, Ordering::Release) };
+    }
+    
+    // Holding a runtime lock (or inside another marked section):
+    // switching away now could deadlock the next GVThread on this worker
+    if !preempt::is_preemptible_in(unsafe { meta.as_ref() }) {
+        DEFERRED.fetch_add(1, Ordering::Relaxed);
+    } else if SWITCH_ENABLED.load(Ordering::Relaxed)
+        && !meta.is_null()
+        && unsafe { force_switch(&*meta, uc.cast()) }
+    {
+        FORCED.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Switch the interrupted GVThread out, if it is somewhere that's safe
+///
+/// It must be running its own code on its own stack: not between
+/// GVThreads (the worker's stack), not on its way to a switch
+/// (`switching`), and not inside a shared library such as libc, whose
+/// malloc and locks are per-thread.
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+unsafe fn force_switch(meta: &GVThreadMetadata, uc: *mut libc::ucontext_t) -> bool {
+    use gvthread_core::state::GVThreadState;
+    
+    if meta.is_switching() || meta.get_state() != GVThreadState::Running {
+        return false;
+    }
+    let (sp, ip) = crate::current_arch::interrupted_sp_ip(uc);
+    let region = crate::memory::memory_region();
+    let id = meta.gvthread_id.load(Ordering::Relaxed);
+    let on_own_stack = (region.stack_bottom(id) as usize..region.stack_top(id) as usize).contains(&sp);
+    let in_program = (PROGRAM_TEXT[0].load(Ordering::Relaxed)..PROGRAM_TEXT[1].load(Ordering::Acquire))
+        .contains(&ip);
+    if !on_own_stack || !in_program {
+        return false;
+    }
+    
+    // Until `context_restore_forced` has read them back
+    meta.begin_switch();
+    let regs = &meta.forced_regs as *const _ as *mut _;
+    let fpu = &meta.fpu_state as *const _ as *mut _;
+    crate::current_arch::save_forced_context(regs, fpu, uc);
+    crate::current_arch::redirect_to_forced_switch(uc);
+    true
+}
+
+#[cfg(not(all(target_os = "linux", target_arch = "x86_64")))]
+unsafe fn force_switch(_meta: &GVThreadMetadata, _uc: *mut libc::ucontext_t) -> bool {
+    false
+}
+
+/// Address range of the main executable's code segments
+#[cfg(target_os = "linux")]
+fn program_text() -> (usize, usize) {
+    unsafe extern "C" fn first_object(
+        info: *mut libc::dl_phdr_info,
+        _size: libc::size_t,
+        data: *mut libc::c_void,
+    ) -> libc::c_int {
+        let info = &*info;
+        let range = &mut *(data as *mut (usize, usize));
+        let phdrs = std::slice::from_raw_parts(info.dlpi_phdr, info.dlpi_phnum as usize);
+        for ph in phdrs {
+            if ph.p_type == libc::PT_LOAD && ph.p_flags & libc::PF_X != 0 {
+                let start = info.dlpi_addr as usize + ph.p_vaddr as usize;
+                let end = start + ph.p_memsz as usize;
+                *range = if range.1 == 0 { (start, end) } else { (range.0.min(start), range.1.max(end)) };
+            }
+        }
+        1 // The executable comes first; stop there
+    }
+    
+    let mut range = (0usize, 0usize);
+    unsafe {
+        libc::dl_iterate_phdr(Some(first_object), &mut range as *mut _ as *mut libc::c_void);
+    }
+    range
+}
+
 /// Send SIGURG to a worker thread
 pub fn send_sigurg(thread_id: u64) -> SchedResult<()> {
-    // TODO: Implement using pthread_kill
-    // unsafe {
-    //     libc::pthread_kill(thread_id as libc::pthread_t, libc::SIGURG);
-    // }
+    match unsafe { libc::pthread_kill(thread_id as libc::pthread_t, libc::SIGURG) } {
+        0 => Ok(()),
+        _ => Err(SchedError::WorkerError(WorkerError::SignalSetupFailed)),
+    }
+}
+
+/// Unblock SIGURG on the calling thread (each worker, as it starts)
+pub fn unblock_sigurg() -> SchedResult<()> {
+    let unblocked = unsafe {
+        let mut set: libc::sigset_t = std::mem::zeroed();
+        libc::sigemptyset(&mut set);
+        libc::sigaddset(&mut set, libc::SIGURG);
+        libc::pthread_sigmask(libc::SIG_UNBLOCK, &set, std::ptr::null_mut()) == 0
+    };
+    if !unblocked {
+        return Err(SchedError::WorkerError(WorkerError::SignalSetupFailed));
+    }
     Ok(())
 }
 
@@ -32,3 +188,31 @@ pub fn block_signals_except_sigurg() -> SchedResult<()> {
     // TODO: Implement signal masking
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gvthread_core::SpinLock;
+    
+    #[test]
+    fn sigurg_in_critical_section_is_deferred() {
+        init_preemption(false).unwrap();
+        let lock = SpinLock::new(());
+        
+        let before = deferred_preemptions();
+        {
+            let _held = lock.lock();
+            unsafe { libc::raise(libc::SIGURG) };
+        }
+        assert_eq!(deferred_preemptions(), before + 1);
+        {
+            let _marked = preempt::disable();
+            unsafe { libc::raise(libc::SIGURG) };
+        }
+        assert_eq!(deferred_preemptions(), before + 2);
+        
+        // Preemptible: not deferred (and, outside a GVThread, ignored)
+        unsafe { libc::raise(libc::SIGURG) };
+        assert_eq!(deferred_preemptions(), before + 2);
+    }
+}
@@ -0,0 +1,56 @@
+//! Runtime statistics snapshot
+//!
+//! A point-in-time view of the scheduler, assembled from counters the
+//! scheduler, slot allocator and ready queue already maintain. Each field
+//! is read with a relaxed load, so the snapshot is not a consistent cut —
+//! good enough for diagnosing stalls, not for invariants.
+
+use std::fmt;
+
+/// Snapshot of scheduler state (see `Scheduler::stats()`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuntimeStats {
+    /// Slots currently allocated
+    pub slots_allocated: u32,
+    /// Maximum number of slots
+    pub max_slots: u32,
+    /// Live GVThreads (Ready, Running or Blocked)
+    pub active_gvthreads: usize,
+    /// GVThreads spawned since start
+    pub spawned_total: u64,
+    /// GVThreads finished since start
+    pub finished_total: u64,
+    /// Entries in the global ready queue
+    pub global_queue_len: usize,
+    /// Entries in each worker's local ready queue
+    pub local_queue_lens: Vec<usize>,
+    /// Workers currently parked (waiting for work)
+    pub parked_workers: usize,
+    /// Total scheduler → GVThread context switches
+    pub context_switches: u64,
+    /// Worker threads still running (below the configured count if some died)
+    pub live_workers: usize,
+    /// Worker restarts after a panic (with `supervise_workers`)
+    pub worker_restarts: usize,
+}
+
+impl RuntimeStats {
+    /// Total ready GVThreads (global + all local queues)
+    pub fn ready_total(&self) -> usize {
+        self.global_queue_len + self.local_queue_lens.iter().sum::<usize>()
+    }
+}
+
+impl fmt::Display for RuntimeStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "slots={}/{} active={} spawned={} finished={} ready={} (global={} local={:?}) parked={} switches={} workers={} restarts={}",
+            self.slots_allocated, self.max_slots,
+            self.active_gvthreads, self.spawned_total, self.finished_total,
+            self.ready_total(), self.global_queue_len, self.local_queue_lens,
+            self.parked_workers, self.context_switches,
+            self.live_workers, self.worker_restarts,
+        )
+    }
+}
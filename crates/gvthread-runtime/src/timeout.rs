@@ -0,0 +1,117 @@
+//! Deadline combinator for GVThread operations
+//!
+//! `timeout(duration, f)` runs `f` in its own GVThread, bound to a fresh
+//! cancellation token, and races it against a watchdog GVThread sleeping
+//! in `sleep_cancellable`. The caller waits on a join slot that whichever
+//! finishes first completes:
+//!
+//! - `f` returns in time → the caller gets its result and the watchdog
+//!   is cancelled (woken at once)
+//! - the watchdog wakes  → `f`'s token is cancelled, which interrupts
+//!   its sleep, channel wait or reactor I/O (`-ECANCELED`), and the
+//!   caller gets `Err(Timeout)` at once
+//!
+//! Either way the caller does not wait for `f`'s GVThread: an `f` that
+//! ignores its token is detached and runs to completion on its own, its
+//! result discarded.
+//!
+//! When called from a GVThread, `f`'s token is a `child_token()` of the
+//! caller's, so cancelling the caller cancels the operation too.
+
+use crate::join::{join_pair, JoinError};
+use crate::scheduler::{self, current_metadata};
+use crate::timer::sleep_cancellable;
+
+use gvthread_core::cancel::CancellationToken;
+use gvthread_core::state::Priority;
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// The operation did not finish before its deadline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeout;
+
+impl std::fmt::Display for Timeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "operation timed out")
+    }
+}
+
+impl std::error::Error for Timeout {}
+
+/// Run `f` with a deadline
+///
+/// `f` receives the token to check (or pass to `sleep_cancellable`); it
+/// is cancelled when the deadline passes. Returns `Err(Timeout)` as soon
+/// as the deadline fires, whether or not `f` has stopped — `f`'s result,
+/// if it still produces one, is discarded. A panic in `f` before the
+/// deadline is resumed in the caller. Also returns `Err(Timeout)` without
+/// running `f` if the scheduler no longer accepts spawns (graceful
+/// shutdown).
+///
+/// Requires the global scheduler to be running. Blocks only the calling
+/// GVThread (or the OS thread, when called outside one).
+pub fn timeout<F, T>(duration: Duration, f: F) -> Result<T, Timeout>
+where
+    F: FnOnce(&CancellationToken) -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let op_token = parent_token().child_token();
+    let watchdog_token = CancellationToken::new();
+
+    // Taken by whichever of `f` and the watchdog finishes first
+    let (handle, completer) = join_pair();
+    let completer = Arc::new(Mutex::new(Some(completer)));
+
+    let op_completer = Arc::clone(&completer);
+    let op_id = scheduler::spawn_with_token(
+        move |token| {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| f(token)));
+            if let Some(completer) = op_completer.lock().unwrap().take() {
+                completer.complete(result.map_err(JoinError::Panic));
+            }
+        },
+        scheduler::current_base_priority(),
+        &op_token,
+    );
+    if op_id.is_none() {
+        // Scheduler shutting down: `f` was dropped and will never run
+        return Err(Timeout);
+    }
+
+    {
+        let op_token = op_token.clone();
+        scheduler::spawn_with_token(
+            move |token| {
+                if sleep_cancellable(duration, token).is_ok() {
+                    op_token.cancel();
+                    if let Some(completer) = completer.lock().unwrap().take() {
+                        completer.complete(Err(JoinError::Cancelled));
+                    }
+                }
+            },
+            Priority::High,
+            &watchdog_token,
+        );
+    }
+
+    let result = handle.join();
+    watchdog_token.cancel();
+
+    match result {
+        Ok(value) => Ok(value),
+        Err(JoinError::Panic(payload)) => panic::resume_unwind(payload),
+        // The watchdog fired, or both were dropped unrun (shutdown)
+        Err(JoinError::Cancelled) => Err(Timeout),
+    }
+}
+
+/// The calling GVThread's own token (a dummy outside a GVThread)
+fn parent_token() -> CancellationToken {
+    match current_metadata() {
+        Some(meta) => CancellationToken::from_metadata(meta),
+        None => CancellationToken::dummy(),
+    }
+}
@@ -3,6 +3,8 @@
 //! Provides:
 //! - Sleep queue (BinaryHeap) for GVThread sleep/wake
 //! - Preemption monitoring for stuck GVThreads
+//! - Lost-wakeup reports: every worker parked with GVThreads ready
+//!   (debug builds with `debug_logging`; see `LostWakeupWatch`)
 //! - Pluggable timer backends for future optimization
 //!
 //! # Architecture
@@ -15,6 +17,19 @@
 //!           │
 //!           └──► check_preemption() ──► set preempt flag / send signal
 //! ```
+//!
+//! # Sleep precision
+//!
+//! The timer thread sleeps until the earliest queued deadline, but at
+//! least `GVT_TIMER_MIN_US` (100us) and at most `GVT_TIMER_MAX_MS`
+//! (10ms); a sleep queued ahead of the timer's current deadline wakes it
+//! early. A GVThread sleep therefore ends about `max(duration,
+//! GVT_TIMER_MIN_US)` after it started, plus the kernel's timer slack
+//! (50us by default) and the time for the timer thread and then a worker
+//! to get a CPU. On an idle machine `sleep_us(500)` wakes within well
+//! under a millisecond; on a loaded one OS scheduling jitter dominates
+//! and can reach milliseconds. Precision is never better than
+//! `GVT_TIMER_MIN_US`: lowering it buys precision with timer wakeups.
 
 mod entry;
 pub mod impls;
@@ -30,13 +45,18 @@ use std::cmp::Ordering as CmpOrdering;
 use std::collections::BinaryHeap;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::thread::{self, JoinHandle};
+use std::thread::{self, JoinHandle, Thread};
 use std::time::{Duration, Instant};
 
+use gvthread_core::cancel::CancellationToken;
+use gvthread_core::error::{SchedError, SchedResult};
 use gvthread_core::id::GVThreadId;
+use gvthread_core::metadata::GVThreadMetadata;
+use gvthread_core::state::{GVThreadState, Priority};
 use gvthread_core::SpinLock;
+use gvthread_core::{kerror, kwarn};
 
-use crate::config::SchedulerConfig;
+use crate::config::{defaults, SchedulerConfig};
 use crate::memory;
 use crate::scheduler;
 use crate::tls;
@@ -81,12 +101,19 @@ fn update_coarse_time() {
 }
 
 /// Get coarse time (updated by timer thread, very cheap)
+///
+/// Nanoseconds since the runtime started, as of the timer thread's last
+/// tick (`GVT_TIMER_MAX_MS` at worst). A single atomic load: use it on
+/// hot paths that only need a rough timestamp, such as worker activity
+/// tracking; sleep deadlines and run-time accounting use `now_ns`.
 #[inline]
 pub fn coarse_now_ns() -> u64 {
     COARSE_TIME_NS.load(Ordering::Acquire)
 }
 
 /// Get precise monotonic time in nanoseconds
+///
+/// A `clock_gettime(CLOCK_MONOTONIC)` (vDSO) per call.
 #[inline]
 pub fn now_ns() -> u64 {
     START_INSTANT.get()
@@ -153,11 +180,32 @@ pub fn init_sleep_queue() {
     init_sleep_queue_with_capacity(65536);
 }
 
+/// The running timer thread, to unpark when a sleep is due before it
+/// would wake
+static TIMER_THREAD: SpinLock<Option<Thread>> = SpinLock::new(None);
+
+/// When the timer thread will next wake on its own (ns, `now_ns` clock);
+/// 0 while none runs
+static TIMER_DEADLINE_NS: AtomicU64 = AtomicU64::new(0);
+
 /// Add to sleep queue - called from GVThread (slot already activated)
 fn add_to_sleep_queue(entry: SleepEntry) {
-    let mut queue = SLEEP_QUEUE.lock();
-    if let Some(ref mut q) = *queue {
-        q.push(entry);
+    {
+        let mut queue = SLEEP_QUEUE.lock();
+        if let Some(ref mut q) = *queue {
+            q.push(entry);
+        }
+    }
+    // Due before the timer's next tick: wake it to re-plan
+    if entry.wake_time_ns < TIMER_DEADLINE_NS.load(Ordering::SeqCst) {
+        wake_timer_thread();
+    }
+}
+
+/// Unpark the timer thread (no-op if none is running)
+fn wake_timer_thread() {
+    if let Some(ref timer) = *TIMER_THREAD.lock() {
+        timer.unpark();
     }
 }
 
@@ -190,10 +238,26 @@ fn process_sleep_queue() {
                 let meta_ptr = memory::get_metadata_ptr(entry.gvthread_id);
                 let meta = unsafe { &*meta_ptr };
                 
-                if meta.get_generation() == entry.generation {
+                // A cancelled sleep leaves its entry behind; the sleeper
+                // cleared wake_time_ns, so the entry no longer matches.
+                let current = || {
+                    meta.get_generation() == entry.generation
+                        && meta.wake_time_ns.load(Ordering::Acquire) == entry.wake_time_ns
+                };
+                // A short sleep may expire before the sleeper has switched
+                // out; it only becomes wakeable once it leaves Running
+                while current() && meta.get_state() == GVThreadState::Running {
+                    std::hint::spin_loop();
+                    std::thread::yield_now();
+                }
+                if current() {
                     let priority = meta.get_priority();
                     // wake_gvthread will set state to Ready and push to queue
-                    scheduler::wake_gvthread(GVThreadId::new(entry.gvthread_id), priority);
+                    scheduler::wake_gvthread_checked(
+                        GVThreadId::new(entry.gvthread_id),
+                        priority,
+                        entry.generation,
+                    );
                 }
             }
             None => break, // No more expired entries
@@ -202,8 +266,13 @@ fn process_sleep_queue() {
 }
 
 /// Get time until next wake (for timer sleep optimization)
+///
+/// Only peeks: if a sleeper holds the queue, report "due now" so the
+/// timer comes back shortly instead of waiting for the lock.
 fn time_until_next_wake() -> Option<Duration> {
-    let queue = SLEEP_QUEUE.lock();
+    let Some(queue) = SLEEP_QUEUE.try_lock() else {
+        return Some(Duration::ZERO);
+    };
     if let Some(ref q) = *queue {
         if let Some(top) = q.peek() {
             let now = now_ns();
@@ -223,30 +292,54 @@ fn time_until_next_wake() -> Option<Duration> {
 // ============================================================================
 
 /// Sleep the current GVThread for the specified duration.
+///
+/// Returns early if the GVThread is cancelled while asleep; check the
+/// token afterwards, or use `sleep_cancellable` to get the error.
+/// Outside a GVThread this is `std::thread::sleep` (warned about on a
+/// worker thread when `debug_logging` is set).
 pub fn sleep(duration: Duration) {
-    if !tls::is_in_gvthread() {
-        std::thread::sleep(duration);
-        return;
-    }
+    let _ = sleep_inner(duration);
+}
+
+/// Sleep the current GVThread, returning early if cancelled
+///
+/// Returns `Err(SchedError::Cancelled)` if `token` or the GVThread's own
+/// cancellation flag is set before or during the sleep. Cancelling the
+/// GVThread's own token, or a token it was spawned with
+/// (`spawn_with_token`), wakes it immediately.
+pub fn sleep_cancellable(duration: Duration, token: &CancellationToken) -> SchedResult<()> {
+    token.check()?;
+    sleep_inner(duration)?;
+    token.check()
+}
+
+/// Block the current GVThread on the sleep queue. `Err(Cancelled)` if the
+/// GVThread was (or became) cancelled instead of sleeping the full time.
+fn sleep_inner(duration: Duration) -> SchedResult<()> {
+    let Some(meta) = tls::current_metadata() else {
+        sleep_os_thread(duration);
+        return Ok(());
+    };
     
     let gvthread_id = tls::current_gvthread_id();
-    let meta_base = tls::current_gvthread_base();
-    
-    if meta_base.is_null() {
-        std::thread::sleep(duration);
-        return;
-    }
-    
-    let meta = unsafe { &*(meta_base as *const gvthread_core::metadata::GVThreadMetadata) };
     let generation = meta.get_generation();
     
     // Calculate wake time
     let wake_time_ns = now_ns() + duration.as_nanos() as u64;
     
-    // Store wake time in metadata (for debugging)
+    // Publish the sleep so the timer (wake_time_ns) and cancellation
+    // (wait kind) can both find it
     meta.wake_time_ns.store(wake_time_ns, Ordering::Release);
+    scheduler::set_wait_kind(meta, scheduler::WAIT_SLEEP);
     
-    // Add to sleep queue (SpinLock is safe - no syscalls)
+    // Cancelled before the wait kind was visible: the wake hook may have
+    // missed us, so don't block at all.
+    if meta.is_cancelled() {
+        end_sleep(meta);
+        return Err(SchedError::Cancelled);
+    }
+    
+    // Add to sleep queue (SpinLock is safe - never switches stacks)
     add_to_sleep_queue(SleepEntry {
         wake_time_ns,
         gvthread_id: gvthread_id.as_u32(),
@@ -255,6 +348,32 @@ pub fn sleep(duration: Duration) {
     
     // Block and yield
     scheduler::block_current();
+    
+    // Woken by the timer or by cancellation
+    end_sleep(meta);
+    if meta.is_cancelled() {
+        return Err(SchedError::Cancelled);
+    }
+    Ok(())
+}
+
+/// Leave the sleep state; also invalidates a still-queued entry after an
+/// early (cancellation) wake.
+#[inline]
+fn end_sleep(meta: &GVThreadMetadata) {
+    scheduler::set_wait_kind(meta, scheduler::WAIT_NONE);
+    meta.wake_time_ns.store(0, Ordering::Release);
+}
+
+/// `sleep` outside a GVThread: fine for the main thread and other OS
+/// threads, but on a worker it stalls every GVThread queued there, so
+/// say so when debug logging is on
+fn sleep_os_thread(duration: Duration) {
+    let on_worker = crate::worker::current_worker_id() != usize::MAX;
+    if on_worker && scheduler::global_scheduler().is_some_and(|s| s.config().debug_logging) {
+        kwarn!("sleep({:?}) outside a GVThread blocks this worker", duration);
+    }
+    std::thread::sleep(duration);
 }
 
 /// Sleep for the specified number of milliseconds
@@ -285,6 +404,8 @@ pub struct TimerThread {
     time_slice_ns: u64,
     grace_period_ns: u64,
     enable_forced_preempt: bool,
+    /// Debug builds with `debug_logging`: see `LostWakeupWatch`
+    watch_lost_wakeups: bool,
 }
 
 impl TimerThread {
@@ -295,6 +416,7 @@ impl TimerThread {
             time_slice_ns: config.time_slice.as_nanos() as u64,
             grace_period_ns: config.grace_period.as_nanos() as u64,
             enable_forced_preempt: config.enable_forced_preempt,
+            watch_lost_wakeups: cfg!(debug_assertions) && config.debug_logging,
         }
     }
     
@@ -303,6 +425,7 @@ impl TimerThread {
         let time_slice_ns = self.time_slice_ns;
         let grace_period_ns = self.grace_period_ns;
         let enable_forced_preempt = self.enable_forced_preempt;
+        let watch_lost_wakeups = self.watch_lost_wakeups;
         
         let handle = thread::Builder::new()
             .name("gvthread-timer".to_string())
@@ -312,6 +435,7 @@ impl TimerThread {
                     time_slice_ns,
                     grace_period_ns,
                     enable_forced_preempt,
+                    watch_lost_wakeups,
                     shutdown,
                 );
             })
@@ -322,6 +446,7 @@ impl TimerThread {
     
     pub fn shutdown(&self) {
         self.shutdown.store(true, Ordering::Release);
+        wake_timer_thread();
     }
     
     pub fn join(&mut self) {
@@ -335,38 +460,185 @@ impl TimerThread {
 // Timer Loop
 // ============================================================================
 
+/// What the timer does about a worker that made no progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StallAction {
+    None,
+    /// `time_slice` passed: set the preempt flag (yield at a safepoint)
+    Flag,
+    /// A further `grace_period` passed: force it off with SIGURG
+    Signal,
+}
+
+#[derive(Default)]
 struct WorkerWatch {
     last_counter: u32,
     first_stall_time: Option<Instant>,
+    /// The preempt flag was set during this stall
+    flagged: bool,
+}
+
+impl WorkerWatch {
+    /// Two-phase preemption: flag after `time_slice` without activity,
+    /// signal after `grace_period` more (only with forced preemption).
+    /// A new stall, or any activity, starts over.
+    fn check(
+        &mut self,
+        counter: u32,
+        now: Instant,
+        time_slice_ns: u64,
+        grace_period_ns: u64,
+        enable_forced_preempt: bool,
+    ) -> StallAction {
+        if counter != self.last_counter {
+            self.last_counter = counter;
+            self.reset();
+            return StallAction::None;
+        }
+        let Some(stall_start) = self.first_stall_time else {
+            self.first_stall_time = Some(now);
+            return StallAction::None;
+        };
+        let stall_ns = now.duration_since(stall_start).as_nanos() as u64;
+        if !self.flagged {
+            if stall_ns > time_slice_ns {
+                self.flagged = true;
+                return StallAction::Flag;
+            }
+        } else if stall_ns > time_slice_ns.saturating_add(grace_period_ns) {
+            // Ignored the flag for the whole grace period
+            self.reset();
+            if enable_forced_preempt {
+                return StallAction::Signal;
+            }
+        }
+        StallAction::None
+    }
+    
+    fn reset(&mut self) {
+        self.first_stall_time = None;
+        self.flagged = false;
+    }
+}
+
+/// How long every worker must sit parked next to ready GVThreads, with
+/// no context switch anywhere, before the timer reports a lost wakeup
+const LOST_WAKEUP_AFTER: Duration = Duration::from_millis(20);
+
+/// Queued GVThreads listed in a lost-wakeup report
+const LOST_WAKEUP_SHOWN: usize = 16;
+
+/// Watches for every worker parked while GVThreads are ready
+///
+/// A push wakes a parked worker, so that state should only last until
+/// the woken worker gets a CPU. Held past `LOST_WAKEUP_AFTER` without a
+/// single context switch, a wake was lost. Parked workers still look
+/// again after `park_timeout`, so a lost wakeup shows up as a stall
+/// rather than a hang — easy to miss, hence the report.
+#[derive(Debug, Default)]
+struct LostWakeupWatch {
+    /// When the state was first seen, with the context switch count then
+    since: Option<(Instant, u64)>,
+    /// Already reported this episode
+    reported: bool,
+}
+
+impl LostWakeupWatch {
+    /// One observation; `true` (once per episode) when it is time to report
+    fn check(&mut self, stuck: bool, context_switches: u64, now: Instant) -> bool {
+        if !stuck {
+            *self = Self::default();
+            return false;
+        }
+        match self.since {
+            Some((since, switches)) if switches == context_switches => {
+                if self.reported || now.duration_since(since) < LOST_WAKEUP_AFTER {
+                    return false;
+                }
+                self.reported = true;
+                true
+            }
+            // First seen, or something ran in between: start over
+            _ => {
+                *self = Self { since: Some((now, context_switches)), reported: false };
+                false
+            }
+        }
+    }
+}
+
+/// Report every worker parked next to ready GVThreads (see
+/// `LostWakeupWatch`), with what is queued and what the workers are doing
+fn check_lost_wakeup(watch: &mut LostWakeupWatch, num_workers: usize, now: Instant) {
+    let Some(sched) = scheduler::global_scheduler() else {
+        return;
+    };
+    let states = worker_states();
+    let stuck = sched.is_running()
+        && (0..num_workers).all(|i| states.get(i).is_parked.load(Ordering::Relaxed))
+        && !sched.ready_queue.is_empty();
+    let switches = sched.context_switches.load(Ordering::Relaxed);
+    if !watch.check(stuck, switches, now) {
+        return;
+    }
+    
+    let stats = sched.stats();
+    kerror!(
+        "lost wakeup: all {} workers parked for at least {:?} with GVThreads ready (global {}, local {:?})",
+        num_workers, LOST_WAKEUP_AFTER, stats.global_queue_len, stats.local_queue_lens
+    );
+    for id in sched.ready_queue.queued(LOST_WAKEUP_SHOWN) {
+        let meta = unsafe { &*memory::get_metadata_ptr(id.as_u32()) };
+        kerror!("  queued {}: {:?}, {:?}", meta.generational_id(), meta.get_state(), meta.get_priority());
+    }
+    for i in 0..num_workers {
+        let worker = states.get(i);
+        kerror!(
+            "  worker {}: parked {}, low priority {}, running {}, activity {}",
+            i,
+            worker.is_parked.load(Ordering::Relaxed),
+            worker.is_low_priority.load(Ordering::Relaxed),
+            GVThreadId::new(worker.current_gthread.load(Ordering::Relaxed)),
+            worker.activity_counter.load(Ordering::Relaxed),
+        );
+    }
 }
 
 fn timer_loop(
     num_workers: usize,
     time_slice_ns: u64,
-    _grace_period_ns: u64,
+    grace_period_ns: u64,
     enable_forced_preempt: bool,
+    watch_lost_wakeups: bool,
     shutdown: Arc<AtomicBool>,
 ) {
     use gvthread_core::env::env_get;
     
     let mut watches: Vec<WorkerWatch> = (0..num_workers)
-        .map(|_| WorkerWatch {
-            last_counter: 0,
-            first_stall_time: None,
-        })
+        .map(|_| WorkerWatch::default())
         .collect();
+    let mut lost_wakeup = LostWakeupWatch::default();
+    
+    let max_sleep = Duration::from_millis(env_get("GVT_TIMER_MAX_MS", defaults::TIMER_MAX_SLEEP_MS));
+    let min_sleep = Duration::from_micros(env_get("GVT_TIMER_MIN_US", defaults::TIMER_MIN_SLEEP_US))
+        .min(max_sleep);
     
-    let max_sleep_ms: u64 = env_get("GVT_TIMER_MAX_MS", 10);
+    *TIMER_THREAD.lock() = Some(thread::current());
     
     while !shutdown.load(Ordering::Acquire) {
-        // Compute sleep duration based on next wake time
+        // Announce the longest sleep before looking at the queue: a sleep
+        // queued after the peek below sees it and unparks us
+        TIMER_DEADLINE_NS.store(now_ns().saturating_add(max_sleep.as_nanos() as u64), Ordering::SeqCst);
+        
+        // Sleep until the next deadline, within [min_sleep, max_sleep]
         let sleep_duration = match time_until_next_wake() {
-            Some(d) if d.is_zero() => Duration::from_micros(100), // Work ready, quick check
-            Some(d) => d.min(Duration::from_millis(max_sleep_ms)),
-            None => Duration::from_millis(max_sleep_ms), // Empty queue
+            Some(d) => d.clamp(min_sleep, max_sleep),
+            None => max_sleep, // Empty queue
         };
+        TIMER_DEADLINE_NS.store(now_ns().saturating_add(sleep_duration.as_nanos() as u64), Ordering::SeqCst);
         
-        thread::sleep(sleep_duration);
+        // An unpark (earlier sleep, shutdown) cuts this short
+        thread::park_timeout(sleep_duration);
         
         update_coarse_time();
         
@@ -382,51 +654,119 @@ fn timer_loop(
             
             let gthread_id = worker.current_gthread.load(Ordering::Acquire);
             if gthread_id == gvthread_core::constants::GVTHREAD_NONE {
-                watch.first_stall_time = None;
+                watch.reset();
                 continue;
             }
             
             let counter = worker.activity_counter.load(Ordering::Acquire);
-            
-            if counter == watch.last_counter {
-                if watch.first_stall_time.is_none() {
-                    watch.first_stall_time = Some(now_instant);
-                } else if let Some(stall_start) = watch.first_stall_time {
-                    let stall_ns = now_instant.duration_since(stall_start).as_nanos() as u64;
-                    
-                    if stall_ns > time_slice_ns {
-                        handle_stuck_gvthread(i, gthread_id, enable_forced_preempt);
-                        watch.first_stall_time = None;
-                    }
-                }
-            } else {
-                watch.last_counter = counter;
-                watch.first_stall_time = None;
+            let action = watch.check(
+                counter,
+                now_instant,
+                time_slice_ns,
+                grace_period_ns,
+                enable_forced_preempt,
+            );
+            if action != StallAction::None {
+                handle_stuck_gvthread(i, gthread_id, action);
             }
         }
+        
+        if watch_lost_wakeups {
+            check_lost_wakeup(&mut lost_wakeup, num_workers, now_instant);
+        }
     }
+    
+    TIMER_DEADLINE_NS.store(0, Ordering::SeqCst);
+    *TIMER_THREAD.lock() = None;
 }
 
 fn handle_stuck_gvthread(
     worker_id: usize,
     gthread_id: u32,
-    enable_forced_preempt: bool,
+    action: StallAction,
 ) {
     let meta_ptr = memory::get_metadata_ptr(gthread_id);
     let meta = unsafe { &*meta_ptr };
     
-    // Set preempt flag
-    meta.preempt_flag.store(1, Ordering::Release);
-    
+    match action {
+        StallAction::Flag => {
+            // Phase 1: ask it to yield at its next safepoint
+            meta.preempt_flag.store(1, Ordering::Release);
+        }
+        StallAction::Signal => {
+            // Phase 2: it didn't within the grace period
+            meta.record_forced_preemption();
+            signal_worker(worker_id);
+        }
+        StallAction::None => {}
+    }
+}
+
+/// Interrupt `worker_id` with SIGURG (forced preemption)
+fn signal_worker(worker_id: usize) {
+    #[cfg(unix)]
+    {
+        let tid = worker_states().get(worker_id).thread_id.load(Ordering::Relaxed);
+        if tid != 0 {
+            let _ = crate::signal::send_sigurg(tid);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = worker_id;
+}
+
+/// A Critical GVThread was just queued: unless a worker is free to take
+/// it, ask the lowest-priority running GVThread to yield, the same way
+/// `handle_stuck_gvthread` does for an expired time slice.
+///
+/// Against thrashing, a GVThread already asked to yield is never picked
+/// again and Critical work is never interrupted, so a burst of Critical
+/// spawns preempts at most one GVThread per worker.
+pub(crate) fn preempt_for_critical(num_workers: usize, enable_forced_preempt: bool) {
+    let states = worker_states();
+    let running = (0..num_workers).filter_map(|i| {
+        let worker = states.get(i);
+        // Low-priority workers never run Critical work
+        if worker.is_low_priority.load(Ordering::Relaxed) {
+            return None;
+        }
+        let id = worker.current_gthread.load(Ordering::Acquire);
+        if id == gvthread_core::constants::GVTHREAD_NONE {
+            return Some(None);
+        }
+        let meta = unsafe { &*memory::get_metadata_ptr(id) };
+        Some(Some(((i, meta), meta.get_priority(), meta.is_preempt_requested())))
+    });
+    let Some((worker_id, meta)) = pick_critical_victim(running) else {
+        return;
+    };
+    // Claim the victim: a concurrent Critical spawn picks another one
+    if meta.preempt_flag.compare_exchange(0, 1, Ordering::AcqRel, Ordering::Relaxed).is_err() {
+        return;
+    }
     if enable_forced_preempt {
-        #[cfg(unix)]
-        {
-            let tid = worker_states().get(worker_id).thread_id.load(Ordering::Relaxed);
-            if tid != 0 {
-                let _ = crate::signal::send_sigurg(tid);
-            }
+        meta.record_forced_preemption();
+        signal_worker(worker_id);
+    }
+}
+
+/// Choose whom `preempt_for_critical` interrupts, given what each
+/// eligible worker runs: `None` if idle, else `(who, priority,
+/// preempt already requested)`. No one if a worker is idle.
+fn pick_critical_victim<T>(
+    running: impl IntoIterator<Item = Option<(T, Priority, bool)>>,
+) -> Option<T> {
+    let mut victim: Option<(T, Priority)> = None;
+    for slot in running {
+        let (who, priority, requested) = slot?;
+        if priority == Priority::Critical || requested {
+            continue;
+        }
+        if victim.as_ref().map_or(true, |(_, best)| priority > *best) {
+            victim = Some((who, priority));
         }
     }
+    victim.map(|(who, _)| who)
 }
 
 // ============================================================================
@@ -448,6 +788,85 @@ mod tests {
         assert_ne!(h1, h3);
     }
 
+    #[test]
+    fn test_stall_flags_then_signals_after_grace_period() {
+        const MS: u64 = 1_000_000;
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut watch = WorkerWatch::default();
+        let mut check = |counter, ms| watch.check(counter, at(ms), 10 * MS, 5 * MS, true);
+        
+        assert_eq!(check(0, 0), StallAction::None); // stall starts
+        assert_eq!(check(0, 8), StallAction::None);
+        assert_eq!(check(0, 11), StallAction::Flag); // time slice
+        assert_eq!(check(0, 13), StallAction::None); // grace period runs
+        assert_eq!(check(0, 16), StallAction::Signal);
+        // Next stall starts over
+        assert_eq!(check(0, 17), StallAction::None);
+        assert_eq!(check(0, 28), StallAction::Flag);
+        // Activity (e.g. a safepoint yield) cancels the signal
+        assert_eq!(check(1, 30), StallAction::None);
+        assert_eq!(check(1, 36), StallAction::None);
+        assert_eq!(check(1, 40), StallAction::None);
+    }
+    
+    #[test]
+    fn test_stall_without_forced_preempt_only_flags() {
+        const MS: u64 = 1_000_000;
+        let start = Instant::now();
+        let mut watch = WorkerWatch::default();
+        let mut actions = Vec::new();
+        for ms in 0..40 {
+            let action = watch.check(0, start + Duration::from_millis(ms), 10 * MS, 5 * MS, false);
+            if action != StallAction::None {
+                actions.push((ms, action));
+            }
+        }
+        // Re-flagged once per time slice + grace period
+        assert_eq!(actions, [(11, StallAction::Flag), (28, StallAction::Flag)]);
+    }
+
+    #[test]
+    fn test_lost_wakeup_reported_once_per_episode() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut watch = LostWakeupWatch::default();
+
+        assert!(!watch.check(true, 7, at(0)));
+        assert!(!watch.check(true, 7, at(10)));
+        assert!(watch.check(true, 7, at(20)));
+        assert!(!watch.check(true, 7, at(30))); // already reported
+        // A worker picks the work up: the next episode reports again
+        assert!(!watch.check(false, 8, at(40)));
+        assert!(!watch.check(true, 8, at(50)));
+        assert!(watch.check(true, 8, at(75)));
+
+        // Something ran in between: not the same stall
+        let mut watch = LostWakeupWatch::default();
+        assert!(!watch.check(true, 1, at(0)));
+        assert!(!watch.check(true, 2, at(15)));
+        assert!(!watch.check(true, 2, at(30)));
+        assert!(watch.check(true, 2, at(35)));
+    }
+
+    #[test]
+    fn test_critical_victim_is_lowest_priority_not_yet_asked() {
+        let run = |who, priority, asked| Some((who, priority, asked));
+        assert_eq!(
+            pick_critical_victim([run(0, Priority::Normal, false), run(1, Priority::Low, false), run(2, Priority::High, false)]),
+            Some(1)
+        );
+        // Already asked to yield: leave it, take the next lowest
+        assert_eq!(
+            pick_critical_victim([run(0, Priority::Normal, false), run(1, Priority::Low, true)]),
+            Some(0)
+        );
+        // Critical work is never interrupted
+        assert_eq!(pick_critical_victim([run(0, Priority::Critical, false), run(1, Priority::Low, true)]), None);
+        // An idle worker will take the new GVThread
+        assert_eq!(pick_critical_victim([run(0, Priority::Low, false), None]), None);
+    }
+
     #[test]
     fn test_timer_entry_creation() {
         let entry = TimerEntry::preempt(42, 3, Duration::from_millis(10));
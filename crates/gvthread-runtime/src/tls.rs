@@ -4,6 +4,7 @@
 
 use gvthread_core::id::GVThreadId;
 use gvthread_core::constants::GVTHREAD_NONE;
+use gvthread_core::metadata::GVThreadMetadata;
 use std::cell::Cell;
 
 thread_local! {
@@ -55,6 +56,23 @@ pub fn current_gvthread_base() -> *mut u8 {
     GVTHREAD_BASE.with(|cell| cell.get())
 }
 
+/// The current GVThread's metadata as a raw pointer (null outside a
+/// GVThread), for the register save area
+#[inline]
+pub fn current_metadata_ptr() -> *mut GVThreadMetadata {
+    current_gvthread_base().cast()
+}
+
+/// The current GVThread's metadata (`None` outside a GVThread)
+#[inline]
+pub fn current_metadata() -> Option<&'static GVThreadMetadata> {
+    if !is_in_gvthread() {
+        return None;
+    }
+    // Slots stay mapped for the life of the runtime
+    unsafe { current_metadata_ptr().as_ref() }
+}
+
 /// Check if we're running inside a GVThread
 #[inline]
 pub fn is_in_gvthread() -> bool {
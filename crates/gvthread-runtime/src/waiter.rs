@@ -0,0 +1,84 @@
+//! Per-waiter wake node shared by the blocking primitives
+//!
+//! A `WaitNode` identifies one blocked waiter — a GVThread (id plus
+//! generation) or a plain OS thread — and carries a small state machine
+//! so a wake is never lost and never misdelivered:
+//!
+//! - `notify()` marks the node `NOTIFIED`, then wakes the waiter. A
+//!   GVThread may still be on its way into `block_current`, and
+//!   `wake_gvthread` ignores one that isn't `Blocked` yet, so the
+//!   notifier waits for it to leave `Running` first.
+//! - `wait()` marks the node `CONSUMED` when it returns, so the notifier
+//!   never wakes a GVThread that already moved on to block elsewhere.
+
+use crate::memory;
+use crate::scheduler;
+
+use gvthread_core::id::GVThreadId;
+use gvthread_core::state::GVThreadState;
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::thread::Thread;
+
+const WAITING: u8 = 0;
+const NOTIFIED: u8 = 1;
+const CONSUMED: u8 = 2;
+
+enum Who {
+    /// GVThread blocked in `wait()`: (id, generation)
+    GVThread(GVThreadId, u32),
+    /// Plain OS thread parked in `wait()`
+    Os(Thread),
+}
+
+pub(crate) struct WaitNode {
+    state: AtomicU8,
+    who: Who,
+}
+
+impl WaitNode {
+    pub(crate) fn current() -> Self {
+        let who = match scheduler::current_waiter() {
+            Some((id, generation)) => Who::GVThread(id, generation),
+            None => Who::Os(std::thread::current()),
+        };
+        Self { state: AtomicU8::new(WAITING), who }
+    }
+
+    /// Block until notified. Wakes that arrive without a notify are
+    /// absorbed here.
+    pub(crate) fn wait(&self) {
+        loop {
+            if self.state
+                .compare_exchange(NOTIFIED, CONSUMED, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                return;
+            }
+            match self.who {
+                Who::GVThread(..) => scheduler::block_current(),
+                Who::Os(_) => std::thread::park(),
+            }
+        }
+    }
+
+    pub(crate) fn notify(&self) {
+        self.state.store(NOTIFIED, Ordering::Release);
+        match &self.who {
+            Who::Os(thread) => thread.unpark(),
+            Who::GVThread(id, generation) => {
+                let meta = unsafe { &*memory::get_metadata_ptr(id.as_u32()) };
+                while meta.get_generation() == *generation
+                    && meta.get_state() == GVThreadState::Running
+                    && self.state.load(Ordering::Acquire) != CONSUMED
+                {
+                    std::hint::spin_loop();
+                    std::thread::yield_now();
+                }
+                if self.state.load(Ordering::Acquire) != CONSUMED {
+                    scheduler::wake_gvthread_checked(*id, meta.get_priority(), *generation);
+                }
+            }
+        }
+    }
+}
@@ -4,11 +4,20 @@
 //! state stored in a contiguous array for efficient timer thread scanning.
 
 use gvthread_core::constants::MAX_WORKERS;
+use gvthread_core::kerror;
 use gvthread_core::metadata::WorkerState;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
 use std::thread::{self, JoinHandle};
 
+/// Restarts a supervised worker gets before it is left dead
+pub const MAX_WORKER_RESTARTS: usize = 8;
+
+/// Recovery hook run on a supervised worker's thread after its loop
+/// panicked, before the loop is restarted
+pub type RecoverFn = Arc<dyn Fn(usize) + Send + Sync>;
+
 /// Contiguous array of worker states (cache-line aligned)
 /// 
 /// This is a single 4KB allocation that the timer thread can scan
@@ -40,6 +49,15 @@ impl WorkerStates {
     }
 }
 
+/// OS thread name of worker `id`, as shown by `top -H` and `perf`
+///
+/// Linux keeps only the first 15 bytes (`/proc/<pid>/task/*/comm`),
+/// so the name is kept short enough for the id to survive.
+fn worker_thread_name(id: usize, is_low_priority: bool) -> String {
+    let kind = if is_low_priority { "low" } else { "wrk" };
+    format!("gvthread-{}-{}", kind, id)
+}
+
 /// Global worker states - lazily initialized
 static WORKER_STATES_CELL: OnceLock<Box<WorkerStates>> = OnceLock::new();
 
@@ -65,6 +83,15 @@ pub struct WorkerPool {
     
     /// Number of workers that have started
     started_count: AtomicUsize,
+    
+    /// Worker threads still running their loop
+    live: Arc<AtomicUsize>,
+    
+    /// Restarts of supervised workers after a panic
+    restarts: Arc<AtomicUsize>,
+    
+    /// Recovery hook; `None` leaves workers unsupervised
+    recover: Option<RecoverFn>,
 }
 
 impl WorkerPool {
@@ -76,9 +103,20 @@ impl WorkerPool {
             num_low_priority_workers,
             shutdown: AtomicBool::new(false),
             started_count: AtomicUsize::new(0),
+            live: Arc::new(AtomicUsize::new(0)),
+            restarts: Arc::new(AtomicUsize::new(0)),
+            recover: None,
         }
     }
     
+    /// Supervise the workers: a worker loop that panics is restarted on
+    /// the same thread (up to `MAX_WORKER_RESTARTS` times) after
+    /// `recover(worker_id)` ran
+    pub fn supervised(mut self, recover: RecoverFn) -> Self {
+        self.recover = Some(recover);
+        self
+    }
+    
     /// Start all worker threads
     pub fn start<F>(&mut self, worker_fn: F) 
     where
@@ -87,14 +125,24 @@ impl WorkerPool {
         for i in 0..self.num_workers {
             let is_low_priority = i >= (self.num_workers - self.num_low_priority_workers);
             let worker_fn = worker_fn.clone();
+            let recover = self.recover.clone();
+            let restarts = Arc::clone(&self.restarts);
             
             // Initialize worker state
             worker_states().get(i).init(i as u8, is_low_priority);
             
+            // Counted live until the thread exits, panic or not
+            let live = LiveGuard::new(&self.live);
             let handle = thread::Builder::new()
-                .name(format!("gvthread-worker-{}", i))
+                .name(worker_thread_name(i, is_low_priority))
                 .spawn(move || {
-                    worker_fn(i, is_low_priority);
+                    let _live = live;
+                    match recover {
+                        Some(recover) => supervise(i, &restarts, &*recover, || {
+                            worker_fn(i, is_low_priority)
+                        }),
+                        None => worker_fn(i, is_low_priority),
+                    }
                 })
                 .expect("Failed to spawn worker thread");
             
@@ -115,11 +163,26 @@ impl WorkerPool {
     
     /// Wait for all workers to finish
     pub fn join(self) {
-        for handle in self.handles {
-            let _ = handle.join();
+        for (i, handle) in self.handles.into_iter().enumerate() {
+            if handle.join().is_err() {
+                kerror!("worker {} died from a panic", i);
+            }
         }
     }
     
+    /// Worker threads still running (fewer than `num_workers` means some
+    /// died or, after shutdown, exited)
+    #[inline]
+    pub fn live_workers(&self) -> usize {
+        self.live.load(Ordering::Relaxed)
+    }
+    
+    /// Times a supervised worker was restarted after a panic
+    #[inline]
+    pub fn restarts(&self) -> usize {
+        self.restarts.load(Ordering::Relaxed)
+    }
+    
     /// Get number of workers
     #[inline]
     pub fn num_workers(&self) -> usize {
@@ -133,6 +196,45 @@ impl WorkerPool {
     }
 }
 
+/// Live-worker count held by a worker thread: dropped on exit or unwind
+struct LiveGuard(Arc<AtomicUsize>);
+
+impl LiveGuard {
+    fn new(live: &Arc<AtomicUsize>) -> Self {
+        live.fetch_add(1, Ordering::Relaxed);
+        Self(Arc::clone(live))
+    }
+}
+
+impl Drop for LiveGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Run `worker_loop`, restarting it after a panic once `recover` has
+/// cleaned up. Gives up (and lets the worker die) after
+/// `MAX_WORKER_RESTARTS` restarts.
+fn supervise(
+    worker_id: usize,
+    restarts: &AtomicUsize,
+    recover: &(dyn Fn(usize) + Send + Sync),
+    worker_loop: impl Fn(),
+) {
+    for attempt in 0.. {
+        if panic::catch_unwind(AssertUnwindSafe(&worker_loop)).is_ok() {
+            return;
+        }
+        recover(worker_id);
+        if attempt == MAX_WORKER_RESTARTS {
+            kerror!("worker {} panicked {} times, giving up", worker_id, attempt + 1);
+            return;
+        }
+        restarts.fetch_add(1, Ordering::Relaxed);
+        kerror!("worker {} panicked, restarting", worker_id);
+    }
+}
+
 /// Thread-local worker ID
 thread_local! {
     static CURRENT_WORKER_ID: std::cell::Cell<usize> = const { std::cell::Cell::new(usize::MAX) };
@@ -156,3 +258,47 @@ pub fn current_worker_state() -> &'static WorkerState {
     debug_assert!(id < MAX_WORKERS, "Worker ID not set or invalid");
     worker_states().get(id)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn supervise_restarts_a_panicking_loop_after_recovery() {
+        let restarts = AtomicUsize::new(0);
+        let recovered = AtomicUsize::new(0);
+        let runs = Cell::new(0);
+        supervise(3, &restarts, &|id| {
+            assert_eq!(id, 3);
+            recovered.fetch_add(1, Ordering::Relaxed);
+        }, || {
+            runs.set(runs.get() + 1);
+            if runs.get() < 3 {
+                panic!("worker loop bug");
+            }
+        });
+        assert_eq!(runs.get(), 3);
+        assert_eq!(recovered.load(Ordering::Relaxed), 2);
+        assert_eq!(restarts.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn worker_thread_names_fit_in_comm() {
+        assert_eq!(worker_thread_name(0, false), "gvthread-wrk-0");
+        assert_eq!(worker_thread_name(7, true), "gvthread-low-7");
+        assert!(worker_thread_name(MAX_WORKERS - 1, true).len() <= 15);
+    }
+
+    #[test]
+    fn supervise_gives_up_after_max_restarts() {
+        let restarts = AtomicUsize::new(0);
+        let runs = Cell::new(0);
+        supervise(0, &restarts, &|_| {}, || {
+            runs.set(runs.get() + 1);
+            panic!("always");
+        });
+        assert_eq!(runs.get(), MAX_WORKER_RESTARTS + 1);
+        assert_eq!(restarts.load(Ordering::Relaxed), MAX_WORKER_RESTARTS);
+    }
+}
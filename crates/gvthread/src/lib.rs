@@ -81,29 +81,53 @@
 // Re-export core types
 pub use gvthread_core::{
     GVThreadId,
+    GenerationalId,
     GVThreadState,
     Priority,
     CancellationToken,
     SchedError,
     SchedResult,
     channel,
+    channel_unbounded,
     Sender,
     Receiver,
     SchedMutex,
+    GVThreadStats,
 };
 
 // Re-export kprint macros for debug logging
-pub use gvthread_core::{kprint, kprintln, kerror, kwarn, kinfo, kdebug, ktrace};
-pub use gvthread_core::kprint::{LogLevel, init as init_logging, set_log_level, set_flush_enabled, set_time_enabled};
+pub use gvthread_core::{kprint, kprintln, kerror, kwarn, kinfo, kdebug, ktrace, kdebug_every, ktrace_every};
+pub use gvthread_core::kprint::{LogLevel, LogFormat, init as init_logging, set_log_level, set_format as set_log_format, set_flush_enabled, set_time_enabled};
+pub use gvthread_core::kprint::{set_sink as set_log_sink, reset_sink as reset_log_sink};
+#[cfg(unix)]
+pub use gvthread_core::kprint::set_sink_fd as set_log_sink_fd;
 
 // Re-export env utilities
-pub use gvthread_core::{env_get, env_get_bool, env_get_opt, env_get_str, env_is_set};
+pub use gvthread_core::{env_get, env_get_bool, env_get_bytes, env_get_duration, env_get_opt, env_get_str, env_is_set};
 
 // Re-export runtime types
 pub use gvthread_runtime::{
     SchedulerConfig,
+    ReadyQueueKind,
     Scheduler,
+    RuntimeStats,
+    JoinError,
+    JoinHandle,
+    LocalKey,
+    gvthread_local,
+    spawn_blocking,
+    SchedCondvar,
+    SchedRwLock,
+    broadcast,
+    oneshot,
+    timeout,
+    Timeout,
+    block_on_future,
+    scope,
+    Scope,
+    ScopedJoinHandle,
     sleep,
+    sleep_cancellable,
     sleep_ms,
     sleep_us,
 };
@@ -122,20 +146,47 @@ pub struct Runtime {
 impl Runtime {
     /// Create a new runtime with the given configuration
     ///
-    /// This does not start the scheduler. Call `start()` or `block_on()` to begin.
+    /// This does not start the scheduler. Call `start()` or `block_on()` to begin;
+    /// GVThreads spawned in the meantime run once it starts.
+    ///
+    /// # Panics
+    ///
+    /// If the configuration is invalid or a runtime already exists in this
+    /// process; see `try_new`.
     pub fn new(config: SchedulerConfig) -> Self {
-        scheduler::init_global_scheduler(config)
-            .expect("Failed to initialize scheduler");
+        Self::try_new(config).unwrap_or_else(|e| panic!("Failed to initialize scheduler: {}", e))
+    }
+    
+    /// Create a new runtime, or fail with `SchedError::InvalidConfig` if
+    /// `config` doesn't validate, or `SchedError::AlreadyInitialized` if
+    /// a runtime already exists in this process (the scheduler is global)
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let runtime = match Runtime::try_new(SchedulerConfig::from_env()) {
+    ///     Ok(rt) => rt,
+    ///     Err(SchedError::AlreadyInitialized) => return reuse_existing(),
+    ///     Err(e) => return Err(e),
+    /// };
+    /// ```
+    pub fn try_new(config: SchedulerConfig) -> SchedResult<Self> {
+        scheduler::init_global_scheduler(config)?;
         
-        Self {
+        Ok(Self {
             started: AtomicBool::new(false),
-        }
+        })
     }
     
     /// Start the scheduler
     ///
     /// This spawns worker threads and begins processing GVThreads.
+    /// Fails with `SchedError::InsideGVThread` when called from a
+    /// GVThread.
     pub fn start(&mut self) -> SchedResult<()> {
+        if gvthread_runtime::tls::is_in_gvthread() {
+            return Err(SchedError::InsideGVThread);
+        }
         if self.started.swap(true, Ordering::SeqCst) {
             return Err(SchedError::AlreadyInitialized);
         }
@@ -147,17 +198,57 @@ impl Runtime {
     /// Run a function with the scheduler active, then shutdown
     ///
     /// This is the typical entry point for applications.
+    ///
+    /// # Panics
+    ///
+    /// When called from inside a GVThread: the caller would wait on the
+    /// very workers it occupies. Code already running in a GVThread
+    /// should `spawn_with_handle` and `join` instead.
     pub fn block_on<F, T>(&mut self, f: F) -> T
     where
         F: FnOnce() -> T,
     {
+        assert_outside_gvthread();
+        let _ = self.start();
+        let result = f();
+        self.shutdown();
+        result
+    }
+    
+    /// Like `block_on`, but after `f` returns wait for every spawned
+    /// GVThread to finish before shutting down
+    ///
+    /// Returns once no GVThread is Ready, Running or Blocked: the last
+    /// one to finish wakes the caller. A GVThread that blocks forever
+    /// keeps this from returning. Panics inside a
+    /// GVThread, like `block_on`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// runtime.block_on_until_idle(|| {
+    ///     for i in 0..100 {
+    ///         spawn(move |_| work(i));
+    ///     }
+    /// }); // all 100 have completed here
+    /// ```
+    pub fn block_on_until_idle<F, T>(&mut self, f: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        assert_outside_gvthread();
         let _ = self.start();
         let result = f();
+        scheduler::wait_until_idle();
         self.shutdown();
         result
     }
     
     /// Spawn a new GVThread with normal priority
+    ///
+    /// May be called before `start()`/`block_on()`: the GVThread waits
+    /// in the ready queue and runs once the workers start. Its parent is
+    /// the calling GVThread, or none when called from an OS thread.
     pub fn spawn<F>(&self, f: F) -> GVThreadId
     where
         F: FnOnce(&CancellationToken) + Send + 'static,
@@ -165,7 +256,7 @@ impl Runtime {
         spawn(f)
     }
     
-    /// Spawn a new GVThread with specified priority
+    /// Spawn a new GVThread with specified priority (see `spawn`)
     pub fn spawn_with_priority<F>(&self, f: F, priority: Priority) -> GVThreadId
     where
         F: FnOnce(&CancellationToken) + Send + 'static,
@@ -173,9 +264,52 @@ impl Runtime {
         spawn_with_priority(f, priority)
     }
     
+    /// Spawn a new GVThread with normal priority, without panicking
+    /// when every slot is in use (see `try_spawn`)
+    pub fn try_spawn<F>(&self, f: F) -> SchedResult<GVThreadId>
+    where
+        F: FnOnce(&CancellationToken) + Send + 'static,
+    {
+        try_spawn(f)
+    }
+    
+    /// Spawn a new GVThread with specified priority, without panicking
+    /// when every slot is in use
+    pub fn try_spawn_with_priority<F>(&self, f: F, priority: Priority) -> SchedResult<GVThreadId>
+    where
+        F: FnOnce(&CancellationToken) + Send + 'static,
+    {
+        try_spawn_with_priority(f, priority)
+    }
+    
+    /// Snapshot of scheduler statistics
+    ///
+    /// Slots, live/finished GVThreads, ready queue depths, parked workers
+    /// and context switches. Safe to call from any thread at any time.
+    pub fn stats(&self) -> RuntimeStats {
+        scheduler::stats()
+    }
+    
+    /// Drain GVThreads, then shut down
+    ///
+    /// Stops accepting new spawns and waits up to `timeout` for running
+    /// and queued GVThreads to finish before stopping workers. Returns
+    /// how many GVThreads were still active (0 = clean drain).
+    pub fn shutdown_graceful(&mut self, timeout: std::time::Duration) -> usize {
+        if self.started.swap(false, Ordering::SeqCst) {
+            scheduler::shutdown_global_scheduler_graceful(timeout)
+        } else {
+            0
+        }
+    }
+    
     /// Shutdown the scheduler
+    ///
+    /// GVThreads that never ran, including ones spawned before a
+    /// `start()` that never came, are dropped unrun.
     pub fn shutdown(&mut self) {
-        if self.started.swap(false, Ordering::SeqCst) {
+        let never_started = || scheduler::global_scheduler().is_some_and(|s| !s.is_running());
+        if self.started.swap(false, Ordering::SeqCst) || never_started() {
             scheduler::shutdown_global_scheduler();
         }
     }
@@ -187,6 +321,13 @@ impl Drop for Runtime {
     }
 }
 
+/// `block_on` from a GVThread would deadlock: fail loudly instead
+fn assert_outside_gvthread() {
+    if gvthread_runtime::tls::is_in_gvthread() {
+        panic!("{}", SchedError::InsideGVThread);
+    }
+}
+
 /// Spawn a new GVThread with normal priority
 ///
 /// The closure receives a `CancellationToken` that can be checked
@@ -221,6 +362,81 @@ where
     scheduler::spawn(f, priority)
 }
 
+/// Spawn a new GVThread with normal priority and get a `JoinHandle` to
+/// its return value
+///
+/// A panic in `f` is contained to the GVThread: `join()` returns
+/// `Err(JoinError::Panic)` carrying the payload. Dropping the handle
+/// detaches the GVThread; its value is dropped when it finishes.
+///
+/// # Example
+///
+/// ```ignore
+/// let handle = gvthread::spawn_with_handle(move |_| parse(input));
+/// match handle.join() {
+///     Ok(ast) => compile(ast),
+///     Err(e) => eprintln!("parser failed: {}", e),
+/// }
+/// ```
+pub fn spawn_with_handle<F, T>(f: F) -> JoinHandle<T>
+where
+    F: FnOnce(&CancellationToken) -> T + Send + 'static,
+    T: Send + 'static,
+{
+    scheduler::spawn_with_handle(f, Priority::Normal)
+}
+
+/// Spawn a new GVThread with normal priority, or fail with
+/// `SchedError::NoSlotsAvailable` once `max_gvthreads` are live
+/// (`SchedError::ShuttingDown` once a graceful shutdown has begun)
+///
+/// `spawn` panics when slots run out; servers should use this and shed load
+/// instead (`f` is dropped on error).
+///
+/// # Example
+///
+/// ```ignore
+/// match gvthread::try_spawn(move |_| handle(conn)) {
+///     Ok(_) => {}
+///     Err(SchedError::NoSlotsAvailable) => { /* conn dropped: back off */ }
+///     Err(e) => return Err(e),
+/// }
+/// ```
+pub fn try_spawn<F>(f: F) -> SchedResult<GVThreadId>
+where
+    F: FnOnce(&CancellationToken) + Send + 'static,
+{
+    scheduler::try_spawn(f, Priority::Normal)
+}
+
+/// Spawn a new GVThread with specified priority, or fail with
+/// `SchedError::NoSlotsAvailable` once `max_gvthreads` are live
+pub fn try_spawn_with_priority<F>(f: F, priority: Priority) -> SchedResult<GVThreadId>
+where
+    F: FnOnce(&CancellationToken) + Send + 'static,
+{
+    scheduler::try_spawn(f, priority)
+}
+
+/// GVThread slots in use and the maximum (`max_gvthreads`)
+///
+/// Lets callers throttle before `try_spawn` starts failing, e.g. stop
+/// accepting connections while `in_use == max`.
+pub fn slot_usage() -> (usize, usize) {
+    scheduler::slot_usage()
+}
+
+/// Spawn a new GVThread that is cancelled along with `token`
+///
+/// Pair with `CancellationToken::child_token()` to cancel a whole group
+/// of GVThreads (e.g. everything spawned for one connection) at once.
+pub fn spawn_with_token<F>(f: F, token: &CancellationToken) -> GVThreadId
+where
+    F: FnOnce(&CancellationToken) + Send + 'static,
+{
+    scheduler::spawn_with_token(f, Priority::Normal, token)
+}
+
 /// Yield execution to the scheduler
 ///
 /// The current GVThread will be placed back in the ready queue
@@ -232,6 +448,16 @@ pub fn yield_now() {
     scheduler::yield_now()
 }
 
+/// Yield directly to another GVThread
+///
+/// If `target` is ready and queued on this worker, switch straight into
+/// it instead of going through the scheduler loop. Otherwise behaves
+/// like `yield_now()`.
+#[inline]
+pub fn yield_to(target: GVThreadId) {
+    scheduler::yield_to(target)
+}
+
 /// Get the current GVThread's ID
 ///
 /// Returns `GVThreadId::NONE` if not running in a GVThread.
@@ -240,6 +466,15 @@ pub fn current_id() -> GVThreadId {
     gvthread_runtime::tls::current_gvthread_id()
 }
 
+/// Scheduling counters of a live GVThread (`None` once it finished)
+///
+/// See [`GVThreadStats`]: e.g. many `forced_preemptions` and few
+/// `voluntary_yields` point at a CPU hog.
+#[inline]
+pub fn gvthread_stats(id: GVThreadId) -> Option<GVThreadStats> {
+    scheduler::gvthread_stats(id)
+}
+
 /// Check if currently executing within a GVThread
 #[inline]
 pub fn is_in_gvthread() -> bool {
@@ -272,4 +507,129 @@ macro_rules! safepoint {
             // 3. If set, yield
         }
     };
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::{Arc, OnceLock};
+    use std::time::{Duration, Instant};
+
+    /// The scheduler is process-global: the tests here that need one
+    /// share it, started on first use and never shut down. Tests that
+    /// need other settings are binaries of their own (`tests/README.md`)
+    fn started_runtime() -> &'static Runtime {
+        static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+        RUNTIME.get_or_init(|| {
+            let config = SchedulerConfig::default()
+                .num_workers(2)
+                .num_low_priority_workers(0);
+            let mut runtime = Runtime::new(config);
+            runtime.start().unwrap();
+            runtime
+        })
+    }
+
+    #[test]
+    fn try_new_rejects_invalid_config() {
+        // Fails validation before touching the global scheduler
+        let config = SchedulerConfig::default().num_workers(0);
+        assert!(matches!(Runtime::try_new(config), Err(SchedError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn second_runtime_is_rejected() {
+        started_runtime();
+        assert!(matches!(
+            Runtime::try_new(SchedulerConfig::default()),
+            Err(SchedError::AlreadyInitialized)
+        ));
+    }
+
+    #[test]
+    fn panicking_gvthread_is_isolated() {
+        started_runtime();
+        let completed = Arc::new(AtomicUsize::new(0));
+
+        // Plain spawn: the panic is logged and the worker carries on
+        spawn(|_| panic!("unjoined boom"));
+
+        let healthy: Vec<_> = (0..8)
+            .map(|_| {
+                let counter = Arc::clone(&completed);
+                spawn_with_handle(move |_| {
+                    yield_now();
+                    counter.fetch_add(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        let failing = spawn_with_handle(|_| panic!("joined boom"));
+
+        match failing.join() {
+            Err(JoinError::Panic(payload)) => {
+                assert_eq!(payload.downcast_ref::<&str>(), Some(&"joined boom"));
+            }
+            other => panic!("expected JoinError::Panic, got {:?}", other),
+        }
+        for handle in healthy {
+            assert!(handle.join().is_ok());
+        }
+        assert_eq!(completed.load(Ordering::SeqCst), 8);
+    }
+
+    #[test]
+    fn join_returns_the_closure_value() {
+        started_runtime();
+        assert_eq!(spawn_with_handle(|_| (1..=10u32).sum::<u32>()).join().unwrap(), 55);
+
+        // Unjoined: the result is freed with the handle's last reference
+        let value = Arc::new(());
+        let returned = Arc::clone(&value);
+        drop(spawn_with_handle(move |_| returned));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Arc::strong_count(&value) > 1 {
+            assert!(Instant::now() < deadline, "unjoined result never freed");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn block_on_inside_gvthread_fails() {
+        started_runtime();
+        let nested = spawn_with_handle(|_| {
+            let mut inner = Runtime { started: AtomicBool::new(false) };
+            assert_eq!(inner.start(), Err(SchedError::InsideGVThread));
+            inner.block_on(|| ());
+        });
+        match nested.join() {
+            Err(JoinError::Panic(payload)) => {
+                let msg = payload.downcast_ref::<String>().unwrap();
+                assert!(msg.contains("cannot block_on from within a GVThread"), "{}", msg);
+            }
+            other => panic!("expected JoinError::Panic, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn spawned_closures_are_freed() {
+        started_runtime();
+        let captured = Arc::new(());
+        let handles: Vec<_> = (0..16)
+            .map(|i| {
+                let captured = Arc::clone(&captured);
+                spawn_with_handle(move |_| {
+                    let _captured = captured;
+                    if i % 4 == 0 {
+                        panic!("freed boom");
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            let _ = handle.join();
+        }
+        // Run or panicked, every closure was freed with its captures
+        assert_eq!(Arc::strong_count(&captured), 1);
+    }
+}
@@ -0,0 +1,105 @@
+//! `block_on_future` from GVThreads sharing a single worker
+
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use gvthread::{block_on_future, spawn_with_handle, yield_now};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::Duration;
+
+/// Pending until someone sets the value and wakes it
+#[derive(Default)]
+struct Slot {
+    value: Mutex<(Option<u32>, Option<Waker>)>,
+}
+
+impl Slot {
+    fn set(&self, value: u32) {
+        let waker = {
+            let mut guard = self.value.lock().unwrap();
+            guard.0 = Some(value);
+            guard.1.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+struct SlotFuture(Arc<Slot>);
+
+impl Future for SlotFuture {
+    type Output = u32;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+        let mut guard = self.0.value.lock().unwrap();
+        match guard.0 {
+            Some(v) => Poll::Ready(v),
+            None => {
+                guard.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Wakes itself from inside `poll` a few times before finishing
+struct SelfWaking(u32);
+
+impl Future for SelfWaking {
+    type Output = u32;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+        if self.0 == 0 {
+            return Poll::Ready(42);
+        }
+        self.0 -= 1;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+#[test]
+fn gvthread_blocks_on_a_future() {
+    let mut runtime = common::runtime(1);
+
+    runtime.block_on(|| {
+        // Woken by another GVThread: only possible if the waiter gave the
+        // only worker up while pending
+        let slot = Arc::new(Slot::default());
+        let waiter = {
+            let slot = Arc::clone(&slot);
+            spawn_with_handle(move |_| block_on_future(SlotFuture(slot)))
+        };
+        let setter = {
+            let slot = Arc::clone(&slot);
+            spawn_with_handle(move |_| {
+                for _ in 0..10 {
+                    yield_now();
+                }
+                slot.set(7);
+            })
+        };
+        setter.join().unwrap();
+        assert_eq!(waiter.join().unwrap(), 7);
+
+        // Woken by an OS thread
+        let slot = Arc::new(Slot::default());
+        let waiter = {
+            let slot = Arc::clone(&slot);
+            spawn_with_handle(move |_| block_on_future(SlotFuture(slot)))
+        };
+        thread::sleep(Duration::from_millis(20));
+        slot.set(9);
+        assert_eq!(waiter.join().unwrap(), 9);
+
+        // Wakes during its own poll: polled again rather than blocked
+        let waiter = spawn_with_handle(|_| block_on_future(SelfWaking(100)));
+        assert_eq!(waiter.join().unwrap(), 42);
+    });
+}
@@ -0,0 +1,34 @@
+//! `Runtime::block_on_until_idle` waits for unjoined GVThreads
+
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use gvthread::{sleep_ms, spawn};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[test]
+fn returns_when_the_last_gvthread_finishes() {
+    let mut runtime = common::runtime(2);
+    let done = Arc::new(AtomicUsize::new(0));
+
+    let started = Instant::now();
+    let counter = Arc::clone(&done);
+    runtime.block_on_until_idle(move || {
+        // Never joined; the last one finishes after ~200ms
+        for i in 1..=20u64 {
+            let counter = Arc::clone(&counter);
+            spawn(move |_| {
+                sleep_ms(10 * i);
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+    });
+    let elapsed = started.elapsed();
+
+    assert_eq!(done.load(Ordering::SeqCst), 20);
+    assert!(elapsed >= Duration::from_millis(200), "returned early: {:?}", elapsed);
+    assert!(elapsed < Duration::from_secs(5), "slow to notice idle: {:?}", elapsed);
+}
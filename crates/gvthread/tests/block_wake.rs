@@ -0,0 +1,69 @@
+//! Blocking and waking GVThreads back and forth across workers
+
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use gvthread::{spawn_with_handle, GVThreadId};
+use gvthread_runtime::scheduler::{block_current, current_waiter, wake_waiter};
+use std::sync::{Arc, Mutex};
+
+const ROUNDS: u64 = 100_000;
+const PAIRS: usize = 4;
+
+#[derive(Default)]
+struct Baton {
+    turn: u64,
+    waiters: [Option<(GVThreadId, u32)>; 2],
+}
+
+/// Wait for our turn, then pass the baton to the other side
+fn play(me: usize, baton: &Mutex<Baton>) {
+    for _ in 0..ROUNDS {
+        loop {
+            let mut b = baton.lock().unwrap();
+            if b.turn % 2 == me as u64 {
+                break;
+            }
+            // Registered under the lock, so the other side sees us
+            // only once we're committed to blocking
+            b.waiters[me] = current_waiter();
+            drop(b);
+            block_current();
+        }
+        let waiter = {
+            let mut b = baton.lock().unwrap();
+            b.turn += 1;
+            b.waiters[1 - me].take()
+        };
+        if let Some((id, generation)) = waiter {
+            wake_waiter(id, generation);
+        }
+    }
+}
+
+// A waker that requeues a GVThread before `block_current()` has saved
+// its registers lets another worker resume a stale context; thousands of
+// hand-offs across several workers make that window show up.
+#[test]
+fn ping_pong_between_workers() {
+    let mut runtime = common::runtime(4);
+    let batons: Vec<_> = (0..PAIRS)
+        .map(|_| Arc::new(Mutex::new(Baton::default())))
+        .collect();
+
+    runtime.block_on(|| {
+        let players: Vec<_> = batons
+            .iter()
+            .flat_map(|baton| (0..2).map(move |me| (me, Arc::clone(baton))))
+            .map(|(me, baton)| spawn_with_handle(move |_| play(me, &baton)))
+            .collect();
+        for player in players {
+            player.join().unwrap();
+        }
+    });
+
+    for baton in &batons {
+        assert_eq!(baton.lock().unwrap().turn, 2 * ROUNDS);
+    }
+}
@@ -0,0 +1,87 @@
+//! Channel halves shared by GVThreads on several workers at once
+
+mod common;
+
+use gvthread::{channel, spawn_with_handle, yield_now, Runtime};
+use std::time::{Duration, Instant};
+
+const PRODUCERS: u64 = 8;
+const CONSUMERS: usize = 4;
+const PER_PRODUCER: u64 = 1_000;
+
+/// How long a producer retries a full channel before yielding
+const SPIN_FOR: Duration = Duration::from_millis(2);
+
+/// Nothing to do until the other side runs: let the GVThreads queued
+/// here run, and the OS run the other workers (with fewer CPUs than
+/// workers, the other side may be on one that is waiting for a CPU)
+fn back_off() {
+    yield_now();
+    std::thread::yield_now();
+}
+
+// The same hammering on plain threads (which ThreadSanitizer can follow)
+// is in `gvthread_core::channel`'s unit tests. Only `try_send`/`try_recv`:
+// blocking `send`/`recv` still wait by spinning the worker, so with more
+// waiters than workers they can starve the GVThreads they wait for.
+#[test]
+fn cloned_halves_across_workers_lose_and_duplicate_nothing() {
+    // Short slices, so forced preemption lands inside channel calls too
+    let config = common::preempting(4, Duration::from_millis(1)).forced_switch(true);
+    let mut runtime = Runtime::new(config);
+
+    runtime.block_on(|| {
+        // Small, so both full and empty are hit constantly
+        let (tx, rx) = channel::<u64>(8);
+
+        let producers: Vec<_> = (0..PRODUCERS)
+            .map(|p| {
+                let tx = tx.clone();
+                spawn_with_handle(move |_| {
+                    for i in 0..PER_PRODUCER {
+                        let mut value = p * PER_PRODUCER + i;
+                        // Retries past its time slice before backing off:
+                        // forced preemption catches it mid-`try_send`
+                        let started = Instant::now();
+                        while let Err(e) = tx.try_send(value) {
+                            value = e.0;
+                            if started.elapsed() > SPIN_FOR {
+                                back_off();
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+        drop(tx);
+
+        let consumers: Vec<_> = (0..CONSUMERS)
+            .map(|_| {
+                let rx = rx.clone();
+                spawn_with_handle(move |_| {
+                    let mut got = Vec::new();
+                    loop {
+                        match rx.try_recv() {
+                            Ok(v) => got.push(v),
+                            // Closed first, then empty: nothing more can come
+                            Err(_) if rx.is_closed() => {
+                                got.extend(rx.try_iter());
+                                return got;
+                            }
+                            Err(_) => back_off(),
+                        }
+                    }
+                })
+            })
+            .collect();
+        drop(rx);
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let mut got: Vec<u64> = consumers.into_iter().flat_map(|c| c.join().unwrap()).collect();
+        got.sort_unstable();
+        assert_eq!(got.len() as u64, PRODUCERS * PER_PRODUCER, "lost or duplicated messages");
+        assert!(got.into_iter().eq(0..PRODUCERS * PER_PRODUCER));
+    });
+}
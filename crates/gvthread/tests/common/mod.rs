@@ -0,0 +1,28 @@
+//! Setup shared by the integration tests (see `tests/README.md`)
+
+// Each test binary compiles its own copy and uses only some of it
+#![allow(dead_code)]
+
+use gvthread::{Runtime, SchedulerConfig};
+use std::time::Duration;
+
+/// `workers` workers, none of them reserved for low priority
+pub fn config(workers: usize) -> SchedulerConfig {
+    SchedulerConfig::default()
+        .num_workers(workers)
+        .num_low_priority_workers(0)
+}
+
+/// `config(workers)` with SIGURG sent to a GVThread still running 1 ms
+/// after its `time_slice` ran out
+pub fn preempting(workers: usize, time_slice: Duration) -> SchedulerConfig {
+    config(workers)
+        .time_slice(time_slice)
+        .grace_period(Duration::from_millis(1))
+        .enable_forced_preempt(true)
+}
+
+/// A runtime on `config(workers)`
+pub fn runtime(workers: usize) -> Runtime {
+    Runtime::new(config(workers))
+}
@@ -0,0 +1,84 @@
+//! `SchedCondvar` with producers and consumers waiting as GVThreads
+
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use gvthread::{spawn_with_handle, SchedCondvar, SchedMutex};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+const CAPACITY: usize = 4;
+const PAIRS: u32 = 4;
+const PER_PRODUCER: u32 = 500;
+
+struct BoundedBuffer {
+    items: SchedMutex<VecDeque<u32>>,
+    not_empty: SchedCondvar,
+    not_full: SchedCondvar,
+}
+
+impl BoundedBuffer {
+    fn push(&self, v: u32) {
+        let mut q = self.not_full
+            .wait_while(self.items.lock().unwrap(), |q| q.len() >= CAPACITY)
+            .unwrap();
+        q.push_back(v);
+        drop(q);
+        self.not_empty.notify_one();
+    }
+
+    fn pop(&self) -> u32 {
+        let mut q = self.not_empty
+            .wait_while(self.items.lock().unwrap(), |q| q.is_empty())
+            .unwrap();
+        let v = q.pop_front().unwrap();
+        drop(q);
+        self.not_full.notify_one();
+        v
+    }
+}
+
+#[test]
+fn bounded_buffer_between_gvthreads() {
+    // One worker: a waiter that blocked the OS thread instead of its
+    // GVThread would keep the notifier from ever running
+    let mut runtime = common::runtime(1);
+    let buf = Arc::new(BoundedBuffer {
+        items: SchedMutex::new(VecDeque::new()),
+        not_empty: SchedCondvar::new(),
+        not_full: SchedCondvar::new(),
+    });
+
+    let total = runtime.block_on(|| {
+        // Consumers first: they find the buffer empty and wait
+        let consumers: Vec<_> = (0..PAIRS)
+            .map(|_| {
+                let buf = Arc::clone(&buf);
+                spawn_with_handle(move |_| {
+                    (0..PER_PRODUCER).map(|_| buf.pop() as u64).sum::<u64>()
+                })
+            })
+            .collect();
+        let producers: Vec<_> = (0..PAIRS)
+            .map(|p| {
+                let buf = Arc::clone(&buf);
+                spawn_with_handle(move |_| {
+                    for i in 0..PER_PRODUCER {
+                        buf.push(p * PER_PRODUCER + i);
+                    }
+                })
+            })
+            .collect();
+
+        for p in producers {
+            p.join().unwrap();
+        }
+        consumers.into_iter().map(|c| c.join().unwrap()).sum::<u64>()
+    });
+
+    let n = (PAIRS * PER_PRODUCER) as u64;
+    assert_eq!(total, n * (n - 1) / 2);
+    assert_eq!(buf.not_empty.waiter_count(), 0);
+    assert_eq!(buf.not_full.waiter_count(), 0);
+}
@@ -0,0 +1,62 @@
+//! Forced (SIGURG) preemption of a GVThread that never reaches a safepoint
+
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use gvthread::{current_id, gvthread_stats, spawn_with_handle, yield_now, Runtime};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[test]
+fn cpu_bound_gvthread_is_preempted() {
+    let config = common::preempting(1, Duration::from_millis(2)).forced_switch(true);
+    let mut runtime = Runtime::new(config);
+    let counter = Arc::new(AtomicU64::new(0));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    runtime.block_on(|| {
+        // No yield, no blocking call: only SIGURG gets it off the worker.
+        // Gives up eventually so a failure doesn't hang the test.
+        let spinner = {
+            let (counter, stop) = (Arc::clone(&counter), Arc::clone(&stop));
+            spawn_with_handle(move |_| {
+                let started = Instant::now();
+                while !stop.load(Ordering::Relaxed) {
+                    let n = counter.fetch_add(1, Ordering::Relaxed);
+                    if n % 0x10000 == 0 && started.elapsed() > Duration::from_secs(10) {
+                        return false;
+                    }
+                }
+                // Counted when the timer signalled it, not when it flagged it
+                gvthread_stats(current_id()).unwrap().forced_preemptions > 0
+            })
+        };
+
+        // Shares the only worker with the spinner, so it runs only while
+        // the spinner is switched out: the counter must stand still
+        let observer = {
+            let (counter, stop) = (Arc::clone(&counter), Arc::clone(&stop));
+            spawn_with_handle(move |_| {
+                while counter.load(Ordering::Relaxed) == 0 {
+                    yield_now();
+                }
+                let before = counter.load(Ordering::Relaxed);
+                let busy = Instant::now();
+                while busy.elapsed() < Duration::from_millis(1) {
+                    std::hint::spin_loop();
+                }
+                let after = counter.load(Ordering::Relaxed);
+                stop.store(true, Ordering::Relaxed);
+                (before, after)
+            })
+        };
+
+        let (before, after) = observer.join().unwrap();
+        assert_eq!(before, after, "the spinner kept running on the only worker");
+        assert!(spinner.join().unwrap(), "the spinner was never preempted");
+    });
+
+    assert!(gvthread_runtime::signal::forced_switches() > 0);
+}
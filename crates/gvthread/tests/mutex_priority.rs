@@ -0,0 +1,73 @@
+//! A High `SchedMutex` waiter on a worker saturated with Normal GVThreads
+
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use gvthread::{
+    spawn, spawn_with_priority, yield_now, Priority, ReadyQueueKind, Runtime, SchedMutex,
+};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Normal GVThreads competing for the worker
+const SPINNERS: usize = 4;
+/// Times the holder yields the worker before releasing
+const HOLD_YIELDS: usize = 20;
+
+#[test]
+fn high_waiter_gets_the_lock_promptly() {
+    // A waiter that kept the worker until forced off would need a time
+    // slice per holder yield: over a second. One that yielded while the
+    // holder stayed queued at Normal would never get the lock.
+    let config = common::config(1)
+        .ready_queue(ReadyQueueKind::Priority)
+        .time_slice(Duration::from_millis(50))
+        .enable_forced_preempt(true);
+    let mut runtime = Runtime::new(config);
+    let mutex = Arc::new(SchedMutex::new(0u32));
+    let done = Arc::new(AtomicBool::new(false));
+    let waited_us = Arc::new(AtomicU64::new(0));
+
+    {
+        let (mutex, done, waited_us) = (Arc::clone(&mutex), Arc::clone(&done), Arc::clone(&waited_us));
+        runtime.block_on_until_idle(move || {
+            for _ in 0..SPINNERS {
+                let done = Arc::clone(&done);
+                spawn(move |_| {
+                    while !done.load(Ordering::Acquire) {
+                        yield_now();
+                    }
+                });
+            }
+
+            spawn(move |_| {
+                let mut guard = mutex.lock().unwrap();
+                // Spawned once the lock is held, so it never has to spin
+                // (at High) waiting for that
+                let waiter_mutex = Arc::clone(&mutex);
+                spawn_with_priority(
+                    move |_| {
+                        // Bounded, so a regression fails rather than hangs
+                        let start = Instant::now();
+                        if let Some(mut guard) = waiter_mutex.lock_timeout(Duration::from_secs(5)) {
+                            *guard += 1;
+                        }
+                        waited_us.store(start.elapsed().as_micros() as u64, Ordering::Release);
+                        done.store(true, Ordering::Release);
+                    },
+                    Priority::High,
+                );
+                for _ in 0..HOLD_YIELDS {
+                    yield_now();
+                }
+                *guard += 1;
+            });
+        });
+    }
+
+    let waited = Duration::from_micros(waited_us.load(Ordering::Acquire));
+    assert!(waited < Duration::from_millis(250), "High waiter waited {:?}", waited);
+    assert_eq!(*mutex.lock().unwrap(), 2);
+}
@@ -0,0 +1,61 @@
+//! A user panic hook sees GVThread panics, even on small stacks
+
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use gvthread::{spawn_with_handle, JoinError, Runtime};
+use std::backtrace::Backtrace;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn user_hook_fires_for_small_stack_gvthreads() {
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let healthy = Arc::new(AtomicUsize::new(0));
+    {
+        let messages = Arc::clone(&messages);
+        std::panic::set_hook(Box::new(move |info| {
+            // What overflows a small stack if run on it
+            let trace = Backtrace::force_capture().to_string();
+            let message = info.payload().downcast_ref::<&str>().copied().unwrap_or_default();
+            let thread = std::thread::current().name().unwrap_or_default().to_string();
+            messages.lock().unwrap().push((message.to_string(), thread, !trace.is_empty()));
+        }));
+    }
+
+    // Too small a stack to symbolize a backtrace on
+    let config = common::config(1).stack_size(64 * 1024);
+    let mut runtime = Runtime::new(config);
+    let counter = Arc::clone(&healthy);
+    runtime.block_on(move || {
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let counter = Arc::clone(&counter);
+                spawn_with_handle(move |_| {
+                    if i % 2 == 0 {
+                        panic!("hooked boom");
+                    }
+                    counter.fetch_add(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for (i, handle) in handles.into_iter().enumerate() {
+            match handle.join() {
+                Err(JoinError::Panic(_)) => assert_eq!(i % 2, 0),
+                Ok(()) => assert_eq!(i % 2, 1),
+                other => panic!("unexpected {:?}", other),
+            }
+        }
+    });
+    let _ = std::panic::take_hook();
+
+    assert_eq!(healthy.load(Ordering::SeqCst), 2);
+    let messages = messages.lock().unwrap();
+    assert_eq!(messages.len(), 2, "{:?}", messages);
+    for (message, thread, traced) in messages.iter() {
+        assert_eq!(message, "hooked boom");
+        assert!(thread.starts_with("gvthread-"), "{}", thread);
+        assert!(traced);
+    }
+}
@@ -0,0 +1,44 @@
+//! `println!` from GVThreads that SIGURG keeps catching on one worker
+
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use gvthread::{current_id, gvthread_stats, spawn_with_handle, yield_now, Runtime};
+use std::time::{Duration, Instant};
+
+const GVTHREADS: usize = 4;
+const ROUNDS: usize = 3;
+/// Printing without a safepoint for this long outlasts slice + grace
+const PRINT_FOR: Duration = Duration::from_millis(5);
+
+#[test]
+fn println_survives_forced_preemption() {
+    // Default `forced_switch`: SIGURG only flags the GVThread, so one
+    // caught inside `println!` still releases stdout before the next runs
+    let config = common::preempting(1, Duration::from_millis(1));
+    let mut runtime = Runtime::new(config);
+
+    let signalled: u64 = runtime.block_on(|| {
+        let handles: Vec<_> = (0..GVTHREADS)
+            .map(|i| {
+                spawn_with_handle(move |_| {
+                    for round in 0..ROUNDS {
+                        let started = Instant::now();
+                        let mut line = 0;
+                        while started.elapsed() < PRINT_FOR {
+                            println!("gvthread {i} round {round} line {line}");
+                            line += 1;
+                        }
+                        yield_now();
+                    }
+                    gvthread_stats(current_id()).unwrap().forced_preemptions
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().expect("println! panicked")).sum()
+    });
+
+    assert!(signalled > 0, "no GVThread was signalled mid-print");
+    assert_eq!(gvthread_runtime::signal::forced_switches(), 0);
+}
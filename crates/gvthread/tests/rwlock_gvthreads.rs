@@ -0,0 +1,92 @@
+//! `SchedRwLock` with readers and writers blocking as GVThreads
+
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use gvthread::{spawn_with_handle, yield_now, SchedRwLock};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+const READERS: usize = 4;
+
+/// Yield until `cond` holds (bounded, so a failure doesn't hang)
+fn yield_until(cond: impl Fn() -> bool) -> bool {
+    for _ in 0..100_000 {
+        if cond() {
+            return true;
+        }
+        yield_now();
+    }
+    false
+}
+
+#[test]
+fn queued_readers_share_then_writer_follows() {
+    // One worker: a waiter that blocked the OS thread instead of its
+    // GVThread would keep the holder from ever running again
+    let mut runtime = common::runtime(1);
+
+    runtime.block_on(|| {
+        let lock = Arc::new(SchedRwLock::new(0u32));
+        let queued = Arc::new(AtomicUsize::new(0));
+        let inside = Arc::new(AtomicUsize::new(0));
+        let readers_done = Arc::new(AtomicUsize::new(0));
+
+        let first_writer = {
+            let (lock, queued) = (Arc::clone(&lock), Arc::clone(&queued));
+            let (inside, readers_done) = (Arc::clone(&inside), Arc::clone(&readers_done));
+            spawn_with_handle(move |_| {
+                let mut w = lock.write().unwrap();
+
+                // Readers first, then a second writer, all behind us
+                let readers: Vec<_> = (0..READERS)
+                    .map(|_| {
+                        let (lock, queued) = (Arc::clone(&lock), Arc::clone(&queued));
+                        let (inside, readers_done) =
+                            (Arc::clone(&inside), Arc::clone(&readers_done));
+                        spawn_with_handle(move |_| {
+                            queued.fetch_add(1, Ordering::SeqCst);
+                            let r = lock.read().unwrap();
+                            inside.fetch_add(1, Ordering::SeqCst);
+                            // All readers hold the lock at once
+                            let shared = yield_until(|| inside.load(Ordering::SeqCst) == READERS);
+                            let seen = *r;
+                            drop(r);
+                            readers_done.fetch_add(1, Ordering::SeqCst);
+                            (shared, seen)
+                        })
+                    })
+                    .collect();
+                let second_writer = {
+                    let (lock, queued) = (Arc::clone(&lock), Arc::clone(&queued));
+                    let readers_done = Arc::clone(&readers_done);
+                    spawn_with_handle(move |_| {
+                        queued.fetch_add(1, Ordering::SeqCst);
+                        let mut w = lock.write().unwrap();
+                        *w = 2;
+                        readers_done.load(Ordering::SeqCst)
+                    })
+                };
+
+                // Everyone is blocked on us, yet we keep running
+                assert!(yield_until(|| queued.load(Ordering::SeqCst) == READERS + 1));
+                for _ in 0..10 {
+                    yield_now();
+                }
+                assert_eq!(inside.load(Ordering::SeqCst), 0);
+                *w = 1;
+                drop(w);
+
+                let seen: Vec<_> = readers.into_iter().map(|r| r.join().unwrap()).collect();
+                (seen, second_writer.join().unwrap())
+            })
+        };
+
+        let (seen, readers_before_second_writer) = first_writer.join().unwrap();
+        assert_eq!(seen, vec![(true, 1); READERS]);
+        assert_eq!(readers_before_second_writer, READERS);
+        assert_eq!(*lock.read().unwrap(), 2);
+        assert!(!lock.is_write_locked());
+    });
+}
@@ -0,0 +1,103 @@
+//! `scope` from a GVThread: borrowed stack data, and panicking children
+
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use gvthread::{scope, spawn_with_handle, yield_now};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const CHILDREN: usize = 8;
+
+/// Counts its drops into borrowed data
+struct DropCounter<'a>(&'a AtomicUsize);
+
+impl Drop for DropCounter<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn scoped_gvthreads_borrow_and_join() {
+    let mut runtime = common::runtime(2);
+
+    runtime.block_on(|| {
+        spawn_with_handle(|_| {
+            borrows_stack_data();
+            panicking_child_joins_the_rest();
+        })
+        .join()
+        .unwrap();
+    });
+}
+
+fn borrows_stack_data() {
+    let input: Vec<usize> = (0..CHILDREN).collect();
+    let mut output = vec![0; CHILDREN];
+    let dropped = AtomicUsize::new(0);
+    let input = &input;
+
+    scope(|s| {
+        for (slot, value) in output.iter_mut().zip(input) {
+            let counter = DropCounter(&dropped);
+            s.spawn(move |_| {
+                let _counter = counter;
+                yield_now();
+                // Read a neighbour's input and spawn a grandchild into the scope
+                let next = input[(value + 1) % CHILDREN];
+                s.spawn(move |_| next * 100);
+                *slot = value * 10 + next;
+            });
+        }
+    });
+
+    let expected: Vec<usize> = (0..CHILDREN).map(|i| i * 10 + (i + 1) % CHILDREN).collect();
+    assert_eq!(output, expected);
+    // Every closure's captures were dropped before `scope` returned
+    assert_eq!(dropped.load(Ordering::SeqCst), CHILDREN);
+}
+
+fn panicking_child_joins_the_rest() {
+    let finished = AtomicUsize::new(0);
+    let dropped = AtomicUsize::new(0);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        scope(|s| {
+            s.spawn(|_| panic!("scoped boom"));
+            for _ in 1..CHILDREN {
+                let counter = DropCounter(&dropped);
+                s.spawn(|_| {
+                    let _counter = counter;
+                    for _ in 0..10 {
+                        yield_now();
+                    }
+                    finished.fetch_add(1, Ordering::SeqCst);
+                });
+            }
+        });
+    }));
+
+    assert!(result.is_err(), "the unjoined panic reached the caller");
+    // Only after every other child had finished and dropped its captures
+    assert_eq!(finished.load(Ordering::SeqCst), CHILDREN - 1);
+    assert_eq!(dropped.load(Ordering::SeqCst), CHILDREN - 1);
+
+    // The scope closure itself panicking: same, with its own payload
+    let finished = AtomicUsize::new(0);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        scope(|s| {
+            for _ in 0..CHILDREN {
+                s.spawn(|_| {
+                    yield_now();
+                    finished.fetch_add(1, Ordering::SeqCst);
+                });
+            }
+            panic!("scope body boom");
+        })
+    }));
+    let payload = result.unwrap_err();
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"scope body boom"));
+    assert_eq!(finished.load(Ordering::SeqCst), CHILDREN);
+}
@@ -0,0 +1,34 @@
+//! Shutdown frees the closures of GVThreads that never ran
+
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use gvthread::{spawn_with_handle, JoinError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn shutdown_reclaims_unrun_gvthreads() {
+    let mut runtime = common::runtime(1);
+    let captured = Arc::new(());
+    let ran = Arc::new(AtomicBool::new(false));
+
+    {
+        let (captured, ran) = (Arc::clone(&captured), Arc::clone(&ran));
+        runtime.spawn(move |_| {
+            let _captured = captured;
+            ran.store(true, Ordering::SeqCst);
+        });
+    }
+    let joined = {
+        let captured = Arc::clone(&captured);
+        spawn_with_handle(move |_| captured)
+    };
+    assert_eq!(Arc::strong_count(&captured), 3);
+
+    runtime.shutdown();
+    assert!(!ran.load(Ordering::SeqCst));
+    assert_eq!(Arc::strong_count(&captured), 1);
+    assert!(matches!(joined.join(), Err(JoinError::Cancelled)));
+}
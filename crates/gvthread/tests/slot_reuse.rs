@@ -0,0 +1,83 @@
+//! Stale wakes while slots are reused as fast as they are released
+
+mod common;
+
+use gvthread::{spawn_with_handle, yield_now, Priority, Runtime};
+use gvthread_runtime::scheduler;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+const ROUNDS: usize = 2_000;
+
+#[test]
+fn stale_wakes_never_reach_the_next_occupant() {
+    let config = common::config(2)
+        // Few slots: every spawn reuses one a finished GVThread left
+        .max_gvthreads(8)
+        // Finished slots keep their old metadata until reused
+        .lazy_stack_free(true);
+    let mut runtime = Runtime::new(config);
+
+    // `(id, generation)` of every GVThread that has finished
+    let stale = Arc::new(Mutex::new(Vec::new()));
+    let done = Arc::new(AtomicBool::new(false));
+    let stale_wakes = Arc::new(AtomicUsize::new(0));
+
+    // Wakes finished GVThreads again and again; each one's slot is
+    // likely someone else's by now
+    let waker = {
+        let (stale, done, stale_wakes) = (Arc::clone(&stale), Arc::clone(&done), Arc::clone(&stale_wakes));
+        thread::spawn(move || {
+            while !done.load(Ordering::Relaxed) {
+                let targets: Vec<_> = stale.lock().unwrap().clone();
+                for (id, generation) in targets {
+                    scheduler::wake_gvthread_checked(id, Priority::Normal, generation);
+                    stale_wakes.fetch_add(1, Ordering::Relaxed);
+                }
+                thread::yield_now();
+            }
+        })
+    };
+
+    let misrouted = runtime.block_on(move || {
+        let mut misrouted = 0;
+        for _ in 0..ROUNDS {
+            let released = Arc::new(AtomicBool::new(false));
+            let waiter = Arc::new(Mutex::new(None));
+            let blocked = {
+                let (released, waiter) = (Arc::clone(&released), Arc::clone(&waiter));
+                spawn_with_handle(move |_| {
+                    *waiter.lock().unwrap() = scheduler::current_waiter();
+                    scheduler::block_current();
+                    // Only our own waker may get us here
+                    released.load(Ordering::Acquire)
+                })
+            };
+
+            let (id, generation) = loop {
+                if let Some(me) = *waiter.lock().unwrap() {
+                    break me;
+                }
+                yield_now();
+            };
+            // Room for a stale wake to land while it is blocked
+            for _ in 0..4 {
+                yield_now();
+                thread::yield_now();
+            }
+            released.store(true, Ordering::Release);
+            scheduler::wake_waiter(id, generation);
+            if !blocked.join().unwrap() {
+                misrouted += 1;
+            }
+            stale.lock().unwrap().push((id, generation));
+        }
+        misrouted
+    });
+
+    done.store(true, Ordering::Relaxed);
+    waker.join().unwrap();
+    assert_eq!(misrouted, 0, "stale wakes resumed a later GVThread in the same slot");
+    assert!(stale_wakes.load(Ordering::Relaxed) > 0);
+}
@@ -0,0 +1,29 @@
+//! `Runtime::spawn` before the scheduler starts
+
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use gvthread::GVThreadId;
+use gvthread_runtime::scheduler::current_metadata;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn spawn_before_start_runs_once_started() {
+    let mut runtime = common::runtime(1);
+    let completed = Arc::new(AtomicUsize::new(0));
+
+    // Queued until the workers come up
+    let counter = Arc::clone(&completed);
+    runtime.spawn(move |_| {
+        let meta = current_metadata().unwrap();
+        assert_eq!(meta.parent_id.load(Ordering::Relaxed), GVThreadId::NONE.as_u32());
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+    assert_eq!(completed.load(Ordering::SeqCst), 0);
+
+    runtime.block_on_until_idle(|| ());
+    assert_eq!(completed.load(Ordering::SeqCst), 1);
+    assert_eq!(Arc::strong_count(&completed), 1);
+}
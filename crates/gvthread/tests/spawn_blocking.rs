@@ -0,0 +1,51 @@
+//! Joining `spawn_blocking` work from a GVThread
+
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use gvthread::{spawn_blocking, spawn_with_handle, yield_now};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[test]
+fn join_parks_only_the_calling_gvthread() {
+    let mut runtime = common::runtime(1);
+    let ticks = Arc::new(AtomicU64::new(0));
+    let done = Arc::new(AtomicBool::new(false));
+
+    let (during, value) = runtime.block_on(|| {
+        // Shares the only worker with the joiner
+        let ticker = {
+            let (ticks, done) = (Arc::clone(&ticks), Arc::clone(&done));
+            spawn_with_handle(move |_| {
+                while !done.load(Ordering::Acquire) {
+                    ticks.fetch_add(1, Ordering::Relaxed);
+                    yield_now();
+                }
+            })
+        };
+        let joiner = {
+            let (ticks, done) = (Arc::clone(&ticks), Arc::clone(&done));
+            spawn_with_handle(move |_| {
+                let before = ticks.load(Ordering::Relaxed);
+                let value = spawn_blocking(|| {
+                    std::thread::sleep(Duration::from_millis(50));
+                    42
+                })
+                .join()
+                .unwrap();
+                let during = ticks.load(Ordering::Relaxed) - before;
+                done.store(true, Ordering::Release);
+                (during, value)
+            })
+        };
+        let result = joiner.join().unwrap();
+        ticker.join().unwrap();
+        result
+    });
+
+    assert_eq!(value, 42);
+    assert!(during > 0, "the worker was blocked while joining");
+}
@@ -0,0 +1,57 @@
+//! GVThreads that allocate and spawn further GVThreads
+
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use gvthread::{spawn_with_handle, yield_now};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+const FANOUT: usize = 4;
+const DEPTH: u32 = 4;
+
+/// Spawn `FANOUT` children down to `DEPTH`, each allocating as it goes
+fn spawn_tree(depth: u32, done: Arc<AtomicUsize>) {
+    // Buffers held across yields, so other GVThreads allocate and free
+    // in between on the same workers
+    let mut names: Vec<String> = Vec::new();
+    let children: Vec<_> = (0..FANOUT)
+        .map(|i| {
+            names.push(format!("{}-{}", depth, i));
+            yield_now();
+            let done = Arc::clone(&done);
+            spawn_with_handle(move |_| {
+                if depth > 1 {
+                    spawn_tree(depth - 1, done);
+                } else {
+                    let buf = vec![i as u8; 4096];
+                    yield_now();
+                    assert!(buf.iter().all(|&b| b == i as u8));
+                    done.fetch_add(1, Ordering::Relaxed);
+                }
+            })
+        })
+        .collect();
+    for child in children {
+        child.join().unwrap();
+    }
+    assert_eq!(names.len(), FANOUT);
+}
+
+// Every server's accept loop spawns from a GVThread, boxing the closure
+// on the spawner's stack; the children allocate on theirs.
+#[test]
+fn nested_spawns_allocate_on_gvthread_stacks() {
+    let mut runtime = common::runtime(2);
+    let done = Arc::new(AtomicUsize::new(0));
+
+    let root = Arc::clone(&done);
+    runtime.block_on(move || {
+        spawn_with_handle(move |_| spawn_tree(DEPTH, root))
+            .join()
+            .unwrap();
+    });
+
+    assert_eq!(done.load(Ordering::Relaxed), FANOUT.pow(DEPTH));
+}
@@ -0,0 +1,63 @@
+//! Spawning with every slot in use, and during a graceful shutdown
+
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use gvthread::{
+    slot_usage, spawn, spawn_with_handle, try_spawn, yield_now, GVThreadId, Runtime, SchedError,
+};
+use gvthread_runtime::scheduler::global_scheduler;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+const MAX: usize = 8;
+
+#[test]
+fn try_spawn_reports_exhaustion_and_shutdown() {
+    let config = common::config(2)
+        .max_gvthreads(MAX);
+    let mut runtime = Runtime::new(config);
+
+    runtime.block_on(|| {
+        // Fill every slot with a GVThread that waits for `release`
+        let release = Arc::new(AtomicBool::new(false));
+        let holders: Vec<_> = (0..MAX)
+            .map(|_| {
+                let release = Arc::clone(&release);
+                spawn_with_handle(move |_| {
+                    while !release.load(Ordering::Acquire) {
+                        yield_now();
+                        std::thread::yield_now();
+                    }
+                })
+            })
+            .collect();
+        assert_eq!(slot_usage(), (MAX, MAX));
+
+        let ran = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&ran);
+        let refused = try_spawn(move |_| flag.store(true, Ordering::SeqCst));
+        assert_eq!(refused, Err(SchedError::NoSlotsAvailable));
+
+        // Freed slots are handed out again
+        release.store(true, Ordering::Release);
+        for holder in holders {
+            holder.join().unwrap();
+        }
+        // join returns before the finished GVThread's slot is released
+        while slot_usage().0 > 0 {
+            std::thread::yield_now();
+        }
+        let flag = Arc::clone(&ran);
+        spawn_with_handle(move |_| flag.store(true, Ordering::SeqCst))
+            .join()
+            .unwrap();
+        assert!(ran.load(Ordering::SeqCst));
+
+        // Once draining, try_spawn says why; spawn keeps its NONE
+        global_scheduler().unwrap().stop_accepting();
+        assert_eq!(try_spawn(|_| ()), Err(SchedError::ShuttingDown));
+        assert_eq!(spawn(|_| ()), GVThreadId::NONE);
+    });
+}
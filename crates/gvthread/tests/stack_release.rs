@@ -0,0 +1,62 @@
+//! Finished GVThreads' stacks stop counting against RSS
+
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use gvthread::{spawn, yield_now, Runtime};
+use gvthread_runtime::memory::resident_kb;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+const GVTHREADS: usize = 64;
+/// Stack each GVThread dirties
+const TOUCHED: usize = 1024 * 1024;
+
+#[inline(never)]
+fn dirty_stack() {
+    let mut buf = [0u8; TOUCHED];
+    for page in buf.chunks_mut(4096) {
+        page[0] = 1;
+    }
+    std::hint::black_box(&mut buf);
+}
+
+#[test]
+fn lazily_freed_stacks_lower_rss() {
+    let config = common::config(1)
+        .max_gvthreads(256)
+        .lazy_stack_free(true);
+    let mut runtime = Runtime::new(config);
+    let touched = Arc::new(AtomicUsize::new(0));
+    let release = Arc::new(AtomicBool::new(false));
+    let mut rss_live = 0;
+
+    runtime.block_on_until_idle(|| {
+        for _ in 0..GVTHREADS {
+            let (touched, release) = (Arc::clone(&touched), Arc::clone(&release));
+            spawn(move |_| {
+                dirty_stack();
+                touched.fetch_add(1, Ordering::SeqCst);
+                while !release.load(Ordering::Acquire) {
+                    yield_now();
+                }
+            });
+        }
+        while touched.load(Ordering::SeqCst) < GVTHREADS {
+            std::thread::yield_now();
+        }
+        rss_live = resident_kb();
+        release.store(true, Ordering::Release);
+    });
+    let rss_done = resident_kb();
+
+    let stacks_kb = (GVTHREADS * TOUCHED / 1024) as u64;
+    assert!(
+        rss_live.saturating_sub(rss_done) > stacks_kb / 2,
+        "RSS {} KB with {} KB of live stacks, {} KB after they finished",
+        rss_live,
+        stacks_kb,
+        rss_done,
+    );
+}
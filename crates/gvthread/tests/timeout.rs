@@ -0,0 +1,65 @@
+//! `timeout` against ops that finish first, get cancelled, or ignore it
+
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use gvthread::{sleep_cancellable, spawn_with_handle, timeout, yield_now, Timeout};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[test]
+fn timeout_returns_by_the_deadline() {
+    let mut runtime = common::runtime(2);
+
+    runtime.block_on(|| {
+        spawn_with_handle(|_| {
+            // The op finishes first: its value, without waiting for the watchdog
+            let start = Instant::now();
+            assert_eq!(timeout(Duration::from_secs(10), |_| 7), Ok(7));
+            assert!(start.elapsed() < Duration::from_secs(5), "waited for the watchdog");
+
+            // The deadline fires: the op's sleep is interrupted
+            let interrupted = Arc::new(AtomicBool::new(false));
+            let start = Instant::now();
+            let result = {
+                let interrupted = Arc::clone(&interrupted);
+                timeout(Duration::from_millis(50), move |token| {
+                    if sleep_cancellable(Duration::from_secs(10), token).is_err() {
+                        interrupted.store(true, Ordering::Release);
+                    }
+                })
+            };
+            assert_eq!(result, Err(Timeout));
+            assert!(start.elapsed() < Duration::from_secs(5));
+            while !interrupted.load(Ordering::Acquire) {
+                yield_now();
+            }
+
+            // The op ignores its token: detached, the caller isn't held up
+            let release = Arc::new(AtomicBool::new(false));
+            let finished = Arc::new(AtomicBool::new(false));
+            let start = Instant::now();
+            let result = {
+                let (release, finished) = (Arc::clone(&release), Arc::clone(&finished));
+                timeout(Duration::from_millis(50), move |_| {
+                    while !release.load(Ordering::Acquire) {
+                        yield_now();
+                        std::thread::yield_now();
+                    }
+                    finished.store(true, Ordering::Release);
+                })
+            };
+            assert_eq!(result, Err(Timeout));
+            assert!(start.elapsed() < Duration::from_secs(5));
+            assert!(!finished.load(Ordering::Acquire), "waited for the uncooperative op");
+            release.store(true, Ordering::Release);
+            while !finished.load(Ordering::Acquire) {
+                yield_now();
+            }
+        })
+        .join()
+        .unwrap();
+    });
+}
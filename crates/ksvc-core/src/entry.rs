@@ -12,6 +12,10 @@ pub struct CorrId(pub u64);
 impl CorrId {
     pub const NONE: Self = Self(u64::MAX);
 
+    /// `user_data` of the backend's own `IORING_OP_ASYNC_CANCEL` SQEs.
+    /// Their completions carry no GVThread's result; skip them.
+    pub const CANCEL: Self = Self(u64::MAX - 1);
+
     #[inline]
     pub fn from_gvthread_id(id: u32) -> Self {
         Self(id as u64)
@@ -21,6 +25,62 @@ impl CorrId {
     pub fn as_gvthread_id(self) -> u32 {
         self.0 as u32
     }
+
+    /// Id tagged with an operation, for servers that drive the backend
+    /// directly: `[op:8][payload:56]`.
+    ///
+    /// `payload` is truncated to 56 bits. Op `0xFF` is reserved: `NONE`
+    /// and `CANCEL` both carry it.
+    #[inline]
+    pub const fn with_op(op: u8, payload: u64) -> Self {
+        Self(((op as u64) << OP_SHIFT) | (payload & PAYLOAD_MASK))
+    }
+
+    /// The op tag of a `with_op` id
+    #[inline]
+    pub const fn op(self) -> u8 {
+        (self.0 >> OP_SHIFT) as u8
+    }
+
+    /// The payload of a `with_op` id
+    #[inline]
+    pub const fn payload(self) -> u64 {
+        self.0 & PAYLOAD_MASK
+    }
+}
+
+const OP_SHIFT: u32 = 56;
+const PAYLOAD_MASK: u64 = (1 << OP_SHIFT) - 1;
+
+/// Optional layout of a `CorrId::with_op` payload for servers that do
+/// file I/O on behalf of a connection: `[state:8][file_idx:16][conn_idx:32]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ConnTag {
+    pub state: u8,
+    pub file_idx: u16,
+    pub conn_idx: u32,
+}
+
+impl ConnTag {
+    /// Tag carrying just a connection index
+    #[inline]
+    pub const fn conn(conn_idx: u32) -> Self {
+        Self { state: 0, file_idx: 0, conn_idx }
+    }
+
+    #[inline]
+    pub const fn pack(self) -> u64 {
+        ((self.state as u64) << 48) | ((self.file_idx as u64) << 32) | self.conn_idx as u64
+    }
+
+    #[inline]
+    pub const fn unpack(payload: u64) -> Self {
+        Self {
+            state: (payload >> 48) as u8,
+            file_idx: (payload >> 32) as u16,
+            conn_idx: payload as u32,
+        }
+    }
 }
 
 /// A syscall submission entry.
@@ -62,6 +122,9 @@ pub mod submit_flags {
     pub const LINKED: u32 = 1 << 0;
     /// Wait for all prior entries to complete before processing this one.
     pub const DRAIN: u32 = 1 << 1;
+    /// Latency-sensitive: worker pools run it ahead of queued entries
+    /// without this flag (e.g. a `setsockopt` behind slow `fsync`s).
+    pub const PRIORITY: u32 = 1 << 2;
 }
 
 /// Completion flags.
@@ -69,3 +132,23 @@ pub mod comp_flags {
     /// More completions are available (hint to keep polling).
     pub const MORE: u32 = 1 << 0;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn op_ids_round_trip() {
+        let id = CorrId::with_op(3, 0x00AB_CDEF_0123_4567);
+        assert_eq!((id.op(), id.payload()), (3, 0x00AB_CDEF_0123_4567));
+        // Payload bits above 56 never reach the op tag
+        assert_eq!(CorrId::with_op(1, u64::MAX).op(), 1);
+        assert_ne!(CorrId::with_op(0xFE, PAYLOAD_MASK), CorrId::NONE);
+
+        let tag = ConnTag { state: 0x7F, file_idx: 0xBEEF, conn_idx: u32::MAX };
+        let id = CorrId::with_op(6, tag.pack());
+        assert_eq!(id.op(), 6);
+        assert_eq!(ConnTag::unpack(id.payload()), tag);
+        assert_eq!(ConnTag::unpack(ConnTag::conn(42).pack()), ConnTag::conn(42));
+    }
+}
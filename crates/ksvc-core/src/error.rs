@@ -20,6 +20,10 @@ pub enum KsvcError {
     MmapFailed(i32),
     /// ioctl failed.
     IoctlFailed(i32),
+    /// An mmap'd ring's header doesn't describe the expected ring.
+    BadRing(&'static str),
+    /// A setup parameter, or combination of them, the backend can't use.
+    InvalidConfig(&'static str),
     /// OS error with errno.
     Os(i32),
 }
@@ -35,6 +39,8 @@ impl fmt::Display for KsvcError {
             Self::NotInitialized => write!(f, "KSVC instance not initialized"),
             Self::MmapFailed(e) => write!(f, "mmap failed: errno {}", e),
             Self::IoctlFailed(e) => write!(f, "ioctl failed: errno {}", e),
+            Self::BadRing(why) => write!(f, "bad ring header: {}", why),
+            Self::InvalidConfig(why) => write!(f, "invalid config: {}", why),
             Self::Os(e) => write!(f, "OS error: errno {}", e),
         }
     }
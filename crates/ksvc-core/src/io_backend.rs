@@ -23,7 +23,11 @@
 //! by implementing a composite backend, or by stacking decorators.
 
 use crate::entry::{CorrId, SubmitEntry};
-use crate::error::Result;
+use crate::error::{KsvcError, Result};
+
+/// Syscall number standing for "cancel": `Unsupported(CANCEL_SYSCALL_NR)`
+/// is what `IoBackend::cancel` returns on backends that can't cancel.
+pub const CANCEL_SYSCALL_NR: u32 = u32::MAX;
 
 /// A completed I/O operation from the backend.
 #[derive(Debug, Clone, Copy)]
@@ -38,7 +42,7 @@ pub struct IoCompletion {
 
 /// Async I/O submission and completion.
 ///
-/// The dispatcher calls `submit()` for each Tier 1 entry, then
+/// The dispatcher calls `submit_batch()` for each run of Tier 1 entries, then
 /// `flush()` once per batch to kick the backend, then `poll_completions()`
 /// to drain finished operations.
 ///
@@ -48,13 +52,33 @@ pub struct IoCompletion {
 pub trait IoBackend: Send + Sync {
     /// Submit a single I/O operation. Queued but not yet kicked.
     ///
-    /// The `entry` contains the syscall number and arguments.
-    /// The implementation translates this to the backend's native format
-    /// (e.g., io_uring SQE) and queues it.
+    /// The `entry` contains the syscall number and arguments, `opcode`
+    /// the native operation the router chose for it
+    /// (`RouteInfo::iouring_opcode`). The implementation translates the
+    /// entry to the backend's native format (e.g., io_uring SQE) and
+    /// queues it.
     ///
     /// Returns `Ok(())` if queued, `Err(RingFull)` if the backend's
-    /// submission queue is full.
-    fn submit(&mut self, entry: &SubmitEntry) -> Result<()>;
+    /// submission queue is full, `Err(Unsupported)` if it can't
+    /// translate the opcode.
+    fn submit(&mut self, entry: &SubmitEntry, opcode: u8) -> Result<()>;
+
+    /// Submit a run of operations in one call. Queued but not yet kicked.
+    ///
+    /// `opcodes[i]` is the routed opcode of `entries[i]`. Queues entries
+    /// from the front until the submission queue fills or an entry is
+    /// refused, and returns how many were queued. The caller learns why
+    /// `entries[n]` was refused by `submit()`ing it.
+    ///
+    /// The default loops over `submit()`; backends override it to check
+    /// queue space once per batch.
+    fn submit_batch(&mut self, entries: &[SubmitEntry], opcodes: &[u8]) -> usize {
+        entries
+            .iter()
+            .zip(opcodes)
+            .take_while(|(entry, &opcode)| self.submit(entry, opcode).is_ok())
+            .count()
+    }
 
     /// Kick all queued submissions to the kernel.
     ///
@@ -72,10 +96,20 @@ pub trait IoBackend: Send + Sync {
     /// **Must not block.** If no completions are ready, returns 0.
     fn poll_completions(&mut self, buf: &mut [IoCompletion], max: usize) -> usize;
 
-    /// Cancel an in-flight operation by correlation ID.
+    /// Cancel an in-flight operation by correlation ID. Queued but not
+    /// yet kicked.
+    ///
+    /// Best-effort — the operation may complete before cancellation takes
+    /// effect. If it doesn't, its completion arrives as usual with
+    /// `-ECANCELED`. io_uring backends submit `IORING_OP_ASYNC_CANCEL`
+    /// targeting `corr_id`; its own completion has `CorrId::CANCEL`.
     ///
-    /// Best-effort — the operation may complete before cancellation takes effect.
-    fn cancel(&mut self, corr_id: CorrId) -> Result<()>;
+    /// The default returns `Err(Unsupported(CANCEL_SYSCALL_NR))`, for
+    /// backends that can't cancel.
+    fn cancel(&mut self, corr_id: CorrId) -> Result<()> {
+        let _ = corr_id;
+        Err(KsvcError::Unsupported(CANCEL_SYSCALL_NR))
+    }
 
     /// How many operations are currently in-flight (submitted but not completed).
     fn inflight(&self) -> usize;
@@ -0,0 +1,58 @@
+//! Legacy fallback execution (Tier 3).
+//!
+//! A `LegacyExecutor` runs syscalls that the router could not place on
+//! io_uring or the worker pool, by calling `libc::syscall(nr, args...)`
+//! directly on a blocking thread. With one wired into the dispatcher,
+//! KSVC is a complete syscall proxy instead of answering `-ENOSYS`.
+//!
+//! # Implementors
+//!
+//! - `NoLegacy` (default): disabled. `ENABLED == false`, so the
+//!   dispatcher compiles the legacy path out and keeps writing `-ENOSYS`.
+//!
+//! - `LegacyThreadPool`: blocking OS thread(s) calling `libc::syscall`.
+
+use crate::entry::SubmitEntry;
+use crate::error::{KsvcError, Result};
+use crate::worker::WorkerCompletion;
+
+/// Executes Tier 3 (Legacy) syscalls with a plain `syscall()`.
+///
+/// **Contract:** same as `WorkerPool` — `submit()` must NEVER block the
+/// caller; results (real return value or `-errno`) are collected via
+/// `poll_completions()`.
+pub trait LegacyExecutor: Send + Sync {
+    /// `false` for the no-op executor; lets the dispatcher drop the
+    /// legacy path at compile time.
+    const ENABLED: bool = true;
+
+    /// Queue a syscall for execution. `Err(WorkerUnavailable)` if full,
+    /// `Err(Unsupported)` for a syscall the executor won't run.
+    fn submit(&self, entry: &SubmitEntry) -> Result<()>;
+
+    /// Poll for completed operations (non-blocking).
+    ///
+    /// Returns the number of completions written into `buf`.
+    fn poll_completions(&self, buf: &mut [WorkerCompletion], max: usize) -> usize;
+
+    /// Shut down the executor threads.
+    fn shutdown(&self);
+}
+
+/// Disabled legacy executor: Tier 3 entries complete with `-ENOSYS`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoLegacy;
+
+impl LegacyExecutor for NoLegacy {
+    const ENABLED: bool = false;
+
+    fn submit(&self, entry: &SubmitEntry) -> Result<()> {
+        Err(KsvcError::Unsupported(entry.syscall_nr))
+    }
+
+    fn poll_completions(&self, _buf: &mut [WorkerCompletion], _max: usize) -> usize {
+        0
+    }
+
+    fn shutdown(&self) {}
+}
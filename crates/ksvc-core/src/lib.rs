@@ -20,6 +20,7 @@ pub mod tier;
 pub mod router;
 pub mod io_backend;
 pub mod worker;
+pub mod legacy;
 pub mod notifier;
 pub mod buffer;
 pub mod shared_page;
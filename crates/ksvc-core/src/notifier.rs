@@ -9,9 +9,16 @@
 //!   The completion handler GVThread polls/reads the eventfd.
 //!   Simple, well-understood, compatible with epoll/io_uring poll.
 //!
-//! - `FutexNotifier` (future): wakes a futex word.
+//! - `FutexNotifier`: wakes a futex word in the completion ring header.
 //!   Lower overhead than eventfd for high-frequency notifications.
 //!   Requires the completion handler to futex_wait on the word.
+//!
+//! # Waking a ring
+//!
+//! A notifier with an `eventfd()` can also wake a thread blocked in
+//! `io_uring_enter`: the thread keeps a `POLL_ADD` on the eventfd in its
+//! ring, and `notify()` completes it. ksvc-gvthread's reactors use this
+//! to pick up requests queued while they wait for completions.
 
 use crate::error::Result;
 
@@ -25,4 +32,9 @@ use crate::error::Result;
 pub trait Notifier: Send + Sync {
     /// Signal that new completions are available.
     fn notify(&self) -> Result<()>;
+
+    /// The eventfd the consumer waits on, if notifications go through one.
+    fn eventfd(&self) -> Option<i32> {
+        None
+    }
 }
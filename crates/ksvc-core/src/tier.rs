@@ -17,6 +17,7 @@ pub enum Tier {
     WorkerPool = 2,
 
     /// Tier 3: Unsupported by KSVC. GVThread falls back to traditional
-    /// syscall() directly. Not submitted to the ring at all.
+    /// syscall() directly. If one does reach the ring, the dispatcher
+    /// runs it on its `LegacyExecutor` (or answers -ENOSYS without one).
     Legacy = 3,
 }
@@ -9,9 +9,9 @@
 //!   N = min(8, nproc/2). Threads share the process context.
 //!   Simple, predictable, safe.
 //!
-//! - `LazyPool` (future): starts with 1 thread, scales up on demand
-//!   when all workers are busy, scales down on idle.
-//!   Same strategy as io-wq in the kernel.
+//! - `LazyPool` (feature = "lazy-pool"): starts with `min_workers`
+//!   threads (default 0), scales up on demand when work queues up,
+//!   scales down on idle. Same strategy as io-wq in the kernel.
 //!
 //! - `InlineWorker` (testing): executes synchronously in the caller.
 //!   Only for unit tests — blocks the dispatcher!
@@ -39,6 +39,9 @@ pub trait WorkerPool: Send + Sync {
     ///
     /// Returns immediately. The worker will execute the syscall
     /// and make the result available via `poll_completions()`.
+    ///
+    /// Entries run in FIFO order, except that pools should start
+    /// entries flagged `submit_flags::PRIORITY` ahead of the others.
     fn enqueue(&self, entry: &SubmitEntry) -> Result<()>;
 
     /// Poll for completed worker operations (non-blocking).
@@ -12,9 +12,9 @@
 //!     5. For each entry:
 //!          route_table[syscall_nr] → Tier 1? submit to io_uring
 //!                                  → Tier 2? enqueue to worker pool
-//!                                  → else? write -ENOSYS completion
+//!                                  → else? legacy executor, or -ENOSYS
 //!     6. Flush io_uring SQEs
-//!     7. If no work → brief sleep
+//!     7. If no work → sleep (exponential backoff, reset on work)
 //! }
 //! ```
 //!
@@ -24,12 +24,14 @@
 use ksvc_core::entry::{CorrId, SubmitEntry, CompletionEntry};
 use ksvc_core::error::KsvcError;
 use ksvc_core::io_backend::{IoBackend, IoCompletion};
+use ksvc_core::legacy::LegacyExecutor;
 use ksvc_core::notifier::Notifier;
 use ksvc_core::router::SyscallRouter;
 use ksvc_core::tier::Tier;
 use ksvc_core::worker::{WorkerCompletion, WorkerPool};
+use ksvc_module::ksvc_sys::{KsvcRingHeader, KSVC_RING_MAGIC};
 
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
 
 /// Configuration for the dispatcher loop.
@@ -40,8 +42,31 @@ pub struct DispatcherConfig {
     pub max_io_completions: usize,
     /// Maximum worker completions to drain per iteration.
     pub max_worker_completions: usize,
-    /// Sleep duration (microseconds) when idle.
+    /// Idle sleep floor (microseconds). Used as the backoff floor when
+    /// `idle_sleep_min_us` is 0.
     pub idle_sleep_us: u64,
+    /// Backoff floor (microseconds): first sleep after work stops.
+    /// 0 = use `idle_sleep_us`.
+    pub idle_sleep_min_us: u64,
+    /// Backoff ceiling (microseconds): sleep never exceeds this.
+    pub idle_sleep_max_us: u64,
+}
+
+impl DispatcherConfig {
+    /// Effective backoff floor.
+    fn idle_floor_us(&self) -> u64 {
+        let floor = if self.idle_sleep_min_us > 0 {
+            self.idle_sleep_min_us
+        } else {
+            self.idle_sleep_us
+        };
+        floor.max(1)
+    }
+
+    /// Effective backoff ceiling (never below the floor).
+    fn idle_ceiling_us(&self) -> u64 {
+        self.idle_sleep_max_us.max(self.idle_floor_us())
+    }
 }
 
 impl Default for DispatcherConfig {
@@ -50,11 +75,287 @@ impl Default for DispatcherConfig {
             max_batch: 64,
             max_io_completions: 128,
             max_worker_completions: 64,
-            idle_sleep_us: 100, // 100μs
+            idle_sleep_us: 100,       // 100μs floor
+            idle_sleep_min_us: 0,     // → idle_sleep_us
+            idle_sleep_max_us: 1_000, // 1ms ceiling
+        }
+    }
+}
+
+/// Dispatcher counters, shared with observers via `Arc<DispatcherStats>`.
+///
+/// The loop accumulates per-iteration counts locally and publishes them
+/// once per iteration with relaxed adds, so atomic traffic stays off the
+/// per-entry hot path. Read from any thread with `snapshot()`.
+#[derive(Default)]
+pub struct DispatcherStats {
+    /// Submit ring entries dequeued and routed.
+    pub submit_entries: AtomicU64,
+    /// io_uring completions drained into the completion ring.
+    pub io_completions: AtomicU64,
+    /// Worker pool completions drained into the completion ring.
+    pub worker_completions: AtomicU64,
+    /// Entries handed to the `LegacyExecutor`.
+    pub legacy_entries: AtomicU64,
+    /// Legacy executor completions drained into the completion ring.
+    pub legacy_completions: AtomicU64,
+    /// `Notifier::notify()` calls.
+    pub notifies: AtomicU64,
+    /// -EAGAIN completions written (io_uring SQ or worker pool full).
+    pub eagain_completions: AtomicU64,
+    /// -ENOSYS completions written (unroutable or backend error).
+    pub enosys_completions: AtomicU64,
+    /// Iterations that found no work (and slept).
+    pub idle_iterations: AtomicU64,
+    /// Completions dropped because the completion ring was full.
+    ///
+    /// Should stay 0: the dispatcher pre-checks ring space (see
+    /// `dispatcher_loop`). A non-zero value means a GVThread is waiting
+    /// on a reply that will never arrive.
+    pub completion_push_failed: AtomicU64,
+    /// Current idle backoff level (debug gauge, not a counter).
+    ///
+    /// 0 = working (or first idle sleep at the floor); N = sleeping
+    /// `floor << N` µs, capped at the ceiling. For tuning
+    /// `idle_sleep_min_us` / `idle_sleep_max_us`.
+    pub idle_backoff_level: AtomicU32,
+}
+
+/// Point-in-time copy of `DispatcherStats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DispatcherStatsSnapshot {
+    pub submit_entries: u64,
+    pub io_completions: u64,
+    pub worker_completions: u64,
+    pub legacy_entries: u64,
+    pub legacy_completions: u64,
+    pub notifies: u64,
+    pub eagain_completions: u64,
+    pub enosys_completions: u64,
+    pub idle_iterations: u64,
+    pub completion_push_failed: u64,
+    pub idle_backoff_level: u32,
+}
+
+impl DispatcherStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read all counters (each individually relaxed; not a consistent cut).
+    pub fn snapshot(&self) -> DispatcherStatsSnapshot {
+        DispatcherStatsSnapshot {
+            submit_entries: self.submit_entries.load(Ordering::Relaxed),
+            io_completions: self.io_completions.load(Ordering::Relaxed),
+            worker_completions: self.worker_completions.load(Ordering::Relaxed),
+            legacy_entries: self.legacy_entries.load(Ordering::Relaxed),
+            legacy_completions: self.legacy_completions.load(Ordering::Relaxed),
+            notifies: self.notifies.load(Ordering::Relaxed),
+            eagain_completions: self.eagain_completions.load(Ordering::Relaxed),
+            enosys_completions: self.enosys_completions.load(Ordering::Relaxed),
+            idle_iterations: self.idle_iterations.load(Ordering::Relaxed),
+            completion_push_failed: self.completion_push_failed.load(Ordering::Relaxed),
+            idle_backoff_level: self.idle_backoff_level.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Per-iteration counts, published to `DispatcherStats` in one go.
+#[derive(Default)]
+struct IterCounts {
+    submit_entries: u64,
+    io_completions: u64,
+    worker_completions: u64,
+    legacy_entries: u64,
+    legacy_completions: u64,
+    notifies: u64,
+    eagain_completions: u64,
+    enosys_completions: u64,
+    idle_iterations: u64,
+    completion_push_failed: u64,
+}
+
+impl IterCounts {
+    /// Add non-zero counts to `stats` and reset.
+    fn publish(&mut self, stats: &DispatcherStats) {
+        #[inline]
+        fn add(counter: &AtomicU64, n: &mut u64) {
+            if *n > 0 {
+                counter.fetch_add(*n, Ordering::Relaxed);
+                *n = 0;
+            }
+        }
+        add(&stats.submit_entries, &mut self.submit_entries);
+        add(&stats.io_completions, &mut self.io_completions);
+        add(&stats.worker_completions, &mut self.worker_completions);
+        add(&stats.legacy_entries, &mut self.legacy_entries);
+        add(&stats.legacy_completions, &mut self.legacy_completions);
+        add(&stats.notifies, &mut self.notifies);
+        add(&stats.eagain_completions, &mut self.eagain_completions);
+        add(&stats.enosys_completions, &mut self.enosys_completions);
+        add(&stats.idle_iterations, &mut self.idle_iterations);
+        add(&stats.completion_push_failed, &mut self.completion_push_failed);
+    }
+
+    /// Write drained io_uring completions; any that didn't fit count as
+    /// failed pushes (they were already taken from the backend).
+    #[inline]
+    fn write_io(&mut self, ring: &mut CompletionRing, completions: &[IoCompletion]) {
+        let written = ring.push_iter(completions.iter().map(|c| (c.corr_id, c.result, c.flags)));
+        self.completion_push_failed += (completions.len() - written) as u64;
+    }
+
+    /// `write_io` for worker pool / legacy executor completions.
+    #[inline]
+    fn write_worker(&mut self, ring: &mut CompletionRing, completions: &[WorkerCompletion]) {
+        let written = ring.push_iter(completions.iter().map(|c| (c.corr_id, c.result, 0)));
+        self.completion_push_failed += (completions.len() - written) as u64;
+    }
+}
+
+/// Hand an entry the router/backend can't place to the legacy executor.
+///
+/// Without one (`NoLegacy`) this compiles down to the -ENOSYS write.
+/// Like the other routes it writes at most one immediate completion.
+#[inline]
+fn route_legacy<L: LegacyExecutor>(
+    legacy: &L,
+    entry: &SubmitEntry,
+    completion_ring: &mut CompletionRing,
+    counts: &mut IterCounts,
+) {
+    if !L::ENABLED {
+        completion_ring.push(entry.corr_id, -(libc::ENOSYS as i64), 0);
+        counts.enosys_completions += 1;
+        return;
+    }
+    match legacy.submit(entry) {
+        Ok(()) => counts.legacy_entries += 1,
+        Err(KsvcError::Unsupported(_)) => {
+            // Refused (e.g. exit, clone, execve on a pool thread)
+            completion_ring.push(entry.corr_id, -(libc::ENOSYS as i64), 0);
+            counts.enosys_completions += 1;
+        }
+        Err(_) => {
+            // Legacy queue full — EAGAIN, the GVThread should retry.
+            completion_ring.push(entry.corr_id, -(libc::EAGAIN as i64), 0);
+            counts.eagain_completions += 1;
         }
     }
 }
 
+/// Submit one io_uring-routed entry with its routed `opcode`, answering
+/// a refusal with an immediate completion (or the legacy executor).
+/// Returns true if an SQE was queued.
+#[inline]
+fn route_io_uring<B: IoBackend, L: LegacyExecutor>(
+    io_backend: &mut B,
+    legacy: &L,
+    entry: &SubmitEntry,
+    opcode: u8,
+    completion_ring: &mut CompletionRing,
+    counts: &mut IterCounts,
+) -> bool {
+    match io_backend.submit(entry, opcode) {
+        Ok(()) => return true,
+        Err(KsvcError::RingFull) => {
+            // io_uring SQ is full — write EAGAIN completion.
+            // The GVThread should retry.
+            completion_ring.push(entry.corr_id, -(libc::EAGAIN as i64), 0);
+            counts.eagain_completions += 1;
+        }
+        Err(KsvcError::Unsupported(_)) => {
+            route_legacy(legacy, entry, completion_ring, counts);
+        }
+        Err(_) => {
+            completion_ring.push(entry.corr_id, -(libc::ENOSYS as i64), 0);
+            counts.enosys_completions += 1;
+        }
+    }
+    false
+}
+
+/// Exponential idle backoff: floor, 2×floor, 4×floor, … up to ceiling.
+struct IdleBackoff {
+    floor_us: u64,
+    ceiling_us: u64,
+    level: u32,
+}
+
+impl IdleBackoff {
+    fn new(config: &DispatcherConfig) -> Self {
+        Self {
+            floor_us: config.idle_floor_us(),
+            ceiling_us: config.idle_ceiling_us(),
+            level: 0,
+        }
+    }
+
+    /// Work appeared — drop back to the floor.
+    #[inline]
+    fn reset(&mut self, stats: &DispatcherStats) {
+        if self.level != 0 {
+            self.level = 0;
+            stats.idle_backoff_level.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Sleep for the current level, then raise the level.
+    fn sleep(&mut self, stats: &DispatcherStats) {
+        let us = self.next_us(stats);
+        std::thread::sleep(std::time::Duration::from_micros(us));
+    }
+
+    /// Sleep length for the current level; raises the level.
+    fn next_us(&mut self, stats: &DispatcherStats) -> u64 {
+        let us = self.floor_us
+            .checked_shl(self.level)
+            .unwrap_or(u64::MAX)
+            .min(self.ceiling_us);
+        stats.idle_backoff_level.store(self.level, Ordering::Relaxed);
+        if us < self.ceiling_us {
+            self.level += 1;
+        }
+        us
+    }
+}
+
+/// Check the header the kernel module wrote at `base` against the ring
+/// we expect: magic, `size` entries (power of two) of `entry_size` bytes.
+///
+/// The header carries no version of its own; the ABI version is checked
+/// on the shared page, and `entry_size` catches a layout mismatch here.
+///
+/// # Safety
+/// `base` must be null or point to at least a readable ring header.
+unsafe fn check_ring_header(
+    base: *const u8,
+    size: u32,
+    entry_size: usize,
+) -> Result<(), KsvcError> {
+    if base.is_null() {
+        return Err(KsvcError::BadRing("null base pointer"));
+    }
+    let header = base as *const KsvcRingHeader;
+    if std::ptr::read_volatile(&(*header).magic) != KSVC_RING_MAGIC {
+        return Err(KsvcError::BadRing("bad magic"));
+    }
+    let ring_size = std::ptr::read_volatile(&(*header).ring_size);
+    if !ring_size.is_power_of_two() {
+        return Err(KsvcError::BadRing("ring_size not power of 2"));
+    }
+    if ring_size != size {
+        return Err(KsvcError::BadRing("ring_size mismatch"));
+    }
+    if std::ptr::read_volatile(&(*header).mask) != ring_size - 1 {
+        return Err(KsvcError::BadRing("mask mismatch"));
+    }
+    if std::ptr::read_volatile(&(*header).entry_size) as usize != entry_size {
+        return Err(KsvcError::BadRing("entry_size mismatch"));
+    }
+    Ok(())
+}
+
 /// The submit ring reader — reads entries from mmap'd submit ring.
 ///
 /// This is the counterpart to RingCompletionSink: it reads from the
@@ -70,20 +371,23 @@ pub struct SubmitRing {
 unsafe impl Send for SubmitRing {}
 
 impl SubmitRing {
+    /// Wrap the mmap'd submit ring at `base`, checking its header first.
+    ///
     /// # Safety
-    /// `base` must point to a valid KSVC submit ring.
-    pub unsafe fn new(base: *const u8, size: u32) -> Self {
-        assert!(size.is_power_of_two());
+    /// `base` must be null or point to a mapping of at least a ring
+    /// header; one whose header checks out must hold `size` entries.
+    pub unsafe fn new(base: *const u8, size: u32) -> Result<Self, KsvcError> {
+        check_ring_header(base, size, std::mem::size_of::<SubmitEntry>())?;
         let entries = base.add(64) as *const SubmitEntry;
         let head_ptr = base.add(16) as *const AtomicU64;
         let current_head = (*head_ptr).load(Ordering::Acquire);
-        Self {
+        Ok(Self {
             base,
             entries,
             size,
             mask: size - 1,
             local_head: current_head,
-        }
+        })
     }
 
     /// Read the producer's tail (how far userspace has written).
@@ -131,26 +435,32 @@ pub struct CompletionRing {
     mask: u32,
     local_tail: u64,
     completions_written: u32,
+    /// Pushes rejected because the ring was full (since last take).
+    push_failed: u64,
 }
 
 unsafe impl Send for CompletionRing {}
 
 impl CompletionRing {
+    /// Wrap the mmap'd completion ring at `base`, checking its header first.
+    ///
     /// # Safety
-    /// `base` must point to a valid KSVC completion ring.
-    pub unsafe fn new(base: *mut u8, size: u32) -> Self {
-        assert!(size.is_power_of_two());
+    /// `base` must be null or point to a mapping of at least a ring
+    /// header; one whose header checks out must hold `size` entries.
+    pub unsafe fn new(base: *mut u8, size: u32) -> Result<Self, KsvcError> {
+        check_ring_header(base, size, std::mem::size_of::<CompletionEntry>())?;
         let entries = base.add(64) as *mut CompletionEntry;
         let tail_ptr = base.add(24) as *const AtomicU64;
         let current_tail = (*tail_ptr).load(Ordering::Acquire);
-        Self {
+        Ok(Self {
             base,
             entries,
             size,
             mask: size - 1,
             local_tail: current_tail,
             completions_written: 0,
-        }
+            push_failed: 0,
+        })
     }
 
     fn read_head(&self) -> u64 {
@@ -167,16 +477,54 @@ impl CompletionRing {
         }
     }
 
-    fn available(&self) -> u32 {
+    /// Free slots in the ring (as seen by the producer).
+    pub fn available(&self) -> u32 {
         let head = self.read_head();
         self.size - (self.local_tail - head) as u32
     }
 
+    /// Ring capacity in entries.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// How many of the next `n` completions are sure to fit.
+    ///
+    /// Only the dispatcher produces and userspace only frees slots, so
+    /// that many `push`es (or a `push_batch` of that length) succeed.
+    pub fn reserve(&self, n: usize) -> usize {
+        n.min(self.available() as usize)
+    }
+
     /// Write a completion entry. Returns false if ring is full.
     pub fn push(&mut self, corr_id: CorrId, result: i64, flags: u32) -> bool {
         if self.available() == 0 {
+            self.push_failed += 1;
             return false;
         }
+        self.write(corr_id, result, flags);
+        true
+    }
+
+    /// Write as many of `completions` as fit, in order. Returns how many
+    /// were written; the rest are the caller's to keep or account for.
+    pub fn push_batch(&mut self, completions: &[(CorrId, i64, u32)]) -> usize {
+        self.push_iter(completions.iter().copied())
+    }
+
+    fn push_iter(&mut self, completions: impl IntoIterator<Item = (CorrId, i64, u32)>) -> usize {
+        let space = self.available() as usize;
+        let mut n = 0;
+        for (corr_id, result, flags) in completions.into_iter().take(space) {
+            self.write(corr_id, result, flags);
+            n += 1;
+        }
+        n
+    }
+
+    /// Store one entry at the local tail (space already checked).
+    #[inline]
+    fn write(&mut self, corr_id: CorrId, result: i64, flags: u32) {
         let idx = (self.local_tail & self.mask as u64) as usize;
         let entry = CompletionEntry {
             corr_id,
@@ -189,7 +537,11 @@ impl CompletionRing {
         }
         self.local_tail += 1;
         self.completions_written += 1;
-        true
+    }
+
+    /// Number of rejected pushes since the last call; resets the count.
+    pub fn take_push_failed(&mut self) -> u64 {
+        std::mem::take(&mut self.push_failed)
     }
 
     /// Publish tail and reset counter. Returns number flushed.
@@ -207,20 +559,45 @@ impl CompletionRing {
 ///
 /// This function runs on a dedicated thread. It returns when the
 /// `shutdown` flag is set.
-pub fn dispatcher_loop<R, B, W, N>(
+///
+/// # Backpressure invariant
+///
+/// Every completion the loop writes has its slot pre-checked:
+/// - steps 1–2 drain at most as many io_uring / worker completions as
+///   `CompletionRing::reserve` grants (the rest stay queued in the
+///   backend for the next iteration);
+/// - step 4 dequeues only when at least `min(max_batch, ring size)`
+///   slots are free, and never more entries than free slots. Routing
+///   writes at most one immediate completion (EAGAIN/ENOSYS) per entry,
+///   so it can never outrun the pre-checked space.
+///
+/// When the ring is near full, submissions stay in the submit ring and
+/// userspace backpressures naturally. `stats.completion_push_failed`
+/// counts any violation.
+///
+/// # Legacy fallback
+///
+/// Entries routed `Tier::Legacy` (or `SharedPage`, or rejected by the
+/// io backend as unsupported) go to `legacy`. Pass `&NoLegacy` to keep
+/// the old -ENOSYS behavior at zero cost.
+#[allow(clippy::too_many_arguments)]
+pub fn dispatcher_loop<R, B, W, N, L>(
     mut submit_ring: SubmitRing,
     mut completion_ring: CompletionRing,
     router: &R,
     io_backend: &mut B,
     worker_pool: &W,
+    legacy: &L,
     notifier: &N,
     config: &DispatcherConfig,
+    stats: Arc<DispatcherStats>,
     shutdown: &AtomicBool,
 ) where
     R: SyscallRouter,
     B: IoBackend,
     W: WorkerPool,
     N: Notifier,
+    L: LegacyExecutor,
 {
     let mut submit_buf = vec![SubmitEntry {
         corr_id: CorrId::NONE,
@@ -228,6 +605,8 @@ pub fn dispatcher_loop<R, B, W, N>(
         flags: 0,
         args: [0; 6],
     }; config.max_batch];
+    // Routed io_uring opcodes of the submit_buf run being submitted
+    let mut opcode_buf = vec![0u8; config.max_batch];
 
     let mut io_comp_buf = vec![IoCompletion {
         corr_id: CorrId::NONE,
@@ -240,6 +619,12 @@ pub fn dispatcher_loop<R, B, W, N>(
         result: 0,
     }; config.max_worker_completions];
 
+    let mut backoff = IdleBackoff::new(config);
+    let mut counts = IterCounts::default();
+
+    // Free completion slots required before dequeuing submissions.
+    let reserve = config.max_batch.min(completion_ring.size() as usize).max(1);
+
     loop {
         if shutdown.load(Ordering::Relaxed) {
             break;
@@ -247,86 +632,97 @@ pub fn dispatcher_loop<R, B, W, N>(
 
         let mut did_work = false;
 
-        // ── Step 1: Drain io_uring completions ──
+        // ── Step 1: Drain io_uring completions (bounded by ring space) ──
         let n_io = io_backend.poll_completions(
             &mut io_comp_buf,
-            config.max_io_completions,
+            completion_ring.reserve(config.max_io_completions),
         );
-        for i in 0..n_io {
-            let c = &io_comp_buf[i];
-            completion_ring.push(c.corr_id, c.result, c.flags);
-        }
+        counts.write_io(&mut completion_ring, &io_comp_buf[..n_io]);
         if n_io > 0 {
+            counts.io_completions += n_io as u64;
             did_work = true;
         }
 
-        // ── Step 2: Drain worker pool completions ──
+        // ── Step 2: Drain worker pool completions (bounded by ring space) ──
         let n_worker = worker_pool.poll_completions(
             &mut worker_comp_buf,
-            config.max_worker_completions,
+            completion_ring.reserve(config.max_worker_completions),
         );
-        for i in 0..n_worker {
-            let c = &worker_comp_buf[i];
-            completion_ring.push(c.corr_id, c.result, 0);
-        }
+        counts.write_worker(&mut completion_ring, &worker_comp_buf[..n_worker]);
         if n_worker > 0 {
+            counts.worker_completions += n_worker as u64;
             did_work = true;
         }
 
+        // ── Step 2b: Drain legacy executor completions (if enabled) ──
+        if L::ENABLED {
+            let n_legacy = legacy.poll_completions(
+                &mut worker_comp_buf,
+                completion_ring.reserve(config.max_worker_completions),
+            );
+            counts.write_worker(&mut completion_ring, &worker_comp_buf[..n_legacy]);
+            if n_legacy > 0 {
+                counts.legacy_completions += n_legacy as u64;
+                did_work = true;
+            }
+        }
+
         // ── Step 3: Flush completions + notify userspace ──
         let flushed = completion_ring.flush();
         if flushed > 0 {
             let _ = notifier.notify();
+            counts.notifies += 1;
         }
 
-        // ── Step 4: Dequeue submit ring entries ──
-        let n_submit = submit_ring.dequeue_batch(
-            &mut submit_buf,
-            config.max_batch,
-        );
+        // ── Step 4: Dequeue submit ring entries (backpressure) ──
+        // Skip when the completion ring can't absorb a batch of immediate
+        // completions; entries stay in the submit ring until next time.
+        let space = completion_ring.available() as usize;
+        let n_submit = if space >= reserve {
+            submit_ring.dequeue_batch(&mut submit_buf, config.max_batch.min(space))
+        } else {
+            0
+        };
 
         // ── Step 5: Route each entry ──
         let mut sqes_queued = 0u32;
-        for i in 0..n_submit {
+        let mut i = 0;
+        while i < n_submit {
             let entry = &submit_buf[i];
             let route = router.route(entry.syscall_nr);
 
             match route.tier {
                 Tier::SharedPage => {
                     // This should never happen — userspace handles Tier 0.
-                    // If it does reach here, return the value from the shared
-                    // page equivalent. For safety, return -ENOSYS.
-                    completion_ring.push(
-                        entry.corr_id,
-                        -(libc::ENOSYS as i64),
-                        0,
-                    );
+                    // If it does reach here, execute it as a plain syscall
+                    // (legacy executor), or return -ENOSYS without one.
+                    route_legacy(legacy, entry, &mut completion_ring, &mut counts);
                 }
                 Tier::IoUring => {
-                    // Translate to io_uring SQE via the backend.
-                    // The backend's submit_with_opcode is specific to BasicIoUring.
-                    // For the trait-generic path, we use the trait's submit().
-                    match io_backend.submit(entry) {
-                        Ok(()) => {
-                            sqes_queued += 1;
-                        }
-                        Err(KsvcError::RingFull) => {
-                            // io_uring SQ is full — write EAGAIN completion.
-                            // The GVThread should retry.
-                            completion_ring.push(
-                                entry.corr_id,
-                                -(libc::EAGAIN as i64),
-                                0,
-                            );
+                    // Translate to io_uring SQEs via the backend, the whole
+                    // run of io_uring entries starting here in one call,
+                    // each with the opcode the router chose for it.
+                    let mut run = 0;
+                    for e in &submit_buf[i..n_submit] {
+                        let route = router.route(e.syscall_nr);
+                        if route.tier != Tier::IoUring {
+                            break;
                         }
-                        Err(_) => {
-                            completion_ring.push(
-                                entry.corr_id,
-                                -(libc::ENOSYS as i64),
-                                0,
-                            );
+                        opcode_buf[run] = route.iouring_opcode;
+                        run += 1;
+                    }
+                    let queued = io_backend.submit_batch(&submit_buf[i..i + run], &opcode_buf[..run]);
+                    sqes_queued += queued as u32;
+                    i += queued;
+                    if queued < run {
+                        // submit() the refused entry to learn why
+                        let entry = &submit_buf[i];
+                        if route_io_uring(io_backend, legacy, entry, opcode_buf[queued], &mut completion_ring, &mut counts) {
+                            sqes_queued += 1;
                         }
+                        i += 1;
                     }
+                    continue;
                 }
                 Tier::WorkerPool => {
                     match worker_pool.enqueue(entry) {
@@ -338,21 +734,21 @@ pub fn dispatcher_loop<R, B, W, N>(
                                 -(libc::EAGAIN as i64),
                                 0,
                             );
+                            counts.eagain_completions += 1;
                         }
                     }
                 }
                 Tier::Legacy => {
-                    // Unsupported — should not reach the ring.
-                    completion_ring.push(
-                        entry.corr_id,
-                        -(libc::ENOSYS as i64),
-                        0,
-                    );
+                    // No io_uring/worker equivalent — run the raw syscall
+                    // on the legacy executor (or -ENOSYS without one).
+                    route_legacy(legacy, entry, &mut completion_ring, &mut counts);
                 }
             }
+            i += 1;
         }
 
         if n_submit > 0 {
+            counts.submit_entries += n_submit as u64;
             did_work = true;
         }
 
@@ -365,36 +761,435 @@ pub fn dispatcher_loop<R, B, W, N>(
         let flushed2 = completion_ring.flush();
         if flushed2 > 0 {
             let _ = notifier.notify();
+            counts.notifies += 1;
         }
 
-        // ── Step 7: Sleep if idle ──
+        // ── Publish this iteration's counters (one batch of relaxed adds) ──
+        counts.completion_push_failed += completion_ring.take_push_failed();
         if !did_work {
-            std::thread::sleep(std::time::Duration::from_micros(
-                config.idle_sleep_us,
-            ));
+            counts.idle_iterations += 1;
+        }
+        counts.publish(&stats);
+
+        // ── Step 7: Sleep if idle (backoff), reset on work ──
+        if did_work {
+            backoff.reset(&stats);
+        } else {
+            backoff.sleep(&stats);
         }
     }
 
     // Shutdown: drain remaining completions
     let n_io = io_backend.poll_completions(
         &mut io_comp_buf,
-        config.max_io_completions,
+        completion_ring.reserve(config.max_io_completions),
     );
-    for i in 0..n_io {
-        let c = &io_comp_buf[i];
-        completion_ring.push(c.corr_id, c.result, c.flags);
-    }
+    counts.write_io(&mut completion_ring, &io_comp_buf[..n_io]);
     let n_worker = worker_pool.poll_completions(
         &mut worker_comp_buf,
-        config.max_worker_completions,
+        completion_ring.reserve(config.max_worker_completions),
     );
-    for i in 0..n_worker {
-        let c = &worker_comp_buf[i];
-        completion_ring.push(c.corr_id, c.result, 0);
-    }
+    counts.write_worker(&mut completion_ring, &worker_comp_buf[..n_worker]);
     let flushed = completion_ring.flush();
     if flushed > 0 {
         let _ = notifier.notify();
+        counts.notifies += 1;
     }
+    counts.io_completions += n_io as u64;
+    counts.worker_completions += n_worker as u64;
+    if L::ENABLED {
+        let n_legacy = legacy.poll_completions(
+            &mut worker_comp_buf,
+            completion_ring.reserve(config.max_worker_completions),
+        );
+        counts.write_worker(&mut completion_ring, &worker_comp_buf[..n_legacy]);
+        counts.legacy_completions += n_legacy as u64;
+        if completion_ring.flush() > 0 {
+            let _ = notifier.notify();
+            counts.notifies += 1;
+        }
+    }
+    counts.completion_push_failed += completion_ring.take_push_failed();
+    counts.publish(&stats);
     worker_pool.shutdown();
+    legacy.shutdown();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ksvc_core::router::RouteInfo;
+    use std::sync::Mutex;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    /// Cache-line aligned, like the mmap'd rings.
+    #[derive(Clone, Copy)]
+    #[repr(C, align(64))]
+    struct Line([u64; 8]);
+
+    /// A ring of `E` (header + `size` entries) in ordinary memory, with
+    /// the header the kernel module would write.
+    fn ring_memory<E>(size: u32) -> Vec<Line> {
+        let entry_size = std::mem::size_of::<E>();
+        let mut mem = vec![Line([0; 8]); (64 + size as usize * entry_size).div_ceil(64)];
+        let header = unsafe { &mut *(mem.as_mut_ptr() as *mut KsvcRingHeader) };
+        header.magic = KSVC_RING_MAGIC;
+        header.ring_size = size;
+        header.mask = size - 1;
+        header.entry_size = entry_size as u32;
+        mem
+    }
+
+    fn set_head(mem: &mut [Line], head: u64) {
+        mem[0].0[2] = head; // offset 16
+    }
+
+    fn set_tail(mem: &mut [Line], tail: u64) {
+        mem[0].0[3] = tail; // offset 24
+    }
+
+    /// A ring header field (16 = head, 24 = tail) while the loop runs.
+    fn header<'a>(base: *mut u8, offset: usize) -> &'a AtomicU64 {
+        unsafe { &*(base.add(offset) as *const AtomicU64) }
+    }
+
+    fn wait_for(mut cond: impl FnMut() -> bool) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !cond() {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        true
+    }
+
+    /// Routes syscall 1 to io_uring, 2 to the worker pool, the rest to legacy.
+    struct MockRouter;
+
+    impl SyscallRouter for MockRouter {
+        fn route(&self, syscall_nr: u32) -> RouteInfo {
+            match syscall_nr {
+                1 => RouteInfo::iouring(0),
+                2 => RouteInfo::worker(),
+                _ => RouteInfo::LEGACY,
+            }
+        }
+
+        fn table_size(&self) -> usize {
+            3
+        }
+    }
+
+    /// Completes each submitted entry on the next poll, with `corr_id + 100`.
+    #[derive(Default)]
+    struct MockBackend {
+        queued: Vec<CorrId>,
+    }
+
+    impl IoBackend for MockBackend {
+        fn submit(&mut self, entry: &SubmitEntry, _opcode: u8) -> ksvc_core::error::Result<()> {
+            self.queued.push(entry.corr_id);
+            Ok(())
+        }
+
+        fn flush(&mut self) -> ksvc_core::error::Result<usize> {
+            Ok(self.queued.len())
+        }
+
+        fn poll_completions(&mut self, buf: &mut [IoCompletion], max: usize) -> usize {
+            let n = self.queued.len().min(max).min(buf.len());
+            for (slot, corr_id) in buf.iter_mut().zip(self.queued.drain(..n)) {
+                *slot = IoCompletion { corr_id, result: corr_id.0 as i64 + 100, flags: 0 };
+            }
+            n
+        }
+
+        fn inflight(&self) -> usize {
+            self.queued.len()
+        }
+
+        fn capacity(&self) -> usize {
+            usize::MAX
+        }
+
+        fn probe_opcodes(&self) -> Vec<u8> {
+            vec![0]
+        }
+
+        fn shutdown(&mut self) {}
+    }
+
+    /// Worker pool (and, wrapped, legacy executor) that answers
+    /// `corr_id + 100` and counts what it was handed.
+    #[derive(Default)]
+    struct MockPool {
+        queued: Mutex<Vec<CorrId>>,
+        submitted: AtomicU64,
+    }
+
+    impl WorkerPool for MockPool {
+        fn enqueue(&self, entry: &SubmitEntry) -> ksvc_core::error::Result<()> {
+            self.queued.lock().unwrap().push(entry.corr_id);
+            self.submitted.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+
+        fn poll_completions(&self, buf: &mut [WorkerCompletion], max: usize) -> usize {
+            let mut queued = self.queued.lock().unwrap();
+            let n = queued.len().min(max).min(buf.len());
+            for (slot, corr_id) in buf.iter_mut().zip(queued.drain(..n)) {
+                *slot = WorkerCompletion { corr_id, result: corr_id.0 as i64 + 100 };
+            }
+            n
+        }
+
+        fn active_workers(&self) -> usize {
+            0
+        }
+
+        fn total_workers(&self) -> usize {
+            1
+        }
+
+        fn max_workers(&self) -> usize {
+            1
+        }
+
+        fn shutdown(&self) {}
+    }
+
+    #[derive(Default)]
+    struct MockLegacy<const ON: bool>(MockPool);
+
+    impl<const ON: bool> LegacyExecutor for MockLegacy<ON> {
+        const ENABLED: bool = ON;
+
+        fn submit(&self, entry: &SubmitEntry) -> ksvc_core::error::Result<()> {
+            self.0.enqueue(entry)
+        }
+
+        fn poll_completions(&self, buf: &mut [WorkerCompletion], max: usize) -> usize {
+            self.0.poll_completions(buf, max)
+        }
+
+        fn shutdown(&self) {}
+    }
+
+    #[derive(Default)]
+    struct MockNotifier(AtomicU64);
+
+    impl Notifier for MockNotifier {
+        fn notify(&self) -> ksvc_core::error::Result<()> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    struct StopOnDrop<'a>(&'a AtomicBool);
+
+    impl Drop for StopOnDrop<'_> {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::Release);
+        }
+    }
+
+    struct Run {
+        stats: DispatcherStatsSnapshot,
+        /// `(corr_id, result)` of every completion written, by corr_id
+        completions: Vec<(u64, i64)>,
+        legacy_submits: u64,
+        notifies: u64,
+    }
+
+    /// Run `dispatcher_loop` over 12 entries (4 per route) queued while
+    /// the completion ring is full, until all have completed.
+    fn run_dispatcher<const LEGACY: bool>() -> Run {
+        const SIZE: u32 = 16;
+        const ENTRIES: u64 = 12;
+
+        let mut submit_mem = ring_memory::<SubmitEntry>(SIZE);
+        for id in 0..ENTRIES {
+            let entry = SubmitEntry { corr_id: CorrId(id), syscall_nr: (id % 3 + 1) as u32, flags: 0, args: [0; 6] };
+            unsafe { (submit_mem.as_mut_ptr().add(1 + id as usize) as *mut SubmitEntry).write(entry) };
+        }
+        set_tail(&mut submit_mem, ENTRIES);
+        // Userspace hasn't read a single completion yet
+        let mut completion_mem = ring_memory::<CompletionEntry>(SIZE);
+        set_tail(&mut completion_mem, SIZE as u64);
+
+        let submit_base = submit_mem.as_mut_ptr() as *mut u8;
+        let completion_base = completion_mem.as_mut_ptr() as *mut u8;
+        let submit_ring = unsafe { SubmitRing::new(submit_base, SIZE) }.unwrap();
+        let completion_ring = unsafe { CompletionRing::new(completion_base, SIZE) }.unwrap();
+
+        let mut backend = MockBackend::default();
+        let pool = MockPool::default();
+        let legacy = MockLegacy::<LEGACY>::default();
+        let notifier = MockNotifier::default();
+        let config = DispatcherConfig { idle_sleep_us: 10, idle_sleep_max_us: 100, ..Default::default() };
+        let stats = Arc::new(DispatcherStats::new());
+        let shutdown = AtomicBool::new(false);
+
+        thread::scope(|s| {
+            let loop_stats = Arc::clone(&stats);
+            let (backend, config, shutdown) = (&mut backend, &config, &shutdown);
+            let (pool, legacy, notifier) = (&pool, &legacy, &notifier);
+            s.spawn(move || {
+                dispatcher_loop(
+                    submit_ring, completion_ring, &MockRouter, backend, pool, legacy, notifier, config,
+                    loop_stats, shutdown,
+                )
+            });
+            // Stops the loop however this closure ends, so a failed
+            // assertion fails the test instead of hanging the scope
+            let _stop = StopOnDrop(shutdown);
+
+            // No room for a batch of completions: nothing is dequeued, so
+            // nothing can be dropped
+            assert!(wait_for(|| stats.idle_iterations.load(Ordering::Relaxed) >= 3));
+            assert_eq!(header(submit_base, 16).load(Ordering::Acquire), 0, "entries left the submit ring");
+            assert_eq!(stats.submit_entries.load(Ordering::Relaxed), 0);
+
+            // Userspace catches up: every entry completes
+            header(completion_base, 16).store(SIZE as u64, Ordering::Release);
+            let all_written = || header(completion_base, 24).load(Ordering::Acquire) == SIZE as u64 + ENTRIES;
+            assert!(wait_for(all_written), "not every entry completed");
+        });
+
+        let entries = unsafe { completion_base.add(64) } as *const CompletionEntry;
+        let mut completions: Vec<_> = (0..ENTRIES as usize)
+            .map(|i| unsafe { *entries.add(i) })
+            .map(|e| (e.corr_id.0, e.result))
+            .collect();
+        completions.sort_unstable();
+        Run {
+            stats: stats.snapshot(),
+            completions,
+            legacy_submits: legacy.0.submitted.load(Ordering::Relaxed),
+            notifies: notifier.0.load(Ordering::Relaxed),
+        }
+    }
+
+    #[test]
+    fn push_batch_reports_the_short_count() {
+        let mut mem = ring_memory::<CompletionEntry>(8);
+        let mut ring = unsafe { CompletionRing::new(mem.as_mut_ptr() as *mut u8, 8) }.unwrap();
+        assert!(ring.push(CorrId(1), 10, 0));
+        assert!(ring.push(CorrId(2), 20, 0));
+        assert!(ring.push(CorrId(3), 30, 0));
+        assert_eq!(ring.reserve(16), 5);
+
+        let batch: Vec<_> = (10..20).map(|i| (CorrId(i), i as i64, 1)).collect();
+        assert_eq!(ring.push_batch(&batch), 5);
+        assert_eq!(ring.reserve(16), 0);
+        assert_eq!(ring.push_batch(&batch), 0);
+        assert!(!ring.push(CorrId(4), 40, 0));
+        assert_eq!(ring.take_push_failed(), 1);
+        assert_eq!(ring.flush(), 8);
+
+        let written: Vec<_> = (0..8)
+            .map(|i| unsafe { *ring.entries.add(i) })
+            .map(|e| (e.corr_id.0, e.result))
+            .collect();
+        assert_eq!(written, [(1, 10), (2, 20), (3, 30), (10, 10), (11, 11), (12, 12), (13, 13), (14, 14)]);
+
+        // Userspace consumed three: exactly three more fit, wrapping around
+        set_head(&mut mem, 3);
+        assert_eq!(ring.reserve(16), 3);
+        assert_eq!(ring.push_batch(&batch[5..]), 3);
+        assert_eq!(unsafe { *ring.entries }.corr_id, CorrId(15));
+        assert_eq!(ring.take_push_failed(), 0);
+    }
+
+    #[test]
+    fn dispatcher_loop_keeps_entries_queued_until_the_ring_has_room() {
+        let run = run_dispatcher::<true>();
+        let expected: Vec<_> = (0..12).map(|id| (id, id as i64 + 100)).collect();
+        assert_eq!(run.completions, expected);
+        assert_eq!(run.legacy_submits, 4);
+
+        let stats = run.stats;
+        assert_eq!((stats.submit_entries, stats.io_completions, stats.worker_completions), (12, 4, 4));
+        assert_eq!((stats.legacy_entries, stats.legacy_completions), (4, 4));
+        assert_eq!((stats.eagain_completions, stats.enosys_completions), (0, 0));
+        assert_eq!(stats.completion_push_failed, 0);
+        assert_eq!(stats.notifies, run.notifies);
+        assert!(stats.idle_iterations >= 3);
+    }
+
+    #[test]
+    fn dispatcher_loop_answers_legacy_entries_itself_when_disabled() {
+        let run = run_dispatcher::<false>();
+        let expected: Vec<_> = (0..12)
+            .map(|id| (id, if id % 3 == 2 { -(libc::ENOSYS as i64) } else { id as i64 + 100 }))
+            .collect();
+        assert_eq!(run.completions, expected);
+        assert_eq!(run.legacy_submits, 0);
+
+        let stats = run.stats;
+        assert_eq!((stats.submit_entries, stats.io_completions, stats.worker_completions), (12, 4, 4));
+        assert_eq!((stats.legacy_entries, stats.legacy_completions), (0, 0));
+        assert_eq!(stats.enosys_completions, 4);
+        assert_eq!(stats.completion_push_failed, 0);
+        assert_eq!(stats.notifies, run.notifies);
+    }
+
+    #[test]
+    fn idle_backoff_doubles_to_the_ceiling_and_resets_on_work() {
+        let stats = DispatcherStats::new();
+        let mut backoff = IdleBackoff::new(&DispatcherConfig::default());
+        let sleeps: Vec<u64> = (0..6).map(|_| backoff.next_us(&stats)).collect();
+        assert_eq!(sleeps, [100, 200, 400, 800, 1_000, 1_000]);
+        assert_eq!(stats.idle_backoff_level.load(Ordering::Relaxed), 4);
+
+        backoff.reset(&stats);
+        assert_eq!(stats.idle_backoff_level.load(Ordering::Relaxed), 0);
+        assert_eq!(backoff.next_us(&stats), 100);
+    }
+
+    #[test]
+    fn idle_backoff_bounds_come_from_the_config() {
+        let bounds = |config: DispatcherConfig| {
+            let mut backoff = IdleBackoff::new(&config);
+            let stats = DispatcherStats::new();
+            let floor = backoff.next_us(&stats);
+            let ceiling = (0..64).map(|_| backoff.next_us(&stats)).last().unwrap();
+            (floor, ceiling)
+        };
+        let config = DispatcherConfig::default;
+        assert_eq!(bounds(config()), (100, 1_000));
+        // idle_sleep_min_us overrides idle_sleep_us as the floor
+        assert_eq!(bounds(DispatcherConfig { idle_sleep_min_us: 10, ..config() }), (10, 1_000));
+        // A ceiling below the floor is raised to it: a fixed sleep
+        assert_eq!(bounds(DispatcherConfig { idle_sleep_max_us: 50, ..config() }), (100, 100));
+        // Huge ceilings don't overflow the shift
+        let unbounded = DispatcherConfig { idle_sleep_us: 1, idle_sleep_max_us: u64::MAX, ..config() };
+        assert_eq!(bounds(unbounded).0, 1);
+    }
+
+    #[test]
+    fn rings_reject_a_bad_header() {
+        fn check(mut mem: Vec<Line>, size: u32) -> Option<&'static str> {
+            let base = mem.as_mut_ptr() as *mut u8;
+            match unsafe { CompletionRing::new(base, size) } {
+                Ok(_) => None,
+                Err(KsvcError::BadRing(why)) => Some(why),
+                Err(e) => panic!("unexpected error {e}"),
+            }
+        }
+        let mut zeroed = ring_memory::<CompletionEntry>(8);
+        zeroed[0] = Line([0; 8]);
+        assert_eq!(check(zeroed, 8), Some("bad magic"));
+        assert_eq!(check(ring_memory::<CompletionEntry>(8), 16), Some("ring_size mismatch"));
+        assert_eq!(check(ring_memory::<CompletionEntry>(8), 8), None);
+
+        // Right magic and size, but it's a completion ring (32-byte entries)
+        let mut mem = ring_memory::<CompletionEntry>(8);
+        let submit = unsafe { SubmitRing::new(mem.as_mut_ptr() as *const u8, 8) };
+        assert!(matches!(submit, Err(KsvcError::BadRing("entry_size mismatch"))));
+        let null = unsafe { SubmitRing::new(std::ptr::null(), 8) };
+        assert!(matches!(null, Err(KsvcError::BadRing("null base pointer"))));
+    }
 }
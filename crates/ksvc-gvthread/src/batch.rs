@@ -0,0 +1,315 @@
+//! # Batched reactor I/O
+//!
+//! `ReactorShared::submit_batch` queues several operations for one
+//! GVThread and parks it once, instead of a reactor round trip each:
+//!
+//! ```text
+//!  submit_batch ──▶ op 0 ─┐
+//!               ──▶ op 1 ─┼─▶ reactor ──▶ CQE per op ──▶ IoBatch
+//!               ──▶ op 2 ─┘                   (counts down, wakes once)
+//! ```
+//!
+//! Each op's SQE carries `BATCH_TAG | index << 32 | slot`. The slot's
+//! single cell in the results slab can't hold them all, so the reactor
+//! routes these CQEs to the slot's `IoBatch` (registered for the length
+//! of the call) instead, which records each result.
+//!
+//! The GVThread is woken once, after the last op. With `BatchWait::Any`
+//! the reactor cancels the other ops as soon as the first completes (and
+//! fails those it hasn't submitted yet); they are still waited for,
+//! since an op the kernel hasn't finished may be writing into its
+//! buffer. Either way the results come back together, in the ops.
+//!
+//! Cancelling the GVThread cancels every op still in flight. Batched ops
+//! take no per-op timeout.
+//!
+//! ```ignore
+//! let mut ops = [up.read_op(&mut up_buf), down.read_op(&mut down_buf)];
+//! let first = shared.submit_batch(&mut ops, BatchWait::Any);
+//! ```
+
+use crate::reactor::{IoRequest, ReactorShared};
+use crate::syscall::{iov_count, NR_READ, NR_READV, NR_RECVFROM, NR_SENDTO, NR_WRITE, NR_WRITEV};
+
+use ksvc_core::entry::CorrId;
+
+use gvthread_core::id::GVThreadId;
+use gvthread_core::spinlock::SpinLock;
+use gvthread_core::state::Priority;
+use gvthread_runtime::scheduler;
+
+use std::io::{IoSlice, IoSliceMut};
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// User-data tag of a batched op's CQE (op index in bits 32..60, slot
+/// in the low 32 bits)
+pub(crate) const BATCH_TAG: u64 = 1 << 60;
+
+/// Most ops one batch can hold
+pub const MAX_BATCH_OPS: usize = 1 << 16;
+
+/// Results cell of an op still in flight
+const PENDING: i64 = i64::MIN;
+
+/// User data of op `index` in `slot`'s batch
+#[inline]
+pub(crate) fn op_user_data(slot: u32, index: usize) -> u64 {
+    BATCH_TAG | (index as u64) << 32 | slot as u64
+}
+
+/// Slot and op index of a batched op's user data, if it is one
+#[inline]
+pub(crate) fn batch_op(user_data: u64) -> Option<(u32, usize)> {
+    (user_data >> 60 == 1).then_some((user_data as u32, ((user_data >> 32) & 0x0FFF_FFFF) as usize))
+}
+
+/// When `submit_batch` returns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchWait {
+    /// Once every op has completed
+    All,
+    /// Once one op has completed; the others are cancelled
+    Any,
+}
+
+/// One operation of a batch, and its result once the batch returns
+///
+/// Borrows its buffers for `'a`, so they outlive the submission.
+pub struct IoOp<'a> {
+    syscall_nr: u32,
+    args: [u64; 6],
+    result: i64,
+    _bufs: PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> IoOp<'a> {
+    fn new(syscall_nr: u32, args: [u64; 6]) -> Self {
+        Self { syscall_nr, args, result: PENDING, _bufs: PhantomData }
+    }
+
+    /// `read(fd, buf)`
+    pub fn read(fd: i32, buf: &'a mut [u8]) -> Self {
+        Self::new(NR_READ, [fd as u64, buf.as_mut_ptr() as u64, buf.len() as u64, 0, 0, 0])
+    }
+
+    /// `write(fd, buf)`
+    pub fn write(fd: i32, buf: &'a [u8]) -> Self {
+        Self::new(NR_WRITE, [fd as u64, buf.as_ptr() as u64, buf.len() as u64, 0, 0, 0])
+    }
+
+    /// `recv(fd, buf, flags)`
+    pub fn recv(fd: i32, buf: &'a mut [u8], flags: i32) -> Self {
+        let args = [fd as u64, buf.as_mut_ptr() as u64, buf.len() as u64, flags as u64, 0, 0];
+        Self::new(NR_RECVFROM, args)
+    }
+
+    /// `send(fd, buf, flags)`
+    pub fn send(fd: i32, buf: &'a [u8], flags: i32) -> Self {
+        let args = [fd as u64, buf.as_ptr() as u64, buf.len() as u64, flags as u64, 0, 0];
+        Self::new(NR_SENDTO, args)
+    }
+
+    /// `readv(fd, bufs)`
+    pub fn readv(fd: i32, bufs: &'a mut [IoSliceMut<'a>]) -> Self {
+        Self::new(NR_READV, [fd as u64, bufs.as_mut_ptr() as u64, iov_count(bufs.len()), 0, 0, 0])
+    }
+
+    /// `writev(fd, bufs)`
+    pub fn writev(fd: i32, bufs: &'a [IoSlice<'a>]) -> Self {
+        Self::new(NR_WRITEV, [fd as u64, bufs.as_ptr() as u64, iov_count(bufs.len()), 0, 0, 0])
+    }
+
+    /// Complete without submitting
+    pub(crate) fn fail(&mut self, errno: i64) {
+        self.result = errno;
+    }
+
+    /// The syscall's return value or negative errno (`-ECANCELED` for an
+    /// op a `BatchWait::Any` batch cancelled). Meaningless until the
+    /// batch has returned.
+    pub fn result(&self) -> i64 {
+        self.result
+    }
+}
+
+/// Per-call batch state shared with the reactor
+pub(crate) struct IoBatch {
+    /// `BatchWait::Any`: the first completion cancels the rest
+    any: bool,
+    /// Ops not completed yet are to be cancelled (or not submitted)
+    cancelled: AtomicBool,
+    /// One cell per op, `PENDING` until it completes
+    results: Box<[AtomicI64]>,
+    /// Ops completed so far
+    done: AtomicUsize,
+    /// Index of the op that completed first (`usize::MAX` until then)
+    first: AtomicUsize,
+    /// The parked GVThread (id, generation)
+    waiter: SpinLock<Option<(GVThreadId, u32)>>,
+}
+
+impl IoBatch {
+    fn new(len: usize, wait: BatchWait) -> Self {
+        Self {
+            any: wait == BatchWait::Any,
+            cancelled: AtomicBool::new(false),
+            results: (0..len).map(|_| AtomicI64::new(PENDING)).collect(),
+            done: AtomicUsize::new(0),
+            first: AtomicUsize::new(usize::MAX),
+            waiter: SpinLock::new(None),
+        }
+    }
+
+    /// Record op `index`'s result; wakes the owner after the last one.
+    /// Called by the reactor, or by the owner for ops it never queued.
+    ///
+    /// Returns true if the ops still pending should now be cancelled
+    /// (the first completion of a `BatchWait::Any` batch).
+    pub(crate) fn complete(&self, index: usize, result: i64) -> bool {
+        let Some(cell) = self.results.get(index) else { return false };
+        if cell.compare_exchange(PENDING, result, Ordering::AcqRel, Ordering::Acquire).is_err() {
+            return false; // Stray duplicate
+        }
+        let first = self.first
+            .compare_exchange(usize::MAX, index, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok();
+        let done = self.done.fetch_add(1, Ordering::AcqRel) + 1;
+        if done == self.results.len() {
+            let waiter = self.waiter.lock().take();
+            if let Some((id, generation)) = waiter {
+                scheduler::wake_waiter(id, generation);
+            }
+            return false;
+        }
+        first && self.any && !self.cancelled.swap(true, Ordering::AcqRel)
+    }
+
+    /// Mark the ops not completed yet as cancelled (see `is_cancelled`)
+    pub(crate) fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// The reactor fails the batch's ops with `-ECANCELED` instead of
+    /// submitting them
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Indices of the ops still in flight
+    pub(crate) fn pending(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.results.len()).filter(|&i| self.results[i].load(Ordering::Acquire) == PENDING)
+    }
+
+    /// Park the calling GVThread until every op has completed
+    fn wait(&self) {
+        let me = scheduler::current_waiter().expect("submit_batch called outside GVThread");
+        *self.waiter.lock() = Some(me);
+        // Same withdraw-or-block handshake as the reactor's space waiters
+        if self.done.load(Ordering::Acquire) == self.results.len()
+            && self.waiter.lock().take().is_some()
+        {
+            return;
+        }
+        scheduler::block_current();
+    }
+}
+
+impl ReactorShared {
+    /// Submit `ops` together and park until `wait` is satisfied (see the
+    /// `batch` module docs). Each op's `result()` holds its outcome.
+    ///
+    /// Returns the index of the op that completed first, or `None` for
+    /// an empty batch. Ops that couldn't be queued complete at once with
+    /// the error (`-ESHUTDOWN`, `-ERANGE`; `-ECANCELED` if the GVThread
+    /// was already cancelled).
+    ///
+    /// # Panics
+    /// Panics outside a GVThread, or with more than `MAX_BATCH_OPS` ops.
+    pub fn submit_batch(&self, ops: &mut [IoOp<'_>], wait: BatchWait) -> Option<usize> {
+        if ops.is_empty() {
+            return None;
+        }
+        assert!(ops.len() <= MAX_BATCH_OPS, "submit_batch: more than {} ops", MAX_BATCH_OPS);
+        let gvt_id = gvthread_runtime::tls::current_gvthread_id();
+        assert!(!gvt_id.is_none(), "submit_batch called outside GVThread");
+        let slot = gvt_id.as_u32();
+
+        let batch = Arc::new(IoBatch::new(ops.len(), wait));
+        self.batches.lock().insert(slot, Arc::clone(&batch));
+
+        // As in `submit_and_park`: publish the wait, then check
+        scheduler::set_current_wait_kind(scheduler::WAIT_IO);
+        if scheduler::current_cancelled() {
+            for index in 0..ops.len() {
+                batch.complete(index, -(libc::ECANCELED as i64));
+            }
+        } else {
+            for (index, op) in ops.iter().enumerate() {
+                let req = IoRequest {
+                    corr_id: CorrId(op_user_data(slot, index)),
+                    syscall_nr: op.syscall_nr,
+                    args: op.args,
+                    priority: Priority::Normal,
+                    generation: 0,
+                    timeout_ns: 0,
+                };
+                if let Err(e) = self.push_request(req) {
+                    batch.complete(index, e);
+                }
+            }
+        }
+
+        batch.wait();
+        scheduler::set_current_wait_kind(scheduler::WAIT_NONE);
+        self.batches.lock().remove(&slot);
+
+        for (op, cell) in ops.iter_mut().zip(batch.results.iter()) {
+            op.result = cell.load(Ordering::Acquire);
+        }
+        Some(batch.first.load(Ordering::Acquire))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_user_data_round_trips_and_stays_apart() {
+        let user_data = op_user_data(42, 3);
+        assert_eq!(batch_op(user_data), Some((42, 3)));
+        assert_eq!(batch_op(op_user_data(u32::MAX - 1, MAX_BATCH_OPS - 1)), Some((u32::MAX - 1, MAX_BATCH_OPS - 1)));
+        assert_eq!(batch_op(42), None);
+        assert_eq!(batch_op(CorrId::CANCEL.0), None);
+        assert_eq!(batch_op(crate::multishot::MULTISHOT_TAG | 42), None);
+        assert_eq!(batch_op(crate::linked::timeout_user_data(42)), None);
+    }
+
+    #[test]
+    fn results_are_recorded_once_in_completion_order() {
+        let batch = IoBatch::new(3, BatchWait::All);
+        assert!(!batch.complete(2, 5));
+        assert!(!batch.complete(0, -(libc::ECANCELED as i64)));
+        assert!(!batch.complete(2, 9)); // duplicate
+        assert!(!batch.complete(7, 1)); // out of range
+        assert_eq!(batch.done.load(Ordering::Relaxed), 2);
+        assert_eq!(batch.first.load(Ordering::Relaxed), 2);
+        assert_eq!(batch.pending().collect::<Vec<_>>(), [1]);
+        assert_eq!(batch.results[2].load(Ordering::Relaxed), 5);
+    }
+
+    #[test]
+    fn first_completion_of_any_cancels_the_rest_once() {
+        let batch = IoBatch::new(3, BatchWait::Any);
+        assert!(batch.complete(1, 4));
+        assert!(batch.is_cancelled());
+        assert!(!batch.complete(0, -(libc::ECANCELED as i64)));
+        assert!(!batch.complete(2, -(libc::ECANCELED as i64)));
+        assert_eq!(batch.first.load(Ordering::Relaxed), 1);
+
+        // A single op is complete at once: nothing to cancel
+        assert!(!IoBatch::new(1, BatchWait::Any).complete(0, 1));
+    }
+}
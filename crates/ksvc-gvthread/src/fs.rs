@@ -0,0 +1,165 @@
+//! # File I/O for GVThreads
+//!
+//! `GvtFile` wraps a file fd opened, read, stat'ed and closed through the
+//! shared reactor, so file serving is plain sequential code:
+//!
+//! ```ignore
+//! let file = GvtFile::open(shared.clone(), "/srv/www/index.html", libc::O_RDONLY)?;
+//! let meta = file.stat()?;
+//! stream.write_all(format!("Content-Length: {}\r\n\r\n", meta.size).as_bytes());
+//! let mut buf = vec![0u8; 16 * 1024]; // GVThread stacks are small
+//! loop {
+//!     let n = file.read(&mut buf);
+//!     if n <= 0 { break; }
+//!     stream.write_all(&buf[..n as usize]);
+//! }
+//! ```
+//!
+//! Errors are negative errno values, like the rest of this crate.
+
+use crate::reactor::ReactorShared;
+use crate::syscall::*;
+
+use std::ffi::CString;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// File metadata from `statx`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileMeta {
+    /// Size in bytes
+    pub size: u64,
+    /// Last modification time
+    pub mtime: SystemTime,
+    /// File type and permission bits (`st_mode`)
+    pub mode: u32,
+}
+
+impl FileMeta {
+    /// True for a regular file
+    pub fn is_file(&self) -> bool {
+        self.mode & libc::S_IFMT == libc::S_IFREG
+    }
+
+    /// True for a directory
+    pub fn is_dir(&self) -> bool {
+        self.mode & libc::S_IFMT == libc::S_IFDIR
+    }
+
+    fn from_statx(stx: &libc::statx) -> Self {
+        let mtime = UNIX_EPOCH
+            + Duration::new(stx.stx_mtime.tv_sec.max(0) as u64, stx.stx_mtime.tv_nsec);
+        Self {
+            size: stx.stx_size,
+            mtime,
+            mode: stx.stx_mode as u32,
+        }
+    }
+}
+
+/// An open file, read sequentially through the reactor
+///
+/// Dropping it closes the fd through the reactor when dropped on a
+/// GVThread, and with a plain `close(2)` otherwise.
+pub struct GvtFile {
+    fd: i32,
+    shared: Arc<ReactorShared>,
+}
+
+impl GvtFile {
+    /// Open `path` with `open(2)` `flags` (`O_CLOEXEC` is added).
+    ///
+    /// Blocks the calling GVThread. Returns negative errno on failure
+    /// (`-EINVAL` if `path` contains a NUL byte).
+    pub fn open(shared: Arc<ReactorShared>, path: &str, flags: i32) -> Result<Self, i64> {
+        let path = CString::new(path).map_err(|_| -(libc::EINVAL as i64))?;
+        let fd = ksvc_openat(&shared, libc::AT_FDCWD, path.as_ptr(), flags | libc::O_CLOEXEC, 0);
+        if fd < 0 {
+            return Err(fd);
+        }
+        Ok(Self { fd: fd as i32, shared })
+    }
+
+    /// Read at the current file position. Blocks the GVThread.
+    /// Returns bytes read, 0 at end of file, or negative errno.
+    pub fn read(&self, buf: &mut [u8]) -> i64 {
+        ksvc_read(&self.shared, self.fd, buf)
+    }
+
+    /// Read from the current position to end of file, appending to `out`
+    ///
+    /// Returns the number of bytes appended or negative errno.
+    pub fn read_to_end(&self, out: &mut Vec<u8>) -> Result<usize, i64> {
+        const CHUNK: usize = 16 * 1024;
+        let start = out.len();
+        if let Ok(meta) = self.stat() {
+            out.reserve(meta.size as usize);
+        }
+        // Read straight into `out` (GVThread stacks are too small for a
+        // chunk buffer)
+        loop {
+            let len = out.len();
+            out.resize(len + CHUNK, 0);
+            let n = self.read(&mut out[len..]);
+            out.truncate(len + n.max(0) as usize);
+            if n < 0 {
+                if n == -(libc::EINTR as i64) {
+                    continue;
+                }
+                return Err(n);
+            }
+            if n == 0 {
+                return Ok(out.len() - start);
+            }
+        }
+    }
+
+    /// Size, mtime and mode of the open file (`statx` on the fd).
+    /// Blocks the GVThread.
+    pub fn stat(&self) -> Result<FileMeta, i64> {
+        let mut stx: libc::statx = unsafe { std::mem::zeroed() };
+        let empty = c"";
+        let ret = ksvc_statx(
+            &self.shared,
+            self.fd,
+            empty.as_ptr(),
+            libc::AT_EMPTY_PATH,
+            libc::STATX_SIZE | libc::STATX_MTIME | libc::STATX_TYPE | libc::STATX_MODE,
+            &mut stx,
+        );
+        if ret < 0 {
+            return Err(ret);
+        }
+        Ok(FileMeta::from_statx(&stx))
+    }
+
+    /// Close the file through the reactor. Returns 0 or negative errno.
+    pub fn close(mut self) -> i64 {
+        let fd = std::mem::replace(&mut self.fd, -1);
+        ksvc_close(&self.shared, fd)
+    }
+
+    /// Get the raw fd.
+    pub fn fd(&self) -> i32 {
+        self.fd
+    }
+}
+
+impl Drop for GvtFile {
+    fn drop(&mut self) {
+        if self.fd < 0 {
+            return;
+        }
+        // A cancelled GVThread gets -ECANCELED without the close being
+        // submitted; the fd must still go
+        if !gvthread_runtime::tls::is_in_gvthread()
+            || ksvc_close(&self.shared, self.fd) == -(libc::ECANCELED as i64)
+        {
+            unsafe { libc::close(self.fd); }
+        }
+    }
+}
+
+// Safety: the fd is valid until close, and the shared Arc is thread-safe.
+unsafe impl Send for GvtFile {}
+unsafe impl Sync for GvtFile {}
@@ -69,16 +69,24 @@
 //! - **CorrId = GVThread slot ID**: Zero-lookup completion routing
 //! - **Dedicated reactor thread**: Always available, never starved by GVThread work
 //! - **Results slab**: O(1) result delivery indexed by slot, no hashmap
-//! - **MPSC queue**: Lock-free crossbeam `ArrayQueue` for request submission
+//! - **Per-worker submission queues**: Lock-free crossbeam `ArrayQueue`s, one per
+//!   scheduler worker, so GVThreads on different workers never contend on submit
 //! - **Stack-safe**: GVThread stacks are stable while blocked (no move/invalidation)
 
 pub mod reactor;
 pub mod worker_reactor;
 pub mod syscall;
 pub mod net;
+pub mod fs;
+mod batch;
+mod linked;
+mod multishot;
+mod wakeup;
 
 // Re-export the main types
 pub use reactor::{Reactor, ReactorConfig, ReactorShared};
-pub use worker_reactor::WorkerReactorPool;
+pub use batch::{BatchWait, IoOp, MAX_BATCH_OPS};
+pub use worker_reactor::{RingStats, WorkerReactorPool};
 pub use syscall::*;
 pub use net::{GvtListener, GvtStream};
+pub use fs::{FileMeta, GvtFile};
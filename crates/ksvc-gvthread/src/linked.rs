@@ -0,0 +1,120 @@
+//! # Linked timeouts
+//!
+//! An operation submitted with an attached `IORING_OP_LINK_TIMEOUT`
+//! produces two CQEs, in either order:
+//!
+//! ```text
+//!  op finished in time:   op → result        timeout → -ECANCELED
+//!  timeout fired first:   op → -ECANCELED    timeout → -ETIME
+//! ```
+//!
+//! The op's CQE carries the GVThread's slot as user data, the timeout's
+//! `LINK_TIMEOUT_TAG | slot`. `LinkedTimeouts` holds the op's result
+//! until both have arrived and then hands out one logical result:
+//! `-ETIME` if the timeout cancelled the op, the op's own result
+//! otherwise. The GVThread is woken only once, after the kernel is done
+//! with both SQEs.
+
+use std::collections::HashMap;
+
+/// User-data tag of a linked timeout's CQE (slot in the low 32 bits)
+pub(crate) const LINK_TIMEOUT_TAG: u64 = 1 << 62;
+
+/// User data for the linked timeout of `slot`'s operation
+#[inline]
+pub(crate) fn timeout_user_data(slot: u32) -> u64 {
+    LINK_TIMEOUT_TAG | slot as u64
+}
+
+struct Pending {
+    /// CQEs still to come (starts at 2)
+    remaining: u8,
+    /// The op's result, once its CQE arrived
+    result: i64,
+    /// The timeout CQE reported `-ETIME`
+    timed_out: bool,
+}
+
+/// Per-ring bookkeeping of in-flight ops with a linked timeout
+#[derive(Default)]
+pub(crate) struct LinkedTimeouts {
+    pending: HashMap<u32, Pending>,
+}
+
+impl LinkedTimeouts {
+    /// Record that `slot`'s op was queued with a linked timeout
+    pub(crate) fn start(&mut self, slot: u32) {
+        self.pending.insert(slot, Pending { remaining: 2, result: 0, timed_out: false });
+    }
+
+    /// Feed one CQE. Returns the slot to wake and its result, or `None`
+    /// while the other half of a linked pair is outstanding.
+    ///
+    /// CQEs of ops without a linked timeout pass straight through.
+    pub(crate) fn complete(&mut self, user_data: u64, result: i64) -> Option<(u32, i64)> {
+        let slot = user_data as u32;
+        let is_timeout = user_data & !0xFFFF_FFFF == LINK_TIMEOUT_TAG;
+        let Some(p) = self.pending.get_mut(&slot) else {
+            return if is_timeout { None } else { Some((slot, result)) };
+        };
+        if is_timeout {
+            p.timed_out = result == -(libc::ETIME as i64);
+        } else {
+            p.result = result;
+        }
+        p.remaining -= 1;
+        if p.remaining > 0 {
+            return None;
+        }
+
+        let p = self.pending.remove(&slot)?;
+        if p.timed_out && p.result == -(libc::ECANCELED as i64) {
+            Some((slot, -(libc::ETIME as i64)))
+        } else {
+            Some((slot, p.result))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ETIME: i64 = -(libc::ETIME as i64);
+    const ECANCELED: i64 = -(libc::ECANCELED as i64);
+
+    #[test]
+    fn plain_completion_passes_through() {
+        let mut linked = LinkedTimeouts::default();
+        assert_eq!(linked.complete(7, 42), Some((7, 42)));
+    }
+
+    #[test]
+    fn op_in_time_delivers_op_result() {
+        let mut linked = LinkedTimeouts::default();
+        linked.start(3);
+        assert_eq!(linked.complete(3, 100), None);
+        assert_eq!(linked.complete(timeout_user_data(3), ECANCELED), Some((3, 100)));
+        assert!(linked.pending.is_empty());
+    }
+
+    #[test]
+    fn timeout_first_delivers_etime_in_either_order() {
+        let mut linked = LinkedTimeouts::default();
+        linked.start(3);
+        assert_eq!(linked.complete(timeout_user_data(3), ETIME), None);
+        assert_eq!(linked.complete(3, ECANCELED), Some((3, ETIME)));
+
+        linked.start(4);
+        assert_eq!(linked.complete(4, ECANCELED), None);
+        assert_eq!(linked.complete(timeout_user_data(4), ETIME), Some((4, ETIME)));
+    }
+
+    #[test]
+    fn explicit_cancel_stays_ecanceled() {
+        let mut linked = LinkedTimeouts::default();
+        linked.start(5);
+        assert_eq!(linked.complete(5, ECANCELED), None);
+        assert_eq!(linked.complete(timeout_user_data(5), ECANCELED), Some((5, ECANCELED)));
+    }
+}
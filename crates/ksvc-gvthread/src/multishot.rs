@@ -0,0 +1,248 @@
+//! # Multishot accept
+//!
+//! One `IORING_OP_ACCEPT` with `IORING_ACCEPT_MULTISHOT` keeps producing
+//! a CQE per incoming connection (flagged `IORING_CQE_F_MORE`) until the
+//! kernel stops it. `MultishotAccept` is the per-listener state shared
+//! with the reactor:
+//!
+//! ```text
+//!  accept()  ── empty? arm once ──▶ reactor ──▶ AcceptMulti SQE
+//!     ▲                                            │ CQE per connection
+//!     └── pop fd ◀── ready queue ◀── reactor ◀─────┘ (wakes waiters)
+//! ```
+//!
+//! Its CQEs carry `MULTISHOT_TAG | id`, where `id` keys the reactor's
+//! registry. A CQE without `F_MORE` means the multishot stopped; the
+//! next `accept()` re-arms it. A first arm rejected with `-EINVAL`
+//! (kernel before 5.19) marks the listener unsupported and `accept()`
+//! falls back to single-shot; the reactor answers so itself, without
+//! submitting, when `IoCapabilities` already rules multishot out.
+
+use crate::reactor::{IoRequest, ReactorShared};
+
+use ksvc_core::entry::CorrId;
+
+use gvthread_core::id::GVThreadId;
+use gvthread_core::spinlock::SpinLock;
+use gvthread_core::state::Priority;
+use gvthread_runtime::scheduler;
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// User-data tag of a multishot accept's CQEs (registry id in the low
+/// 32 bits)
+pub(crate) const MULTISHOT_TAG: u64 = 1 << 61;
+
+/// `IoRequest::syscall_nr` that arms multishot accept:
+/// `args = [listen_fd, id, accept_flags]`
+pub(crate) const ARM_ACCEPT_SYSCALL_NR: u32 = u32::MAX - 1;
+
+/// `IORING_CQE_F_MORE`: the multishot stays armed after this CQE
+pub(crate) const CQE_F_MORE: u32 = 1 << 1;
+
+/// Registry id of a multishot CQE's user data, if it is one
+#[inline]
+pub(crate) fn multishot_id(user_data: u64) -> Option<u32> {
+    (user_data & !0xFFFF_FFFF == MULTISHOT_TAG).then_some(user_data as u32)
+}
+
+/// Per-listener multishot accept state
+pub(crate) struct MultishotAccept {
+    /// Accepted fds (or negative errno) not yet taken by `accept()`
+    ready: SpinLock<VecDeque<i64>>,
+    /// GVThreads blocked in `accept()`: (id, generation)
+    waiters: SpinLock<Vec<(GVThreadId, u32)>>,
+    /// The multishot SQE is (about to be) live in the kernel
+    armed: AtomicBool,
+    /// The kernel rejected multishot accept
+    unsupported: AtomicBool,
+    /// At least one connection came through (so `-EINVAL` is a real error)
+    delivered: AtomicBool,
+    /// The listener is gone: close whatever still arrives
+    closed: AtomicBool,
+}
+
+impl MultishotAccept {
+    pub(crate) fn new() -> Self {
+        Self {
+            ready: SpinLock::new(VecDeque::new()),
+            waiters: SpinLock::new(Vec::new()),
+            armed: AtomicBool::new(false),
+            unsupported: AtomicBool::new(false),
+            delivered: AtomicBool::new(false),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// Block the calling GVThread until a connection arrives on
+    /// `listen_fd` (registry `id`), arming the multishot if needed.
+    ///
+    /// Returns the new fd or negative errno, or `None` if the kernel
+    /// lacks multishot accept and the caller should use single-shot.
+    pub(crate) fn accept(&self, shared: &ReactorShared, id: u32, listen_fd: i32) -> Option<i64> {
+        loop {
+            if let Some(result) = self.ready.lock().pop_front() {
+                return Some(result);
+            }
+            if self.is_unsupported() {
+                return None;
+            }
+
+            // Cancellation reaches us through the reactor (see
+            // `cancel_waiter`), as for any other reactor wait
+            scheduler::set_current_wait_kind(scheduler::WAIT_IO);
+            if scheduler::current_cancelled() {
+                scheduler::set_current_wait_kind(scheduler::WAIT_NONE);
+                return Some(-(libc::ECANCELED as i64));
+            }
+            if !self.armed.swap(true, Ordering::AcqRel) {
+                let slot = gvthread_runtime::tls::current_gvthread_id().as_u32();
+                let arm = IoRequest {
+                    corr_id: CorrId::from_gvthread_id(slot),
+                    syscall_nr: ARM_ACCEPT_SYSCALL_NR,
+                    args: [listen_fd as u64, id as u64, libc::SOCK_CLOEXEC as u64, 0, 0, 0],
+                    priority: Priority::Normal,
+                    generation: 0,
+                    timeout_ns: 0,
+                };
+                if let Err(e) = shared.push_request(arm) {
+                    self.arm_failed();
+                    scheduler::set_current_wait_kind(scheduler::WAIT_NONE);
+                    return Some(e);
+                }
+            }
+            self.wait();
+            scheduler::set_current_wait_kind(scheduler::WAIT_NONE);
+        }
+    }
+
+    fn is_unsupported(&self) -> bool {
+        self.unsupported.load(Ordering::Acquire)
+    }
+
+    /// Reactor: arming failed before reaching the kernel (waiters retry)
+    pub(crate) fn arm_failed(&self) {
+        self.armed.store(false, Ordering::Release);
+        self.wake_all();
+    }
+
+    pub(crate) fn is_armed(&self) -> bool {
+        self.armed.load(Ordering::Acquire)
+    }
+
+    /// Register the calling GVThread and block until `complete` (or a
+    /// cancellation) wakes it. Returns at once if a result or the
+    /// unsupported verdict arrived meanwhile.
+    fn wait(&self) {
+        let me = scheduler::current_waiter()
+            .expect("multishot accept outside GVThread");
+        self.waiters.lock().push(me);
+        // Same withdraw-or-block handshake as the reactor's space waiters
+        if !self.ready.lock().is_empty() || self.is_unsupported() || !self.is_armed() {
+            let mut waiters = self.waiters.lock();
+            if let Some(pos) = waiters.iter().position(|w| *w == me) {
+                waiters.swap_remove(pos);
+                return;
+            }
+        }
+        scheduler::block_current();
+    }
+
+    /// Reactor: handle one CQE. Returns true once the multishot stopped
+    /// and the listener is closed, i.e. the registry entry can go.
+    pub(crate) fn complete(&self, result: i64, more: bool) -> bool {
+        let closed = {
+            // `closed` is set under this lock, so no fd slips past `close`
+            let mut ready = self.ready.lock();
+            let closed = self.closed.load(Ordering::Acquire);
+            if result >= 0 {
+                self.delivered.store(true, Ordering::Release);
+                if closed {
+                    unsafe { libc::close(result as i32); }
+                } else {
+                    ready.push_back(result);
+                }
+            } else if result == -(libc::EINVAL as i64) && !self.delivered.load(Ordering::Acquire) {
+                self.unsupported.store(true, Ordering::Release);
+            } else if !closed && (more || result != -(libc::ECANCELED as i64)) {
+                // Hand real errors to accept(); a plain stop just re-arms
+                ready.push_back(result);
+            }
+            closed
+        };
+        if !more {
+            self.armed.store(false, Ordering::Release);
+        }
+        self.wake_all();
+        !more && closed
+    }
+
+    /// Reactor: GVThread `id` was cancelled; wake it if it waits here
+    pub(crate) fn cancel_waiter(&self, id: GVThreadId) {
+        let waiter = {
+            let mut waiters = self.waiters.lock();
+            waiters.iter().position(|w| w.0 == id).map(|pos| waiters.swap_remove(pos))
+        };
+        if let Some((id, generation)) = waiter {
+            scheduler::wake_waiter(id, generation);
+        }
+    }
+
+    /// Listener dropped: close buffered fds and any still to arrive.
+    /// The owner then retires the registry entry via the reactor.
+    pub(crate) fn close(&self) {
+        let mut ready = self.ready.lock();
+        self.closed.store(true, Ordering::Release);
+        for fd in ready.drain(..) {
+            if fd >= 0 {
+                unsafe { libc::close(fd as i32); }
+            }
+        }
+    }
+
+    fn wake_all(&self) {
+        let waiters = std::mem::take(&mut *self.waiters.lock());
+        for (id, generation) in waiters {
+            scheduler::wake_waiter(id, generation);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multishot_id_only_matches_its_tag() {
+        assert_eq!(multishot_id(MULTISHOT_TAG | 7), Some(7));
+        assert_eq!(multishot_id(7), None);
+        assert_eq!(multishot_id(CorrId::CANCEL.0), None);
+    }
+
+    #[test]
+    fn first_einval_means_unsupported() {
+        let accept = MultishotAccept::new();
+        accept.armed.store(true, Ordering::Release);
+        assert!(!accept.complete(-(libc::EINVAL as i64), false));
+        assert!(accept.is_unsupported());
+        assert!(!accept.is_armed());
+        assert!(accept.ready.lock().is_empty());
+    }
+
+    #[test]
+    fn connections_queue_until_stop_and_cancel_is_not_an_error() {
+        let accept = MultishotAccept::new();
+        accept.armed.store(true, Ordering::Release);
+        assert!(!accept.complete(10, true));
+        assert!(!accept.complete(-(libc::EMFILE as i64), true));
+        assert!(accept.is_armed());
+        assert!(!accept.complete(-(libc::ECANCELED as i64), false));
+        assert!(!accept.is_armed());
+        assert!(!accept.is_unsupported());
+        assert_eq!(
+            accept.ready.lock().iter().copied().collect::<Vec<_>>(),
+            [10, -(libc::EMFILE as i64)]
+        );
+    }
+}
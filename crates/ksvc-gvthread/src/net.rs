@@ -15,12 +15,32 @@
 //!
 //! // Shared reactor path (legacy):
 //! let listener = GvtListener::bind(reactor.shared(), 8080)?;
+//!
+//! // A specific address, IPv4 or IPv6:
+//! let listener = GvtListener::bind_addr(reactor.shared(), "[::1]:8080".parse()?)?;
+//!
+//! // Shared reactor, one multishot accept SQE for many connections:
+//! let listener = GvtListener::bind(reactor.shared(), 8080)?.with_multishot();
 //! ```
 
-use crate::reactor::ReactorShared;
+use crate::batch::{BatchWait, IoOp};
+use crate::multishot::MultishotAccept;
+use crate::reactor::{IoRequest, ReactorShared, RELEASE_ACCEPT_SYSCALL_NR};
 use crate::syscall::*;
 
+use ksvc_core::entry::CorrId;
+
+use gvthread_core::state::Priority;
+
+use gvthread::Timeout;
+
+use std::io::{IoSlice, IoSliceMut};
+use std::net::{Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, SocketAddrV4, SocketAddrV6};
 use std::sync::Arc;
+use std::time::Duration;
+
+/// `listen(2)` backlog of bound listeners
+pub const LISTEN_BACKLOG: i32 = 4096;
 
 /// A TCP listener bound to a port, using io_uring for accept().
 ///
@@ -32,36 +52,52 @@ use std::sync::Arc;
 pub struct GvtListener {
     fd: i32,
     shared: Option<Arc<ReactorShared>>,
+    /// Multishot accept state and its reactor registry id
+    multishot: Option<(Arc<MultishotAccept>, u32)>,
 }
 
 impl GvtListener {
     /// Create a listener from an existing fd + reactor shared state.
     pub fn from_raw(fd: i32, shared: Arc<ReactorShared>) -> Self {
-        Self { fd, shared: Some(shared) }
+        Self { fd, shared: Some(shared), multishot: None }
     }
 
     /// Create a listener from an existing fd, using worker-local I/O.
     pub fn from_raw_local(fd: i32) -> Self {
-        Self { fd, shared: None }
+        Self { fd, shared: None, multishot: None }
     }
 
     /// Bind and listen on a port using the shared reactor.
     pub fn bind(shared: Arc<ReactorShared>, port: u16) -> Result<Self, i32> {
-        let fd = Self::bind_socket(port)?;
-        Ok(Self { fd, shared: Some(shared) })
+        Self::bind_addr(shared, any_addr(port))
     }
 
     /// Bind and listen on a port using worker-local io_uring.
     pub fn bind_local(port: u16) -> Result<Self, i32> {
-        let fd = Self::bind_socket(port)?;
-        Ok(Self { fd, shared: None })
+        Self::bind_addr_local(any_addr(port))
+    }
+
+    /// Bind and listen on `addr` (IPv4 or IPv6) using the shared reactor.
+    pub fn bind_addr(shared: Arc<ReactorShared>, addr: SocketAddr) -> Result<Self, i32> {
+        let fd = Self::bind_socket(addr)?;
+        Ok(Self { fd, shared: Some(shared), multishot: None })
+    }
+
+    /// Bind and listen on `addr` (IPv4 or IPv6) using worker-local io_uring.
+    pub fn bind_addr_local(addr: SocketAddr) -> Result<Self, i32> {
+        let fd = Self::bind_socket(addr)?;
+        Ok(Self { fd, shared: None, multishot: None })
     }
 
     /// Common socket setup: create, setsockopt, bind, listen.
-    fn bind_socket(port: u16) -> Result<i32, i32> {
+    fn bind_socket(addr: SocketAddr) -> Result<i32, i32> {
+        let family = match addr {
+            SocketAddr::V4(_) => libc::AF_INET,
+            SocketAddr::V6(_) => libc::AF_INET6,
+        };
         let fd = unsafe {
             libc::socket(
-                libc::AF_INET,
+                family,
                 libc::SOCK_STREAM | libc::SOCK_CLOEXEC,
                 0,
             )
@@ -97,25 +133,16 @@ impl GvtListener {
             );
         }
 
-        let mut addr: libc::sockaddr_in = unsafe { std::mem::zeroed() };
-        addr.sin_family = libc::AF_INET as u16;
-        addr.sin_addr.s_addr = 0; // INADDR_ANY
-        addr.sin_port = port.to_be();
-
+        let (storage, len) = to_sockaddr(addr);
         let ret = unsafe {
-            libc::bind(
-                fd,
-                &addr as *const _ as *const libc::sockaddr,
-                std::mem::size_of_val(&addr) as u32,
-            )
+            libc::bind(fd, &storage as *const _ as *const libc::sockaddr, len)
         };
-        if ret != 0 {
+        if ret != 0 || unsafe { libc::listen(fd, LISTEN_BACKLOG) } != 0 {
+            let errno = unsafe { *libc::__errno_location() };
             unsafe { libc::close(fd); }
-            return Err(unsafe { *libc::__errno_location() });
+            return Err(errno);
         }
 
-        unsafe { libc::listen(fd, 4096); }
-
         Ok(fd)
     }
 
@@ -123,19 +150,24 @@ impl GvtListener {
     ///
     /// Returns a `GvtStream` for the new connection.
     pub fn accept(&self) -> Result<GvtStream, i64> {
-        let mut addr: libc::sockaddr_in = unsafe { std::mem::zeroed() };
+        let mut addr: libc::sockaddr_storage = unsafe { std::mem::zeroed() };
         let mut addr_len: libc::socklen_t =
-            std::mem::size_of::<libc::sockaddr_in>() as u32;
+            std::mem::size_of::<libc::sockaddr_storage>() as u32;
 
-        let client_fd = match &self.shared {
-            Some(shared) => ksvc_accept4(
+        let multishot = match (&self.shared, &self.multishot) {
+            (Some(shared), Some((accept, id))) => accept.accept(shared, *id, self.fd),
+            _ => None,
+        };
+        let client_fd = match (multishot, &self.shared) {
+            (Some(result), _) => result,
+            (None, Some(shared)) => ksvc_accept4(
                 shared,
                 self.fd,
                 &mut addr as *mut _ as *mut libc::sockaddr,
                 &mut addr_len,
                 libc::SOCK_CLOEXEC,
             ),
-            None => wr_accept4(
+            (None, None) => wr_accept4(
                 self.fd,
                 &mut addr as *mut _ as *mut libc::sockaddr,
                 &mut addr_len,
@@ -147,22 +179,35 @@ impl GvtListener {
             return Err(client_fd);
         }
 
-        // TCP_NODELAY on accepted socket
-        unsafe {
-            let opt: i32 = 1;
-            libc::setsockopt(
-                client_fd as i32,
-                libc::IPPROTO_TCP,
-                libc::TCP_NODELAY,
-                &opt as *const _ as *const _,
-                4,
-            );
-        }
-
-        Ok(GvtStream {
+        let stream = GvtStream {
             fd: client_fd as i32,
             shared: self.shared.clone(),
-        })
+        };
+        // TCP_NODELAY on accepted socket (best effort)
+        let _ = stream.set_nodelay(true);
+        Ok(stream)
+    }
+
+    /// Accept through one multishot `IORING_OP_ACCEPT` (kernel 5.19+)
+    ///
+    /// Connections arriving between `accept()` calls are queued, so a
+    /// busy listener costs one SQE rather than one per connection. The
+    /// SQE is armed on the first `accept()` and re-armed whenever the
+    /// kernel stops it. Kernels without multishot accept fall back to
+    /// single-shot transparently. No-op for worker-local listeners.
+    pub fn with_multishot(mut self) -> Self {
+        if let (Some(shared), None) = (&self.shared, &self.multishot) {
+            let accept = Arc::new(MultishotAccept::new());
+            let id = shared.register_multishot(Arc::clone(&accept));
+            self.multishot = Some((accept, id));
+        }
+        self
+    }
+
+    /// Address the listener is bound to (`getsockname`), e.g. to learn
+    /// the port picked for port 0.
+    pub fn local_addr(&self) -> Result<SocketAddr, i64> {
+        sockname(self.fd, libc::getsockname)
     }
 
     /// Get the raw fd.
@@ -173,6 +218,19 @@ impl GvtListener {
 
 impl Drop for GvtListener {
     fn drop(&mut self) {
+        if let (Some(shared), Some((accept, id))) = (&self.shared, &self.multishot) {
+            accept.close();
+            // The kernel holds its own reference to the listening socket:
+            // the multishot must be cancelled, not just the fd closed
+            shared.push_control(IoRequest {
+                corr_id: CorrId(0),
+                syscall_nr: RELEASE_ACCEPT_SYSCALL_NR,
+                args: [*id as u64, 0, 0, 0, 0, 0],
+                priority: Priority::Normal,
+                generation: 0,
+                timeout_ns: 0,
+            });
+        }
         unsafe { libc::close(self.fd); }
     }
 }
@@ -205,6 +263,29 @@ impl GvtStream {
         }
     }
 
+    /// Read with a deadline. Like `read`, but gives up with
+    /// `Err(Timeout)` if no data arrives within `timeout`.
+    ///
+    /// The kernel cancels the pending recv, so the buffer is free again
+    /// when this returns.
+    pub fn read_timeout(&self, buf: &mut [u8], timeout: Duration) -> Result<i64, Timeout> {
+        let n = match &self.shared {
+            Some(s) => ksvc_recv_timeout(s, self.fd, buf, 0, timeout),
+            None => wr_recv_timeout(self.fd, buf, 0, timeout),
+        };
+        if n == -(libc::ETIME as i64) { Err(Timeout) } else { Ok(n) }
+    }
+
+    /// Single send with a deadline. Returns bytes sent or negative errno,
+    /// or `Err(Timeout)` if the peer stopped reading for `timeout`.
+    pub fn write_timeout(&self, buf: &[u8], timeout: Duration) -> Result<i64, Timeout> {
+        let n = match &self.shared {
+            Some(s) => ksvc_send_timeout(s, self.fd, buf, 0, timeout),
+            None => wr_send_timeout(self.fd, buf, 0, timeout),
+        };
+        if n == -(libc::ETIME as i64) { Err(Timeout) } else { Ok(n) }
+    }
+
     /// Write buffer. Blocks until all bytes are sent.
     /// Returns total bytes written or negative errno.
     pub fn write_all(&self, buf: &[u8]) -> i64 {
@@ -222,7 +303,71 @@ impl GvtStream {
         }
     }
 
+    /// Scatter read into `bufs`, filled in order (`readv`). Returns
+    /// total bytes read, 0 for EOF, or negative errno.
+    pub fn read_vectored(&self, bufs: &mut [IoSliceMut<'_>]) -> i64 {
+        match &self.shared {
+            Some(s) => ksvc_readv(s, self.fd, bufs),
+            None => wr_readv(self.fd, bufs),
+        }
+    }
+
+    /// Gather write of `bufs` in order (single `writev`), e.g. header
+    /// and body without copying them together. Returns bytes sent
+    /// (possibly fewer than asked) or negative errno.
+    pub fn write_vectored(&self, bufs: &[IoSlice<'_>]) -> i64 {
+        match &self.shared {
+            Some(s) => ksvc_writev(s, self.fd, bufs),
+            None => wr_writev(self.fd, bufs),
+        }
+    }
+
+    /// Gather write of all of `bufs`, continuing into the remaining
+    /// slices after a partial write. Returns total bytes written or
+    /// negative errno.
+    pub fn write_all_vectored(&self, bufs: &[IoSlice<'_>]) -> i64 {
+        match &self.shared {
+            Some(s) => ksvc_writev_all(s, self.fd, bufs),
+            None => wr_writev_all(self.fd, bufs),
+        }
+    }
+
+    /// A `recv` into `buf` for `submit_batch`.
+    pub fn read_op<'a>(&self, buf: &'a mut [u8]) -> IoOp<'a> {
+        IoOp::recv(self.fd, buf, 0)
+    }
+
+    /// A single `send` of `buf` for `submit_batch`.
+    pub fn write_op<'a>(&self, buf: &'a [u8]) -> IoOp<'a> {
+        IoOp::send(self.fd, buf, 0)
+    }
+
+    /// A gather write of `bufs` for `submit_batch`.
+    pub fn write_vectored_op<'a>(&self, bufs: &'a [IoSlice<'a>]) -> IoOp<'a> {
+        IoOp::writev(self.fd, bufs)
+    }
+
+    /// Submit `ops` (on this or other streams) through this stream's
+    /// reactor and park once (see `ReactorShared::submit_batch`).
+    ///
+    /// The worker-local path has no batches: every op fails with
+    /// `-ENOSYS` and `None` is returned.
+    pub fn submit_batch(&self, ops: &mut [IoOp<'_>], wait: BatchWait) -> Option<usize> {
+        match &self.shared {
+            Some(s) => s.submit_batch(ops, wait),
+            None => {
+                for op in ops.iter_mut() {
+                    op.fail(-(libc::ENOSYS as i64));
+                }
+                None
+            }
+        }
+    }
+
     /// Close the connection via io_uring.
+    ///
+    /// The stream still owns the fd number afterwards and `Drop` closes
+    /// it again; prefer `close`.
     pub fn close_uring(&self) -> i64 {
         match &self.shared {
             Some(s) => ksvc_close(s, self.fd),
@@ -230,6 +375,57 @@ impl GvtStream {
         }
     }
 
+    /// Close the connection via io_uring, consuming the stream.
+    /// Blocks the GVThread. Returns 0 or negative errno.
+    pub fn close(mut self) -> i64 {
+        let ret = self.close_uring();
+        self.fd = -1; // Nothing left for drop
+        ret
+    }
+
+    /// Half-close the connection through io_uring (`shutdown(2)`).
+    /// Blocks the GVThread. Returns 0 or negative errno.
+    ///
+    /// `Shutdown::Write` sends FIN while reads keep working, e.g. to
+    /// signal end of request and still collect the response.
+    pub fn shutdown(&self, how: Shutdown) -> i64 {
+        let how = match how {
+            Shutdown::Read => libc::SHUT_RD,
+            Shutdown::Write => libc::SHUT_WR,
+            Shutdown::Both => libc::SHUT_RDWR,
+        };
+        match &self.shared {
+            Some(s) => ksvc_shutdown(s, self.fd, how),
+            None => wr_shutdown(self.fd, how),
+        }
+    }
+
+    /// Enable or disable Nagle's algorithm (`TCP_NODELAY`).
+    /// Accepted streams start with it set.
+    pub fn set_nodelay(&self, nodelay: bool) -> Result<(), i64> {
+        setsockopt_bool(self.fd, libc::IPPROTO_TCP, libc::TCP_NODELAY, nodelay)
+    }
+
+    /// Current `TCP_NODELAY` setting.
+    pub fn nodelay(&self) -> Result<bool, i64> {
+        getsockopt_bool(self.fd, libc::IPPROTO_TCP, libc::TCP_NODELAY)
+    }
+
+    /// Enable or disable `SO_KEEPALIVE` (kernel default probe timings).
+    pub fn set_keepalive(&self, keepalive: bool) -> Result<(), i64> {
+        setsockopt_bool(self.fd, libc::SOL_SOCKET, libc::SO_KEEPALIVE, keepalive)
+    }
+
+    /// Address of the remote peer (`getpeername`).
+    pub fn peer_addr(&self) -> Result<SocketAddr, i64> {
+        sockname(self.fd, libc::getpeername)
+    }
+
+    /// Local address of this end of the connection (`getsockname`).
+    pub fn local_addr(&self) -> Result<SocketAddr, i64> {
+        sockname(self.fd, libc::getsockname)
+    }
+
     /// Get the raw fd.
     pub fn fd(&self) -> i32 {
         self.fd
@@ -244,7 +440,103 @@ impl GvtStream {
 impl Drop for GvtStream {
     fn drop(&mut self) {
         // Use synchronous close in drop (simpler, always works)
-        unsafe { libc::close(self.fd); }
+        if self.fd >= 0 {
+            unsafe { libc::close(self.fd); }
+        }
+    }
+}
+
+// ── Socket options and addresses ──
+//
+// Plain syscalls on the fd: they never block, so a reactor round trip
+// would only add latency.
+
+fn errno() -> i64 {
+    -(unsafe { *libc::__errno_location() } as i64)
+}
+
+fn setsockopt_bool(fd: i32, level: i32, name: i32, value: bool) -> Result<(), i64> {
+    let opt = value as i32;
+    let ret = unsafe {
+        libc::setsockopt(
+            fd,
+            level,
+            name,
+            &opt as *const _ as *const _,
+            std::mem::size_of::<i32>() as u32,
+        )
+    };
+    if ret != 0 { Err(errno()) } else { Ok(()) }
+}
+
+fn getsockopt_bool(fd: i32, level: i32, name: i32) -> Result<bool, i64> {
+    let mut opt: i32 = 0;
+    let mut len = std::mem::size_of::<i32>() as libc::socklen_t;
+    let ret = unsafe {
+        libc::getsockopt(fd, level, name, &mut opt as *mut _ as *mut _, &mut len)
+    };
+    if ret != 0 { Err(errno()) } else { Ok(opt != 0) }
+}
+
+/// `0.0.0.0:port` (INADDR_ANY)
+fn any_addr(port: u16) -> SocketAddr {
+    SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, port))
+}
+
+/// `addr` as the `sockaddr` `bind(2)` takes, with its length
+fn to_sockaddr(addr: SocketAddr) -> (libc::sockaddr_storage, libc::socklen_t) {
+    let mut storage: libc::sockaddr_storage = unsafe { std::mem::zeroed() };
+    let len = match addr {
+        SocketAddr::V4(v4) => {
+            let a = unsafe { &mut *(&mut storage as *mut _ as *mut libc::sockaddr_in) };
+            a.sin_family = libc::AF_INET as libc::sa_family_t;
+            a.sin_port = v4.port().to_be();
+            a.sin_addr.s_addr = u32::from(*v4.ip()).to_be();
+            std::mem::size_of::<libc::sockaddr_in>()
+        }
+        SocketAddr::V6(v6) => {
+            let a = unsafe { &mut *(&mut storage as *mut _ as *mut libc::sockaddr_in6) };
+            a.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+            a.sin6_port = v6.port().to_be();
+            a.sin6_addr.s6_addr = v6.ip().octets();
+            a.sin6_flowinfo = v6.flowinfo();
+            a.sin6_scope_id = v6.scope_id();
+            std::mem::size_of::<libc::sockaddr_in6>()
+        }
+    };
+    (storage, len as libc::socklen_t)
+}
+
+/// `getsockname`/`getpeername` into a `SocketAddr` (`-EAFNOSUPPORT` for
+/// non-IP sockets)
+fn sockname(
+    fd: i32,
+    f: unsafe extern "C" fn(i32, *mut libc::sockaddr, *mut libc::socklen_t) -> i32,
+) -> Result<SocketAddr, i64> {
+    let mut storage: libc::sockaddr_storage = unsafe { std::mem::zeroed() };
+    let mut len = std::mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+    let ret = unsafe { f(fd, &mut storage as *mut _ as *mut libc::sockaddr, &mut len) };
+    if ret != 0 {
+        return Err(errno());
+    }
+    match storage.ss_family as i32 {
+        libc::AF_INET => {
+            let a = unsafe { &*(&storage as *const _ as *const libc::sockaddr_in) };
+            Ok(SocketAddr::V4(SocketAddrV4::new(
+                Ipv4Addr::from(u32::from_be(a.sin_addr.s_addr)),
+                u16::from_be(a.sin_port),
+            )))
+        }
+        libc::AF_INET6 => {
+            let a = unsafe { &*(&storage as *const _ as *const libc::sockaddr_in6) };
+            Ok(SocketAddr::V6(SocketAddrV6::new(
+                Ipv6Addr::from(a.sin6_addr.s6_addr),
+                u16::from_be(a.sin6_port),
+                a.sin6_flowinfo,
+                a.sin6_scope_id,
+            )))
+        }
+        _ => Err(-(libc::EAFNOSUPPORT as i64)),
     }
 }
 
@@ -254,3 +546,31 @@ unsafe impl Send for GvtStream {}
 unsafe impl Sync for GvtStream {}
 unsafe impl Send for GvtListener {}
 unsafe impl Sync for GvtListener {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::fd::IntoRawFd;
+
+    #[test]
+    fn addresses_and_options_on_raw_fd() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let server_addr = listener.local_addr().unwrap();
+        let client = std::net::TcpStream::connect(server_addr).unwrap();
+        let (_server, client_addr) = listener.accept().unwrap();
+
+        let stream = GvtStream::from_raw_local(client.into_raw_fd());
+        assert_eq!(stream.peer_addr(), Ok(server_addr));
+        assert_eq!(stream.local_addr(), Ok(client_addr));
+
+        stream.set_nodelay(true).unwrap();
+        assert_eq!(stream.nodelay(), Ok(true));
+        stream.set_nodelay(false).unwrap();
+        assert_eq!(stream.nodelay(), Ok(false));
+        stream.set_keepalive(true).unwrap();
+
+        let bad = GvtStream::from_raw_local(-1);
+        assert_eq!(bad.peer_addr(), Err(-(libc::EBADF as i64)));
+        assert_eq!(bad.set_nodelay(true), Err(-(libc::EBADF as i64)));
+    }
+}
@@ -1,13 +1,87 @@
 //! # Reactor — the io_uring completion poller
 //!
 //! The reactor runs on a dedicated OS thread. It:
-//! 1. Dequeues `IoRequest`s from a lock-free MPSC queue
+//! 1. Dequeues `IoRequest`s from the per-worker submission queues
 //! 2. Submits them to io_uring via `BasicIoUring`
-//! 3. Polls completions via `flush_and_wait()`
+//! 3. Polls completions via `flush_and_wait()`, woken early when new
+//!    requests arrive (see "Wakeup")
 //! 4. Writes results to a results slab
-//! 5. Wakes the corresponding GVThread via `scheduler::wake_gvthread()`
+//! 5. Wakes the corresponding GVThread via `scheduler::wake_waiter()`
 //!
 //! This is the GVThread equivalent of Go's netpoller.
+//!
+//! ## Submission queues
+//!
+//! With one GVThread per connection, every worker submits all the time;
+//! a single shared queue has them all fighting over its head and tail.
+//! Instead each scheduler worker gets its own queue (sized
+//! `queue_capacity`), which only that worker's GVThreads push to, and
+//! the reactor drains them round-robin. Pushes from other threads — OS
+//! threads, the cancel hook — go to one extra shared queue.
+//!
+//! The per-worker queues are still `ArrayQueue`s rather than SPSC rings:
+//! a GVThread preempted mid-push lets another one on the same worker
+//! push, and the cancel hook runs wherever the cancel came from. Being
+//! effectively single-producer, they see no contention either way.
+//!
+//! ## Wakeup
+//!
+//! With nothing queued, the reactor blocks in `flush_and_wait(1)`. A
+//! `POLL_ADD` on an eventfd (`EventFdNotifier`) is kept in the ring, so
+//! the wait also ends when a request is pushed or the reactor is shut
+//! down: pushers notify the eventfd while the reactor is blocked (see
+//! `wakeup`). No sleep-and-retry loop, and no request waits for an
+//! unrelated CQE.
+//!
+//! ## Cancellation
+//!
+//! Cancelling a GVThread blocked in a reactor call adds its slot to the
+//! reactor's cancel list, which unlike the submission queues is never
+//! full, so a cancel is never lost or held up. (A `CANCEL_SYSCALL_NR`
+//! request through the queues does the same.) The reactor issues
+//! `IORING_OP_ASYNC_CANCEL`, retrying on a later pass while the SQ is
+//! full; the original operation then completes with `-ECANCELED` and
+//! wakes the GVThread as usual, so its buffer is never released while
+//! the kernel still owns it. Requests from an already cancelled GVThread
+//! are failed with `-ECANCELED` without submitting.
+//!
+//! ## Backpressure
+//!
+//! A request is never dropped. When its worker's queue is full, the
+//! submitting GVThread registers itself as a space waiter and blocks;
+//! the reactor wakes all space waiters after each drain and they retry.
+//! Only the submitter is parked — its worker keeps running other
+//! GVThreads. Submitters still waiting when the reactor shuts down get
+//! `-ESHUTDOWN`.
+//!
+//! ## Results slab
+//!
+//! Results are routed by `CorrId` = GVThread slot, straight into a slab
+//! indexed by slot. The slab covers `max_slots`, raised at start to the
+//! running scheduler's `max_gvthreads`. A request from a slot outside it
+//! fails with `-ERANGE` before it is queued; a completion for one is
+//! logged and dropped rather than misrouted.
+//!
+//! ## Multishot accept
+//!
+//! `ARM_ACCEPT_SYSCALL_NR` / `RELEASE_ACCEPT_SYSCALL_NR` requests arm and
+//! retire a listener's multishot accept; its CQEs are routed to the
+//! listener's `MultishotAccept` by registry id (see `multishot`).
+//!
+//! ## Timeouts
+//!
+//! A request with `timeout_ns != 0` is submitted with a linked
+//! `IORING_OP_LINK_TIMEOUT`. The GVThread is woken once both CQEs are in
+//! and gets `-ETIME` if the timeout cancelled the operation (see
+//! `linked`).
+//!
+//! ## Batches
+//!
+//! `submit_batch` queues several requests for one GVThread; their CQEs
+//! carry `BATCH_TAG | index << 32 | slot` and are counted down in the
+//! slot's `IoBatch` rather than the results slab (see `batch`). When a
+//! batch is satisfied early (`BatchWait::Any`) the reactor cancels its
+//! remaining ops itself, and fails those still queued.
 
 use ksvc_core::entry::{CorrId, SubmitEntry};
 use ksvc_core::io_backend::{IoBackend, IoCompletion};
@@ -16,15 +90,61 @@ use ksvc_core::router::SyscallRouter;
 use ksvc_module::basic_iouring::{BasicIoUring, BasicIoUringConfig};
 use ksvc_module::probe_router::ProbeRouter;
 
+use crate::batch::{self, BatchWait, IoBatch, IoOp};
+use crate::linked::{self, LinkedTimeouts};
+use crate::multishot::{self, MultishotAccept, ARM_ACCEPT_SYSCALL_NR};
+use crate::wakeup::{RingWakeup, WAKEUP_TAG};
+
 use gvthread_core::id::GVThreadId;
+use gvthread_core::spinlock::SpinLock;
 use gvthread_core::state::Priority;
 use gvthread_runtime::scheduler;
 
 use crossbeam_queue::ArrayQueue;
 
-use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU32, Ordering};
+use std::sync::{Arc, Weak};
 use std::thread;
+use std::time::Duration;
+
+/// `IoRequest::syscall_nr` of a cancel request for `corr_id`'s operation.
+pub use ksvc_core::io_backend::CANCEL_SYSCALL_NR;
+
+/// `IoRequest::syscall_nr` retiring multishot accept `args[0]` (registry
+/// id): cancels it if armed, and forgets it once it has stopped.
+pub(crate) const RELEASE_ACCEPT_SYSCALL_NR: u32 = u32::MAX - 2;
+
+/// Id of the next reactor thread (`ksvc-reactor-{id}`).
+static NEXT_REACTOR_ID: AtomicU32 = AtomicU32::new(0);
+
+/// Running reactors, for the scheduler's I/O cancel hook.
+static REACTORS: SpinLock<Vec<Weak<ReactorShared>>> = SpinLock::new(Vec::new());
+
+/// I/O cancel hook: ask every reactor, and the worker reactor pool, to
+/// cancel `id`'s operation. Reactors that don't own it get `-ENOENT` from
+/// the kernel and move on.
+pub(crate) fn cancel_io(id: GVThreadId) {
+    crate::worker_reactor::cancel_io(id);
+
+    let reactors: Vec<_> = REACTORS.lock().iter().filter_map(Weak::upgrade).collect();
+    for shared in reactors {
+        if (id.as_u32() as usize) < shared.max_slots {
+            shared.request_cancel(id.as_u32());
+        }
+    }
+}
+
+/// Wake the GVThread in `slot` once its result is written
+///
+/// It pushed its request and is committed to `block_current`, but may
+/// not have got there yet if the reactor beat its worker — a plain
+/// `wake_gvthread` would then be lost. `wake_waiter` waits it out.
+/// `generation` is the submitter's, recorded with its request: should
+/// the slot have been reused since, the wake is ignored.
+fn wake_submitter(slot: u32, generation: u32) {
+    scheduler::wake_waiter(GVThreadId::new(slot), generation);
+}
 
 /// An I/O request from a GVThread to the reactor.
 #[derive(Debug)]
@@ -37,15 +157,23 @@ pub struct IoRequest {
     pub args: [u64; 6],
     /// Priority of the requesting GVThread (for wake).
     pub priority: Priority,
+    /// Slot generation of the requesting GVThread (for wake; 0 for
+    /// requests nobody parks for).
+    pub generation: u32,
+    /// Linked timeout in nanoseconds (0 = none). On expiry the operation
+    /// is cancelled and completes with `-ETIME`.
+    pub timeout_ns: u64,
 }
 
 /// Reactor configuration.
 pub struct ReactorConfig {
     /// io_uring SQ size (power of 2).
     pub sq_entries: u32,
-    /// Max GVThreads (determines results slab size).
+    /// Results slab size (min): raised to the scheduler's `max_gvthreads`
+    /// if it is running when the reactor starts.
     pub max_slots: usize,
-    /// MPSC queue capacity for incoming requests.
+    /// Capacity of each submission queue (one per scheduler worker, plus
+    /// one for everyone else).
     pub queue_capacity: usize,
 }
 
@@ -59,30 +187,193 @@ impl Default for ReactorConfig {
     }
 }
 
+/// One queue per scheduler worker, plus one for other threads (see
+/// "Submission queues" above).
+struct SubmitQueues {
+    /// Index = worker id.
+    workers: Box<[WorkerQueue]>,
+    /// OS threads, the cancel hook, workers started after the reactor.
+    other: ArrayQueue<IoRequest>,
+}
+
+/// Cache-line aligned so neighbouring workers' queues don't share lines.
+#[repr(align(64))]
+struct WorkerQueue(ArrayQueue<IoRequest>);
+
+impl SubmitQueues {
+    fn new(workers: usize, capacity: usize) -> Self {
+        Self {
+            workers: (0..workers).map(|_| WorkerQueue(ArrayQueue::new(capacity))).collect(),
+            other: ArrayQueue::new(capacity),
+        }
+    }
+
+    /// The queue the calling thread pushes to.
+    fn for_caller(&self) -> &ArrayQueue<IoRequest> {
+        self.workers
+            .get(gvthread_runtime::worker::current_worker_id())
+            .map_or(&self.other, |q| &q.0)
+    }
+
+    fn queues(&self) -> impl Iterator<Item = &ArrayQueue<IoRequest>> + Clone {
+        self.workers.iter().map(|q| &q.0).chain(std::iter::once(&self.other))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.queues().all(ArrayQueue::is_empty)
+    }
+
+    /// Move up to `max` requests into `batch`, starting at queue `*start`
+    /// and moving the start on by one so no queue is always served last.
+    fn drain_into(&self, batch: &mut Vec<IoRequest>, max: usize, start: &mut usize) {
+        let count = self.workers.len() + 1;
+        let first = *start % count;
+        *start = first + 1;
+        for queue in self.queues().cycle().skip(first).take(count) {
+            while batch.len() < max {
+                match queue.pop() {
+                    Some(req) => batch.push(req),
+                    None => break,
+                }
+            }
+        }
+    }
+}
+
 /// Shared state between reactor thread and GVThreads.
 pub struct ReactorShared {
-    /// MPSC queue: GVThreads push, reactor pops.
-    pub(crate) request_queue: ArrayQueue<IoRequest>,
+    /// Submission queues: GVThreads push, reactor pops.
+    requests: SubmitQueues,
     /// Results slab indexed by GVThread slot index.
     /// Reactor writes, GVThread reads after wake.
     pub(crate) results: Box<[AtomicI64]>,
+    /// Generation of each slot's submitter, as of its current request.
+    /// Index = GVThread slot ID.
+    generations: Box<[AtomicU32]>,
     /// Shutdown signal.
     pub(crate) shutdown: AtomicBool,
     /// How many slots are available.
     pub(crate) max_slots: usize,
+    /// GVThreads parked on a full submission queue: (id, generation).
+    space_waiters: SpinLock<Vec<(GVThreadId, u32)>>,
+    /// Slots whose ops are to be cancelled (see "Cancellation" above).
+    cancels: SpinLock<Vec<u32>>,
+    /// Listeners with multishot accept, by registry id.
+    multishot: SpinLock<HashMap<u32, Arc<MultishotAccept>>>,
+    next_multishot_id: AtomicU32,
+    /// Batches in progress, by GVThread slot.
+    pub(crate) batches: SpinLock<HashMap<u32, Arc<IoBatch>>>,
+    /// Ends the reactor's wait for CQEs when a request is pushed.
+    wakeup: RingWakeup,
 }
 
 impl ReactorShared {
     fn new(config: &ReactorConfig) -> Self {
-        let mut results = Vec::with_capacity(config.max_slots);
-        for _ in 0..config.max_slots {
+        let max_slots = scheduler::global_scheduler()
+            .map_or(config.max_slots, |s| s.config().max_gvthreads.max(config.max_slots));
+        let mut results = Vec::with_capacity(max_slots);
+        for _ in 0..max_slots {
             results.push(AtomicI64::new(0));
         }
+        // Reactors are started after the runtime: size for its workers
+        let workers = scheduler::global_scheduler().map_or(0, |s| s.config().num_workers);
         Self {
-            request_queue: ArrayQueue::new(config.queue_capacity),
+            requests: SubmitQueues::new(workers, config.queue_capacity),
             results: results.into_boxed_slice(),
+            generations: (0..max_slots).map(|_| AtomicU32::new(0)).collect(),
             shutdown: AtomicBool::new(false),
-            max_slots: config.max_slots,
+            max_slots,
+            space_waiters: SpinLock::new(Vec::new()),
+            cancels: SpinLock::new(Vec::new()),
+            multishot: SpinLock::new(HashMap::new()),
+            next_multishot_id: AtomicU32::new(0),
+            batches: SpinLock::new(HashMap::new()),
+            wakeup: RingWakeup::new(),
+        }
+    }
+
+    /// Register a listener's multishot state; returns its registry id.
+    pub(crate) fn register_multishot(&self, accept: Arc<MultishotAccept>) -> u32 {
+        let id = self.next_multishot_id.fetch_add(1, Ordering::Relaxed);
+        self.multishot.lock().insert(id, accept);
+        id
+    }
+
+    /// Queue a control request (no result, nobody parks for it).
+    ///
+    /// Off a GVThread a full queue is waited out with `yield_now`. Dropped
+    /// if the reactor has shut down.
+    pub(crate) fn push_control(&self, mut req: IoRequest) {
+        if scheduler::current_waiter().is_some() {
+            let _ = self.push_request(req);
+            return;
+        }
+        while !self.shutdown.load(Ordering::Acquire) {
+            req = match self.requests.for_caller().push(req) {
+                Ok(()) => {
+                    self.wakeup.wake();
+                    return;
+                }
+                Err(req) => req,
+            };
+            thread::yield_now();
+        }
+    }
+
+    /// Queue a request from the calling GVThread, parking it while the
+    /// queue is full (see "Backpressure" above).
+    ///
+    /// Returns `Err(-ESHUTDOWN)` if the reactor stopped meanwhile, and
+    /// `Err(-ERANGE)` if the slot has no place in the results slab.
+    pub(crate) fn push_request(&self, mut req: IoRequest) -> Result<(), i64> {
+        if req.corr_id.as_gvthread_id() as usize >= self.max_slots {
+            return Err(-(libc::ERANGE as i64));
+        }
+        loop {
+            if self.shutdown.load(Ordering::Acquire) {
+                return Err(-(libc::ESHUTDOWN as i64));
+            }
+            // Fixed for this attempt: the re-check below must look at
+            // the queue we failed to push to
+            let queue = self.requests.for_caller();
+            req = match queue.push(req) {
+                Ok(()) => {
+                    self.wakeup.wake();
+                    return Ok(());
+                }
+                Err(req) => req,
+            };
+
+            let me = scheduler::current_waiter()
+                .expect("ReactorShared::push_request called outside GVThread");
+            self.space_waiters.lock().push(me);
+            // A drain between the failed push and registering would not
+            // wake us: re-check, and withdraw if the reactor hasn't
+            // claimed the registration yet. Once claimed, its wake is
+            // coming and we must block for it.
+            if !queue.is_full() || self.shutdown.load(Ordering::Acquire) {
+                let mut waiters = self.space_waiters.lock();
+                if let Some(pos) = waiters.iter().position(|w| *w == me) {
+                    waiters.swap_remove(pos);
+                    continue;
+                }
+            }
+            scheduler::block_current();
+        }
+    }
+
+    /// Have the reactor cancel `slot`'s op. Never blocks, whatever the
+    /// state of the submission queues.
+    fn request_cancel(&self, slot: u32) {
+        self.cancels.lock().push(slot);
+        self.wakeup.wake();
+    }
+
+    /// Wake every GVThread parked on a full queue. Called by the reactor.
+    fn wake_space_waiters(&self) {
+        let waiters = std::mem::take(&mut *self.space_waiters.lock());
+        for (id, generation) in waiters {
+            scheduler::wake_waiter(id, generation);
         }
     }
 
@@ -93,9 +384,48 @@ impl ReactorShared {
     }
 
     /// Write the I/O result for a given slot. Called by the reactor.
+    ///
+    /// A slot outside the slab is logged and dropped: returns false.
     #[inline]
-    fn write_result(&self, slot: u32, result: i64) {
-        self.results[slot as usize].store(result, Ordering::Release);
+    fn write_result(&self, slot: u32, result: i64) -> bool {
+        let Some(cell) = self.results.get(slot as usize) else {
+            eprintln!(
+                "ksvc-reactor: completion for slot {} outside the results slab ({} slots), dropped",
+                slot, self.max_slots
+            );
+            return false;
+        };
+        cell.store(result, Ordering::Release);
+        true
+    }
+
+    /// Write `slot`'s result and wake its GVThread.
+    fn deliver(&self, slot: u32, result: i64) {
+        if self.write_result(slot, result) {
+            wake_submitter(slot, self.generations[slot as usize].load(Ordering::Relaxed));
+        }
+    }
+
+    /// The batch `slot` is running, if any
+    fn batch(&self, slot: u32) -> Option<Arc<IoBatch>> {
+        self.batches.lock().get(&slot).cloned()
+    }
+
+    /// Complete the request `corr_id`: a batched op in its batch, any
+    /// other in the slab.
+    ///
+    /// Returns the batch if its remaining ops are now to be cancelled.
+    fn complete_request(&self, corr_id: CorrId, result: i64) -> Option<(u32, Arc<IoBatch>)> {
+        match batch::batch_op(corr_id.0) {
+            Some((slot, index)) => {
+                let batch = self.batch(slot)?;
+                batch.complete(index, result).then_some((slot, batch))
+            }
+            None => {
+                self.deliver(corr_id.as_gvthread_id(), result);
+                None
+            }
+        }
     }
 }
 
@@ -111,8 +441,15 @@ impl Reactor {
         let shared = Arc::new(ReactorShared::new(&config));
         let shared_clone = shared.clone();
 
+        {
+            let mut reactors = REACTORS.lock();
+            reactors.retain(|w| w.strong_count() > 0);
+            reactors.push(Arc::downgrade(&shared));
+        }
+        scheduler::set_io_cancel_hook(cancel_io);
+
         let thread = thread::Builder::new()
-            .name("ksvc-reactor".into())
+            .name(format!("ksvc-reactor-{}", NEXT_REACTOR_ID.fetch_add(1, Ordering::Relaxed)))
             .spawn(move || {
                 reactor_loop(shared_clone, config.sq_entries);
             })
@@ -129,9 +466,15 @@ impl Reactor {
         self.shared.clone()
     }
 
+    /// Submit `ops` and park once (see `ReactorShared::submit_batch`).
+    pub fn submit_batch(&self, ops: &mut [IoOp<'_>], wait: BatchWait) -> Option<usize> {
+        self.shared.submit_batch(ops, wait)
+    }
+
     /// Shutdown the reactor.
     pub fn shutdown(&mut self) {
         self.shared.shutdown.store(true, Ordering::Release);
+        self.shared.wakeup.wake();
         if let Some(handle) = self.thread.take() {
             let _ = handle.join();
         }
@@ -144,6 +487,34 @@ impl Drop for Reactor {
     }
 }
 
+/// Cancel `slot`'s batched ops in flight; fail the ones still queued
+fn cancel_batch(io: &mut BasicIoUring, slot: u32, batch: &IoBatch) {
+    batch.cancel();
+    for index in batch.pending() {
+        let _ = io.cancel(CorrId(batch::op_user_data(slot, index)));
+    }
+}
+
+/// Cancel `slot`'s op (and batch) and wake it from a multishot accept.
+///
+/// Returns `false`, having done nothing, if the SQ has no room for the
+/// cancel.
+fn cancel_slot(io: &mut BasicIoUring, shared: &ReactorShared, slot: u32) -> bool {
+    if io.cancel(CorrId::from_gvthread_id(slot)).is_err() {
+        return false;
+    }
+    if let Some(batch) = shared.batch(slot) {
+        cancel_batch(io, slot, &batch);
+    }
+    // A GVThread waiting on a multishot accept has no op of its own to
+    // cancel: wake it directly
+    let accepts: Vec<_> = shared.multishot.lock().values().cloned().collect();
+    for accept in accepts {
+        accept.cancel_waiter(GVThreadId::new(slot));
+    }
+    true
+}
+
 /// The reactor loop — runs on a dedicated OS thread.
 fn reactor_loop(shared: Arc<ReactorShared>, sq_entries: u32) {
     // Initialize io_uring
@@ -152,13 +523,13 @@ fn reactor_loop(shared: Arc<ReactorShared>, sq_entries: u32) {
         ..Default::default()
     }).expect("ksvc-reactor: io_uring setup failed");
 
-    // Probe opcodes and build routing table
-    let supported = io.probe_opcodes_static();
-    let router = ProbeRouter::new(&supported);
+    // Probe kernel capabilities and build routing table
+    let caps = io.capabilities();
+    let router = ProbeRouter::from_capabilities(caps);
     let counts = router.tier_counts();
     eprintln!(
-        "ksvc-reactor: started — T0:{} T1:{} T2:{} T3:{} sq:{}",
-        counts.tier0, counts.tier1, counts.tier2, counts.tier3, sq_entries
+        "ksvc-reactor: started — T0:{} T1:{} T2:{} T3:{} sq:{} ({})",
+        counts.tier0, counts.tier1, counts.tier2, counts.tier3, sq_entries, caps
     );
 
     let mut comp_buf = vec![IoCompletion {
@@ -167,8 +538,19 @@ fn reactor_loop(shared: Arc<ReactorShared>, sq_entries: u32) {
         flags: 0,
     }; 256];
 
-    // Batch buffer for draining the request queue
+    // Batch buffer for draining the submission queues
     let mut batch: Vec<IoRequest> = Vec::with_capacity(128);
+    let mut first_queue = 0;
+
+    // Cancels taken from the cancel list, and those the SQ had no room for
+    let mut cancels: Vec<u32> = Vec::new();
+    let mut deferred_cancels: Vec<u32> = Vec::new();
+
+    // Ops with a linked timeout awaiting their second CQE
+    let mut linked = LinkedTimeouts::default();
+
+    // The wakeup poll is in the ring (re-armed after each wakeup)
+    let mut wakeup_armed = false;
 
     loop {
         if shared.shutdown.load(Ordering::Relaxed) {
@@ -177,16 +559,64 @@ fn reactor_loop(shared: Arc<ReactorShared>, sq_entries: u32) {
 
         let mut did_work = false;
 
-        // ── Step 1: Drain request queue → submit to io_uring ──
+        // ── Step 1: Drain submission queues → submit to io_uring ──
         batch.clear();
-        while let Some(req) = shared.request_queue.pop() {
-            batch.push(req);
-            if batch.len() >= 128 {
-                break;
-            }
-        }
+        shared.requests.drain_into(&mut batch, 128, &mut first_queue);
 
         for req in &batch {
+            let slot = req.corr_id.as_gvthread_id();
+            if req.syscall_nr == CANCEL_SYSCALL_NR {
+                if !cancel_slot(&mut io, &shared, slot) {
+                    deferred_cancels.push(slot);
+                }
+                continue;
+            }
+            if req.syscall_nr == ARM_ACCEPT_SYSCALL_NR {
+                let id = req.args[1] as u32;
+                let user_data = multishot::MULTISHOT_TAG | id as u64;
+                if !caps.supports_multishot_accept {
+                    // What the kernel would answer: accept() goes single-shot
+                    let accept = shared.multishot.lock().get(&id).cloned();
+                    if let Some(accept) = accept {
+                        accept.complete(-(libc::EINVAL as i64), false);
+                    }
+                } else if io.submit_accept_multishot(req.args[0] as i32, req.args[2] as i32, user_data).is_err() {
+                    let accept = shared.multishot.lock().get(&id).cloned();
+                    if let Some(accept) = accept {
+                        accept.arm_failed();
+                    }
+                }
+                continue;
+            }
+            if req.syscall_nr == RELEASE_ACCEPT_SYSCALL_NR {
+                let id = req.args[0] as u32;
+                let accept = shared.multishot.lock().get(&id).cloned();
+                match accept {
+                    // Its final CQE removes it
+                    Some(accept) if accept.is_armed() => {
+                        let _ = io.cancel(CorrId(multishot::MULTISHOT_TAG | id as u64));
+                    }
+                    _ => {
+                        shared.multishot.lock().remove(&id);
+                    }
+                }
+                continue;
+            }
+            // For `deliver`: in range, as `push_request` checked
+            shared.generations[slot as usize].store(req.generation, Ordering::Relaxed);
+
+            // Cancelled after the GVThread's own check but before we got
+            // here (its cancel request, if any, was processed already)
+            // A batched op whose batch is done with (or gone) isn't submitted
+            let cancelled = batch::batch_op(req.corr_id.0).is_some()
+                && !matches!(shared.batch(slot), Some(b) if !b.is_cancelled());
+            if cancelled || scheduler::is_gvthread_cancelled(GVThreadId::new(slot)) {
+                if let Some((slot, batch)) = shared.complete_request(req.corr_id, -(libc::ECANCELED as i64)) {
+                    cancel_batch(&mut io, slot, &batch);
+                }
+                continue;
+            }
+
             let route = router.route(req.syscall_nr);
             let entry = SubmitEntry {
                 corr_id: req.corr_id,
@@ -197,44 +627,63 @@ fn reactor_loop(shared: Arc<ReactorShared>, sq_entries: u32) {
 
             match route.tier {
                 ksvc_core::tier::Tier::IoUring => {
-                    if let Err(_e) = io.submit_with_opcode(&entry, route.iouring_opcode) {
+                    let submitted = if req.timeout_ns > 0 {
+                        io.submit_with_link_timeout(
+                            &entry,
+                            route.iouring_opcode,
+                            Duration::from_nanos(req.timeout_ns),
+                            linked::timeout_user_data(slot),
+                        )
+                        .map(|()| linked.start(slot))
+                    } else {
+                        io.submit(&entry, route.iouring_opcode)
+                    };
+                    if let Err(_e) = submitted {
                         // Ring full or unsupported — return EAGAIN
-                        let slot = req.corr_id.as_gvthread_id();
-                        shared.write_result(slot, -(libc::EAGAIN as i64));
-                        scheduler::wake_gvthread(
-                            GVThreadId::new(slot),
-                            req.priority,
-                        );
+                        if let Some((slot, batch)) = shared.complete_request(req.corr_id, -(libc::EAGAIN as i64)) {
+                            cancel_batch(&mut io, slot, &batch);
+                        }
                     }
                 }
                 _ => {
                     // Not routable to io_uring — return ENOSYS
                     // (Tier 2/3 fallback could be added here)
-                    let slot = req.corr_id.as_gvthread_id();
-                    shared.write_result(slot, -(libc::ENOSYS as i64));
-                    scheduler::wake_gvthread(
-                        GVThreadId::new(slot),
-                        req.priority,
-                    );
+                    if let Some((slot, batch)) = shared.complete_request(req.corr_id, -(libc::ENOSYS as i64)) {
+                        cancel_batch(&mut io, slot, &batch);
+                    }
                 }
             }
         }
 
         if !batch.is_empty() {
             did_work = true;
+            shared.wake_space_waiters();
+        }
+
+        // After the batch, so ops drained with their cancel are covered
+        std::mem::swap(&mut cancels, &mut *shared.cancels.lock());
+        cancels.append(&mut deferred_cancels);
+        for slot in cancels.drain(..) {
+            did_work = true;
+            if !cancel_slot(&mut io, &shared, slot) {
+                deferred_cancels.push(slot);
+            }
         }
 
         // ── Step 2: Flush + wait for completions ──
-        if io.inflight() > 0 || !batch.is_empty() {
-            // Flush any pending SQEs. If inflight > 0, wait for at least 1 CQE.
-            // If nothing inflight, just flush (non-blocking).
-            let min_wait = if io.inflight() > 0 && batch.is_empty() { 1 } else { 0 };
-            let _ = io.flush_and_wait(min_wait);
-        } else if !did_work {
-            // Nothing happening — flush pending then brief sleep
-            let _ = io.flush();
-            std::thread::sleep(std::time::Duration::from_micros(50));
-            continue;
+        // Block only with nothing queued. Anything queued after the
+        // check wakes us through the eventfd (see "Wakeup").
+        if !wakeup_armed {
+            wakeup_armed = shared.wakeup.arm(&mut io);
+        }
+        let idle = || {
+            shared.requests.is_empty()
+                && shared.cancels.lock().is_empty()
+                && !shared.shutdown.load(Ordering::Acquire)
+        };
+        if wakeup_armed && batch.is_empty() && deferred_cancels.is_empty() && shared.wakeup.prepare_wait(idle) {
+            let _ = io.flush_and_wait(1);
+            shared.wakeup.finish_wait();
         } else {
             let _ = io.flush();
         }
@@ -243,35 +692,165 @@ fn reactor_loop(shared: Arc<ReactorShared>, sq_entries: u32) {
         let n = io.poll_completions(&mut comp_buf, 256);
         for i in 0..n {
             let cqe = &comp_buf[i];
-            let slot = cqe.corr_id.as_gvthread_id();
-            if slot == u32::MAX {
-                continue; // Cancel sentinel or invalid
+            if cqe.corr_id.0 == WAKEUP_TAG {
+                shared.wakeup.reset();
+                wakeup_armed = false;
+                continue;
             }
+            if let Some(id) = multishot::multishot_id(cqe.corr_id.0) {
+                let more = cqe.flags & multishot::CQE_F_MORE != 0;
+                let accept = shared.multishot.lock().get(&id).cloned();
+                if let Some(accept) = accept {
+                    if accept.complete(cqe.result, more) {
+                        shared.multishot.lock().remove(&id);
+                    }
+                }
+                continue;
+            }
+            if cqe.corr_id == CorrId::CANCEL {
+                continue; // The cancel SQE itself; its target completes separately
+            }
+            if batch::batch_op(cqe.corr_id.0).is_some() {
+                if let Some((slot, batch)) = shared.complete_request(cqe.corr_id, cqe.result) {
+                    cancel_batch(&mut io, slot, &batch);
+                }
+                continue;
+            }
+            // Half of a linked pair
+            let Some((slot, result)) = linked.complete(cqe.corr_id.0, cqe.result) else {
+                continue;
+            };
 
-            // Write result to slab
-            shared.write_result(slot, cqe.result);
-
-            // Wake the GVThread
-            // We use Normal priority as default; the original priority
-            // was stashed in the request but we don't track it per-inflight
-            // for simplicity. The scheduler handles priority from metadata.
-            scheduler::wake_gvthread(
-                GVThreadId::new(slot),
-                Priority::Normal,
-            );
+            // Write result to slab and wake the GVThread (at its own
+            // priority, from metadata)
+            shared.deliver(slot, result);
         }
 
         if n > 0 {
             did_work = true;
         }
 
-        // If no work at all, yield to avoid busy-spinning
+        // Only possible without the wakeup poll (SQ full): don't spin
+        // flat out until it fits
         if !did_work {
             std::thread::yield_now();
         }
     }
 
-    // Shutdown: drain remaining completions
+    // Shutdown: release parked submitters, drain remaining completions
+    shared.wake_space_waiters();
     io.shutdown();
     eprintln!("ksvc-reactor: shutdown");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syscall::ksvc_close;
+    use gvthread::{spawn, Runtime, SchedulerConfig};
+    use std::sync::atomic::AtomicUsize;
+
+    fn request(slot: u32) -> IoRequest {
+        IoRequest {
+            corr_id: CorrId::from_gvthread_id(slot),
+            syscall_nr: libc::SYS_close as u32,
+            args: [0; 6],
+            priority: Priority::Normal,
+            generation: 0,
+            timeout_ns: 0,
+        }
+    }
+
+    #[test]
+    fn out_of_range_slots_are_rejected() {
+        let shared = ReactorShared::new(&ReactorConfig { max_slots: 4, ..Default::default() });
+        let outside = shared.max_slots as u32;
+
+        assert_eq!(shared.push_request(request(outside)), Err(-(libc::ERANGE as i64)));
+        assert!(shared.requests.queues().all(|q| q.is_empty()));
+
+        // A stray completion is dropped without touching the slab or
+        // waking anyone
+        assert!(!shared.write_result(outside, 7));
+        shared.deliver(u32::MAX - 1, 7);
+        assert!(shared.write_result(outside - 1, 7));
+        assert_eq!(shared.read_result(outside - 1), 7);
+    }
+
+    #[test]
+    fn cancels_bypass_full_submit_queues() {
+        let shared = ReactorShared::new(&ReactorConfig { queue_capacity: 1, ..Default::default() });
+        shared.requests.for_caller().push(request(1)).unwrap();
+        assert!(shared.requests.for_caller().is_full());
+
+        shared.request_cancel(2);
+        shared.request_cancel(3);
+        assert_eq!(*shared.cancels.lock(), [2, 3]);
+    }
+
+    #[test]
+    fn submit_queues_drain_round_robin() {
+        let queues = SubmitQueues::new(2, 8);
+        // Not a worker thread: lands in the shared queue
+        queues.for_caller().push(request(9)).unwrap();
+        assert_eq!(queues.other.len(), 1);
+        for slot in 0..3 {
+            queues.workers[0].0.push(request(slot)).unwrap();
+            queues.workers[1].0.push(request(10 + slot)).unwrap();
+        }
+
+        let slots = |batch: &[IoRequest]| -> Vec<u32> {
+            batch.iter().map(|r| r.corr_id.as_gvthread_id()).collect()
+        };
+        let mut start = 0;
+        let mut batch = Vec::new();
+        queues.drain_into(&mut batch, 4, &mut start);
+        assert_eq!(slots(&batch), [0, 1, 2, 10]);
+
+        // Next drain starts at worker 1's queue
+        batch.clear();
+        queues.drain_into(&mut batch, 8, &mut start);
+        assert_eq!(slots(&batch), [11, 12, 9]);
+        assert!(queues.queues().all(|q| q.is_empty()));
+    }
+
+    // The scheduler is process-global, so this is the crate's only
+    // test that starts one.
+    #[test]
+    fn full_queue_parks_submitters_without_losing_requests() {
+        const GVTHREADS: usize = 256;
+        const CALLS: usize = 20;
+
+        let mut runtime = Runtime::new(
+            SchedulerConfig::default().num_workers(2).num_low_priority_workers(0),
+        );
+        // A tiny queue that thousands of requests must squeeze through
+        let mut reactor = Reactor::start(ReactorConfig {
+            max_slots: 4096,
+            queue_capacity: 4,
+            ..Default::default()
+        });
+        let shared = reactor.shared();
+        let ebadf = Arc::new(AtomicUsize::new(0));
+
+        let counter = Arc::clone(&ebadf);
+        runtime.block_on_until_idle(move || {
+            for _ in 0..GVTHREADS {
+                let shared = Arc::clone(&shared);
+                let counter = Arc::clone(&counter);
+                spawn(move |_| {
+                    for _ in 0..CALLS {
+                        // Every request reaches the kernel and fails there
+                        if ksvc_close(&shared, -1) == -(libc::EBADF as i64) {
+                            counter.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                });
+            }
+        });
+
+        assert_eq!(ebadf.load(Ordering::Relaxed), GVTHREADS * CALLS);
+        assert!(reactor.shared().space_waiters.lock().is_empty());
+        reactor.shutdown();
+    }
+}
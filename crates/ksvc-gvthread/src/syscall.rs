@@ -21,27 +21,36 @@ use gvthread_runtime::scheduler;
 
 use crate::reactor::{IoRequest, ReactorShared};
 
+use std::io::{IoSlice, IoSliceMut};
 use std::sync::Arc;
+use std::time::Duration;
 
 // ── Linux x86_64 syscall numbers ──
 
-const NR_READ: u32 = 0;
-const NR_WRITE: u32 = 1;
+pub(crate) const NR_READ: u32 = 0;
+pub(crate) const NR_WRITE: u32 = 1;
 const NR_CLOSE: u32 = 3;
-const NR_SENDTO: u32 = 44;
-const NR_RECVFROM: u32 = 45;
+pub(crate) const NR_READV: u32 = 19;
+pub(crate) const NR_WRITEV: u32 = 20;
+pub(crate) const NR_SENDTO: u32 = 44;
+pub(crate) const NR_RECVFROM: u32 = 45;
 const NR_CONNECT: u32 = 42;
 const NR_ACCEPT4: u32 = 288;
 const NR_OPENAT: u32 = 257;
 const NR_SOCKET: u32 = 41;
 const NR_SHUTDOWN: u32 = 48;
+const NR_STATX: u32 = 332;
 
 /// Submit a syscall to the reactor and block until completion.
 ///
-/// This is the core primitive. All typed wrappers call this.
+/// This is the core primitive. All typed wrappers call this. If the
+/// reactor's request queue is full, the GVThread parks until it drains;
+/// the request is never dropped.
 ///
 /// # Returns
 /// The syscall return value (>= 0 on success, negative errno on error).
+/// `-ECANCELED` if the GVThread is cancelled before or while waiting.
+/// `-ESHUTDOWN` if the reactor shut down before taking the request.
 ///
 /// # Panics
 /// Panics if called outside a GVThread context.
@@ -50,35 +59,50 @@ fn submit_and_park(
     shared: &ReactorShared,
     syscall_nr: u32,
     args: [u64; 6],
+) -> i64 {
+    submit_and_park_timeout(shared, syscall_nr, args, 0)
+}
+
+/// `submit_and_park` with a linked kernel timeout (`timeout_ns`, 0 = none).
+///
+/// Returns `-ETIME` if the timeout fired and cancelled the operation.
+#[inline]
+fn submit_and_park_timeout(
+    shared: &ReactorShared,
+    syscall_nr: u32,
+    args: [u64; 6],
+    timeout_ns: u64,
 ) -> i64 {
     let gvt_id = gvthread_runtime::tls::current_gvthread_id();
     assert!(!gvt_id.is_none(), "ksvc_syscall called outside GVThread");
     let slot = gvt_id.as_u32();
 
+    // Publish the wait before checking for cancellation, so a concurrent
+    // cancel either sees it (and cancels the op) or is seen here.
+    scheduler::set_current_wait_kind(scheduler::WAIT_IO);
+    if scheduler::current_cancelled() {
+        scheduler::set_current_wait_kind(scheduler::WAIT_NONE);
+        return -(libc::ECANCELED as i64);
+    }
+
     let req = IoRequest {
         corr_id: CorrId::from_gvthread_id(slot),
         syscall_nr,
         args,
         priority: Priority::Normal,
+        generation: scheduler::current_metadata().map_or(0, |meta| meta.get_generation()),
+        timeout_ns,
     };
 
-    // Push to reactor queue
-    // If queue is full, spin-yield until space. In practice the queue
-    // should be sized large enough that this is rare.
-    let mut pending = req;
-    loop {
-        match shared.request_queue.push(pending) {
-            Ok(()) => break,
-            Err(returned) => {
-                // Queue full — yield and retry
-                std::thread::yield_now();
-                pending = returned;
-            }
-        }
+    // Push to reactor queue; a full queue parks us until it drains
+    if let Err(e) = shared.push_request(req) {
+        scheduler::set_current_wait_kind(scheduler::WAIT_NONE);
+        return e;
     }
 
     // Block this GVThread — the worker thread is now free to run others
     scheduler::block_current();
+    scheduler::set_current_wait_kind(scheduler::WAIT_NONE);
 
     // We're back! The reactor wrote our result to the slab.
     shared.read_result(slot)
@@ -110,6 +134,33 @@ pub fn ksvc_write(shared: &ReactorShared, fd: i32, buf: &[u8]) -> i64 {
     ])
 }
 
+/// Scatter read (`readv`) into `bufs`, filled in order. Returns total
+/// bytes read or negative errno.
+///
+/// `IoSliceMut` is an `iovec`: the array is handed to the kernel as is
+/// and stays put while this GVThread is blocked.
+#[inline]
+pub fn ksvc_readv(shared: &ReactorShared, fd: i32, bufs: &mut [IoSliceMut<'_>]) -> i64 {
+    submit_and_park(shared, NR_READV, [
+        fd as u64,
+        bufs.as_mut_ptr() as u64,
+        iov_count(bufs.len()),
+        0, 0, 0,
+    ])
+}
+
+/// Gather write (`writev`) of `bufs` in order. Returns total bytes
+/// written (possibly fewer than asked) or negative errno.
+#[inline]
+pub fn ksvc_writev(shared: &ReactorShared, fd: i32, bufs: &[IoSlice<'_>]) -> i64 {
+    submit_and_park(shared, NR_WRITEV, [
+        fd as u64,
+        bufs.as_ptr() as u64,
+        iov_count(bufs.len()),
+        0, 0, 0,
+    ])
+}
+
 /// Close a file descriptor.
 #[inline]
 pub fn ksvc_close(shared: &ReactorShared, fd: i32) -> i64 {
@@ -165,6 +216,50 @@ pub fn ksvc_send(shared: &ReactorShared, fd: i32, buf: &[u8], flags: i32) -> i64
     ])
 }
 
+/// Linked-timeout value for `timeout` (never 0, which means "none")
+#[inline]
+fn timeout_ns(timeout: Duration) -> u64 {
+    timeout.as_nanos().clamp(1, u64::MAX as u128) as u64
+}
+
+/// Receive with a deadline. Returns bytes received, negative errno, or
+/// `-ETIME` if nothing arrived within `timeout`.
+#[inline]
+pub fn ksvc_recv_timeout(
+    shared: &ReactorShared,
+    fd: i32,
+    buf: &mut [u8],
+    flags: i32,
+    timeout: Duration,
+) -> i64 {
+    submit_and_park_timeout(shared, NR_RECVFROM, [
+        fd as u64,
+        buf.as_mut_ptr() as u64,
+        buf.len() as u64,
+        flags as u64,
+        0, 0,
+    ], timeout_ns(timeout))
+}
+
+/// Send with a deadline. Returns bytes sent, negative errno, or `-ETIME`
+/// if the socket did not accept data within `timeout`.
+#[inline]
+pub fn ksvc_send_timeout(
+    shared: &ReactorShared,
+    fd: i32,
+    buf: &[u8],
+    flags: i32,
+    timeout: Duration,
+) -> i64 {
+    submit_and_park_timeout(shared, NR_SENDTO, [
+        fd as u64,
+        buf.as_ptr() as u64,
+        buf.len() as u64,
+        flags as u64,
+        0, 0,
+    ], timeout_ns(timeout))
+}
+
 /// Connect a socket. Returns 0 on success or negative errno.
 #[inline]
 pub fn ksvc_connect(
@@ -199,6 +294,29 @@ pub fn ksvc_openat(
     ])
 }
 
+/// Get file status. Returns 0 (with `*statxbuf` filled) or negative errno.
+///
+/// With `flags = AT_EMPTY_PATH` and an empty `pathname`, stats `dirfd`
+/// itself (like `fstat`).
+#[inline]
+pub fn ksvc_statx(
+    shared: &ReactorShared,
+    dirfd: i32,
+    pathname: *const libc::c_char,
+    flags: i32,
+    mask: u32,
+    statxbuf: *mut libc::statx,
+) -> i64 {
+    submit_and_park(shared, NR_STATX, [
+        dirfd as u64,
+        pathname as u64,
+        flags as u64,
+        mask as u64,
+        statxbuf as u64,
+        0,
+    ])
+}
+
 /// Create a socket. Returns fd or negative errno.
 #[inline]
 pub fn ksvc_socket(
@@ -246,6 +364,65 @@ pub fn ksvc_send_all(shared: &ReactorShared, fd: i32, mut buf: &[u8]) -> i64 {
     total as i64
 }
 
+/// Gather-write all of `bufs`, retrying partial writes from where the
+/// last one stopped.
+pub fn ksvc_writev_all(shared: &ReactorShared, fd: i32, bufs: &[IoSlice<'_>]) -> i64 {
+    writev_all(bufs, |iov| ksvc_writev(shared, fd, iov))
+}
+
+/// `writev` takes at most `IOV_MAX` slices; the rest is a short write
+pub(crate) fn iov_count(len: usize) -> u64 {
+    len.min(libc::UIO_MAXIOV as usize) as u64
+}
+
+/// Drive `writev` until every byte of `bufs` is written.
+///
+/// After a partial write the untouched slices are passed on as they are;
+/// only the one cut in the middle is re-sliced.
+fn writev_all(bufs: &[IoSlice<'_>], mut writev: impl FnMut(&[IoSlice<'_>]) -> i64) -> i64 {
+    let mut remaining: usize = bufs.iter().map(|b| b.len()).sum();
+    let mut total: usize = 0;
+    let (mut first, mut offset) = (0, 0);
+    let mut partial = Vec::new();
+    while remaining > 0 {
+        let n = if offset == 0 {
+            writev(&bufs[first..])
+        } else {
+            partial.clear();
+            partial.push(IoSlice::new(&bufs[first][offset..]));
+            partial.extend_from_slice(&bufs[first + 1..]);
+            writev(&partial)
+        };
+        if n < 0 {
+            if n == -(libc::EAGAIN as i64) || n == -(libc::EINTR as i64) {
+                gvthread::yield_now();
+                continue;
+            }
+            return n;
+        }
+        let n = n as usize;
+        total += n;
+        remaining -= n;
+        advance(bufs, &mut first, &mut offset, n);
+    }
+    total as i64
+}
+
+/// Move the position (`bufs[first]`, byte `offset`) on by `n` bytes,
+/// past any slices that are now fully written
+fn advance(bufs: &[IoSlice<'_>], first: &mut usize, offset: &mut usize, mut n: usize) {
+    while let Some(buf) = bufs.get(*first) {
+        let left = buf.len() - *offset;
+        if n < left {
+            *offset += n;
+            return;
+        }
+        n -= left;
+        *first += 1;
+        *offset = 0;
+    }
+}
+
 // ══════════════════════════════════════════════════════════════════════
 // Worker-local path — submit directly to this worker's io_uring
 // ══════════════════════════════════════════════════════════════════════
@@ -264,20 +441,42 @@ use crate::worker_reactor;
 /// pool has not been initialized.
 #[inline]
 fn submit_and_park_worker(syscall_nr: u32, args: [u64; 6]) -> i64 {
+    submit_and_park_worker_timeout(syscall_nr, args, 0)
+}
+
+/// `submit_and_park_worker` with a linked kernel timeout (0 = none).
+#[inline]
+fn submit_and_park_worker_timeout(syscall_nr: u32, args: [u64; 6], timeout_ns: u64) -> i64 {
     let gvt_id = gvthread_runtime::tls::current_gvthread_id();
     assert!(!gvt_id.is_none(), "ksvc_syscall called outside GVThread");
     let slot = gvt_id.as_u32();
 
+    // Stay on this worker (and its ring) until we've blocked
+    scheduler::prepare_block();
     let worker_id = gvthread_runtime::worker::current_worker_id();
     assert!(worker_id != usize::MAX, "ksvc_syscall: not on a worker thread");
 
     let pool = worker_reactor::global_pool();
 
+    // Publish the wait before checking for cancellation, so a concurrent
+    // cancel either sees it (and has this worker cancel the op at its
+    // next poll) or is seen here.
+    pool.set_owner(slot, worker_id);
+    scheduler::set_current_wait_kind(scheduler::WAIT_IO);
+    if scheduler::current_cancelled() {
+        scheduler::set_current_wait_kind(scheduler::WAIT_NONE);
+        return -(libc::ECANCELED as i64);
+    }
+
     // Submit SQE directly to this worker's io_uring (inline, no MPSC!)
-    pool.submit(worker_id, slot, syscall_nr, &args);
+    if let Err(e) = pool.submit(worker_id, slot, syscall_nr, &args, timeout_ns) {
+        scheduler::set_current_wait_kind(scheduler::WAIT_NONE);
+        return e;
+    }
 
     // Block this GVThread — worker is free to run others + poll CQEs
     scheduler::block_current();
+    scheduler::set_current_wait_kind(scheduler::WAIT_NONE);
 
     // Woken! Result was written to the slab by this worker's poll loop.
     pool.read_result(slot)
@@ -303,12 +502,41 @@ pub fn wr_write(fd: i32, buf: &[u8]) -> i64 {
     ])
 }
 
+/// Worker-local readv.
+#[inline]
+pub fn wr_readv(fd: i32, bufs: &mut [IoSliceMut<'_>]) -> i64 {
+    submit_and_park_worker(NR_READV, [
+        fd as u64, bufs.as_mut_ptr() as u64, iov_count(bufs.len()),
+        0, 0, 0,
+    ])
+}
+
+/// Worker-local writev.
+#[inline]
+pub fn wr_writev(fd: i32, bufs: &[IoSlice<'_>]) -> i64 {
+    submit_and_park_worker(NR_WRITEV, [
+        fd as u64, bufs.as_ptr() as u64, iov_count(bufs.len()),
+        0, 0, 0,
+    ])
+}
+
+/// Worker-local writev_all (retries partial writes).
+pub fn wr_writev_all(fd: i32, bufs: &[IoSlice<'_>]) -> i64 {
+    writev_all(bufs, |iov| wr_writev(fd, iov))
+}
+
 /// Worker-local close.
 #[inline]
 pub fn wr_close(fd: i32) -> i64 {
     submit_and_park_worker(NR_CLOSE, [fd as u64, 0, 0, 0, 0, 0])
 }
 
+/// Worker-local shutdown.
+#[inline]
+pub fn wr_shutdown(fd: i32, how: i32) -> i64 {
+    submit_and_park_worker(NR_SHUTDOWN, [fd as u64, how as u64, 0, 0, 0, 0])
+}
+
 /// Worker-local accept4.
 #[inline]
 pub fn wr_accept4(
@@ -341,6 +569,24 @@ pub fn wr_send(fd: i32, buf: &[u8], flags: i32) -> i64 {
     ])
 }
 
+/// Worker-local recv with a deadline (`-ETIME` on expiry).
+#[inline]
+pub fn wr_recv_timeout(fd: i32, buf: &mut [u8], flags: i32, timeout: Duration) -> i64 {
+    submit_and_park_worker_timeout(NR_RECVFROM, [
+        fd as u64, buf.as_mut_ptr() as u64, buf.len() as u64,
+        flags as u64, 0, 0,
+    ], timeout_ns(timeout))
+}
+
+/// Worker-local send with a deadline (`-ETIME` on expiry).
+#[inline]
+pub fn wr_send_timeout(fd: i32, buf: &[u8], flags: i32, timeout: Duration) -> i64 {
+    submit_and_park_worker_timeout(NR_SENDTO, [
+        fd as u64, buf.as_ptr() as u64, buf.len() as u64,
+        flags as u64, 0, 0,
+    ], timeout_ns(timeout))
+}
+
 /// Worker-local send_all (retries partial writes).
 pub fn wr_send_all(fd: i32, mut buf: &[u8]) -> i64 {
     let mut total: usize = 0;
@@ -358,4 +604,35 @@ pub fn wr_send_all(fd: i32, mut buf: &[u8]) -> i64 {
         buf = &buf[n..];
     }
     total as i64
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writev_all_resumes_inside_a_partially_written_slice() {
+        let header = b"HTTP/1.1 200 OK\r\n\r\n";
+        let body = b"hello";
+        let bufs = [IoSlice::new(header), IoSlice::new(&[]), IoSlice::new(body)];
+
+        // A socket taking at most 7 bytes per writev
+        let mut sent = Vec::new();
+        let mut calls = 0;
+        let n = writev_all(&bufs, |iov| {
+            calls += 1;
+            let before = sent.len();
+            for buf in iov {
+                let room = 7 - (sent.len() - before);
+                sent.extend_from_slice(&buf[..buf.len().min(room)]);
+            }
+            (sent.len() - before) as i64
+        });
+
+        assert_eq!(n, (header.len() + body.len()) as i64);
+        assert_eq!(sent, [&header[..], &body[..]].concat());
+        assert_eq!(calls, 4);
+        assert_eq!(writev_all(&bufs, |_| -(libc::EPIPE as i64)), -(libc::EPIPE as i64));
+        assert_eq!(writev_all(&[], |_| unreachable!()), 0);
+    }
+}
@@ -0,0 +1,86 @@
+//! # Waking a thread blocked in its ring
+//!
+//! A thread that drives an io_uring blocks in `io_uring_enter` until a
+//! CQE arrives. Work handed to it meanwhile — a request pushed to the
+//! reactor's queues, a shutdown — would wait for some unrelated CQE.
+//!
+//! `RingWakeup` keeps a `POLL_ADD` on an `EventFdNotifier` in the ring.
+//! Whoever hands over work calls `wake()`, which notifies the eventfd;
+//! its poll completes with `WAKEUP_TAG` and ends the wait. The owner
+//! resets the eventfd and re-arms the poll.
+//!
+//! Notifying costs a syscall, so it's only done while the owner is
+//! blocked (or about to be). The owner publishes that in
+//! `prepare_wait`, then re-checks for work; `wake` publishes the work
+//! first, then checks. With a full fence on both sides, at least one of
+//! them sees the other:
+//!
+//! ```text
+//!  owner:  waiting = true;  fence;  work queued?  → don't block
+//!  waker:  queue work;      fence;  waiting?      → notify
+//! ```
+
+use ksvc_core::notifier::Notifier;
+
+use ksvc_module::basic_iouring::BasicIoUring;
+use ksvc_module::eventfd_notifier::EventFdNotifier;
+
+use std::sync::atomic::{fence, AtomicBool, Ordering};
+
+/// User data of the wakeup poll's CQE
+pub(crate) const WAKEUP_TAG: u64 = 1 << 59;
+
+pub(crate) struct RingWakeup {
+    notifier: EventFdNotifier,
+    /// The owner is blocked in its ring, or about to be
+    waiting: AtomicBool,
+}
+
+impl RingWakeup {
+    pub(crate) fn new() -> Self {
+        Self {
+            notifier: EventFdNotifier::create().expect("ksvc: wakeup eventfd creation failed"),
+            waiting: AtomicBool::new(false),
+        }
+    }
+
+    /// Queue the wakeup poll on `io`; `false` if the SQ is full
+    pub(crate) fn arm(&self, io: &mut BasicIoUring) -> bool {
+        let fd = self.notifier.eventfd().expect("EventFdNotifier has an eventfd");
+        io.submit_poll_readable(fd, WAKEUP_TAG).is_ok()
+    }
+
+    /// Owner, before blocking: announce it, then re-check with `idle`
+    ///
+    /// Returns `false` (and withdraws) if `idle` found work; the owner
+    /// must not block then. Otherwise `finish_wait` once the wait is over.
+    pub(crate) fn prepare_wait(&self, idle: impl FnOnce() -> bool) -> bool {
+        self.waiting.store(true, Ordering::Relaxed);
+        fence(Ordering::SeqCst);
+        if idle() {
+            return true;
+        }
+        self.waiting.store(false, Ordering::Relaxed);
+        false
+    }
+
+    pub(crate) fn finish_wait(&self) {
+        self.waiting.store(false, Ordering::Relaxed);
+    }
+
+    /// Owner: the poll's CQE arrived. Resets the eventfd; the poll is
+    /// spent and must be re-armed.
+    pub(crate) fn reset(&self) {
+        self.notifier.drain();
+    }
+
+    /// Any thread, after publishing work the owner's `idle` check sees:
+    /// end the owner's wait if it is blocked
+    pub(crate) fn wake(&self) {
+        fence(Ordering::SeqCst);
+        if self.waiting.load(Ordering::Relaxed) && self.waiting.swap(false, Ordering::Relaxed) {
+            // Never blocks; a failure leaves the owner to its next CQE
+            let _ = self.notifier.notify();
+        }
+    }
+}
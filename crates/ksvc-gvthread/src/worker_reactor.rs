@@ -21,6 +21,36 @@
 //! let pool = WorkerReactorPool::init_global(num_workers, 1024, 100_000);
 //! // ... hooks are auto-installed, workers will poll their own ring
 //! ```
+//!
+//! ## Wakeup
+//!
+//! A worker with I/O in flight and no GVThread to run blocks in its ring.
+//! Each ring keeps a poll on its own eventfd (see `wakeup`), so
+//! `wake(worker_id)` ends that wait at once from any thread; the graceful
+//! shutdown uses it to reach blocked workers. The wait is still capped at
+//! `WAIT_IO_TIMEOUT`: GVThreads made ready by other threads don't wake a
+//! worker blocked in its ring.
+//!
+//! ## Cancellation
+//!
+//! A ring is only touched by its worker, so cancelling a GVThread parked
+//! on a worker-local op (the scheduler's I/O cancel hook) queues its slot
+//! for the ring's owner and wakes it. At its next poll the owner submits
+//! `IORING_OP_ASYNC_CANCEL` for the op, which then completes with
+//! `-ECANCELED` and wakes the GVThread as usual.
+//!
+//! ## Graceful shutdown
+//!
+//! `shutdown_graceful(timeout)`, called while the workers still run,
+//! mirrors the dispatcher's shutdown drain in `ksvc-executor`:
+//!
+//! 1. New submissions fail with `-ESHUTDOWN`.
+//! 2. Each worker cancels what its ring has in flight at its next poll
+//!    (blocked workers are woken); cancelled ops wake their GVThreads
+//!    with `-ESHUTDOWN`.
+//! 3. Ops still outstanding at the timeout are abandoned: their GVThreads
+//!    are woken with `-ESHUTDOWN` regardless.
+//! 4. Each worker closes its ring.
 
 use ksvc_core::entry::{CorrId, SubmitEntry};
 use ksvc_core::io_backend::{IoBackend, IoCompletion};
@@ -29,13 +59,27 @@ use ksvc_core::router::SyscallRouter;
 use ksvc_module::basic_iouring::{BasicIoUring, BasicIoUringConfig};
 use ksvc_module::probe_router::ProbeRouter;
 
+use crate::linked::{self, LinkedTimeouts};
+use crate::wakeup::{RingWakeup, WAKEUP_TAG};
+
 use gvthread_core::id::GVThreadId;
+use gvthread_core::spinlock::SpinLock;
 use gvthread_core::state::Priority;
 use gvthread_runtime::scheduler;
 
 use std::cell::UnsafeCell;
-use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Longest a worker blocks in its ring before looking for other work
+const WAIT_IO_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// How often `shutdown_graceful` checks on the drain
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Results slab value of a slot whose op hasn't completed yet
+const PENDING: i64 = i64::MIN;
 
 // ── Per-worker io_uring instance ─────────────────────────────────────
 
@@ -45,6 +89,43 @@ struct WorkerRing {
     io: BasicIoUring,
     router: ProbeRouter,
     comp_buf: Vec<IoCompletion>,
+    /// Ops with a linked timeout awaiting their second CQE
+    linked: LinkedTimeouts,
+    /// Drain: in-flight ops have been cancelled
+    cancel_sent: bool,
+    /// Drain: the ring has been shut down by its worker
+    closed: bool,
+    /// The wakeup poll is in the ring (not counted as I/O in flight)
+    wakeup_armed: bool,
+}
+
+/// Per-worker ring counters, written by the owning worker with relaxed
+/// stores and readable from any thread. Cache-line aligned so workers
+/// don't contend on each other's counters.
+#[repr(align(64))]
+#[derive(Default)]
+struct RingCounters {
+    /// SQEs queued (a linked timeout counts as its own SQE)
+    submitted: AtomicU64,
+    /// CQEs reaped
+    completed: AtomicU64,
+    /// Last seen value of the kernel's CQ overflow counter
+    cq_overflow: AtomicU64,
+}
+
+/// Snapshot of one worker ring (see `WorkerReactorPool::stats`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RingStats {
+    /// Owning worker
+    pub worker_id: usize,
+    /// SQEs submitted whose CQE has not been reaped yet
+    pub inflight: u64,
+    /// SQEs submitted since start
+    pub submitted: u64,
+    /// CQEs reaped since start
+    pub completed: u64,
+    /// CQEs dropped because the CQ ring was full
+    pub cq_overflow: u64,
 }
 
 // ── Worker Reactor Pool ──────────────────────────────────────────────
@@ -60,10 +141,22 @@ struct WorkerRing {
 pub struct WorkerReactorPool {
     /// Per-worker rings.  Index = worker_id.
     rings: Vec<UnsafeCell<WorkerRing>>,
+    /// Per-worker counters.  Index = worker_id.
+    counters: Box<[RingCounters]>,
+    /// Per-worker wakeups for a worker blocked in its ring.  Index = worker_id.
+    wakeups: Box<[RingWakeup]>,
     /// Results slab indexed by GVThread slot ID.
     results: Box<[AtomicI64]>,
+    /// Worker whose ring holds each slot's op.  Index = GVThread slot ID.
+    owners: Box<[AtomicU32]>,
+    /// Slots whose ops each worker must cancel.  Index = worker_id.
+    cancels: Box<[SpinLock<Vec<u32>>]>,
     /// Number of workers.
     num_workers: usize,
+    /// Graceful shutdown in progress: refuse submissions, cancel the rest.
+    draining: AtomicBool,
+    /// Rings closed by their workers during a graceful shutdown.
+    closed_rings: AtomicUsize,
     /// Shutdown flag.
     shutdown: AtomicBool,
 }
@@ -84,8 +177,8 @@ impl WorkerReactorPool {
             })
             .unwrap_or_else(|e| panic!("worker-reactor[{}]: io_uring setup failed: {:?}", i, e));
 
-            let supported = io.probe_opcodes_static();
-            let router = ProbeRouter::new(&supported);
+            // Probed by the first ring only; the rest reuse its result
+            let router = ProbeRouter::from_capabilities(io.capabilities());
 
             if i == 0 {
                 let counts = router.tier_counts();
@@ -103,6 +196,10 @@ impl WorkerReactorPool {
                     IoCompletion { corr_id: CorrId(0), result: 0, flags: 0 };
                     256
                 ],
+                linked: LinkedTimeouts::default(),
+                cancel_sent: false,
+                closed: false,
+                wakeup_armed: false,
             }));
         }
 
@@ -113,8 +210,14 @@ impl WorkerReactorPool {
 
         Self {
             rings,
+            counters: (0..num_workers).map(|_| RingCounters::default()).collect(),
+            wakeups: (0..num_workers).map(|_| RingWakeup::new()).collect(),
             results: results.into_boxed_slice(),
+            owners: (0..max_slots).map(|_| AtomicU32::new(0)).collect(),
+            cancels: (0..num_workers).map(|_| SpinLock::new(Vec::new())).collect(),
             num_workers,
+            draining: AtomicBool::new(false),
+            closed_rings: AtomicUsize::new(0),
             shutdown: AtomicBool::new(false),
         }
     }
@@ -140,6 +243,7 @@ impl WorkerReactorPool {
             hook_has_io,
             hook_wait_io,
         );
+        scheduler::set_io_cancel_hook(crate::reactor::cancel_io);
 
         pool
     }
@@ -151,6 +255,13 @@ impl WorkerReactorPool {
     /// Called inline from `submit_and_park_worker()` — no MPSC, no
     /// cross-thread hop.  The SQE is queued in the ring's SQ and will
     /// be flushed on the next `poll()` call in the worker loop.
+    /// A nonzero `timeout_ns` attaches a linked timeout (result `-ETIME`).
+    ///
+    /// Returns `Err(-EAGAIN)` if the SQ is full, `Err(-ENOSYS)` if the
+    /// syscall can't go through io_uring, `Err(-ERANGE)` if `slot` is
+    /// outside the results slab and `Err(-ESHUTDOWN)` once a graceful
+    /// shutdown has begun; nothing was queued then and the caller must
+    /// not park.
     #[inline]
     pub(crate) fn submit(
         &self,
@@ -158,7 +269,16 @@ impl WorkerReactorPool {
         slot: u32,
         syscall_nr: u32,
         args: &[u64; 6],
-    ) {
+        timeout_ns: u64,
+    ) -> Result<(), i64> {
+        if slot as usize >= self.results.len() {
+            return Err(-(libc::ERANGE as i64));
+        }
+        // Same thread as this ring's polls: either this op is refused or
+        // the drain's cancel covers it
+        if self.draining.load(Ordering::Acquire) {
+            return Err(-(libc::ESHUTDOWN as i64));
+        }
         let ring = unsafe { &mut *self.rings[worker_id].get() };
         let route = ring.router.route(syscall_nr);
 
@@ -171,17 +291,54 @@ impl WorkerReactorPool {
 
         match route.tier {
             ksvc_core::tier::Tier::IoUring => {
-                if let Err(_e) = ring.io.submit_with_opcode(&entry, route.iouring_opcode) {
-                    // Ring full — return EAGAIN, wake immediately
-                    self.results[slot as usize].store(-(libc::EAGAIN as i64), Ordering::Release);
-                    scheduler::wake_gvthread(GVThreadId::new(slot), Priority::Normal);
+                let submitted = if timeout_ns > 0 {
+                    ring.io.submit_with_link_timeout(
+                        &entry,
+                        route.iouring_opcode,
+                        Duration::from_nanos(timeout_ns),
+                        linked::timeout_user_data(slot),
+                    )
+                    .map(|()| ring.linked.start(slot))
+                } else {
+                    ring.io.submit(&entry, route.iouring_opcode)
+                };
+                if submitted.is_err() {
+                    return Err(-(libc::EAGAIN as i64)); // Ring full
                 }
+                let sqes = if timeout_ns > 0 { 2 } else { 1 };
+                self.counters[worker_id].submitted.fetch_add(sqes, Ordering::Relaxed);
+                self.results[slot as usize].store(PENDING, Ordering::Relaxed);
+                Ok(())
             }
-            _ => {
-                // Not routable to io_uring
-                self.results[slot as usize].store(-(libc::ENOSYS as i64), Ordering::Release);
-                scheduler::wake_gvthread(GVThreadId::new(slot), Priority::Normal);
-            }
+            // Not routable to io_uring
+            _ => Err(-(libc::ENOSYS as i64)),
+        }
+    }
+
+    /// Record that `slot`'s next op goes to `worker_id`'s ring.
+    ///
+    /// Called before the GVThread publishes its `WAIT_IO` wait, so the
+    /// cancel hook finds the right ring (see "Cancellation").
+    #[inline]
+    pub(crate) fn set_owner(&self, slot: u32, worker_id: usize) {
+        if let Some(owner) = self.owners.get(slot as usize) {
+            owner.store(worker_id as u32, Ordering::Relaxed);
+        }
+    }
+
+    /// Cancel `slot`'s worker-local op, if it has one (any thread).
+    ///
+    /// Queued for the owning worker, which submits the cancel at its next
+    /// poll; that poll comes after the GVThread has parked, so an op
+    /// submitted after this call is still covered.
+    pub(crate) fn cancel(&self, slot: u32) {
+        let Some(owner) = self.owners.get(slot as usize) else {
+            return;
+        };
+        let worker_id = owner.load(Ordering::Relaxed) as usize;
+        if worker_id < self.num_workers {
+            self.cancels[worker_id].lock().push(slot);
+            self.wake(worker_id);
         }
     }
 
@@ -191,31 +348,22 @@ impl WorkerReactorPool {
     /// Returns number of GVThreads woken.
     pub(crate) fn poll(&self, worker_id: usize) -> usize {
         let ring = unsafe { &mut *self.rings[worker_id].get() };
+        if self.drain_step(worker_id, ring) {
+            return 0;
+        }
+        self.submit_cancels(worker_id, ring);
 
         // Flush any pending SQEs to kernel
         let _ = ring.io.flush();
 
-        // Drain available CQEs
-        let n = ring.io.poll_completions(&mut ring.comp_buf, 256);
-
-        for i in 0..n {
-            let cqe = &ring.comp_buf[i];
-            let slot = cqe.corr_id.as_gvthread_id();
-            if slot == u32::MAX {
-                continue; // Cancel sentinel
-            }
-            self.results[slot as usize].store(cqe.result, Ordering::Release);
-            scheduler::wake_gvthread(GVThreadId::new(slot), Priority::Normal);
-        }
-
-        n
+        self.reap(worker_id, ring)
     }
 
     /// Check if this worker has any inflight I/O operations.
     #[inline]
     pub(crate) fn has_inflight(&self, worker_id: usize) -> bool {
         let ring = unsafe { &*self.rings[worker_id].get() };
-        ring.io.inflight() > 0
+        ring.io.inflight() > ring.wakeup_armed as usize
     }
 
     /// Blocking wait: flush + wait for ≥1 CQE, then drain all.
@@ -223,27 +371,148 @@ impl WorkerReactorPool {
     ///
     /// Uses `io_uring_enter(min_complete=1)` — the kernel blocks this
     /// thread until a CQE is available, then returns instantly.  Zero
-    /// CPU waste while waiting.
+    /// CPU waste while waiting.  `wake` ends the wait early; it is
+    /// capped at `WAIT_IO_TIMEOUT` (see "Wakeup").
     pub(crate) fn wait_and_poll(&self, worker_id: usize) -> usize {
         let ring = unsafe { &mut *self.rings[worker_id].get() };
+        if self.drain_step(worker_id, ring) {
+            return 0;
+        }
+        self.submit_cancels(worker_id, ring);
 
-        // Flush + block until ≥1 CQE
-        let _ = ring.io.flush_and_wait(1);
+        let wakeup = &self.wakeups[worker_id];
+        if !ring.wakeup_armed {
+            ring.wakeup_armed = wakeup.arm(&mut ring.io);
+        }
+        // A drain begun since `drain_step`, or a cancel queued since
+        // `submit_cancels`, has a step for us to take
+        let idle = || {
+            (!self.draining.load(Ordering::Acquire)
+                || (ring.cancel_sent && !self.shutdown.load(Ordering::Acquire)))
+                && self.cancels[worker_id].lock().is_empty()
+        };
+        if wakeup.prepare_wait(idle) {
+            // Flush + block until ≥1 CQE
+            let _ = ring.io.flush_and_wait_timeout(1, WAIT_IO_TIMEOUT);
+            wakeup.finish_wait();
+        } else {
+            let _ = ring.io.flush();
+        }
 
-        // Drain all available CQEs
+        self.reap(worker_id, ring)
+    }
+
+    /// End `worker_id`'s wait in its ring, if it is blocked there.
+    ///
+    /// Callable from any thread; a no-op for a worker that isn't blocked.
+    pub fn wake(&self, worker_id: usize) {
+        if let Some(wakeup) = self.wakeups.get(worker_id) {
+            wakeup.wake();
+        }
+    }
+
+    /// Drain available CQEs, store results and wake their GVThreads.
+    fn reap(&self, worker_id: usize, ring: &mut WorkerRing) -> usize {
         let n = ring.io.poll_completions(&mut ring.comp_buf, 256);
 
+        let draining = self.draining.load(Ordering::Relaxed);
+        let mut wakeups = 0;
         for i in 0..n {
             let cqe = &ring.comp_buf[i];
-            let slot = cqe.corr_id.as_gvthread_id();
+            if cqe.corr_id.0 == WAKEUP_TAG {
+                // Woken (or cancelled by the drain): re-armed on the next wait
+                self.wakeups[worker_id].reset();
+                ring.wakeup_armed = false;
+                wakeups += 1;
+                continue;
+            }
+            if cqe.corr_id == CorrId::CANCEL {
+                continue; // The drain's cancel-all
+            }
+            let Some((slot, mut result)) = ring.linked.complete(cqe.corr_id.0, cqe.result) else {
+                continue; // First half of a linked pair
+            };
             if slot == u32::MAX {
+                continue; // Cancel sentinel
+            }
+            let Some(cell) = self.results.get(slot as usize) else {
+                eprintln!(
+                    "worker-reactor[{}]: completion for slot {} outside the results slab, dropped",
+                    worker_id, slot
+                );
+                continue;
+            };
+            if draining && result == -(libc::ECANCELED as i64) {
+                result = -(libc::ESHUTDOWN as i64);
+            }
+            // Lost to `shutdown_graceful` giving up on it: already woken
+            if cell.compare_exchange(PENDING, result, Ordering::Release, Ordering::Relaxed).is_err() {
                 continue;
             }
-            self.results[slot as usize].store(cqe.result, Ordering::Release);
             scheduler::wake_gvthread(GVThreadId::new(slot), Priority::Normal);
         }
 
-        n
+        if n > wakeups {
+            let counters = &self.counters[worker_id];
+            counters.completed.fetch_add((n - wakeups) as u64, Ordering::Relaxed);
+            counters.cq_overflow.store(ring.io.dropped_completions(), Ordering::Relaxed);
+        }
+        n - wakeups
+    }
+
+    /// Submit the cancels queued for this worker (see "Cancellation").
+    ///
+    /// Slots whose op has already completed are skipped; with a full SQ
+    /// the rest wait for a later poll.
+    fn submit_cancels(&self, worker_id: usize, ring: &mut WorkerRing) {
+        let mut queued = self.cancels[worker_id].lock();
+        if queued.is_empty() {
+            return;
+        }
+        if !ring.io.capabilities().supports_async_cancel {
+            queued.clear(); // Left to finish or time out
+            return;
+        }
+        while let Some(&slot) = queued.last() {
+            if self.results[slot as usize].load(Ordering::Acquire) == PENDING {
+                if ring.io.cancel(CorrId::from_gvthread_id(slot)).is_err() {
+                    return;
+                }
+                self.counters[worker_id].submitted.fetch_add(1, Ordering::Relaxed);
+            }
+            queued.pop();
+        }
+    }
+
+    /// This worker's part of a graceful shutdown. Returns `true` once
+    /// its ring is closed, after which it must not be touched.
+    #[inline]
+    fn drain_step(&self, worker_id: usize, ring: &mut WorkerRing) -> bool {
+        if !self.draining.load(Ordering::Acquire) {
+            return false;
+        }
+        if self.shutdown.load(Ordering::Acquire) {
+            if !ring.closed {
+                ring.io.shutdown();
+                ring.closed = true;
+                self.closed_rings.fetch_add(1, Ordering::Release);
+            }
+            return true;
+        }
+        if ring.cancel_sent {
+            return false;
+        }
+        // Without async cancel the ops are left to finish or time out.
+        // A full SQ gets the cancel on a later poll.
+        let counters = &self.counters[worker_id];
+        let inflight = counters.submitted.load(Ordering::Relaxed) > counters.completed.load(Ordering::Relaxed);
+        if !inflight || !ring.io.capabilities().supports_async_cancel {
+            ring.cancel_sent = true;
+        } else if ring.io.cancel_all().is_ok() {
+            ring.cancel_sent = true;
+            counters.submitted.fetch_add(1, Ordering::Relaxed);
+        }
+        false
     }
 
     // ── Results slab ─────────────────────────────────────────────────
@@ -255,6 +524,82 @@ impl WorkerReactorPool {
         self.results[slot as usize].load(Ordering::Acquire)
     }
 
+    // ── Metrics ──────────────────────────────────────────────────────
+
+    /// Per-worker ring counters, indexed by worker id.
+    ///
+    /// Relaxed loads, so each entry is a cheap approximate snapshot;
+    /// a steadily high `inflight` next to idle workers points at a
+    /// saturated ring rather than the application.
+    pub fn stats(&self) -> Vec<RingStats> {
+        self.counters
+            .iter()
+            .enumerate()
+            .map(|(worker_id, c)| {
+                let completed = c.completed.load(Ordering::Relaxed);
+                let submitted = c.submitted.load(Ordering::Relaxed);
+                RingStats {
+                    worker_id,
+                    inflight: submitted.saturating_sub(completed),
+                    submitted,
+                    completed,
+                    cq_overflow: c.cq_overflow.load(Ordering::Relaxed),
+                }
+            })
+            .collect()
+    }
+
+    /// Drain in-flight I/O, then close the rings (see the module docs).
+    ///
+    /// Call while the runtime's workers are still running: they cancel
+    /// and close their own rings. Blocks the caller (a GVThread sleeps)
+    /// for at most about `timeout`.
+    ///
+    /// Returns `true` if every op completed or was cancelled in time.
+    /// Otherwise the rest were abandoned; the kernel may still write to
+    /// their buffers until it has torn the ring down.
+    pub fn shutdown_graceful(&self, timeout: Duration) -> bool {
+        if self.draining.swap(true, Ordering::AcqRel) || self.shutdown.load(Ordering::Acquire) {
+            return false; // Already shutting down
+        }
+        self.wake_all();
+        let deadline = Instant::now() + timeout;
+        while self.stats().iter().any(|s| s.inflight > 0) && Instant::now() < deadline {
+            gvthread_runtime::timer::sleep(DRAIN_POLL_INTERVAL);
+        }
+
+        let mut abandoned = 0;
+        for (slot, cell) in self.results.iter().enumerate() {
+            let shutdown = -(libc::ESHUTDOWN as i64);
+            if cell.compare_exchange(PENDING, shutdown, Ordering::Release, Ordering::Relaxed).is_ok() {
+                scheduler::wake_gvthread(GVThreadId::new(slot as u32), Priority::Normal);
+                abandoned += 1;
+            }
+        }
+
+        self.shutdown.store(true, Ordering::Release);
+        self.wake_all();
+        let close_deadline = deadline.max(Instant::now() + 2 * WAIT_IO_TIMEOUT);
+        while self.closed_rings.load(Ordering::Acquire) < self.num_workers
+            && Instant::now() < close_deadline
+        {
+            gvthread_runtime::timer::sleep(DRAIN_POLL_INTERVAL);
+        }
+        eprintln!(
+            "worker-reactor: graceful shutdown ({} workers, {} rings closed, {} ops abandoned)",
+            self.num_workers,
+            self.closed_rings.load(Ordering::Relaxed),
+            abandoned,
+        );
+        abandoned == 0
+    }
+
+    fn wake_all(&self) {
+        for worker_id in 0..self.num_workers {
+            self.wake(worker_id);
+        }
+    }
+
     /// Shutdown all worker rings.
     pub fn shutdown(&self) {
         self.shutdown.store(true, Ordering::Release);
@@ -322,3 +667,81 @@ fn hook_wait_io(worker_id: usize) -> usize {
         }
     }
 }
+
+/// The worker-local half of the scheduler's I/O cancel hook (see
+/// `reactor::cancel_io`).
+pub(crate) fn cancel_io(id: GVThreadId) {
+    // Via a raw pointer, not a reference to the `static mut`
+    if let Some(pool) = unsafe { (*std::ptr::addr_of!(GLOBAL_POOL)).as_ref() } {
+        pool.cancel(id.as_u32());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn graceful_shutdown_cancels_inflight_ops() {
+        let pool = WorkerReactorPool::new(1, 8, 4);
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        let mut byte = 0u8;
+        let args = [fds[0] as u64, &mut byte as *mut u8 as u64, 1, 0, 0, 0];
+        let read = libc::SYS_read as u32;
+
+        std::thread::scope(|s| {
+            // Stands in for worker 0: a read nothing will ever satisfy
+            s.spawn(|| {
+                pool.submit(0, 3, read, &args, 0).unwrap();
+                while pool.closed_rings.load(Ordering::Acquire) == 0 {
+                    pool.wait_and_poll(0);
+                }
+            });
+            while pool.stats()[0].inflight == 0 {
+                std::thread::yield_now();
+            }
+            assert!(pool.shutdown_graceful(Duration::from_secs(5)));
+        });
+
+        assert_eq!(pool.read_result(3), -(libc::ESHUTDOWN as i64));
+        assert_eq!(pool.stats()[0].inflight, 0);
+        assert_eq!(pool.submit(0, 2, read, &args, 0), Err(-(libc::ESHUTDOWN as i64)));
+        unsafe {
+            libc::close(fds[0]);
+            libc::close(fds[1]);
+        }
+    }
+
+    #[test]
+    fn cancel_interrupts_an_inflight_op_from_another_thread() {
+        let pool = WorkerReactorPool::new(1, 8, 4);
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        let mut byte = 0u8;
+        let args = [fds[0] as u64, &mut byte as *mut u8 as u64, 1, 0, 0, 0];
+        let read = libc::SYS_read as u32;
+
+        std::thread::scope(|s| {
+            // Stands in for worker 0, blocked in its ring on a read
+            // nothing will ever satisfy
+            s.spawn(|| {
+                pool.set_owner(3, 0);
+                pool.submit(0, 3, read, &args, 0).unwrap();
+                while pool.read_result(3) == PENDING {
+                    pool.wait_and_poll(0);
+                }
+            });
+            while pool.stats()[0].inflight == 0 {
+                std::thread::yield_now();
+            }
+            pool.cancel(3);
+        });
+
+        assert_eq!(pool.read_result(3), -(libc::ECANCELED as i64));
+        unsafe {
+            libc::close(fds[0]);
+            libc::close(fds[1]);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Batched reactor I/O (`submit_batch`) end to end
+//!
+//! Its own test binary: the scheduler is process-global.
+
+use gvthread::{spawn_with_handle, Runtime, SchedulerConfig};
+use ksvc_gvthread::{BatchWait, IoOp, Reactor, ReactorConfig};
+use std::time::Duration;
+
+fn pipe() -> [i32; 2] {
+    let mut fds = [0; 2];
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    fds
+}
+
+#[test]
+fn batches_wait_for_all_or_any() {
+    let mut runtime = Runtime::new(
+        SchedulerConfig::default().num_workers(2).num_low_priority_workers(0),
+    );
+    let mut reactor = Reactor::start(ReactorConfig { max_slots: 4096, ..Default::default() });
+    let shared = reactor.shared();
+    let (a, b, c) = (pipe(), pipe(), pipe());
+
+    runtime.block_on(move || {
+        // All: two reads with data waiting plus a write, one park
+        let all = spawn_with_handle(move |_| {
+            assert_eq!(unsafe { libc::write(a[1], b"x".as_ptr().cast(), 1) }, 1);
+            assert_eq!(unsafe { libc::write(b[1], b"yz".as_ptr().cast(), 2) }, 2);
+            let (mut from_a, mut from_b) = ([0u8; 4], [0u8; 4]);
+            let mut ops = [
+                IoOp::read(a[0], &mut from_a),
+                IoOp::read(b[0], &mut from_b),
+                IoOp::write(c[1], b"w"),
+            ];
+            assert!(shared.submit_batch(&mut ops, BatchWait::All).is_some());
+            let results: Vec<_> = ops.iter().map(IoOp::result).collect();
+            assert_eq!(results, [1, 2, 1]);
+            assert_eq!((&from_a[..1], &from_b[..2]), (&b"x"[..], &b"yz"[..]));
+
+            // Any: only the second pipe gets data; the first read is
+            // cancelled, and the call waits for that too
+            let writer = std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(20));
+                assert_eq!(unsafe { libc::write(b[1], b"q".as_ptr().cast(), 1) }, 1);
+            });
+            let mut ops = [IoOp::read(a[0], &mut from_a), IoOp::read(b[0], &mut from_b)];
+            let first = shared.submit_batch(&mut ops, BatchWait::Any);
+            writer.join().unwrap();
+            assert_eq!(first, Some(1));
+            assert_eq!(ops[0].result(), -(libc::ECANCELED as i64));
+            assert_eq!(ops[1].result(), 1);
+            assert_eq!(from_b[0], b'q');
+            assert_eq!(shared.submit_batch(&mut [], BatchWait::Any), None);
+        });
+        all.join().unwrap();
+    });
+    reactor.shutdown();
+}
@@ -0,0 +1,36 @@
+//! `GvtListener::bind_addr` on IPv6 and specific IPv4 addresses
+//!
+//! Its own test binary: the scheduler is process-global.
+
+use gvthread::{spawn_with_handle, Runtime, SchedulerConfig};
+use ksvc_gvthread::{GvtListener, Reactor, ReactorConfig};
+use std::net::{SocketAddr, TcpStream};
+
+#[test]
+fn binds_ipv6_loopback_and_accepts() {
+    let mut runtime = Runtime::new(
+        SchedulerConfig::default().num_workers(2).num_low_priority_workers(0),
+    );
+    let mut reactor = Reactor::start(ReactorConfig { max_slots: 4096, ..Default::default() });
+
+    let listener = GvtListener::bind_addr(reactor.shared(), "[::1]:0".parse().unwrap()).unwrap();
+    let addr = listener.local_addr().unwrap();
+    assert!(addr.is_ipv6() && addr.ip().is_loopback() && addr.port() != 0);
+
+    let v4: SocketAddr = "127.0.0.1:0".parse().unwrap();
+    let local = GvtListener::bind_addr_local(v4).unwrap().local_addr().unwrap();
+    assert_eq!(local.ip(), v4.ip());
+
+    let client = std::thread::spawn(move || TcpStream::connect(addr).unwrap());
+    runtime.block_on(move || {
+        let accepted = spawn_with_handle(move |_| {
+            let stream = listener.accept().unwrap();
+            (stream.local_addr().unwrap(), stream.peer_addr().unwrap())
+        });
+        let (server_end, peer) = accepted.join().unwrap();
+        let client = client.join().unwrap();
+        assert_eq!(server_end, addr);
+        assert_eq!(peer, client.local_addr().unwrap());
+    });
+    reactor.shutdown();
+}
@@ -0,0 +1,47 @@
+//! A reactor blocked on a slow op still picks up new requests
+//!
+//! Its own test binary: the scheduler is process-global.
+
+use gvthread::{spawn_with_handle, Runtime, SchedulerConfig};
+use ksvc_gvthread::{ksvc_read, ksvc_write, Reactor, ReactorConfig};
+use std::time::{Duration, Instant};
+
+fn pipe() -> [i32; 2] {
+    let mut fds = [0; 2];
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    fds
+}
+
+#[test]
+fn blocked_reactor_is_woken_by_new_requests() {
+    let mut runtime = Runtime::new(
+        SchedulerConfig::default().num_workers(2).num_low_priority_workers(0),
+    );
+    let mut reactor = Reactor::start(ReactorConfig { max_slots: 4096, ..Default::default() });
+    let shared = reactor.shared();
+    let (slow, fast) = (pipe(), pipe());
+
+    // The only thing that completes the slow read, long after the
+    // fast write must have been served
+    let unblock = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_secs(5));
+        assert_eq!(unsafe { libc::write(slow[1], b"s".as_ptr().cast(), 1) }, 1);
+    });
+
+    runtime.block_on(move || {
+        let reader = {
+            let shared = shared.clone();
+            spawn_with_handle(move |_| ksvc_read(&shared, slow[0], &mut [0u8; 1]))
+        };
+        // Let the reactor block on the read
+        gvthread::sleep(Duration::from_millis(50));
+
+        let started = Instant::now();
+        let writer = spawn_with_handle(move |_| ksvc_write(&shared, fast[1], b"f"));
+        assert_eq!(writer.join().unwrap(), 1);
+        assert!(started.elapsed() < Duration::from_secs(1), "the write waited for the read");
+        assert_eq!(reader.join().unwrap(), 1);
+    });
+    unblock.join().unwrap();
+    reactor.shutdown();
+}
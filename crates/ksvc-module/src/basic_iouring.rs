@@ -8,14 +8,38 @@ use ksvc_core::entry::{CorrId, SubmitEntry};
 use ksvc_core::error::{KsvcError, Result};
 use ksvc_core::io_backend::{IoBackend, IoCompletion};
 
+use crate::capabilities::IoCapabilities;
+
 use std::os::unix::io::{AsRawFd, RawFd};
+use std::time::Duration;
 
 /// Configuration for BasicIoUring.
+///
+/// Everything here is fixed at `io_uring_setup` time. `validate()`
+/// rejects combinations the kernel would refuse (or silently ignore)
+/// with `KsvcError::InvalidConfig`; the constructors call it first.
 pub struct BasicIoUringConfig {
     /// Number of SQ entries. Must be power of 2.
     pub sq_entries: u32,
-    /// Number of CQ entries. Defaults to 2 * sq_entries.
+    /// Number of CQ entries. Defaults to 2 * sq_entries; raised to
+    /// sq_entries if smaller (the kernel's minimum).
     pub cq_entries: Option<u32>,
+    /// SQPOLL kernel thread idle timeout in milliseconds.
+    /// Only used by `SqpollIoUring`; ignored by `BasicIoUring`.
+    pub sq_thread_idle_ms: u32,
+    /// Pin the SQPOLL kernel thread to this CPU (`IORING_SETUP_SQ_AFF`).
+    /// SQPOLL only: `BasicIoUring` rejects it.
+    pub sq_thread_cpu: Option<u32>,
+    /// `IORING_SETUP_COOP_TASKRUN` (5.19+): run completion task work
+    /// when the submitter next enters the kernel instead of interrupting
+    /// it. Fewer IPIs for a thread that polls its own ring. Not with
+    /// SQPOLL.
+    pub coop_taskrun: bool,
+    /// `IORING_SETUP_SINGLE_ISSUER` (6.0+): promise that only the thread
+    /// that created the ring submits to it (with SQPOLL, only the kernel
+    /// thread does). Lets the kernel skip locking; submitting from
+    /// another thread then fails with `EEXIST`.
+    pub single_issuer: bool,
 }
 
 impl Default for BasicIoUringConfig {
@@ -23,7 +47,33 @@ impl Default for BasicIoUringConfig {
         Self {
             sq_entries: 256,
             cq_entries: None,
+            sq_thread_idle_ms: 2000,
+            sq_thread_cpu: None,
+            coop_taskrun: false,
+            single_issuer: false,
+        }
+    }
+}
+
+/// Largest SQ the kernel accepts (`IORING_MAX_ENTRIES`)
+const MAX_SQ_ENTRIES: u32 = 32768;
+
+impl BasicIoUringConfig {
+    /// Check the parameters for a plain (`sqpoll == false`) or SQPOLL ring.
+    pub fn validate(&self, sqpoll: bool) -> Result<()> {
+        if !self.sq_entries.is_power_of_two() || self.sq_entries > MAX_SQ_ENTRIES {
+            return Err(KsvcError::InvalidConfig("sq_entries must be a power of 2 up to 32768"));
+        }
+        if self.cq_entries == Some(0) {
+            return Err(KsvcError::InvalidConfig("cq_entries must not be 0"));
+        }
+        if self.sq_thread_cpu.is_some() && !sqpoll {
+            return Err(KsvcError::InvalidConfig("sq_thread_cpu needs an SQPOLL ring"));
         }
+        if self.coop_taskrun && sqpoll {
+            return Err(KsvcError::InvalidConfig("coop_taskrun can't be combined with SQPOLL"));
+        }
+        Ok(())
     }
 }
 
@@ -39,31 +89,125 @@ pub struct BasicIoUring {
     ring: io_uring::IoUring,
     inflight: usize,
     pending_submit: u32,
+    /// Timespecs of queued LINK_TIMEOUT SQEs. The kernel reads them when
+    /// the SQEs are submitted, so they are dropped after each flush.
+    /// Boxed so their addresses survive the Vec growing.
+    #[allow(clippy::vec_box)]
+    link_timespecs: Vec<Box<io_uring::types::Timespec>>,
+    /// User data of multishot SQEs still armed (counted in `inflight`).
+    multishot: Vec<u64>,
+    /// Completions the kernel dropped on CQ overflow (last seen value of
+    /// the CQ ring's `overflow` counter).
+    dropped: u64,
 }
 
+/// `IORING_ENTER_GETEVENTS`
+const ENTER_GETEVENTS: u32 = 1 << 0;
+
 impl BasicIoUring {
     pub fn new(config: BasicIoUringConfig) -> Result<Self> {
-        let ring = io_uring::IoUring::builder()
-            .build(config.sq_entries)
-            .map_err(|e| KsvcError::IoUringSetup(e.raw_os_error().unwrap_or(-1)))?;
+        let ring = Self::build_ring(io_uring::IoUring::builder(), &config, false)?;
 
         Ok(Self {
             ring,
             inflight: 0,
             pending_submit: 0,
+            link_timespecs: Vec::new(),
+            multishot: Vec::new(),
+            dropped: 0,
         })
     }
 
+    /// Validate `config` and build a ring with its sizes and flags.
+    /// Shared with the other io_uring backends in this crate.
+    pub(crate) fn build_ring(
+        mut builder: io_uring::Builder,
+        config: &BasicIoUringConfig,
+        sqpoll: bool,
+    ) -> Result<io_uring::IoUring> {
+        config.validate(sqpoll)?;
+        let cq_entries = config
+            .cq_entries
+            .unwrap_or(config.sq_entries.saturating_mul(2))
+            .max(config.sq_entries);
+        if let Some(cpu) = config.sq_thread_cpu {
+            builder.setup_sqpoll_cpu(cpu);
+        }
+        if config.coop_taskrun {
+            builder.setup_coop_taskrun();
+        }
+        if config.single_issuer {
+            builder.setup_single_issuer();
+        }
+        builder
+            .setup_cqsize(cq_entries)
+            .build(config.sq_entries)
+            .map_err(|e| KsvcError::IoUringSetup(e.raw_os_error().unwrap_or(-1)))
+    }
+
+    /// Have the kernel post CQEs it held back on CQ overflow.
+    ///
+    /// With `IORING_FEAT_NODROP` (Linux 5.5+) a full CQ makes the kernel
+    /// buffer further CQEs and raise `IORING_SQ_CQ_OVERFLOW`; they only
+    /// reach the CQ ring on a later `io_uring_enter(GETEVENTS)`. Returns
+    /// false (and does nothing) if no CQEs are held back.
+    /// Shared with the other io_uring backends in this crate.
+    pub(crate) fn flush_cq_overflow(ring: &mut io_uring::IoUring) -> bool {
+        if !ring.submission().cq_overflow() {
+            return false;
+        }
+        // Nothing to submit or wait for: the kernel just flushes
+        let _ = unsafe {
+            ring.submitter().enter::<libc::sigset_t>(0, 0, ENTER_GETEVENTS, None)
+        };
+        true
+    }
+
+    /// Drain CQEs into `buf` (never taking more than fits).
+    fn drain_cq(&mut self, buf: &mut [IoCompletion]) -> usize {
+        let mut cq = self.ring.completion();
+        let mut count = 0;
+        while count < buf.len() {
+            let Some(cqe) = cq.next() else { break };
+            buf[count] = IoCompletion {
+                corr_id: CorrId(cqe.user_data()),
+                result: cqe.result() as i64,
+                flags: cqe.flags(),
+            };
+            count += 1;
+            // A multishot SQE stays in flight until its last CQE
+            if !io_uring::cqueue::more(cqe.flags()) {
+                self.inflight = self.inflight.saturating_sub(1);
+                if !self.multishot.is_empty() {
+                    self.multishot.retain(|&ud| ud != cqe.user_data());
+                }
+            }
+        }
+        count
+    }
+
     /// Get the io_uring fd for passing to the kernel module.
     pub fn fd(&self) -> RawFd {
         self.ring.as_raw_fd()
     }
 
-    /// Probe supported opcodes via IORING_REGISTER_PROBE.
+    /// Supported opcodes (probed once per process, see `capabilities`).
     pub fn probe_opcodes_static(&self) -> Vec<u8> {
+        self.capabilities().opcodes().to_vec()
+    }
+
+    /// What the kernel's io_uring supports; the first ring to ask probes
+    /// it via IORING_REGISTER_PROBE, later ones get the cached result.
+    pub fn capabilities(&self) -> &'static IoCapabilities {
+        IoCapabilities::of_ring(&self.ring)
+    }
+
+    /// Probe the opcodes supported by `ring`.
+    /// Shared with the other io_uring backends in this crate.
+    pub(crate) fn probe_ring(ring: &io_uring::IoUring) -> Vec<u8> {
         let mut probe = io_uring::Probe::new();
         // Register probe with the kernel to discover supported opcodes
-        if self.ring.submitter().register_probe(&mut probe).is_err() {
+        if ring.submitter().register_probe(&mut probe).is_err() {
             eprintln!("ksvc: IORING_REGISTER_PROBE failed, Tier 1 disabled");
             return Vec::new();
         }
@@ -76,6 +220,18 @@ impl BasicIoUring {
         opcodes
     }
 
+    /// Register fixed buffers (`IORING_REGISTER_BUFFERS`) with this ring.
+    ///
+    /// After this, `READ_FIXED`/`WRITE_FIXED` SQEs may reference the
+    /// buffers by index. See `RegisteredBuffers` for lifetime rules.
+    #[cfg(feature = "fixed-buffers")]
+    pub fn register_buffers(
+        &self,
+        buffers: &crate::registered_buffers::RegisteredBuffers,
+    ) -> Result<()> {
+        crate::registered_buffers::register_buffers(&self.ring.submitter(), buffers)
+    }
+
     /// Translate a KSVC SubmitEntry to an io_uring SQE and push it.
     ///
     /// This is the core translation layer. The SubmitEntry's syscall_nr
@@ -102,7 +258,7 @@ impl BasicIoUring {
         //   args[3] = offset/flags, args[4] = ..., args[5] = ...
         //
         // We construct the typed opcode entry from the io-uring crate.
-        let sqe = self.build_sqe(entry, opcode)?;
+        let sqe = Self::build_sqe(entry, opcode)?;
 
         // Push the SQE
         unsafe {
@@ -114,12 +270,22 @@ impl BasicIoUring {
         Ok(())
     }
 
+    /// Build the `IORING_OP_ASYNC_CANCEL` SQE for the op `corr_id`.
+    ///
+    /// Its own completion carries `CorrId::CANCEL`.
+    /// Shared with the other io_uring backends in this crate.
+    pub(crate) fn build_cancel_sqe(corr_id: CorrId) -> io_uring::squeue::Entry {
+        io_uring::opcode::AsyncCancel::new(corr_id.0)
+            .build()
+            .user_data(CorrId::CANCEL.0)
+    }
+
     /// Build a raw io_uring SQE from a KSVC submit entry.
     ///
     /// Uses the opaque entry type for maximum flexibility.
     /// Each opcode's argument mapping is documented inline.
-    fn build_sqe(
-        &self,
+    /// Shared with the other io_uring backends in this crate.
+    pub(crate) fn build_sqe(
         entry: &SubmitEntry,
         opcode: u8,
     ) -> Result<io_uring::squeue::Entry> {
@@ -146,6 +312,20 @@ impl BasicIoUring {
                     .offset(u64::MAX)
                     .build()
             }
+            // read(fd, buf, count) with a registered buffer
+            //   → READ_FIXED(fd, buf, len, offset=-1, buf_index=args[5])
+            super::probe_router::op::READ_FIXED => {
+                opcode::ReadFixed::new(fd, a[1] as *mut u8, a[2] as u32, a[5] as u16)
+                    .offset(u64::MAX)
+                    .build()
+            }
+            // write(fd, buf, count) with a registered buffer
+            //   → WRITE_FIXED(fd, buf, len, offset=-1, buf_index=args[5])
+            super::probe_router::op::WRITE_FIXED => {
+                opcode::WriteFixed::new(fd, a[1] as *const u8, a[2] as u32, a[5] as u16)
+                    .offset(u64::MAX)
+                    .build()
+            }
             // readv(fd, iov, iovcnt) → READV(fd, iov, iovcnt)
             super::probe_router::op::READV => {
                 let iov = a[1] as *const libc::iovec;
@@ -322,23 +502,27 @@ impl BasicIoUring {
 }
 
 impl IoBackend for BasicIoUring {
-    fn submit(&mut self, entry: &SubmitEntry) -> Result<()> {
-        // The opcode must be looked up from the router, but it's passed
-        // via the entry's route. We'll need the opcode from the caller.
-        // For now, we store it... actually the dispatcher knows the opcode
-        // from the route_table lookup. We need it passed in.
-        //
-        // Design decision: the IoBackend::submit takes a SubmitEntry
-        // which has syscall_nr but not the opcode. The dispatcher
-        // translates syscall_nr → opcode via the router, and we need
-        // that opcode here.
-        //
-        // Solution: store opcode in a reserved field of SubmitEntry,
-        // or have a separate method. For now, we'll look it up from
-        // a helper that the Instance provides.
-        //
-        // TODO: clean this up in the trait design
-        Err(KsvcError::Unsupported(entry.syscall_nr))
+    fn submit(&mut self, entry: &SubmitEntry, opcode: u8) -> Result<()> {
+        self.translate_and_push(entry, opcode)
+    }
+
+    /// Queues from the front until the SQ fills or an entry can't be
+    /// translated. SQ space is checked once for the whole batch.
+    fn submit_batch(&mut self, entries: &[SubmitEntry], opcodes: &[u8]) -> usize {
+        let mut sq = self.ring.submission();
+        let room = sq.capacity() - sq.len();
+        let mut queued = 0;
+        for (entry, &opcode) in entries.iter().zip(opcodes).take(room) {
+            let Ok(sqe) = Self::build_sqe(entry, opcode) else { break };
+            // Can't fail: `room` slots are free
+            if unsafe { sq.push(&sqe) }.is_err() {
+                break;
+            }
+            queued += 1;
+        }
+        drop(sq); // publishes the new SQ tail
+        self.pending_submit += queued as u32;
+        queued
     }
 
     fn flush(&mut self) -> Result<usize> {
@@ -349,32 +533,37 @@ impl IoBackend for BasicIoUring {
             .map_err(|e| KsvcError::IoUringSubmit(e.raw_os_error().unwrap_or(-1)))?;
         self.inflight += submitted;
         self.pending_submit = 0;
+        self.link_timespecs.clear();
         Ok(submitted)
     }
 
     fn poll_completions(&mut self, buf: &mut [IoCompletion], max: usize) -> usize {
-        let cq = self.ring.completion();
-        let mut count = 0;
-        for cqe in cq {
-            if count >= max || count >= buf.len() {
+        let limit = max.min(buf.len());
+        let mut count = self.drain_cq(&mut buf[..limit]);
+
+        // Held-back CQEs would otherwise sit in the kernel until the next
+        // submit, and their GVThreads with them
+        while count < limit && Self::flush_cq_overflow(&mut self.ring) {
+            let n = self.drain_cq(&mut buf[count..limit]);
+            if n == 0 {
                 break;
             }
-            buf[count] = IoCompletion {
-                corr_id: CorrId(cqe.user_data()),
-                result: cqe.result() as i64,
-                flags: cqe.flags(),
-            };
-            count += 1;
-            self.inflight = self.inflight.saturating_sub(1);
+            count += n;
+        }
+
+        let overflow = self.ring.completion().overflow() as u64;
+        if overflow > self.dropped {
+            eprintln!(
+                "ksvc: io_uring CQ overflow dropped {} completions",
+                overflow - self.dropped,
+            );
+            self.dropped = overflow;
         }
         count
     }
 
     fn cancel(&mut self, corr_id: CorrId) -> Result<()> {
-        use io_uring::opcode;
-        let sqe = opcode::AsyncCancel::new(corr_id.0)
-            .build()
-            .user_data(u64::MAX - 1); // sentinel for cancel completions
+        let sqe = Self::build_cancel_sqe(corr_id);
         unsafe {
             self.ring.submission()
                 .push(&sqe)
@@ -409,6 +598,7 @@ impl IoBackend for BasicIoUring {
         }
         self.inflight = 0;
         self.pending_submit = 0;
+        self.multishot.clear();
         // io_uring::IoUring::drop() handles fd close + munmap
     }
 }
@@ -418,13 +608,8 @@ impl IoBackend for BasicIoUring {
 // (closes fd, unmaps SQ/CQ rings). We call shutdown()
 // explicitly from KsvcInstance::drop() for orderly drain.
 
-/// Extended submit method that takes the pre-resolved opcode.
-/// This is what the dispatcher actually calls.
+/// Submit methods beyond the `IoBackend` trait.
 impl BasicIoUring {
-    pub fn submit_with_opcode(&mut self, entry: &SubmitEntry, opcode: u8) -> Result<()> {
-        self.translate_and_push(entry, opcode)
-    }
-
     /// Flush pending SQEs AND block until at least `min_complete` CQEs are ready.
     ///
     /// This is the key performance method. Instead of:
@@ -439,6 +624,377 @@ impl BasicIoUring {
             .map_err(|e| KsvcError::IoUringSubmit(e.raw_os_error().unwrap_or(-1)))?;
         self.inflight += submitted;
         self.pending_submit = 0;
+        self.link_timespecs.clear();
         Ok(submitted)
     }
+
+    /// `flush_and_wait`, but gives up after `timeout`.
+    ///
+    /// Lets a thread blocked on its ring notice other work now and then.
+    /// Returns the number of SQEs submitted, whether or not CQEs arrived.
+    /// Kernels without `IORING_FEAT_EXT_ARG` (pre-5.11) can't bound the
+    /// wait and block as in `flush_and_wait`.
+    pub fn flush_and_wait_timeout(&mut self, min_complete: usize, timeout: Duration) -> Result<usize> {
+        use io_uring::types::{SubmitArgs, Timespec};
+
+        if !self.ring.params().is_feature_ext_arg() {
+            return self.flush_and_wait(min_complete);
+        }
+        let ts = Timespec::from(timeout);
+        let args = SubmitArgs::new().timespec(&ts);
+        let submitted = match self.ring.submitter().submit_with_args(min_complete, &args) {
+            Ok(n) => n,
+            // Timed out or interrupted with nothing submitted
+            Err(e) if matches!(e.raw_os_error(), Some(libc::ETIME | libc::EINTR)) => 0,
+            Err(e) => return Err(KsvcError::IoUringSubmit(e.raw_os_error().unwrap_or(-1))),
+        };
+        self.inflight += submitted;
+        self.pending_submit = 0;
+        self.link_timespecs.clear();
+        Ok(submitted)
+    }
+
+    /// Queue one `IORING_OP_ASYNC_CANCEL` for every op in flight (5.19+).
+    ///
+    /// Cancelled ops complete with `-ECANCELED`, the cancel itself
+    /// (`CorrId::CANCEL`) with the number cancelled. Older kernels fail
+    /// the cancel with `-EINVAL` and leave the ops alone.
+    pub fn cancel_all(&mut self) -> Result<()> {
+        use io_uring::{opcode, types};
+
+        let sqe = opcode::AsyncCancel2::new(types::CancelBuilder::any())
+            .build()
+            .user_data(CorrId::CANCEL.0);
+        unsafe {
+            self.ring.submission()
+                .push(&sqe)
+                .map_err(|_| KsvcError::RingFull)?;
+        }
+        self.pending_submit += 1;
+        Ok(())
+    }
+
+    /// Queue a multishot `IORING_OP_ACCEPT` on `fd` (kernel 5.19+).
+    ///
+    /// Each connection completes with `user_data` and the new fd (or
+    /// negative errno), flagged `IORING_CQE_F_MORE` while the accept
+    /// stays armed. A CQE without it ends the multishot; kernels without
+    /// support end it at once with `-EINVAL`.
+    pub fn submit_accept_multishot(&mut self, fd: RawFd, flags: i32, user_data: u64) -> Result<()> {
+        use io_uring::{opcode, types};
+
+        let sqe = opcode::AcceptMulti::new(types::Fd(fd))
+            .flags(flags)
+            .build()
+            .user_data(user_data);
+        unsafe {
+            self.ring.submission()
+                .push(&sqe)
+                .map_err(|_| KsvcError::RingFull)?;
+        }
+        self.pending_submit += 1;
+        self.multishot.push(user_data);
+        Ok(())
+    }
+
+    /// Queue a one-shot `IORING_OP_POLL_ADD` for `fd` becoming readable.
+    ///
+    /// Completes with `user_data` and the ready `POLLIN` mask (or negative
+    /// errno). Lets a thread blocked in `flush_and_wait` be woken through
+    /// an eventfd (see `EventFdNotifier`).
+    pub fn submit_poll_readable(&mut self, fd: RawFd, user_data: u64) -> Result<()> {
+        use io_uring::{opcode, types};
+
+        let sqe = opcode::PollAdd::new(types::Fd(fd), libc::POLLIN as u32)
+            .build()
+            .user_data(user_data);
+        unsafe {
+            self.ring.submission()
+                .push(&sqe)
+                .map_err(|_| KsvcError::RingFull)?;
+        }
+        self.pending_submit += 1;
+        Ok(())
+    }
+
+    /// Number of armed multishot SQEs (included in `inflight()`)
+    ///
+    /// These may never complete, so callers should not block in
+    /// `flush_and_wait` on their account.
+    pub fn multishot_inflight(&self) -> usize {
+        self.multishot.len()
+    }
+
+    /// Completions lost to CQ overflow since the ring was created.
+    ///
+    /// Stays 0 on kernels with `IORING_FEAT_NODROP` unless the kernel
+    /// fails to buffer an overflowing CQE; their owners never complete.
+    pub fn dropped_completions(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Submit `entry` with a linked `IORING_OP_LINK_TIMEOUT` of `timeout`.
+    ///
+    /// Two CQEs result. If the operation finishes first, it completes
+    /// normally and the timeout (`timeout_user_data`) with `-ECANCELED`.
+    /// If the timeout fires first, the operation completes with
+    /// `-ECANCELED` and the timeout with `-ETIME`.
+    ///
+    /// Both SQEs are queued or neither is (`RingFull`).
+    pub fn submit_with_link_timeout(
+        &mut self,
+        entry: &SubmitEntry,
+        opcode: u8,
+        timeout: Duration,
+        timeout_user_data: u64,
+    ) -> Result<()> {
+        use io_uring::{opcode, squeue, types};
+
+        let free = unsafe {
+            let sq = self.ring.submission_shared();
+            sq.capacity() - sq.len()
+        };
+        if free < 2 {
+            return Err(KsvcError::RingFull);
+        }
+
+        let op = Self::build_sqe(entry, opcode)?.flags(squeue::Flags::IO_LINK);
+        let ts = Box::new(
+            types::Timespec::new()
+                .sec(timeout.as_secs())
+                .nsec(timeout.subsec_nanos()),
+        );
+        let link = opcode::LinkTimeout::new(&*ts)
+            .build()
+            .user_data(timeout_user_data);
+        self.link_timespecs.push(ts);
+
+        unsafe {
+            let mut sq = self.ring.submission();
+            sq.push(&op).map_err(|_| KsvcError::RingFull)?;
+            sq.push(&link).map_err(|_| KsvcError::RingFull)?;
+        }
+        self.pending_submit += 2;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::probe_router::op;
+
+    #[test]
+    fn config_combinations_are_validated() {
+        let invalid = |config: BasicIoUringConfig, sqpoll| match config.validate(sqpoll) {
+            Err(KsvcError::InvalidConfig(why)) => why,
+            other => panic!("expected InvalidConfig, got {:?}", other),
+        };
+        invalid(BasicIoUringConfig { sq_entries: 100, ..Default::default() }, false);
+        invalid(BasicIoUringConfig { sq_entries: 65536, ..Default::default() }, false);
+        invalid(BasicIoUringConfig { cq_entries: Some(0), ..Default::default() }, false);
+        let pinned = || BasicIoUringConfig { sq_thread_cpu: Some(0), ..Default::default() };
+        assert!(invalid(pinned(), false).contains("SQPOLL"));
+        assert!(pinned().validate(true).is_ok());
+        let coop = || BasicIoUringConfig { coop_taskrun: true, ..Default::default() };
+        assert!(invalid(coop(), true).contains("SQPOLL"));
+        assert!(coop().validate(false).is_ok());
+        assert!(matches!(
+            BasicIoUring::new(pinned()),
+            Err(KsvcError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn tuned_ring_submits_from_its_own_thread() {
+        let Ok(mut io) = BasicIoUring::new(BasicIoUringConfig {
+            coop_taskrun: true,
+            single_issuer: true,
+            ..Default::default()
+        }) else {
+            return; // Kernel older than 6.0
+        };
+        let nop_close = SubmitEntry {
+            corr_id: CorrId(1),
+            syscall_nr: 3,
+            flags: 0,
+            args: [u64::MAX, 0, 0, 0, 0, 0], // fd -1
+        };
+        io.submit(&nop_close, op::CLOSE).unwrap();
+        io.flush_and_wait(1).unwrap();
+        let mut buf = [IoCompletion { corr_id: CorrId(0), result: 0, flags: 0 }; 1];
+        assert_eq!(io.poll_completions(&mut buf, 1), 1);
+        assert_eq!((buf[0].corr_id, buf[0].result), (CorrId(1), -(libc::EBADF as i64)));
+    }
+
+    #[test]
+    fn overflowed_completions_are_not_lost() {
+        const OPS: u64 = 64;
+        let mut io = BasicIoUring::new(BasicIoUringConfig {
+            sq_entries: 8,
+            cq_entries: Some(8),
+            ..Default::default()
+        })
+        .unwrap();
+
+        // Eight times more completions than the CQ holds, none reaped
+        for i in 0..OPS {
+            let close_bad_fd = SubmitEntry {
+                corr_id: CorrId(i),
+                syscall_nr: 3,
+                flags: 0,
+                args: [u64::MAX, 0, 0, 0, 0, 0], // fd -1
+            };
+            io.submit(&close_bad_fd, op::CLOSE).unwrap();
+            if (i + 1) % 8 == 0 {
+                io.flush().unwrap();
+            }
+        }
+
+        let mut buf = [IoCompletion { corr_id: CorrId(0), result: 0, flags: 0 }; 5];
+        let mut seen = vec![false; OPS as usize];
+        for _ in 0..OPS {
+            let n = io.poll_completions(&mut buf, 5);
+            for c in &buf[..n] {
+                assert_eq!(c.result, -(libc::EBADF as i64));
+                assert!(!std::mem::replace(&mut seen[c.corr_id.0 as usize], true));
+            }
+        }
+        assert!(seen.iter().all(|&s| s), "lost completions");
+        assert_eq!(io.inflight(), 0);
+        assert_eq!(io.dropped_completions(), 0);
+    }
+
+    #[test]
+    fn batch_fills_sq_and_reports_how_many_were_queued() {
+        let mut io = BasicIoUring::new(BasicIoUringConfig {
+            sq_entries: 8,
+            ..Default::default()
+        })
+        .unwrap();
+        let entries: Vec<SubmitEntry> = (0..12)
+            .map(|i| SubmitEntry {
+                corr_id: CorrId(i),
+                syscall_nr: 3,
+                flags: 0,
+                args: [u64::MAX, 0, 0, 0, 0, 0], // close(-1)
+            })
+            .collect();
+        let opcodes = [op::CLOSE; 12];
+
+        // 3 fit in front of the batch, then an untranslatable opcode
+        let mut bad = opcodes;
+        bad[3] = u8::MAX;
+        assert_eq!(io.submit_batch(&entries[..6], &bad[..6]), 3);
+        // The remaining 5 SQ slots cut the next batch short
+        assert_eq!(io.submit_batch(&entries[3..], &opcodes[3..]), 5);
+        assert_eq!(io.flush().unwrap(), 8);
+
+        let mut buf = [IoCompletion { corr_id: CorrId(0), result: 0, flags: 0 }; 16];
+        let mut ids: Vec<u64> = Vec::new();
+        while ids.len() < 8 {
+            let n = io.poll_completions(&mut buf, 16);
+            ids.extend(buf[..n].iter().map(|c| c.corr_id.0));
+        }
+        ids.sort();
+        assert_eq!(ids, (0..8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn cancel_completes_the_original_with_ecanceled() {
+        let mut io = BasicIoUring::new(BasicIoUringConfig::default()).unwrap();
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+
+        // A read from an empty pipe stays in flight until cancelled
+        let mut byte = 0u8;
+        let read = SubmitEntry {
+            corr_id: CorrId(7),
+            syscall_nr: 0,
+            flags: 0,
+            args: [fds[0] as u64, &mut byte as *mut u8 as u64, 1, 0, 0, 0],
+        };
+        io.submit(&read, op::READ).unwrap();
+        io.flush().unwrap();
+        io.cancel(CorrId(7)).unwrap();
+        io.flush_and_wait(2).unwrap();
+
+        let mut buf = [IoCompletion { corr_id: CorrId(0), result: 0, flags: 0 }; 4];
+        let mut got = Vec::new();
+        while got.len() < 2 {
+            let n = io.poll_completions(&mut buf, 4);
+            got.extend(buf[..n].iter().map(|c| (c.corr_id, c.result)));
+        }
+        got.sort_by_key(|&(id, _)| id.0);
+        assert_eq!(got, [(CorrId(7), -(libc::ECANCELED as i64)), (CorrId::CANCEL, 0)]);
+        assert_eq!(io.inflight(), 0);
+        unsafe {
+            libc::close(fds[0]);
+            libc::close(fds[1]);
+        }
+    }
+
+    #[test]
+    fn bounded_wait_returns_and_cancel_all_empties_the_ring() {
+        let mut io = BasicIoUring::new(BasicIoUringConfig::default()).unwrap();
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+
+        let mut bytes = [0u8; 2];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            let read = SubmitEntry {
+                corr_id: CorrId(i as u64),
+                syscall_nr: 0,
+                flags: 0,
+                args: [fds[0] as u64, byte as *mut u8 as u64, 1, 0, 0, 0],
+            };
+            io.submit(&read, op::READ).unwrap();
+        }
+        // Neither read can complete: the wait has to give up on its own
+        assert_eq!(io.flush_and_wait_timeout(1, Duration::from_millis(10)).unwrap(), 2);
+        let mut buf = [IoCompletion { corr_id: CorrId(0), result: 0, flags: 0 }; 4];
+        assert_eq!(io.poll_completions(&mut buf, 4), 0);
+
+        io.cancel_all().unwrap();
+        io.flush_and_wait(3).unwrap();
+        let mut got = Vec::new();
+        while got.len() < 3 {
+            let n = io.poll_completions(&mut buf, 4);
+            got.extend(buf[..n].iter().map(|c| (c.corr_id, c.result)));
+        }
+        got.sort_by_key(|&(id, _)| id.0);
+        let ecanceled = -(libc::ECANCELED as i64);
+        assert_eq!(got, [(CorrId(0), ecanceled), (CorrId(1), ecanceled), (CorrId::CANCEL, 2)]);
+        assert_eq!(io.inflight(), 0);
+        unsafe {
+            libc::close(fds[0]);
+            libc::close(fds[1]);
+        }
+    }
+
+    #[test]
+    fn eventfd_poll_wakes_a_blocked_wait() {
+        use crate::eventfd_notifier::EventFdNotifier;
+        use ksvc_core::notifier::Notifier;
+
+        let mut io = BasicIoUring::new(BasicIoUringConfig::default()).unwrap();
+        let notifier = std::sync::Arc::new(EventFdNotifier::create().unwrap());
+        io.submit_poll_readable(notifier.fd(), 42).unwrap();
+
+        let waker = {
+            let notifier = std::sync::Arc::clone(&notifier);
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(20));
+                notifier.notify().unwrap();
+            })
+        };
+        io.flush_and_wait(1).unwrap();
+        waker.join().unwrap();
+
+        let mut buf = [IoCompletion { corr_id: CorrId(0), result: 0, flags: 0 }; 2];
+        assert_eq!(io.poll_completions(&mut buf, 2), 1);
+        assert_eq!(buf[0].corr_id, CorrId(42));
+        assert_ne!(buf[0].result as i16 & libc::POLLIN, 0);
+        assert_eq!(io.inflight(), 0);
+    }
 }
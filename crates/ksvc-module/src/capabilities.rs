@@ -0,0 +1,171 @@
+//! `IoCapabilities` — what the running kernel's io_uring can do.
+//!
+//! Probed once per process (`IORING_REGISTER_PROBE` plus `uname`) and
+//! cached: every ring in the process talks to the same kernel, so the
+//! reactor, each worker ring and `ProbeRouter` share one probe.
+//!
+//! Opcode support comes straight from the probe. Features that are a
+//! flag on an existing opcode rather than an opcode of their own
+//! (multishot accept) can't be probed, and are judged by kernel version.
+
+use crate::basic_iouring::BasicIoUring;
+use crate::probe_router::op;
+
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Running kernel release, `major.minor.patch`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct KernelVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl KernelVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch }
+    }
+
+    /// Parse a `uname -r` release such as `6.8.0-45-generic`.
+    /// A missing patch level reads as 0.
+    pub fn parse(release: &str) -> Option<Self> {
+        let mut parts = release.split(|c: char| !c.is_ascii_digit());
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        let patch = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+        Some(Self::new(major, minor, patch))
+    }
+
+    /// The running kernel's version, from `uname(2)`
+    pub fn running() -> Option<Self> {
+        let mut uts: libc::utsname = unsafe { std::mem::zeroed() };
+        if unsafe { libc::uname(&mut uts) } != 0 {
+            return None;
+        }
+        let release = unsafe { std::ffi::CStr::from_ptr(uts.release.as_ptr()) };
+        Self::parse(release.to_str().ok()?)
+    }
+}
+
+impl fmt::Display for KernelVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// First kernel with `IORING_ACCEPT_MULTISHOT`
+const MULTISHOT_ACCEPT_SINCE: KernelVersion = KernelVersion::new(5, 19, 0);
+
+/// Typed view of the kernel's io_uring support.
+#[derive(Debug, Clone)]
+pub struct IoCapabilities {
+    /// Supported `IORING_OP_*` values, ascending
+    opcodes: Vec<u8>,
+    /// `IORING_OP_ACCEPT` with `IORING_ACCEPT_MULTISHOT` (5.19+). Assumed
+    /// when the kernel version is unknown; the multishot accept path
+    /// still falls back if the kernel then rejects it.
+    pub supports_multishot_accept: bool,
+    /// `IORING_OP_READ_FIXED` and `IORING_OP_WRITE_FIXED`
+    pub supports_read_fixed: bool,
+    /// `IORING_OP_LINK_TIMEOUT`
+    pub supports_link_timeout: bool,
+    /// `IORING_OP_ASYNC_CANCEL`
+    pub supports_async_cancel: bool,
+    /// `None` if `uname` couldn't be read or parsed
+    pub kernel_version: Option<KernelVersion>,
+}
+
+static CAPABILITIES: OnceLock<IoCapabilities> = OnceLock::new();
+
+impl IoCapabilities {
+    /// Capabilities from a list of supported opcodes and a kernel version
+    pub fn from_opcodes(opcodes: &[u8], kernel_version: Option<KernelVersion>) -> Self {
+        let mut opcodes = opcodes.to_vec();
+        opcodes.sort_unstable();
+        opcodes.dedup();
+        let has = |opcode| opcodes.binary_search(&opcode).is_ok();
+        Self {
+            supports_multishot_accept: has(op::ACCEPT)
+                && !matches!(kernel_version, Some(v) if v < MULTISHOT_ACCEPT_SINCE),
+            supports_read_fixed: has(op::READ_FIXED) && has(op::WRITE_FIXED),
+            supports_link_timeout: has(op::LINK_TIMEOUT),
+            supports_async_cancel: has(op::ASYNC_CANCEL),
+            kernel_version,
+            opcodes,
+        }
+    }
+
+    /// The process's capabilities, probing `ring` if nobody has yet
+    pub(crate) fn of_ring(ring: &io_uring::IoUring) -> &'static Self {
+        CAPABILITIES.get_or_init(|| {
+            Self::from_opcodes(&BasicIoUring::probe_ring(ring), KernelVersion::running())
+        })
+    }
+
+    /// The cached capabilities, if a ring has been probed
+    pub fn cached() -> Option<&'static Self> {
+        CAPABILITIES.get()
+    }
+
+    /// Supported `IORING_OP_*` values, ascending (input to `ProbeRouter`)
+    pub fn opcodes(&self) -> &[u8] {
+        &self.opcodes
+    }
+
+    /// Whether the kernel supports `opcode`
+    pub fn supports(&self, opcode: u8) -> bool {
+        self.opcodes.binary_search(&opcode).is_ok()
+    }
+}
+
+impl fmt::Display for IoCapabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kernel_version {
+            Some(v) => write!(f, "kernel {}", v)?,
+            None => write!(f, "kernel ?")?,
+        }
+        write!(f, ", {} opcodes", self.opcodes.len())?;
+        for (name, on) in [
+            ("multishot-accept", self.supports_multishot_accept),
+            ("fixed-rw", self.supports_read_fixed),
+            ("link-timeout", self.supports_link_timeout),
+            ("async-cancel", self.supports_async_cancel),
+        ] {
+            write!(f, " {}{}", if on { '+' } else { '-' }, name)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_kernel_releases() {
+        assert_eq!(KernelVersion::parse("6.8.0-45-generic"), Some(KernelVersion::new(6, 8, 0)));
+        assert_eq!(KernelVersion::parse("5.19"), Some(KernelVersion::new(5, 19, 0)));
+        assert_eq!(KernelVersion::parse("6.18.44-fc-v130"), Some(KernelVersion::new(6, 18, 44)));
+        assert_eq!(KernelVersion::parse("linux"), None);
+    }
+
+    #[test]
+    fn features_follow_opcodes_and_version() {
+        let all: Vec<u8> = (0..=op::LISTEN).collect();
+        let caps = IoCapabilities::from_opcodes(&all, Some(KernelVersion::new(6, 1, 0)));
+        assert!(caps.supports_multishot_accept && caps.supports_read_fixed);
+        assert!(caps.supports_link_timeout && caps.supports_async_cancel);
+
+        // ACCEPT is there, but not its multishot flag
+        let old = IoCapabilities::from_opcodes(&all, Some(KernelVersion::new(5, 15, 0)));
+        assert!(!old.supports_multishot_accept);
+        // Unknown version: try it and rely on the -EINVAL fallback
+        assert!(IoCapabilities::from_opcodes(&all, None).supports_multishot_accept);
+
+        let no_fixed: Vec<u8> = all.iter().copied().filter(|&o| o != op::WRITE_FIXED).collect();
+        let caps = IoCapabilities::from_opcodes(&no_fixed, None);
+        assert!(!caps.supports_read_fixed && caps.supports(op::READ_FIXED));
+        assert!(!IoCapabilities::from_opcodes(&[], None).supports_async_cancel);
+    }
+}
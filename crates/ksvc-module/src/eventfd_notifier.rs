@@ -40,6 +40,22 @@ impl EventFdNotifier {
     pub fn fd(&self) -> RawFd {
         self.fd
     }
+
+    /// Consumer side: read and reset the counter.
+    ///
+    /// Returns the notifications since the last drain, 0 if none (needs
+    /// an `EFD_NONBLOCK` eventfd, as `create()` makes).
+    pub fn drain(&self) -> u64 {
+        let mut val: u64 = 0;
+        let ret = unsafe {
+            libc::read(
+                self.fd,
+                &mut val as *mut u64 as *mut libc::c_void,
+                std::mem::size_of::<u64>(),
+            )
+        };
+        if ret < 0 { 0 } else { val }
+    }
 }
 
 impl Notifier for EventFdNotifier {
@@ -63,6 +79,10 @@ impl Notifier for EventFdNotifier {
         }
         Ok(())
     }
+
+    fn eventfd(&self) -> Option<i32> {
+        Some(self.fd)
+    }
 }
 
 impl Drop for EventFdNotifier {
@@ -4,9 +4,13 @@
 //! MPMC queue, execute the syscall via libc, and push results to a
 //! lock-free result queue. The dispatcher polls the result queue.
 //!
+//! Entries flagged `submit_flags::PRIORITY` wait in a second queue that
+//! workers check first, so a quick `setsockopt` doesn't sit behind a
+//! backlog of `fsync`s.
+//!
 //! No dynamic scaling. Simple, predictable, safe.
 
-use ksvc_core::entry::SubmitEntry;
+use ksvc_core::entry::{submit_flags, SubmitEntry};
 use ksvc_core::error::{KsvcError, Result};
 use ksvc_core::worker::{WorkerCompletion, WorkerPool};
 
@@ -15,16 +19,64 @@ use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::thread;
 
-/// A work item for the pool.
-#[derive(Clone, Copy)]
-struct WorkItem {
-    entry: SubmitEntry,
+/// Priority entries a worker takes in a row before letting one normal
+/// entry through, so a stream of them can't starve the rest.
+const PRIORITY_STREAK: u32 = 16;
+
+/// Work queue: dispatcher → workers. Two FIFO levels, priority first.
+/// Shared with the other worker pools in this crate.
+pub(crate) struct WorkQueues {
+    priority: ArrayQueue<SubmitEntry>,
+    normal: ArrayQueue<SubmitEntry>,
+}
+
+impl WorkQueues {
+    /// `depth`: max pending entries per level.
+    pub(crate) fn new(depth: usize) -> Self {
+        Self {
+            priority: ArrayQueue::new(depth),
+            normal: ArrayQueue::new(depth),
+        }
+    }
+
+    /// Queue `entry` on its level. Fails if that level is full.
+    pub(crate) fn push(&self, entry: &SubmitEntry) -> Result<()> {
+        let queue = if entry.flags & submit_flags::PRIORITY != 0 {
+            &self.priority
+        } else {
+            &self.normal
+        };
+        queue.push(*entry).map_err(|_| KsvcError::WorkerUnavailable)
+    }
+
+    /// Next entry for a worker. `streak` is the worker's count of
+    /// priority entries taken in a row.
+    pub(crate) fn pop(&self, streak: &mut u32) -> Option<SubmitEntry> {
+        if *streak < PRIORITY_STREAK {
+            if let Some(entry) = self.priority.pop() {
+                *streak += 1;
+                return Some(entry);
+            }
+        }
+        *streak = 0;
+        self.normal.pop().or_else(|| self.priority.pop())
+    }
+
+    #[cfg_attr(not(feature = "lazy-pool"), allow(dead_code))]
+    pub(crate) fn len(&self) -> usize {
+        self.priority.len() + self.normal.len()
+    }
+
+    #[cfg_attr(not(feature = "lazy-pool"), allow(dead_code))]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.priority.is_empty() && self.normal.is_empty()
+    }
 }
 
 /// Shared state between dispatcher and workers.
 struct PoolInner {
-    /// Work queue: dispatcher → workers.
-    work_queue: ArrayQueue<WorkItem>,
+    /// Work queues: dispatcher → workers.
+    work_queue: WorkQueues,
     /// Result queue: workers → dispatcher.
     result_queue: ArrayQueue<WorkerCompletion>,
     /// Number of workers currently executing a syscall.
@@ -45,9 +97,14 @@ impl FixedPool {
     ///
     /// `queue_depth`: max pending work items before enqueue fails.
     pub fn new(n: usize, queue_depth: usize) -> Self {
+        Self::named(n, queue_depth, "ksvc-worker")
+    }
+
+    /// Like `new()`, with threads named `{prefix}-{i}`.
+    pub(crate) fn named(n: usize, queue_depth: usize, prefix: &str) -> Self {
         let n = n.max(1).min(32);
         let inner = Arc::new(PoolInner {
-            work_queue: ArrayQueue::new(queue_depth),
+            work_queue: WorkQueues::new(queue_depth),
             result_queue: ArrayQueue::new(queue_depth),
             active: AtomicUsize::new(0),
             shutdown: AtomicBool::new(false),
@@ -58,7 +115,7 @@ impl FixedPool {
         for worker_id in 0..n {
             let inner = Arc::clone(&inner);
             let handle = thread::Builder::new()
-                .name(format!("ksvc-worker-{}", worker_id))
+                .name(format!("{}-{}", prefix, worker_id))
                 .spawn(move || worker_loop(inner, worker_id))
                 .expect("failed to spawn worker thread");
             handles.push(handle);
@@ -82,11 +139,7 @@ impl WorkerPool for FixedPool {
         if self.inner.shutdown.load(Ordering::Relaxed) {
             return Err(KsvcError::WorkerUnavailable);
         }
-        let item = WorkItem { entry: *entry };
-        self.inner
-            .work_queue
-            .push(item)
-            .map_err(|_| KsvcError::WorkerUnavailable)
+        self.inner.work_queue.push(entry)
     }
 
     fn poll_completions(&self, buf: &mut [WorkerCompletion], max: usize) -> usize {
@@ -132,31 +185,23 @@ impl Drop for FixedPool {
 
 /// Worker thread main loop.
 fn worker_loop(inner: Arc<PoolInner>, _worker_id: usize) {
+    let mut streak = 0;
     loop {
         if inner.shutdown.load(Ordering::Relaxed) {
             break;
         }
 
-        match inner.work_queue.pop() {
-            Some(item) => {
+        match inner.work_queue.pop(&mut streak) {
+            Some(entry) => {
                 inner.active.fetch_add(1, Ordering::Relaxed);
-                let result = execute_syscall(&item.entry);
+                let result = execute_syscall(&entry);
                 inner.active.fetch_sub(1, Ordering::Relaxed);
 
                 let completion = WorkerCompletion {
-                    corr_id: item.entry.corr_id,
+                    corr_id: entry.corr_id,
                     result,
                 };
-                // If result queue is full, we spin-retry briefly.
-                // This should be rare — the dispatcher drains it each loop.
-                let mut retries = 0;
-                while inner.result_queue.push(completion).is_err() {
-                    retries += 1;
-                    if retries > 1000 || inner.shutdown.load(Ordering::Relaxed) {
-                        break;
-                    }
-                    std::hint::spin_loop();
-                }
+                push_result(&inner.result_queue, completion, &inner.shutdown);
             }
             None => {
                 // No work available — brief sleep to avoid busy-wait.
@@ -167,10 +212,30 @@ fn worker_loop(inner: Arc<PoolInner>, _worker_id: usize) {
     }
 }
 
+/// Hand a completion to the dispatcher.
+/// Shared with the other worker pools in this crate.
+pub(crate) fn push_result(
+    result_queue: &ArrayQueue<WorkerCompletion>,
+    completion: WorkerCompletion,
+    shutdown: &AtomicBool,
+) {
+    // If result queue is full, we spin-retry briefly.
+    // This should be rare — the dispatcher drains it each loop.
+    let mut retries = 0;
+    while result_queue.push(completion).is_err() {
+        retries += 1;
+        if retries > 1000 || shutdown.load(Ordering::Relaxed) {
+            break;
+        }
+        std::hint::spin_loop();
+    }
+}
+
 /// Execute a Tier 2 syscall via libc::syscall.
 ///
 /// This runs on a worker thread — it MAY block. That's the point.
-fn execute_syscall(entry: &SubmitEntry) -> i64 {
+/// Shared with the other worker pools in this crate.
+pub(crate) fn execute_syscall(entry: &SubmitEntry) -> i64 {
     let a = &entry.args;
     // Safety: we're making a raw syscall with the provided arguments.
     // The caller (GVThread) is responsible for argument validity.
@@ -194,3 +259,41 @@ fn execute_syscall(entry: &SubmitEntry) -> i64 {
         ret as i64
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ksvc_core::entry::CorrId;
+
+    fn entry(id: u64, flags: u32) -> SubmitEntry {
+        SubmitEntry { corr_id: CorrId(id), syscall_nr: 0, flags, args: [0; 6] }
+    }
+
+    fn drain(queues: &WorkQueues) -> Vec<u64> {
+        let mut streak = 0;
+        std::iter::from_fn(|| queues.pop(&mut streak)).map(|e| e.corr_id.0).collect()
+    }
+
+    #[test]
+    fn priority_entries_jump_ahead_fifo_otherwise() {
+        let queues = WorkQueues::new(8);
+        for (id, flags) in [(1, 0), (2, 0), (3, submit_flags::PRIORITY), (4, 0), (5, submit_flags::PRIORITY)] {
+            queues.push(&entry(id, flags)).unwrap();
+        }
+        assert_eq!(queues.len(), 5);
+        assert_eq!(drain(&queues), [3, 5, 1, 2, 4]);
+        assert!(queues.is_empty());
+    }
+
+    #[test]
+    fn priority_stream_lets_normal_entries_through() {
+        let queues = WorkQueues::new(64);
+        queues.push(&entry(100, 0)).unwrap();
+        for id in 0..20 {
+            queues.push(&entry(id, submit_flags::PRIORITY)).unwrap();
+        }
+        let order = drain(&queues);
+        assert_eq!(order.iter().position(|&id| id == 100), Some(PRIORITY_STREAK as usize));
+        assert_eq!(order.len(), 21);
+    }
+}
@@ -0,0 +1,181 @@
+//! `FutexNotifier` — futex-based `Notifier` implementation.
+//!
+//! Wakes the userspace completion handler through a shared `AtomicU32`
+//! futex word instead of an eventfd. No fd per instance, and a burst of
+//! `notify()` calls costs one `FUTEX_WAKE` at most.
+//!
+//! Word protocol:
+//!   0 = idle (consumer drained, may be sleeping in `wait()`)
+//!   1 = notified (completions pending)
+//!
+//! `notify()`: if the word is already 1 this is a plain load — coalesced.
+//! Otherwise swap to 1 and `FUTEX_WAKE` one waiter.
+//! `wait()`: swap to 0; if it was 1 return immediately, else `FUTEX_WAIT`.
+//!
+//! The word normally lives in the completion ring header
+//! (`KsvcRingHeader::_reserved[0]`, offset `RING_FUTEX_OFFSET`) so the
+//! consumer needs nothing but the mmap'd ring.
+
+use ksvc_core::error::{KsvcError, Result};
+use ksvc_core::notifier::Notifier;
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+/// Byte offset of the futex word in the completion ring header.
+pub const RING_FUTEX_OFFSET: usize = 32;
+
+const IDLE: u32 = 0;
+const NOTIFIED: u32 = 1;
+
+pub struct FutexNotifier {
+    word: *const AtomicU32,
+    /// Backing storage when not placed in a ring header.
+    _owned: Option<Box<AtomicU32>>,
+}
+
+// Safety: the word is only accessed atomically, and the pointee is
+// either owned by us or guaranteed live by the `from_ring` caller.
+unsafe impl Send for FutexNotifier {}
+unsafe impl Sync for FutexNotifier {}
+
+impl FutexNotifier {
+    /// Create a notifier with its own futex word (not shared via mmap).
+    pub fn new() -> Self {
+        let owned = Box::new(AtomicU32::new(IDLE));
+        Self {
+            word: &*owned as *const AtomicU32,
+            _owned: Some(owned),
+        }
+    }
+
+    /// Create a notifier whose futex word lives in a completion ring header.
+    ///
+    /// # Safety
+    /// - `base` must point to a valid KSVC ring header (≥ 64 bytes,
+    ///   4-byte aligned at `RING_FUTEX_OFFSET`).
+    /// - The memory must remain valid for the lifetime of this notifier.
+    pub unsafe fn from_ring(base: *mut u8) -> Self {
+        let word = base.add(RING_FUTEX_OFFSET) as *const AtomicU32;
+        Self { word, _owned: None }
+    }
+
+    /// The futex word (for a consumer in another component).
+    #[inline]
+    pub fn word(&self) -> &AtomicU32 {
+        unsafe { &*self.word }
+    }
+
+    /// Consumer side: wait until notified or `timeout` elapses.
+    ///
+    /// Consumes the pending notification. Returns `true` if one was
+    /// pending (or arrived), `false` on timeout. Callers must drain the
+    /// ring after a `true` return — further notifies during the drain
+    /// set the word again and are not lost.
+    pub fn wait(&self, timeout: Option<Duration>) -> bool {
+        let word = self.word();
+        if word.swap(IDLE, Ordering::AcqRel) == NOTIFIED {
+            return true;
+        }
+
+        let ts = timeout.map(|d| libc::timespec {
+            tv_sec: d.as_secs() as libc::time_t,
+            tv_nsec: d.subsec_nanos() as libc::c_long,
+        });
+        let ts_ptr = match &ts {
+            Some(t) => t as *const libc::timespec,
+            None => std::ptr::null(),
+        };
+
+        // Sleep only while the word is still IDLE. Not FUTEX_PRIVATE:
+        // the word may be shared across processes via mmap.
+        unsafe {
+            libc::syscall(
+                libc::SYS_futex,
+                word.as_ptr(),
+                libc::FUTEX_WAIT,
+                IDLE,
+                ts_ptr,
+                std::ptr::null::<u32>(),
+                0u32,
+            );
+        }
+
+        word.swap(IDLE, Ordering::AcqRel) == NOTIFIED
+    }
+}
+
+impl Default for FutexNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Notifier for FutexNotifier {
+    fn notify(&self) -> Result<()> {
+        let word = self.word();
+        // Already notified and not yet consumed — coalesce.
+        if word.load(Ordering::Acquire) == NOTIFIED {
+            return Ok(());
+        }
+        if word.swap(NOTIFIED, Ordering::AcqRel) == NOTIFIED {
+            return Ok(());
+        }
+        let ret = unsafe {
+            libc::syscall(
+                libc::SYS_futex,
+                word.as_ptr(),
+                libc::FUTEX_WAKE,
+                1i32,
+                std::ptr::null::<libc::timespec>(),
+                std::ptr::null::<u32>(),
+                0u32,
+            )
+        };
+        if ret < 0 {
+            return Err(KsvcError::Os(unsafe { *libc::__errno_location() }));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn notify_then_wait_returns_immediately() {
+        let n = FutexNotifier::new();
+        n.notify().unwrap();
+        n.notify().unwrap(); // coalesced
+        assert_eq!(n.word().load(Ordering::Relaxed), NOTIFIED);
+        assert!(n.wait(Some(Duration::from_secs(1))));
+        assert_eq!(n.word().load(Ordering::Relaxed), IDLE);
+    }
+
+    #[test]
+    fn wait_times_out() {
+        let n = FutexNotifier::new();
+        assert!(!n.wait(Some(Duration::from_millis(10))));
+    }
+
+    #[test]
+    fn cross_thread_wake() {
+        let n = Arc::new(FutexNotifier::new());
+        let n2 = Arc::clone(&n);
+        let h = thread::spawn(move || n2.wait(Some(Duration::from_secs(10))));
+        thread::sleep(Duration::from_millis(20));
+        n.notify().unwrap();
+        assert!(h.join().unwrap());
+    }
+
+    #[test]
+    fn from_ring_uses_header_word() {
+        let mut header = [0u64; 8];
+        let n = unsafe { FutexNotifier::from_ring(header.as_mut_ptr() as *mut u8) };
+        n.notify().unwrap();
+        assert_eq!(header[RING_FUTEX_OFFSET / 8] as u32, NOTIFIED);
+    }
+}
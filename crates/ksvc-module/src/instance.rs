@@ -2,7 +2,9 @@
 //!
 //! This is the "dependency injection" point. All trait impls are
 //! generic parameters with defaults. To swap an implementation,
-//! change the type alias — no other code changes needed.
+//! change the type alias — no other code changes needed — or hand it
+//! to `InstanceBuilder`, which builds any `KsvcInstance` the
+//! dispatcher can run.
 //!
 //! ```text
 //! KsvcInstance<
@@ -22,6 +24,7 @@ use ksvc_core::buffer::BufferProvider;
 use ksvc_core::error::Result;
 
 use crate::basic_iouring::{BasicIoUring, BasicIoUringConfig};
+use crate::capabilities::{IoCapabilities, KernelVersion};
 use crate::eventfd_notifier::EventFdNotifier;
 use crate::fixed_pool::FixedPool;
 use crate::heap_buffers::HeapBuffers;
@@ -44,7 +47,7 @@ where
     pub worker_pool: W,
     pub notifier: N,
     pub buffer_provider: P,
-    /// The eventfd for waking userspace.
+    /// The eventfd for waking userspace, or -1 if the notifier has none.
     pub eventfd_raw: i32,
 }
 
@@ -57,23 +60,77 @@ pub type DefaultInstance = KsvcInstance<
     HeapBuffers,
 >;
 
-/// Builder for constructing a default KSVC instance.
-///
-/// Each component can be overridden before building.
-pub struct InstanceBuilder {
+/// Settings the builder's default components are made from.
+struct Settings {
     sq_entries: u32,
     worker_count: usize,
     worker_queue_depth: usize,
     buffer_size: usize,
 }
 
+/// A component handed to the builder, or how to make the default one.
+type Make<T> = Box<dyn FnOnce(&Settings) -> Result<T>>;
+
+/// The router, made once the io backend's capabilities are known.
+type MakeRouter<R> = Box<dyn FnOnce(&IoCapabilities) -> R>;
+
+/// Builder for constructing a KSVC instance.
+///
+/// `InstanceBuilder::new()` builds a `DefaultInstance`. Each component
+/// can be swapped for another implementation before building, which
+/// changes the instance's type accordingly:
+///
+/// ```ignore
+/// let inst = InstanceBuilder::new()
+///     .io_backend(SqpollIoUring::new(config)?)
+///     .notifier(FutexNotifier::new())
+///     .build()?; // KsvcInstance<ProbeRouter, SqpollIoUring, FixedPool, FutexNotifier, HeapBuffers>
+/// ```
+///
+/// The sizing options only apply to the default components: a supplied
+/// io backend ignores `sq_entries`, a supplied worker pool ignores
+/// `worker_count` and `worker_queue_depth`, and supplied buffers ignore
+/// `buffer_size`.
+pub struct InstanceBuilder<
+    R = ProbeRouter,
+    B = BasicIoUring,
+    W = FixedPool,
+    N = EventFdNotifier,
+    P = HeapBuffers,
+> {
+    settings: Settings,
+    router: MakeRouter<R>,
+    io_backend: Make<B>,
+    worker_pool: Make<W>,
+    notifier: Make<N>,
+    buffer_provider: Make<P>,
+}
+
 impl Default for InstanceBuilder {
     fn default() -> Self {
         Self {
-            sq_entries: 256,
-            worker_count: 0, // 0 = auto
-            worker_queue_depth: 256,
-            buffer_size: 8192,
+            settings: Settings {
+                sq_entries: 256,
+                worker_count: 0, // 0 = auto
+                worker_queue_depth: 256,
+                buffer_size: 8192,
+            },
+            router: Box::new(ProbeRouter::from_capabilities),
+            io_backend: Box::new(|s| {
+                BasicIoUring::new(BasicIoUringConfig {
+                    sq_entries: s.sq_entries,
+                    ..Default::default()
+                })
+            }),
+            worker_pool: Box::new(|s| {
+                Ok(if s.worker_count == 0 {
+                    FixedPool::auto_sized(s.worker_queue_depth)
+                } else {
+                    FixedPool::new(s.worker_count, s.worker_queue_depth)
+                })
+            }),
+            notifier: Box::new(|_| EventFdNotifier::create()),
+            buffer_provider: Box::new(|s| Ok(HeapBuffers::new(s.buffer_size))),
         }
     }
 }
@@ -82,28 +139,102 @@ impl InstanceBuilder {
     pub fn new() -> Self {
         Self::default()
     }
+}
 
+impl<R, B, W, N, P> InstanceBuilder<R, B, W, N, P>
+where
+    R: SyscallRouter,
+    B: IoBackend,
+    W: WorkerPool,
+    N: Notifier,
+    P: BufferProvider,
+{
     pub fn sq_entries(mut self, n: u32) -> Self {
-        self.sq_entries = n;
+        self.settings.sq_entries = n;
         self
     }
 
     pub fn worker_count(mut self, n: usize) -> Self {
-        self.worker_count = n;
+        self.settings.worker_count = n;
         self
     }
 
     pub fn worker_queue_depth(mut self, n: usize) -> Self {
-        self.worker_queue_depth = n;
+        self.settings.worker_queue_depth = n;
         self
     }
 
     pub fn buffer_size(mut self, n: usize) -> Self {
-        self.buffer_size = n;
+        self.settings.buffer_size = n;
         self
     }
 
-    /// Build the default instance.
+    /// Use `router` instead of probing the io backend for a `ProbeRouter`.
+    pub fn router<R2: SyscallRouter + 'static>(self, router: R2) -> InstanceBuilder<R2, B, W, N, P> {
+        InstanceBuilder {
+            settings: self.settings,
+            router: Box::new(move |_| router),
+            io_backend: self.io_backend,
+            worker_pool: self.worker_pool,
+            notifier: self.notifier,
+            buffer_provider: self.buffer_provider,
+        }
+    }
+
+    /// Use `io_backend` instead of a `BasicIoUring`.
+    pub fn io_backend<B2: IoBackend + 'static>(self, io_backend: B2) -> InstanceBuilder<R, B2, W, N, P> {
+        InstanceBuilder {
+            settings: self.settings,
+            router: self.router,
+            io_backend: Box::new(move |_| Ok(io_backend)),
+            worker_pool: self.worker_pool,
+            notifier: self.notifier,
+            buffer_provider: self.buffer_provider,
+        }
+    }
+
+    /// Use `worker_pool` instead of a `FixedPool`.
+    pub fn worker_pool<W2: WorkerPool + 'static>(self, worker_pool: W2) -> InstanceBuilder<R, B, W2, N, P> {
+        InstanceBuilder {
+            settings: self.settings,
+            router: self.router,
+            io_backend: self.io_backend,
+            worker_pool: Box::new(move |_| Ok(worker_pool)),
+            notifier: self.notifier,
+            buffer_provider: self.buffer_provider,
+        }
+    }
+
+    /// Use `notifier` instead of an `EventFdNotifier`.
+    ///
+    /// The instance's `eventfd_raw` is -1 unless the notifier has an eventfd.
+    pub fn notifier<N2: Notifier + 'static>(self, notifier: N2) -> InstanceBuilder<R, B, W, N2, P> {
+        InstanceBuilder {
+            settings: self.settings,
+            router: self.router,
+            io_backend: self.io_backend,
+            worker_pool: self.worker_pool,
+            notifier: Box::new(move |_| Ok(notifier)),
+            buffer_provider: self.buffer_provider,
+        }
+    }
+
+    /// Use `buffer_provider` instead of `HeapBuffers`.
+    pub fn buffer_provider<P2: BufferProvider + 'static>(
+        self,
+        buffer_provider: P2,
+    ) -> InstanceBuilder<R, B, W, N, P2> {
+        InstanceBuilder {
+            settings: self.settings,
+            router: self.router,
+            io_backend: self.io_backend,
+            worker_pool: self.worker_pool,
+            notifier: self.notifier,
+            buffer_provider: Box::new(move |_| Ok(buffer_provider)),
+        }
+    }
+
+    /// Build the instance.
     ///
     /// 1. Creates io_uring ring
     /// 2. Probes supported opcodes
@@ -111,39 +242,36 @@ impl InstanceBuilder {
     /// 4. Spawns worker pool
     /// 5. Creates eventfd notifier
     /// 6. Creates buffer provider
-    pub fn build(self) -> Result<DefaultInstance> {
+    ///
+    /// Components handed to the builder are used as they are.
+    pub fn build(self) -> Result<KsvcInstance<R, B, W, N, P>> {
+        let settings = &self.settings;
+
         // 1. io_uring
-        let io_backend = BasicIoUring::new(BasicIoUringConfig {
-            sq_entries: self.sq_entries,
-            ..Default::default()
-        })?;
+        let io_backend = (self.io_backend)(settings)?;
 
-        // 2. Probe opcodes from the running kernel
-        let supported = io_backend.probe_opcodes();
+        // 2. Probe what the backend supports on the running kernel
+        let caps = IoCapabilities::from_opcodes(&io_backend.probe_opcodes(), KernelVersion::running());
 
         // 3. Build routing table
-        let router = ProbeRouter::new(&supported);
+        let router = (self.router)(&caps);
 
         // Log tier counts for diagnostics
         let counts = router.tier_counts();
         eprintln!(
-            "ksvc: routing table built — T0:{} T1:{} T2:{} T3:{}",
-            counts.tier0, counts.tier1, counts.tier2, counts.tier3
+            "ksvc: routing table built — T0:{} T1:{} T2:{} T3:{} ({})",
+            counts.tier0, counts.tier1, counts.tier2, counts.tier3, caps
         );
 
         // 4. Worker pool
-        let worker_pool = if self.worker_count == 0 {
-            FixedPool::auto_sized(self.worker_queue_depth)
-        } else {
-            FixedPool::new(self.worker_count, self.worker_queue_depth)
-        };
+        let worker_pool = (self.worker_pool)(settings)?;
 
-        // 5. Eventfd notifier
-        let notifier = EventFdNotifier::create()?;
-        let eventfd_raw = notifier.fd();
+        // 5. Notifier
+        let notifier = (self.notifier)(settings)?;
+        let eventfd_raw = notifier.eventfd().unwrap_or(-1);
 
         // 6. Buffer provider
-        let buffer_provider = HeapBuffers::new(self.buffer_size);
+        let buffer_provider = (self.buffer_provider)(settings)?;
 
         Ok(KsvcInstance {
             router,
@@ -178,3 +306,32 @@ where
         eprintln!("ksvc: instance shut down cleanly");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::futex_notifier::FutexNotifier;
+    use crate::static_router::StaticRouter;
+    use ksvc_core::tier::Tier;
+
+    #[test]
+    fn builder_swaps_components() {
+        let default = InstanceBuilder::new().sq_entries(8).worker_count(1).build().unwrap();
+        assert!(default.eventfd_raw >= 0);
+        drop(default);
+
+        let inst: KsvcInstance<StaticRouter, BasicIoUring, FixedPool, FutexNotifier, HeapBuffers> =
+            InstanceBuilder::new()
+                .sq_entries(8)
+                .worker_count(1)
+                .router(StaticRouter::kernel_6_8())
+                .notifier(FutexNotifier::new())
+                .build()
+                .unwrap();
+        assert_eq!(inst.eventfd_raw, -1);
+        assert_eq!(inst.worker_pool.total_workers(), 1);
+        assert!(matches!(inst.router.route(libc::SYS_read as u32).tier, Tier::IoUring));
+        inst.notifier.notify().unwrap();
+        assert!(inst.notifier.wait(Some(std::time::Duration::ZERO)));
+    }
+}
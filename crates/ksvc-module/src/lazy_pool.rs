@@ -0,0 +1,317 @@
+//! `LazyPool` — on-demand `WorkerPool` (feature = "lazy-pool").
+//!
+//! Same queues and execute path as `FixedPool`, but threads come and go
+//! with the load:
+//!
+//! - Starts with `min_workers` threads (default 0).
+//! - `enqueue()` spawns one more thread (up to `max_workers`) when more
+//!   than `spawn_threshold` items are waiting.
+//! - A thread idle for `idle_timeout` exits, as long as `min_workers`
+//!   remain.
+//!
+//! A mostly idle instance costs no threads at all. Spawning happens on
+//! the enqueuing thread: a `clone()`, not a wait on other work.
+
+use ksvc_core::entry::SubmitEntry;
+use ksvc_core::error::{KsvcError, Result};
+use ksvc_core::worker::{WorkerCompletion, WorkerPool};
+
+use crate::fixed_pool::{execute_syscall, push_result, WorkQueues};
+
+use crossbeam_queue::ArrayQueue;
+use std::sync::atomic::{fence, AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Configuration for `LazyPool`.
+#[derive(Debug, Clone)]
+pub struct LazyPoolConfig {
+    /// Threads kept alive even when idle (spawned at creation).
+    pub min_workers: usize,
+    /// Upper bound on live threads.
+    pub max_workers: usize,
+    /// Spawn a thread when more than this many items are queued.
+    /// With 0, any queued item finding no spare thread spawns one.
+    pub spawn_threshold: usize,
+    /// Idle time after which a thread above `min_workers` exits.
+    pub idle_timeout: Duration,
+    /// Max pending work items (per priority level) before enqueue fails.
+    pub queue_depth: usize,
+}
+
+impl Default for LazyPoolConfig {
+    fn default() -> Self {
+        let cpus = thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(4);
+        Self {
+            min_workers: 0,
+            max_workers: (cpus / 2).clamp(2, 8),
+            spawn_threshold: 0,
+            idle_timeout: Duration::from_secs(5),
+            queue_depth: 1024,
+        }
+    }
+}
+
+/// Shared state between dispatcher and workers.
+struct PoolInner {
+    /// Work queues: dispatcher → workers (priority level first).
+    work_queue: WorkQueues,
+    /// Result queue: workers → dispatcher.
+    result_queue: ArrayQueue<WorkerCompletion>,
+    /// Number of workers currently executing a syscall.
+    active: AtomicUsize,
+    /// Live worker threads (including ones about to start).
+    live: AtomicUsize,
+    /// Threads spawned so far (for naming).
+    spawned: AtomicUsize,
+    /// Shutdown flag.
+    shutdown: AtomicBool,
+    min_workers: usize,
+    max_workers: usize,
+    idle_timeout: Duration,
+}
+
+impl PoolInner {
+    /// Reserve a thread slot below `max_workers`.
+    fn try_reserve(&self) -> bool {
+        self.live
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n < self.max_workers).then_some(n + 1)
+            })
+            .is_ok()
+    }
+
+    /// Give back a thread slot unless that would drop below `min_workers`.
+    fn try_retire(&self) -> bool {
+        self.live
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n > self.min_workers).then_some(n - 1)
+            })
+            .is_ok()
+    }
+}
+
+pub struct LazyPool {
+    inner: Arc<PoolInner>,
+    spawn_threshold: usize,
+}
+
+impl LazyPool {
+    pub fn new(config: LazyPoolConfig) -> Self {
+        let max_workers = config.max_workers.clamp(1, 32);
+        let inner = Arc::new(PoolInner {
+            work_queue: WorkQueues::new(config.queue_depth),
+            result_queue: ArrayQueue::new(config.queue_depth),
+            active: AtomicUsize::new(0),
+            live: AtomicUsize::new(0),
+            spawned: AtomicUsize::new(0),
+            shutdown: AtomicBool::new(false),
+            min_workers: config.min_workers.min(max_workers),
+            max_workers,
+            idle_timeout: config.idle_timeout,
+        });
+
+        for _ in 0..inner.min_workers {
+            if inner.try_reserve() {
+                spawn_worker(&inner);
+            }
+        }
+
+        LazyPool { inner, spawn_threshold: config.spawn_threshold }
+    }
+
+    /// Spawn a thread if the queue is deeper than the threshold and
+    /// every live thread is busy (or there are none).
+    fn maybe_grow(&self) {
+        let inner = &self.inner;
+        let live = inner.live.load(Ordering::SeqCst);
+        let idle = live.saturating_sub(inner.active.load(Ordering::Relaxed));
+        let backlog = inner.work_queue.len();
+        let wanted = live == 0 || (backlog > self.spawn_threshold && backlog > idle);
+        if wanted && inner.try_reserve() {
+            spawn_worker(inner);
+        }
+    }
+}
+
+impl WorkerPool for LazyPool {
+    fn enqueue(&self, entry: &SubmitEntry) -> Result<()> {
+        if self.inner.shutdown.load(Ordering::Relaxed) {
+            return Err(KsvcError::WorkerUnavailable);
+        }
+        self.inner.work_queue.push(entry)?;
+        // Pairs with the fence in worker_loop: either we see a retiring
+        // worker gone (and spawn), or it sees our item (and stays)
+        fence(Ordering::SeqCst);
+        self.maybe_grow();
+        Ok(())
+    }
+
+    fn poll_completions(&self, buf: &mut [WorkerCompletion], max: usize) -> usize {
+        let mut count = 0;
+        while count < max && count < buf.len() {
+            match self.inner.result_queue.pop() {
+                Some(comp) => {
+                    buf[count] = comp;
+                    count += 1;
+                }
+                None => break,
+            }
+        }
+        count
+    }
+
+    fn active_workers(&self) -> usize {
+        self.inner.active.load(Ordering::Relaxed)
+    }
+
+    fn total_workers(&self) -> usize {
+        self.inner.live.load(Ordering::Relaxed)
+    }
+
+    fn max_workers(&self) -> usize {
+        self.inner.max_workers
+    }
+
+    fn shutdown(&self) {
+        self.inner.shutdown.store(true, Ordering::SeqCst);
+        // Workers will see the flag and exit after current work
+    }
+}
+
+impl Drop for LazyPool {
+    fn drop(&mut self) {
+        self.inner.shutdown.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Start a worker for a slot already reserved with `try_reserve`.
+fn spawn_worker(inner: &Arc<PoolInner>) {
+    let id = inner.spawned.fetch_add(1, Ordering::Relaxed);
+    let worker = Arc::clone(inner);
+    let spawned = thread::Builder::new()
+        .name(format!("ksvc-lazy-{}", id))
+        .spawn(move || worker_loop(worker));
+    if spawned.is_err() {
+        // Out of threads: the queued work waits for the live ones
+        inner.live.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Worker thread main loop: like `FixedPool`'s, plus idle retirement.
+fn worker_loop(inner: Arc<PoolInner>) {
+    let mut idle_since = Instant::now();
+    let mut streak = 0;
+    loop {
+        if inner.shutdown.load(Ordering::Relaxed) {
+            inner.live.fetch_sub(1, Ordering::SeqCst);
+            break;
+        }
+
+        match inner.work_queue.pop(&mut streak) {
+            Some(entry) => {
+                inner.active.fetch_add(1, Ordering::Relaxed);
+                let result = execute_syscall(&entry);
+                inner.active.fetch_sub(1, Ordering::Relaxed);
+
+                let completion = WorkerCompletion { corr_id: entry.corr_id, result };
+                push_result(&inner.result_queue, completion, &inner.shutdown);
+                idle_since = Instant::now();
+            }
+            None => {
+                if idle_since.elapsed() >= inner.idle_timeout && inner.try_retire() {
+                    fence(Ordering::SeqCst);
+                    // An enqueue that still counted us may have skipped
+                    // spawning: stay if its item is here
+                    if inner.work_queue.is_empty() || !inner.try_reserve() {
+                        break;
+                    }
+                    idle_since = Instant::now();
+                    continue;
+                }
+                thread::park_timeout(Duration::from_millis(1));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ksvc_core::entry::CorrId;
+
+    fn getpid(id: u64) -> SubmitEntry {
+        SubmitEntry { corr_id: CorrId(id), syscall_nr: libc::SYS_getpid as u32, flags: 0, args: [0; 6] }
+    }
+
+    /// Poll until `n` completions arrived (or 5 s passed).
+    fn collect(pool: &LazyPool, n: usize) -> Vec<WorkerCompletion> {
+        let mut out = Vec::new();
+        let mut buf = [WorkerCompletion { corr_id: CorrId(0), result: 0 }; 16];
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while out.len() < n && Instant::now() < deadline {
+            let k = pool.poll_completions(&mut buf, 16);
+            out.extend_from_slice(&buf[..k]);
+            thread::sleep(Duration::from_millis(1));
+        }
+        out
+    }
+
+    fn wait_for(mut cond: impl FnMut() -> bool) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !cond() {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        true
+    }
+
+    #[test]
+    fn starts_empty_and_spawns_on_demand() {
+        let pool = LazyPool::new(LazyPoolConfig { max_workers: 4, ..Default::default() });
+        assert_eq!(pool.total_workers(), 0);
+
+        pool.enqueue(&getpid(7)).unwrap();
+        let done = collect(&pool, 1);
+        assert_eq!(done.len(), 1);
+        assert_eq!(done[0].corr_id, CorrId(7));
+        assert_eq!(done[0].result, std::process::id() as i64);
+        assert_eq!(pool.total_workers(), 1);
+    }
+
+    #[test]
+    fn grows_to_max_under_blocking_load_then_reaps_to_min() {
+        static NAP: libc::timespec = libc::timespec { tv_sec: 0, tv_nsec: 20_000_000 };
+        let pool = LazyPool::new(LazyPoolConfig {
+            min_workers: 1,
+            max_workers: 3,
+            idle_timeout: Duration::from_millis(50),
+            ..Default::default()
+        });
+        assert_eq!(pool.total_workers(), 1);
+
+        for i in 0..12 {
+            let nap = SubmitEntry {
+                corr_id: CorrId(i),
+                syscall_nr: libc::SYS_nanosleep as u32,
+                flags: 0,
+                args: [&NAP as *const _ as u64, 0, 0, 0, 0, 0],
+            };
+            pool.enqueue(&nap).unwrap();
+        }
+        assert_eq!(pool.total_workers(), 3);
+        let done = collect(&pool, 12);
+        assert_eq!(done.len(), 12);
+        assert!(done.iter().all(|c| c.result == 0));
+
+        assert!(wait_for(|| pool.total_workers() == 1), "idle workers not reaped");
+        // The survivor still serves work
+        pool.enqueue(&getpid(99)).unwrap();
+        assert_eq!(collect(&pool, 1).len(), 1);
+    }
+}
@@ -0,0 +1,139 @@
+//! `LegacyThreadPool` — `LegacyExecutor` backed by blocking OS threads.
+//!
+//! Tier 3 syscalls have no io_uring opcode and aren't on the worker
+//! pool's list, but `libc::syscall(nr, args...)` still runs them. This
+//! shim reuses `FixedPool`'s machinery (same queues, same execute path)
+//! on a separate, small set of threads so legacy traffic can't starve
+//! Tier 2 workers.
+//!
+//! Syscalls that act on the calling thread or process as a whole (exit,
+//! clone/fork, exec, signal return, TID address) would hit the pool
+//! thread instead of the GVThread, so `submit()` refuses them.
+
+use ksvc_core::entry::SubmitEntry;
+use ksvc_core::error::{KsvcError, Result};
+use ksvc_core::legacy::LegacyExecutor;
+use ksvc_core::worker::{WorkerCompletion, WorkerPool};
+
+use crate::fixed_pool::FixedPool;
+
+/// Syscalls never run on a pool thread: `submit()` answers
+/// `Unsupported` (→ -ENOSYS) instead.
+#[cfg(target_arch = "x86_64")]
+const DENIED: &[libc::c_long] = &[
+    libc::SYS_exit,
+    libc::SYS_exit_group,
+    libc::SYS_clone,
+    libc::SYS_clone3,
+    libc::SYS_fork,
+    libc::SYS_vfork,
+    libc::SYS_execve,
+    libc::SYS_execveat,
+    libc::SYS_rt_sigreturn,
+    libc::SYS_set_tid_address,
+];
+
+/// Syscalls never run on a pool thread (aarch64 has no fork/vfork).
+#[cfg(not(target_arch = "x86_64"))]
+const DENIED: &[libc::c_long] = &[
+    libc::SYS_exit,
+    libc::SYS_exit_group,
+    libc::SYS_clone,
+    libc::SYS_clone3,
+    libc::SYS_execve,
+    libc::SYS_execveat,
+    libc::SYS_rt_sigreturn,
+    libc::SYS_set_tid_address,
+];
+
+pub struct LegacyThreadPool {
+    pool: FixedPool,
+}
+
+impl LegacyThreadPool {
+    /// Create a legacy executor with `n` blocking threads.
+    ///
+    /// `queue_depth`: max pending syscalls before `submit()` fails.
+    pub fn new(n: usize, queue_depth: usize) -> Self {
+        Self { pool: FixedPool::named(n, queue_depth, "ksvc-legacy") }
+    }
+}
+
+impl LegacyExecutor for LegacyThreadPool {
+    fn submit(&self, entry: &SubmitEntry) -> Result<()> {
+        if DENIED.contains(&(entry.syscall_nr as libc::c_long)) {
+            return Err(KsvcError::Unsupported(entry.syscall_nr));
+        }
+        self.pool.enqueue(entry)
+    }
+
+    fn poll_completions(&self, buf: &mut [WorkerCompletion], max: usize) -> usize {
+        self.pool.poll_completions(buf, max)
+    }
+
+    fn shutdown(&self) {
+        self.pool.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ksvc_core::entry::CorrId;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    fn entry(id: u64, nr: libc::c_long, args: [u64; 6]) -> SubmitEntry {
+        SubmitEntry { corr_id: CorrId(id), syscall_nr: nr as u32, flags: 0, args }
+    }
+
+    /// Poll until a completion arrives (or 5 s passed).
+    fn collect_one(pool: &LegacyThreadPool) -> Option<WorkerCompletion> {
+        let mut buf = [WorkerCompletion { corr_id: CorrId(0), result: 0 }; 1];
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            if pool.poll_completions(&mut buf, 1) == 1 {
+                return Some(buf[0]);
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        None
+    }
+
+    #[test]
+    fn runs_a_harmless_syscall() {
+        let pool = LegacyThreadPool::new(1, 4);
+        pool.submit(&entry(7, libc::SYS_getpid, [0; 6])).unwrap();
+        let done = collect_one(&pool).expect("no completion");
+        assert_eq!(done.corr_id, CorrId(7));
+        assert_eq!(done.result, std::process::id() as i64);
+        pool.shutdown();
+    }
+
+    #[test]
+    fn refuses_thread_and_process_syscalls() {
+        let pool = LegacyThreadPool::new(1, 4);
+        for &nr in DENIED {
+            let err = pool.submit(&entry(1, nr, [0; 6])).unwrap_err();
+            assert!(matches!(err, KsvcError::Unsupported(n) if n == nr as u32));
+        }
+        // Nothing reached the pool thread
+        thread::sleep(Duration::from_millis(20));
+        let mut buf = [WorkerCompletion { corr_id: CorrId(0), result: 0 }; 4];
+        assert_eq!(pool.poll_completions(&mut buf, 4), 0);
+        pool.shutdown();
+    }
+
+    #[test]
+    fn full_queue_is_an_error() {
+        static NAP: libc::timespec = libc::timespec { tv_sec: 0, tv_nsec: 200_000_000 };
+        let pool = LegacyThreadPool::new(1, 2);
+        let nap = |id| entry(id, libc::SYS_nanosleep, [&NAP as *const _ as u64, 0, 0, 0, 0, 0]);
+
+        // The one thread naps on the first (or not yet): a queue of two
+        // is full by the fourth submission either way
+        let refused = (0..4).map(|i| pool.submit(&nap(i))).filter(Result::is_err).count();
+        assert!(refused >= 1);
+        pool.shutdown();
+    }
+}
@@ -9,19 +9,30 @@
 //! | Trait           | Default Impl       | Feature-gated alternative |
 //! |-----------------|--------------------|---------------------------|
 //! | IoBackend       | BasicIoUring       | SqpollIoUring (sqpoll)    |
-//! | WorkerPool      | FixedPool          | LazyPool (future)         |
+//! | WorkerPool      | FixedPool          | LazyPool (lazy-pool)      |
 //! | CompletionSink  | RingCompletionSink | DirectWakeSink (future)   |
-//! | Notifier        | EventFdNotifier    | FutexNotifier (future)    |
+//! | Notifier        | EventFdNotifier    | FutexNotifier             |
 //! | BufferProvider  | HeapBuffers        | RegisteredBuffers (fixed) |
 //! | SyscallRouter   | ProbeRouter        | StaticRouter (compile)    |
 //! | SharedPage      | MmapSharedPage     | CachedSharedPage (future) |
+//! | LegacyExecutor  | NoLegacy (core)    | LegacyThreadPool          |
 
 pub mod basic_iouring;
+#[cfg(feature = "sqpoll")]
+pub mod sqpoll_iouring;
 pub mod probe_router;
+pub mod capabilities;
+pub mod static_router;
 pub mod fixed_pool;
+#[cfg(feature = "lazy-pool")]
+pub mod lazy_pool;
+pub mod legacy_pool;
 pub mod eventfd_notifier;
+pub mod futex_notifier;
 pub mod ring_completion;
 pub mod heap_buffers;
+#[cfg(feature = "fixed-buffers")]
+pub mod registered_buffers;
 pub mod mmap_shared_page;
 pub mod submit_ring;
 pub mod instance;
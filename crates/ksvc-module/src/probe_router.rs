@@ -9,12 +9,18 @@
 //!
 //! The table is a flat array indexed by `__NR_*` syscall number.
 //! Lookup is O(1): one array index.
+//!
+//! `override_tier` / `override_opcode` then patch single entries, e.g. to
+//! keep a syscall off an opcode that is buggy on a particular kernel.
+//! Overrides are not checked against the probe.
 
 use ksvc_core::router::{RouteInfo, SyscallRouter, TierCounts};
 use ksvc_core::tier::Tier;
 
+use crate::capabilities::IoCapabilities;
+
 /// Maximum syscall number we track. Linux x86_64 has ~450 syscalls.
-const TABLE_SIZE: usize = 512;
+pub(crate) const TABLE_SIZE: usize = 512;
 
 /// The "candidate" mapping: syscall_nr → (io_uring opcode, is this Tier 2 if no opcode?).
 /// Built at compile time. The probe step then filters by what's actually supported.
@@ -94,7 +100,7 @@ pub mod op {
 }
 
 // ── Linux x86_64 syscall numbers (from asm/unistd_64.h) ──
-mod nr {
+pub(crate) mod nr {
     pub const READ: u32 = 0;
     pub const WRITE: u32 = 1;
     pub const OPEN: u32 = 2;
@@ -226,6 +232,17 @@ mod nr {
 
 pub struct ProbeRouter {
     table: [RouteInfo; TABLE_SIZE],
+    /// READ_FIXED and WRITE_FIXED are both supported by the kernel.
+    fixed_rw: bool,
+}
+
+/// Map a Tier 1 opcode to its fixed-buffer variant, if one exists.
+pub fn fixed_buffer_opcode(opcode: u8) -> Option<u8> {
+    match opcode {
+        op::READ => Some(op::READ_FIXED),
+        op::WRITE => Some(op::WRITE_FIXED),
+        _ => None,
+    }
 }
 
 impl ProbeRouter {
@@ -309,6 +326,8 @@ impl ProbeRouter {
         for &opc in supported_opcodes {
             opcode_supported[opc as usize] = true;
         }
+        let fixed_rw = opcode_supported[op::READ_FIXED as usize]
+            && opcode_supported[op::WRITE_FIXED as usize];
 
         for &(syscall_nr, opcode, tier2_fallback) in tier1_candidates {
             if opcode_supported[opcode as usize] {
@@ -367,7 +386,45 @@ impl ProbeRouter {
         table[nr::FSTAT as usize] = table[nr::STATX as usize];
         table[nr::LSTAT as usize] = table[nr::STATX as usize];
 
-        ProbeRouter { table }
+        ProbeRouter { table, fixed_rw }
+    }
+
+    /// Route a syscall whose buffer is a registered fixed buffer.
+    ///
+    /// Same as `route()`, but read/write-family Tier 1 routes are switched
+    /// to `READ_FIXED`/`WRITE_FIXED` when the kernel supports them.
+    /// The caller must put the buffer index in
+    /// `args[registered_buffers::FIXED_BUF_INDEX_ARG]`.
+    pub fn route_fixed(&self, syscall_nr: u32) -> RouteInfo {
+        let route = self.route(syscall_nr);
+        if self.fixed_rw && route.tier == Tier::IoUring {
+            if let Some(fixed) = fixed_buffer_opcode(route.iouring_opcode) {
+                return RouteInfo::iouring(fixed);
+            }
+        }
+        route
+    }
+
+    /// Force `syscall_nr` to `tier`, whatever the probe decided.
+    ///
+    /// The io_uring opcode is kept, so forcing `Tier::IoUring` only makes
+    /// sense for a syscall that has one; otherwise use `override_opcode`.
+    pub fn override_tier(mut self, syscall_nr: u32, tier: Tier) -> Self {
+        assert!((syscall_nr as usize) < TABLE_SIZE, "syscall number out of range");
+        self.table[syscall_nr as usize].tier = tier;
+        self
+    }
+
+    /// Route `syscall_nr` to Tier 1 as io_uring `opcode`.
+    pub fn override_opcode(mut self, syscall_nr: u32, opcode: u8) -> Self {
+        assert!((syscall_nr as usize) < TABLE_SIZE, "syscall number out of range");
+        self.table[syscall_nr as usize] = RouteInfo::iouring(opcode);
+        self
+    }
+
+    /// Build the routing table from probed kernel capabilities.
+    pub fn from_capabilities(caps: &IoCapabilities) -> Self {
+        Self::new(caps.opcodes())
     }
 
     /// Convenience: create with ALL opcodes supported (for testing
@@ -460,6 +517,38 @@ mod tests {
         assert_eq!(r.iouring_opcode, op::OPENAT);
     }
 
+    #[test]
+    fn route_fixed_selects_fixed_variants() {
+        let router = ProbeRouter::kernel_6_8();
+        assert_eq!(router.route_fixed(nr::READ).iouring_opcode, op::READ_FIXED);
+        assert_eq!(router.route_fixed(nr::WRITE).iouring_opcode, op::WRITE_FIXED);
+        // No fixed variant → unchanged
+        assert_eq!(router.route_fixed(nr::RECVFROM).iouring_opcode, op::RECV);
+
+        // Kernel without fixed opcodes → plain READ
+        let no_fixed: Vec<u8> = (0..=op::FTRUNCATE)
+            .filter(|&o| o != op::READ_FIXED && o != op::WRITE_FIXED)
+            .collect();
+        let router = ProbeRouter::new(&no_fixed);
+        assert_eq!(router.route_fixed(nr::READ).iouring_opcode, op::READ);
+    }
+
+    #[test]
+    fn overrides_replace_probed_routes() {
+        let probed = ProbeRouter::kernel_6_8().tier_counts();
+        let router = ProbeRouter::kernel_6_8()
+            .override_tier(nr::READ, Tier::WorkerPool)
+            .override_opcode(nr::PREAD64, op::READ_FIXED);
+        assert_eq!(router.route(nr::READ).tier, Tier::WorkerPool);
+        assert_eq!(router.route(nr::WRITE).tier, Tier::IoUring);
+        let r = router.route(nr::PREAD64);
+        assert_eq!((r.tier, r.iouring_opcode), (Tier::IoUring, op::READ_FIXED));
+
+        let counts = router.tier_counts();
+        assert_eq!(counts.tier1, probed.tier1 - 1);
+        assert_eq!(counts.tier2, probed.tier2 + 1);
+    }
+
     #[test]
     fn tier_counts_reasonable() {
         let router = ProbeRouter::kernel_6_8();
@@ -0,0 +1,272 @@
+//! `RegisteredBuffers` — fixed-buffer `BufferProvider` implementation.
+//!
+//! Pre-allocates `count` buffers of `buf_size` bytes in one page-aligned
+//! region and registers them with io_uring (`IORING_REGISTER_BUFFERS`).
+//! The kernel pins the pages once; reads/writes then use
+//! `IORING_OP_READ_FIXED` / `IORING_OP_WRITE_FIXED` with a buffer index
+//! instead of pinning the user pages on every operation.
+//!
+//! # Lifetime constraint
+//!
+//! A buffer MUST NOT be released while an SQE referencing it is in flight.
+//! The kernel reads/writes the buffer until the CQE is posted; releasing
+//! it early lets another request acquire the same index and race with the
+//! kernel. Release only after the completion for that operation is reaped.
+//!
+//! The region itself must outlive every ring it is registered with
+//! (drop or `unregister_buffers()` the ring first).
+
+use ksvc_core::buffer::{BufferHandle, BufferProvider};
+use ksvc_core::error::{KsvcError, Result};
+
+use crossbeam_queue::ArrayQueue;
+
+use std::alloc::{self, Layout};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// Kernel limit on registered buffers (`IORING_MAX_REG_BUFFERS`).
+pub const MAX_REGISTERED_BUFFERS: usize = 16384;
+
+/// `SubmitEntry::args` slot that carries the fixed buffer index for
+/// `READ_FIXED` / `WRITE_FIXED`. `read`/`write` leave args[3..6] unused.
+pub const FIXED_BUF_INDEX_ARG: usize = 5;
+
+pub struct RegisteredBuffers {
+    /// Base of the contiguous, page-aligned buffer region.
+    base: *mut u8,
+    /// Layout used to allocate `base` (for dealloc).
+    layout: Layout,
+    /// Size of each buffer in bytes.
+    buf_size: usize,
+    /// Number of buffers.
+    count: usize,
+    /// Free buffer indices.
+    free: ArrayQueue<u16>,
+    /// One bit per buffer, set while it is handed out (catches a
+    /// double release, which would put the index on `free` twice).
+    held: Box<[AtomicU64]>,
+    /// Number of buffers currently in use (for diagnostics).
+    in_use: AtomicUsize,
+}
+
+// Safety: the region is owned by this struct; each index is handed out
+// to exactly one holder at a time via the lock-free free list.
+unsafe impl Send for RegisteredBuffers {}
+unsafe impl Sync for RegisteredBuffers {}
+
+impl RegisteredBuffers {
+    /// Allocate `count` buffers of `buf_size` bytes each.
+    ///
+    /// Fails with `InvalidConfig` if `count` is 0 or exceeds
+    /// `MAX_REGISTERED_BUFFERS`, if `buf_size` is 0, or if the region
+    /// (`buf_size * count`) would not fit in the address space.
+    pub fn new(buf_size: usize, count: usize) -> Result<Self> {
+        if count == 0 || count > MAX_REGISTERED_BUFFERS {
+            return Err(KsvcError::InvalidConfig(
+                "RegisteredBuffers: count must be 1..=MAX_REGISTERED_BUFFERS",
+            ));
+        }
+        if buf_size == 0 {
+            return Err(KsvcError::InvalidConfig("RegisteredBuffers: buf_size must be > 0"));
+        }
+
+        let layout = buf_size
+            .checked_mul(count)
+            .and_then(|size| Layout::from_size_align(size, 4096).ok())
+            .ok_or(KsvcError::InvalidConfig("RegisteredBuffers: region too large"))?;
+        let base = unsafe { alloc::alloc_zeroed(layout) };
+        if base.is_null() {
+            alloc::handle_alloc_error(layout);
+        }
+
+        let free = ArrayQueue::new(count);
+        for i in 0..count {
+            let _ = free.push(i as u16);
+        }
+
+        Ok(Self {
+            base,
+            layout,
+            buf_size,
+            count,
+            free,
+            held: (0..count.div_ceil(64)).map(|_| AtomicU64::new(0)).collect(),
+            in_use: AtomicUsize::new(0),
+        })
+    }
+
+    /// Size of each buffer in bytes.
+    pub fn buf_size(&self) -> usize {
+        self.buf_size
+    }
+
+    /// iovec table for `IORING_REGISTER_BUFFERS`. Index i ↔ buf_index i.
+    pub fn iovecs(&self) -> Vec<libc::iovec> {
+        (0..self.count)
+            .map(|i| libc::iovec {
+                iov_base: unsafe { self.base.add(i * self.buf_size) } as *mut libc::c_void,
+                iov_len: self.buf_size,
+            })
+            .collect()
+    }
+
+    /// Acquire a free fixed buffer. Returns its index and contents.
+    ///
+    /// Returns `None` if the pool is exhausted.
+    #[allow(clippy::mut_from_ref)]
+    pub fn acquire_fixed(&self) -> Option<(u16, &mut [u8])> {
+        let idx = self.free.pop()?;
+        let (word, bit) = Self::held_bit(idx);
+        self.held[word].fetch_or(bit, Ordering::AcqRel);
+        self.in_use.fetch_add(1, Ordering::Relaxed);
+        // Safety: idx was on the free list, so no one else holds it.
+        let buf = unsafe {
+            std::slice::from_raw_parts_mut(self.buf_ptr(idx), self.buf_size)
+        };
+        Some((idx, buf))
+    }
+
+    /// Return a fixed buffer to the pool.
+    ///
+    /// Must only be called once the CQE for every SQE that referenced
+    /// `buf_index` has been reaped (see module docs). Releasing a buffer
+    /// that isn't held is a bug: it panics in debug builds and is ignored
+    /// otherwise.
+    pub fn release_fixed(&self, buf_index: u16) {
+        debug_assert!((buf_index as usize) < self.count);
+        let (word, bit) = Self::held_bit(buf_index);
+        let held = self.held[word].fetch_and(!bit, Ordering::AcqRel) & bit != 0;
+        debug_assert!(held, "RegisteredBuffers: buffer {} released twice", buf_index);
+        if held && self.free.push(buf_index).is_ok() {
+            self.in_use.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Word and mask of `idx` in `held`.
+    #[inline]
+    fn held_bit(idx: u16) -> (usize, u64) {
+        (idx as usize / 64, 1 << (idx % 64))
+    }
+
+    #[inline]
+    fn buf_ptr(&self, idx: u16) -> *mut u8 {
+        unsafe { self.base.add(idx as usize * self.buf_size) }
+    }
+}
+
+/// Register `buffers` (`IORING_REGISTER_BUFFERS`) through a ring's
+/// `Submitter`; shared by every io_uring backend.
+pub(crate) fn register_buffers(
+    submitter: &io_uring::Submitter<'_>,
+    buffers: &RegisteredBuffers,
+) -> Result<()> {
+    let iovecs = buffers.iovecs();
+    // Safety: RegisteredBuffers keeps the region alive and documents
+    // that it must outlive the ring.
+    unsafe { submitter.register_buffers(&iovecs) }
+        .map_err(|e| KsvcError::IoUringSetup(e.raw_os_error().unwrap_or(-1)))
+}
+
+impl BufferProvider for RegisteredBuffers {
+    fn acquire(&self, min_size: usize) -> Option<BufferHandle> {
+        if min_size > self.buf_size {
+            return None;
+        }
+        let (idx, buf) = self.acquire_fixed()?;
+        Some(BufferHandle {
+            ptr: buf.as_mut_ptr(),
+            len: buf.len(),
+            buf_index: idx,
+        })
+    }
+
+    fn release(&self, handle: BufferHandle) {
+        self.release_fixed(handle.buf_index);
+    }
+
+    fn is_registered(&self) -> bool {
+        true
+    }
+
+    fn pool_size(&self) -> usize {
+        self.count
+    }
+
+    fn in_use(&self) -> usize {
+        self.in_use.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for RegisteredBuffers {
+    fn drop(&mut self) {
+        unsafe { alloc::dealloc(self.base, self.layout) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acquire_release_roundtrip() {
+        let pool = RegisteredBuffers::new(4096, 4).unwrap();
+        let (idx, buf) = pool.acquire_fixed().unwrap();
+        assert_eq!(buf.len(), 4096);
+        buf[0] = 0xAB;
+        assert_eq!(pool.in_use(), 1);
+        pool.release_fixed(idx);
+        assert_eq!(pool.in_use(), 0);
+    }
+
+    #[test]
+    fn exhaustion_returns_none() {
+        let pool = RegisteredBuffers::new(512, 2).unwrap();
+        let a = pool.acquire(100).unwrap();
+        let b = pool.acquire(100).unwrap();
+        assert_ne!(a.buf_index, b.buf_index);
+        assert!(pool.acquire(100).is_none());
+        pool.release(a);
+        assert!(pool.acquire(100).is_some());
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "released twice"))]
+    fn double_release_is_caught() {
+        let pool = RegisteredBuffers::new(512, 2).unwrap();
+        let (idx, _) = pool.acquire_fixed().unwrap();
+        pool.release_fixed(idx);
+        pool.release_fixed(idx);
+        // Release builds ignore it: the index is on the free list once
+        let a = pool.acquire(1).unwrap();
+        let b = pool.acquire(1).unwrap();
+        assert_ne!(a.buf_index, b.buf_index);
+        assert!(pool.acquire(1).is_none());
+        assert_eq!(pool.in_use(), 2);
+    }
+
+    #[test]
+    fn oversize_request_rejected() {
+        let pool = RegisteredBuffers::new(512, 2).unwrap();
+        assert!(pool.acquire(513).is_none());
+        assert_eq!(pool.in_use(), 0);
+    }
+
+    #[test]
+    fn iovecs_match_indices() {
+        let pool = RegisteredBuffers::new(1024, 3).unwrap();
+        let iov = pool.iovecs();
+        assert_eq!(iov.len(), 3);
+        let h = pool.acquire(1).unwrap();
+        assert_eq!(iov[h.buf_index as usize].iov_base as *mut u8, h.ptr);
+        assert_eq!(iov[0].iov_base as usize % 4096, 0);
+    }
+
+    #[test]
+    fn bad_sizes_are_errors() {
+        assert!(RegisteredBuffers::new(0, 4).is_err());
+        assert!(RegisteredBuffers::new(512, 0).is_err());
+        assert!(RegisteredBuffers::new(512, MAX_REGISTERED_BUFFERS + 1).is_err());
+        // buf_size * count overflows usize
+        assert!(RegisteredBuffers::new(usize::MAX / 2, 4).is_err());
+    }
+}
@@ -0,0 +1,267 @@
+//! `SqpollIoUring` — `IoBackend` with a kernel-side SQ polling thread.
+//!
+//! Sets up the ring with `IORING_SETUP_SQPOLL`. A kernel thread polls the
+//! submission queue, so steady-state submission needs no `io_uring_enter()`.
+//! After `sq_thread_idle_ms` without work the kernel thread sleeps and sets
+//! `IORING_SQ_NEED_WAKEUP`; the next flush must wake it with
+//! `IORING_ENTER_SQ_WAKEUP`. `sq_thread_cpu` pins the kernel thread to a CPU.
+//!
+//! Same SQE translation as `BasicIoUring`. Requires Linux 5.11+ for
+//! unprivileged use (older kernels need CAP_SYS_ADMIN).
+
+use ksvc_core::entry::{CorrId, SubmitEntry};
+use ksvc_core::error::{KsvcError, Result};
+use ksvc_core::io_backend::{IoBackend, IoCompletion};
+
+use crate::basic_iouring::{BasicIoUring, BasicIoUringConfig};
+use crate::capabilities::IoCapabilities;
+
+use std::os::unix::io::{AsRawFd, RawFd};
+
+/// SQPOLL io_uring backend.
+///
+/// Drop-in replacement for `BasicIoUring`:
+/// 1. `submit()` queues the SQE (kernel thread picks it up)
+/// 2. `flush()` only enters the kernel if the SQ thread went idle
+/// 3. `poll_completions()` drains CQEs
+pub struct SqpollIoUring {
+    ring: io_uring::IoUring,
+    inflight: usize,
+    pending_submit: u32,
+    /// Number of times the SQ thread had to be woken after idling.
+    wakeups: u64,
+}
+
+impl SqpollIoUring {
+    pub fn new(config: BasicIoUringConfig) -> Result<Self> {
+        let mut builder = io_uring::IoUring::builder();
+        builder.setup_sqpoll(config.sq_thread_idle_ms);
+        let ring = BasicIoUring::build_ring(builder, &config, true)?;
+
+        Ok(Self {
+            ring,
+            inflight: 0,
+            pending_submit: 0,
+            wakeups: 0,
+        })
+    }
+
+    /// Get the io_uring fd for passing to the kernel module.
+    pub fn fd(&self) -> RawFd {
+        self.ring.as_raw_fd()
+    }
+
+    /// Supported opcodes (probed once per process, see `capabilities`).
+    pub fn probe_opcodes_static(&self) -> Vec<u8> {
+        self.capabilities().opcodes().to_vec()
+    }
+
+    /// What the kernel's io_uring supports (see `BasicIoUring::capabilities`).
+    pub fn capabilities(&self) -> &'static IoCapabilities {
+        IoCapabilities::of_ring(&self.ring)
+    }
+
+    /// Register fixed buffers (`IORING_REGISTER_BUFFERS`) with this ring.
+    #[cfg(feature = "fixed-buffers")]
+    pub fn register_buffers(
+        &self,
+        buffers: &crate::registered_buffers::RegisteredBuffers,
+    ) -> Result<()> {
+        crate::registered_buffers::register_buffers(&self.ring.submitter(), buffers)
+    }
+
+    /// Number of times `flush()` found the SQ thread asleep and woke it.
+    pub fn wakeups(&self) -> u64 {
+        self.wakeups
+    }
+
+    /// Flush pending SQEs AND block until at least `min_complete` CQEs are ready.
+    ///
+    /// With `min_complete == 0` this behaves like `flush()`.
+    pub fn flush_and_wait(&mut self, min_complete: usize) -> Result<usize> {
+        self.note_wakeup();
+        self.ring.submit_and_wait(min_complete)
+            .map_err(|e| KsvcError::IoUringSubmit(e.raw_os_error().unwrap_or(-1)))?;
+        Ok(self.take_pending())
+    }
+
+    /// Count a wakeup if the SQ thread went idle. The actual
+    /// `IORING_ENTER_SQ_WAKEUP` is issued by `submit()`/`submit_and_wait()`.
+    #[inline]
+    fn note_wakeup(&mut self) {
+        if self.pending_submit > 0 && self.ring.submission().need_wakeup() {
+            self.wakeups += 1;
+        }
+    }
+
+    /// With SQPOLL the kernel consumes SQEs asynchronously, so the enter
+    /// return value is not a submit count. Everything pushed is in flight.
+    #[inline]
+    fn take_pending(&mut self) -> usize {
+        let submitted = self.pending_submit as usize;
+        self.inflight += submitted;
+        self.pending_submit = 0;
+        submitted
+    }
+}
+
+impl IoBackend for SqpollIoUring {
+    /// Translate a KSVC SubmitEntry to an io_uring SQE and push it.
+    fn submit(&mut self, entry: &SubmitEntry, opcode: u8) -> Result<()> {
+        let sqe = BasicIoUring::build_sqe(entry, opcode)?;
+        unsafe {
+            self.ring.submission()
+                .push(&sqe)
+                .map_err(|_| KsvcError::RingFull)?;
+        }
+        self.pending_submit += 1;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<usize> {
+        if self.pending_submit == 0 {
+            return Ok(0);
+        }
+        self.note_wakeup();
+        // No syscall unless the SQ thread needs a wakeup (or CQ overflowed).
+        self.ring.submit()
+            .map_err(|e| KsvcError::IoUringSubmit(e.raw_os_error().unwrap_or(-1)))?;
+        Ok(self.take_pending())
+    }
+
+    fn poll_completions(&mut self, buf: &mut [IoCompletion], max: usize) -> usize {
+        let limit = max.min(buf.len());
+        let mut count = 0;
+        loop {
+            let mut cq = self.ring.completion();
+            while count < limit {
+                let Some(cqe) = cq.next() else { break };
+                buf[count] = IoCompletion {
+                    corr_id: CorrId(cqe.user_data()),
+                    result: cqe.result() as i64,
+                    flags: cqe.flags(),
+                };
+                count += 1;
+                self.inflight = self.inflight.saturating_sub(1);
+            }
+            drop(cq);
+            // The SQ thread doesn't flush held-back CQEs either
+            if count == limit || !BasicIoUring::flush_cq_overflow(&mut self.ring) {
+                return count;
+            }
+        }
+    }
+
+    fn cancel(&mut self, corr_id: CorrId) -> Result<()> {
+        let sqe = BasicIoUring::build_cancel_sqe(corr_id);
+        unsafe {
+            self.ring.submission()
+                .push(&sqe)
+                .map_err(|_| KsvcError::RingFull)?;
+        }
+        self.pending_submit += 1;
+        Ok(())
+    }
+
+    fn inflight(&self) -> usize {
+        self.inflight
+    }
+
+    fn capacity(&self) -> usize {
+        self.ring.params().sq_entries() as usize
+    }
+
+    fn probe_opcodes(&self) -> Vec<u8> {
+        self.probe_opcodes_static()
+    }
+
+    fn shutdown(&mut self) {
+        let mut buf = [IoCompletion { corr_id: CorrId(0), result: 0, flags: 0 }; 64];
+        loop {
+            let n = self.poll_completions(&mut buf, 64);
+            if n == 0 {
+                break;
+            }
+        }
+        self.inflight = 0;
+        self.pending_submit = 0;
+        // io_uring::IoUring::drop() closes the fd, which also stops the SQ thread
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::probe_router::op;
+    use std::time::Duration;
+
+    /// Submit one op and wait for its completion
+    fn run(io: &mut SqpollIoUring, entry: &SubmitEntry, opcode: u8) -> i64 {
+        io.submit(entry, opcode).unwrap();
+        io.flush_and_wait(1).unwrap();
+        let mut buf = [IoCompletion { corr_id: CorrId(0), result: 0, flags: 0 }; 1];
+        while io.poll_completions(&mut buf, 1) == 0 {
+            io.flush_and_wait(1).unwrap();
+        }
+        assert_eq!(buf[0].corr_id, entry.corr_id);
+        buf[0].result
+    }
+
+    #[test]
+    fn echoes_over_a_socket_and_wakes_the_idle_sq_thread() {
+        let mut io = match SqpollIoUring::new(BasicIoUringConfig {
+            sq_thread_idle_ms: 10,
+            ..Default::default()
+        }) {
+            Ok(io) => io,
+            Err(e) => {
+                // No SQPOLL for this user / kernel: say so rather than pass
+                eprintln!("SKIPPED echoes_over_a_socket_and_wakes_the_idle_sq_thread: SQPOLL unavailable ({})", e);
+                return;
+            }
+        };
+        let mut fds = [0; 2];
+        assert_eq!(
+            unsafe { libc::socketpair(libc::AF_UNIX, libc::SOCK_STREAM, 0, fds.as_mut_ptr()) },
+            0
+        );
+        let (server, client) = (fds[0], fds[1]);
+
+        let mut buf = [0u8; 64];
+        for round in 0..3u64 {
+            if round == 2 {
+                // Long past sq_thread_idle_ms: the SQ thread is asleep
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            let msg = format!("ping {}", round);
+            let sent = unsafe { libc::write(client, msg.as_ptr().cast(), msg.len()) };
+            assert_eq!(sent, msg.len() as isize);
+
+            let recv = SubmitEntry {
+                corr_id: CorrId(2 * round),
+                syscall_nr: 45,
+                flags: 0,
+                args: [server as u64, buf.as_mut_ptr() as u64, buf.len() as u64, 0, 0, 0],
+            };
+            let n = run(&mut io, &recv, op::RECV);
+            assert_eq!(n, msg.len() as i64);
+            let send = SubmitEntry {
+                corr_id: CorrId(2 * round + 1),
+                syscall_nr: 44,
+                flags: 0,
+                args: [server as u64, buf.as_ptr() as u64, n as u64, 0, 0, 0],
+            };
+            assert_eq!(run(&mut io, &send, op::SEND), n);
+
+            let mut echoed = [0u8; 64];
+            let got = unsafe { libc::read(client, echoed.as_mut_ptr().cast(), echoed.len()) };
+            assert_eq!(&echoed[..got as usize], msg.as_bytes());
+        }
+        assert_eq!(io.inflight(), 0);
+        assert!(io.wakeups() > 0, "the idle SQ thread was never woken");
+        unsafe {
+            libc::close(server);
+            libc::close(client);
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! `StaticRouter` — compile-time `SyscallRouter` implementation.
+//!
+//! The routing table is a `const` array built by a `const fn` from a
+//! static `(syscall_nr, RouteInfo)` list. No probing, no allocation.
+//! `route()` is a single clamped array read — no branches.
+//!
+//! Use it when the target kernel is known. It will NOT auto-promote
+//! syscalls when the kernel gains new opcodes; use `ProbeRouter` for that.
+//!
+//! ```ignore
+//! // One-line swap in the instance type alias:
+//! pub type DefaultInstance = KsvcInstance<StaticRouter, BasicIoUring, ...>;
+//! ```
+
+use ksvc_core::router::{RouteInfo, SyscallRouter};
+
+use crate::probe_router::{nr, op, TABLE_SIZE};
+
+/// Compile-time routing table.
+pub struct StaticRouter {
+    table: [RouteInfo; TABLE_SIZE],
+}
+
+/// Routes for a stock Linux 6.8 kernel (opcodes up to `FTRUNCATE`).
+///
+/// Matches what `ProbeRouter::kernel_6_8()` builds at runtime.
+pub const KERNEL_6_8_ROUTES: &[(u32, RouteInfo)] = &[
+    // ── Tier 0: shared page ──
+    (nr::GETPID,      RouteInfo::shared_page()),
+    (nr::GETPPID,     RouteInfo::shared_page()),
+    (nr::GETUID,      RouteInfo::shared_page()),
+    (nr::GETGID,      RouteInfo::shared_page()),
+    (nr::GETEUID,     RouteInfo::shared_page()),
+    (nr::GETEGID,     RouteInfo::shared_page()),
+    (nr::GETSID,      RouteInfo::shared_page()),
+    (nr::UNAME,       RouteInfo::shared_page()),
+    (nr::GETPGRP,     RouteInfo::shared_page()),
+    (nr::GETRLIMIT,   RouteInfo::shared_page()),
+    // ── Tier 1: file I/O ──
+    (nr::READ,        RouteInfo::iouring(op::READ)),
+    (nr::WRITE,       RouteInfo::iouring(op::WRITE)),
+    (nr::PREAD64,     RouteInfo::iouring(op::READ)),
+    (nr::PWRITE64,    RouteInfo::iouring(op::WRITE)),
+    (nr::READV,       RouteInfo::iouring(op::READV)),
+    (nr::WRITEV,      RouteInfo::iouring(op::WRITEV)),
+    (nr::PREADV,      RouteInfo::iouring(op::READV)),
+    (nr::PWRITEV,     RouteInfo::iouring(op::WRITEV)),
+    // ── Tier 1: file lifecycle ──
+    (nr::OPENAT,      RouteInfo::iouring(op::OPENAT)),
+    (nr::OPENAT2,     RouteInfo::iouring(op::OPENAT2)),
+    (nr::CLOSE,       RouteInfo::iouring(op::CLOSE)),
+    (nr::STATX,       RouteInfo::iouring(op::STATX)),
+    (nr::FALLOCATE,   RouteInfo::iouring(op::FALLOCATE)),
+    (nr::FTRUNCATE,   RouteInfo::iouring(op::FTRUNCATE)),
+    // ── Tier 1: sync ──
+    (nr::FSYNC,       RouteInfo::iouring(op::FSYNC)),
+    (nr::FDATASYNC,   RouteInfo::iouring(op::FSYNC)),
+    (nr::SYNC_FILE_RANGE, RouteInfo::iouring(op::SYNC_FILE_RANGE)),
+    // ── Tier 1: metadata ──
+    (nr::RENAMEAT2,   RouteInfo::iouring(op::RENAMEAT)),
+    (nr::RENAMEAT,    RouteInfo::iouring(op::RENAMEAT)),
+    (nr::UNLINKAT,    RouteInfo::iouring(op::UNLINKAT)),
+    (nr::MKDIRAT,     RouteInfo::iouring(op::MKDIRAT)),
+    (nr::SYMLINKAT,   RouteInfo::iouring(op::SYMLINKAT)),
+    (nr::LINKAT,      RouteInfo::iouring(op::LINKAT)),
+    (nr::FADVISE64,   RouteInfo::iouring(op::FADVISE)),
+    (nr::MADVISE,     RouteInfo::iouring(op::MADVISE)),
+    (nr::SETXATTR,    RouteInfo::iouring(op::SETXATTR)),
+    (nr::GETXATTR,    RouteInfo::iouring(op::GETXATTR)),
+    (nr::FSETXATTR,   RouteInfo::iouring(op::FSETXATTR)),
+    (nr::FGETXATTR,   RouteInfo::iouring(op::FGETXATTR)),
+    // ── Tier 1: network ──
+    (nr::ACCEPT4,     RouteInfo::iouring(op::ACCEPT)),
+    (nr::CONNECT,     RouteInfo::iouring(op::CONNECT)),
+    (nr::SENDTO,      RouteInfo::iouring(op::SEND)),
+    (nr::RECVFROM,    RouteInfo::iouring(op::RECV)),
+    (nr::SENDMSG,     RouteInfo::iouring(op::SENDMSG)),
+    (nr::RECVMSG,     RouteInfo::iouring(op::RECVMSG)),
+    (nr::SHUTDOWN,    RouteInfo::iouring(op::SHUTDOWN)),
+    (nr::SOCKET,      RouteInfo::iouring(op::SOCKET)),
+    // ── Tier 1: splice, process sync, epoll ──
+    (nr::SPLICE,      RouteInfo::iouring(op::SPLICE)),
+    (nr::TEE,         RouteInfo::iouring(op::TEE)),
+    (nr::WAITID,      RouteInfo::iouring(op::WAITID)),
+    (nr::EPOLL_CTL,   RouteInfo::iouring(op::EPOLL_CTL)),
+    // ── Legacy syscalls routed like their *at() / statx variants ──
+    (nr::OPEN,        RouteInfo::iouring(op::OPENAT)),
+    (nr::RENAME,      RouteInfo::iouring(op::RENAMEAT)),
+    (nr::UNLINK,      RouteInfo::iouring(op::UNLINKAT)),
+    (nr::MKDIR,       RouteInfo::iouring(op::MKDIRAT)),
+    (nr::SYMLINK,     RouteInfo::iouring(op::SYMLINKAT)),
+    (nr::LINK,        RouteInfo::iouring(op::LINKAT)),
+    (nr::RMDIR,       RouteInfo::iouring(op::UNLINKAT)),
+    (nr::STAT,        RouteInfo::iouring(op::STATX)),
+    (nr::FSTAT,       RouteInfo::iouring(op::STATX)),
+    (nr::LSTAT,       RouteInfo::iouring(op::STATX)),
+    // ── Tier 2: no opcode on 6.8 ──
+    (nr::BIND,        RouteInfo::worker()),
+    (nr::LISTEN,      RouteInfo::worker()),
+    (nr::DUP,         RouteInfo::worker()),
+    (nr::DUP2,        RouteInfo::worker()),
+    (nr::DUP3,        RouteInfo::worker()),
+    (nr::FCNTL,       RouteInfo::worker()),
+    (nr::IOCTL,       RouteInfo::worker()),
+    (nr::LSEEK,       RouteInfo::worker()),
+    (nr::SETSOCKOPT,  RouteInfo::worker()),
+    (nr::GETSOCKOPT,  RouteInfo::worker()),
+    (nr::GETSOCKNAME, RouteInfo::worker()),
+    (nr::GETPEERNAME, RouteInfo::worker()),
+    (nr::GETDENTS64,  RouteInfo::worker()),
+    (nr::ACCESS,      RouteInfo::worker()),
+    (nr::FACCESSAT,   RouteInfo::worker()),
+    (nr::FCHMOD,      RouteInfo::worker()),
+    (nr::FCHMODAT,    RouteInfo::worker()),
+    (nr::FCHOWN,      RouteInfo::worker()),
+    (nr::FCHOWNAT,    RouteInfo::worker()),
+    (nr::UTIMENSAT,   RouteInfo::worker()),
+    (nr::FLOCK,       RouteInfo::worker()),
+    (nr::READLINKAT,  RouteInfo::worker()),
+    (nr::READLINK,    RouteInfo::worker()),
+    (nr::PIPE2,       RouteInfo::worker()),
+    (nr::GETCWD,      RouteInfo::worker()),
+    (nr::GETRANDOM,   RouteInfo::worker()),
+];
+
+impl StaticRouter {
+    /// Build a table from a static route list. Unlisted syscalls are Legacy.
+    ///
+    /// The last table slot is reserved (always Legacy) so that `route()`
+    /// can clamp out-of-range numbers onto it instead of branching.
+    ///
+    /// # Panics
+    /// Panics (at compile time when used in a `const`) if a syscall
+    /// number is `>= TABLE_SIZE - 1`.
+    pub const fn from_routes(routes: &[(u32, RouteInfo)]) -> Self {
+        let mut table = [RouteInfo::LEGACY; TABLE_SIZE];
+        let mut i = 0;
+        while i < routes.len() {
+            let (syscall_nr, route) = routes[i];
+            assert!((syscall_nr as usize) < TABLE_SIZE - 1, "syscall number out of range");
+            table[syscall_nr as usize] = route;
+            i += 1;
+        }
+        Self { table }
+    }
+
+    /// Routing table for a stock Linux 6.8 kernel.
+    pub const fn kernel_6_8() -> Self {
+        Self::from_routes(KERNEL_6_8_ROUTES)
+    }
+}
+
+impl Default for StaticRouter {
+    fn default() -> Self {
+        Self::kernel_6_8()
+    }
+}
+
+impl SyscallRouter for StaticRouter {
+    #[inline]
+    fn route(&self, syscall_nr: u32) -> RouteInfo {
+        // Clamp (cmov, not a branch) onto the reserved Legacy slot.
+        self.table[(syscall_nr as usize).min(TABLE_SIZE - 1)]
+    }
+
+    fn table_size(&self) -> usize {
+        TABLE_SIZE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::probe_router::ProbeRouter;
+    use ksvc_core::tier::Tier;
+
+    static ROUTER: StaticRouter = StaticRouter::kernel_6_8();
+
+    #[test]
+    fn matches_probe_router_6_8() {
+        let probe = ProbeRouter::kernel_6_8();
+        for n in 0..TABLE_SIZE as u32 {
+            let a = ROUTER.route(n);
+            let b = probe.route(n);
+            assert_eq!(a.tier, b.tier, "tier mismatch for syscall {}", n);
+            if a.tier == Tier::IoUring {
+                assert_eq!(a.iouring_opcode, b.iouring_opcode, "opcode mismatch for syscall {}", n);
+            }
+        }
+    }
+
+    #[test]
+    fn out_of_range_is_legacy() {
+        assert_eq!(ROUTER.route(TABLE_SIZE as u32 - 1).tier, Tier::Legacy);
+        assert_eq!(ROUTER.route(10_000).tier, Tier::Legacy);
+        assert_eq!(ROUTER.route(u32::MAX).tier, Tier::Legacy);
+    }
+
+    #[test]
+    fn tier_counts_match_probe_router() {
+        let a = ROUTER.tier_counts();
+        let b = ProbeRouter::kernel_6_8().tier_counts();
+        assert_eq!((a.tier0, a.tier1, a.tier2, a.tier3), (b.tier0, b.tier1, b.tier2, b.tier3));
+    }
+}
@@ -150,6 +150,7 @@ struct ksvc_create_params {
 /* Submission flags */
 #define KSVC_FLAG_LINKED    (1U << 0)
 #define KSVC_FLAG_DRAIN     (1U << 1)
+#define KSVC_FLAG_PRIORITY  (1U << 2)
 
 /* Completion flags */
 #define KSVC_COMP_MORE      (1U << 0)